@@ -294,7 +294,7 @@ fn parsed_to_value(v: ParsedValue) -> Value {
     }
 }
 
-fn value_to_string(v: &Value) -> String {
+pub fn value_to_string(v: &Value) -> String {
     match v {
         Value::Bool(b) => b.to_string(),
         Value::String(s) => s.replace('\n', r"\n"),
@@ -318,6 +318,64 @@ fn value_to_string(v: &Value) -> String {
     }
 }
 
+/// Parse cfg text into a value of the same kind as `current`, keeping its enum
+/// options and numeric range. Without a current value the type is guessed the
+/// same way orphaned entries are.
+pub fn value_from_text(current: Option<&Value>, raw: &str) -> Result<Value, String> {
+    let raw = raw.trim();
+    match current {
+        Some(Value::Bool(_)) => Ok(Value::Bool(raw.parse::<bool>().map_err(|e| e.to_string())?)),
+        Some(Value::String(_)) => Ok(Value::String(raw.replace(r"\n", "\n"))),
+        Some(Value::Int(n)) => Ok(Value::Int(Num {
+            value: parse_num_i32(raw, None)?.value,
+            range: n.range.clone(),
+        })),
+        Some(Value::Float(n)) => Ok(Value::Float(Num {
+            value: parse_num_f32(raw, None)?.value,
+            range: n.range.clone(),
+        })),
+        Some(Value::Enum { options, .. }) => {
+            let index = options
+                .iter()
+                .position(|opt| opt == raw)
+                .ok_or_else(|| format!("`{raw}` is not one of: {}", options.join(", ")))?;
+            Ok(Value::Enum {
+                index,
+                options: options.clone(),
+            })
+        }
+        Some(Value::Flags { options, .. }) => {
+            let mut indicies = vec![];
+            for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let index = options
+                    .iter()
+                    .position(|opt| opt == part)
+                    .ok_or_else(|| format!("`{part}` is not one of: {}", options.join(", ")))?;
+                indicies.push(index);
+            }
+            Ok(Value::Flags {
+                indicies,
+                options: options.clone(),
+            })
+        }
+        None => match check_value_type(raw).as_str() {
+            "Boolean" => match raw.parse::<bool>() {
+                Ok(b) => Ok(Value::Bool(b)),
+                Err(_) => Ok(Value::String(raw.to_string())),
+            },
+            "Int32" => match parse_num_i32(raw, None) {
+                Ok(n) => Ok(Value::Int(n)),
+                Err(_) => Ok(Value::String(raw.to_string())),
+            },
+            "Single" => match parse_num_f32(raw, None) {
+                Ok(n) => Ok(Value::Float(n)),
+                Err(_) => Ok(Value::String(raw.to_string())),
+            },
+            _ => Ok(Value::String(raw.replace(r"\n", "\n"))),
+        },
+    }
+}
+
 fn render_entry_comments(
     entry: &Entry,
     type_name: &str,
@@ -0,0 +1,528 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+// Reads `BepInEx/LogOutput.log` of the games the launcher starts. BepInEx writes
+// one `[Level  :Source] message` header per record; lines without a header (stack
+// traces, multi-line messages) belong to the record above. While a game runs its
+// log is tailed into `logs/bepinex/<session>/LogOutput.log`, so every run keeps a
+// copy after the next launch truncates the original, and new records are sent to
+// the UI as they appear.
+
+pub const RECORDS_EVENT: &str = "bepinex-log://records";
+pub const FINISHED_EVENT: &str = "bepinex-log://finished";
+
+const SESSION_FILE: &str = "session.json";
+const LOG_COPY_FILE: &str = "LogOutput.log";
+const MAX_SESSIONS: usize = 30;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Chainloader messages about a plugin that did not load.
+const LOAD_FAILURE_MARKERS: [&str; 4] = [
+    "Could not load [",
+    "Error loading [",
+    "Skipping [",
+    "Failed to load [",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Message,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "message" => Some(LogLevel::Message),
+            "warning" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            "fatal" => Some(LogLevel::Fatal),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// 1-based line of the header in the log.
+    pub line: usize,
+    pub level: LogLevel,
+    /// Plugin or subsystem that logged it, e.g. `BepInEx` or `HQoL`.
+    pub source: String,
+    pub message: String,
+    pub exception: bool,
+    /// Plugin a chainloader message says was not loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_plugin: Option<String>,
+}
+
+impl LogRecord {
+    pub fn is_problem(&self) -> bool {
+        self.level >= LogLevel::Error || self.exception || self.failed_plugin.is_some()
+    }
+
+    fn finalize(mut self) -> Self {
+        self.exception = looks_like_exception(&self.message);
+        self.failed_plugin = failed_plugin(self.level, &self.message);
+        self
+    }
+}
+
+fn parse_header(line: &str) -> Option<(LogLevel, String, String)> {
+    let rest = line.strip_prefix('[')?;
+    let (header, message) = rest.split_once(']')?;
+    let (level, source) = header.split_once(':')?;
+    Some((
+        LogLevel::parse(level)?,
+        source.trim().to_string(),
+        message.strip_prefix(' ').unwrap_or(message).to_string(),
+    ))
+}
+
+fn looks_like_exception(message: &str) -> bool {
+    message.lines().any(|line| {
+        let line = line.trim();
+        line.contains("Exception:")
+            || line.ends_with("Exception")
+            || line.starts_with("Stack trace:")
+            || (line.starts_with("at ") && line.contains('('))
+    })
+}
+
+fn failed_plugin(level: LogLevel, message: &str) -> Option<String> {
+    if level < LogLevel::Warning {
+        return None;
+    }
+    let marker = LOAD_FAILURE_MARKERS
+        .iter()
+        .find_map(|marker| message.find(marker).map(|at| at + marker.len()))?;
+    let (name, _) = message[marker..].split_once(']')?;
+    // `[Name 1.2.3]`: drop the version.
+    let name = match name.rsplit_once(' ') {
+        Some((plugin, version)) if version.starts_with(|c: char| c.is_ascii_digit()) => plugin,
+        _ => name,
+    };
+    Some(name.trim().to_string()).filter(|name| !name.is_empty())
+}
+
+/// Incremental parser for a growing log. A record is returned once the next header
+/// shows that no more lines belong to it.
+#[derive(Debug, Default)]
+pub struct LogParser {
+    partial: Vec<u8>,
+    current: Option<LogRecord>,
+    line: usize,
+}
+
+impl LogParser {
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<LogRecord> {
+        let mut done = vec![];
+        self.partial.extend_from_slice(bytes);
+        while let Some(end) = self.partial.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.partial.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&raw[..end]);
+            self.push_line(line.trim_end_matches('\r'), &mut done);
+        }
+        done
+    }
+
+    pub fn finish(&mut self) -> Vec<LogRecord> {
+        let mut done = vec![];
+        if !self.partial.is_empty() {
+            let raw = std::mem::take(&mut self.partial);
+            let line = String::from_utf8_lossy(&raw).to_string();
+            self.push_line(line.trim_end_matches('\r'), &mut done);
+        }
+        done.extend(self.current.take().map(LogRecord::finalize));
+        done
+    }
+
+    fn push_line(&mut self, line: &str, done: &mut Vec<LogRecord>) {
+        self.line += 1;
+        if let Some((level, source, message)) = parse_header(line) {
+            done.extend(self.current.take().map(LogRecord::finalize));
+            self.current = Some(LogRecord {
+                line: self.line,
+                level,
+                source,
+                message,
+                exception: false,
+                failed_plugin: None,
+            });
+        } else if let Some(current) = self.current.as_mut() {
+            current.message.push('\n');
+            current.message.push_str(line);
+        }
+    }
+}
+
+pub fn parse(bytes: &[u8]) -> Vec<LogRecord> {
+    let mut parser = LogParser::default();
+    let mut records = parser.feed(bytes);
+    records.extend(parser.finish());
+    records
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCounts {
+    pub errors: usize,
+    pub warnings: usize,
+    pub exceptions: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogSummary {
+    pub records: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub exceptions: usize,
+    pub plugin_load_failures: Vec<String>,
+    /// Only sources that logged a warning or worse.
+    pub plugins: BTreeMap<String, PluginCounts>,
+}
+
+impl LogSummary {
+    pub fn add(&mut self, record: &LogRecord) {
+        self.records += 1;
+        let error = record.level >= LogLevel::Error;
+        let warning = record.level == LogLevel::Warning;
+        self.errors += usize::from(error);
+        self.warnings += usize::from(warning);
+        self.exceptions += usize::from(record.exception);
+        if let Some(plugin) = &record.failed_plugin {
+            if !self.plugin_load_failures.contains(plugin) {
+                self.plugin_load_failures.push(plugin.clone());
+            }
+        }
+        if error || warning || record.exception {
+            let counts = self.plugins.entry(record.source.clone()).or_default();
+            counts.errors += usize::from(error);
+            counts.warnings += usize::from(warning);
+            counts.exceptions += usize::from(record.exception);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RecordFilter {
+    /// Matches the source, or the plugin a load failure names.
+    #[serde(default)]
+    pub plugin: Option<String>,
+    #[serde(default)]
+    pub min_level: Option<LogLevel>,
+    #[serde(default)]
+    pub problems_only: bool,
+}
+
+impl RecordFilter {
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        let plugin_matches = self.plugin.as_deref().is_none_or(|plugin| {
+            record.source.eq_ignore_ascii_case(plugin)
+                || record
+                    .failed_plugin
+                    .as_deref()
+                    .is_some_and(|failed| failed.eq_ignore_ascii_case(plugin))
+        });
+        plugin_matches
+            && self.min_level.is_none_or(|level| record.level >= level)
+            && (!self.problems_only || record.is_problem())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogSessionInfo {
+    pub id: String,
+    pub version: u32,
+    pub mode_label: String,
+    /// Unix seconds.
+    pub started_at: u64,
+    #[serde(default)]
+    pub finished_at: Option<u64>,
+    #[serde(default)]
+    pub summary: LogSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RecordsPayload<'a> {
+    session_id: &'a str,
+    version: u32,
+    records: &'a [LogRecord],
+    summary: &'a LogSummary,
+}
+
+/// `BepInEx/LogOutput.log` of a version folder.
+pub fn log_path(game_dir: &Path) -> PathBuf {
+    game_dir.join("BepInEx").join("LogOutput.log")
+}
+
+pub fn sessions_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("logs")
+        .join("bepinex"))
+}
+
+fn session_dir(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || id.contains(['/', '\\', '.']) {
+        return Err(format!("invalid log session id: {id}"));
+    }
+    Ok(sessions_dir(app)?.join(id))
+}
+
+/// Saved copy of the BepInEx log of session `id`.
+pub fn session_log_path(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    Ok(session_dir(app, id)?.join(LOG_COPY_FILE))
+}
+
+fn write_info(dir: &Path, info: &LogSessionInfo) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(info).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(SESSION_FILE), json).map_err(|e| e.to_string())
+}
+
+/// Saved sessions, newest first.
+pub fn list_sessions(app: &tauri::AppHandle) -> Result<Vec<LogSessionInfo>, String> {
+    let Ok(entries) = std::fs::read_dir(sessions_dir(app)?) else {
+        return Ok(vec![]);
+    };
+    let mut sessions: Vec<LogSessionInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let text = std::fs::read_to_string(entry.path().join(SESSION_FILE)).ok()?;
+            serde_json::from_str(&text).ok()
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    Ok(sessions)
+}
+
+pub fn get_session(app: &tauri::AppHandle, id: &str) -> Result<LogSessionInfo, String> {
+    let text = std::fs::read_to_string(session_dir(app, id)?.join(SESSION_FILE))
+        .map_err(|e| format!("log session {id} not found: {e}"))?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn read_records(app: &tauri::AppHandle, id: &str) -> Result<Vec<LogRecord>, String> {
+    let bytes = std::fs::read(session_log_path(app, id)?).map_err(|e| e.to_string())?;
+    Ok(parse(&bytes))
+}
+
+fn prune_sessions(app: &tauri::AppHandle) {
+    let Ok(sessions) = list_sessions(app) else {
+        return;
+    };
+    for old in sessions.iter().skip(MAX_SESSIONS.saturating_sub(1)) {
+        if let Ok(dir) = session_dir(app, &old.id) {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Stops the tail of a session when dropped with the game that owns it.
+#[derive(Debug)]
+pub struct TailHandle {
+    pub session_id: String,
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<Option<LogSessionInfo>>>,
+}
+
+impl TailHandle {
+    /// Stop following the log and wait for the session copy to be complete.
+    pub fn finish(mut self) -> Option<LogSessionInfo> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread.take()?.join().ok().flatten()
+    }
+}
+
+impl Drop for TailHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Follow the BepInEx log in `game_dir` for a game launched just now.
+pub fn start_tail(
+    app: tauri::AppHandle,
+    game_dir: &Path,
+    info: LogSessionInfo,
+) -> Result<TailHandle, String> {
+    prune_sessions(&app);
+    let dir = session_dir(&app, &info.id)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    write_info(&dir, &info)?;
+    let copy = std::fs::File::create(dir.join(LOG_COPY_FILE)).map_err(|e| e.to_string())?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let session_id = info.id.clone();
+    let source = log_path(game_dir);
+    let thread = std::thread::spawn({
+        let stop = stop.clone();
+        move || {
+            tail(&app, &source, &dir, copy, info, &stop)
+                .map_err(|e| log::warn!("BepInEx log tail of {} stopped: {e}", source.display()))
+                .ok()
+        }
+    });
+    Ok(TailHandle {
+        session_id,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+fn tail(
+    app: &tauri::AppHandle,
+    source: &Path,
+    dir: &Path,
+    mut copy: std::fs::File,
+    mut info: LogSessionInfo,
+    stop: &AtomicBool,
+) -> Result<LogSessionInfo, String> {
+    // The log left by the previous run is still there until BepInEx starts over,
+    // so only a file written after the launch belongs to this session.
+    let launched = SystemTime::now() - Duration::from_secs(1);
+    let mut started = false;
+    let mut offset = 0u64;
+    let mut parser = LogParser::default();
+    loop {
+        let stopping = stop.load(Ordering::Relaxed);
+        let mut records = vec![];
+        if let Ok(meta) = std::fs::metadata(source) {
+            started = started || meta.modified().is_ok_and(|modified| modified >= launched);
+            if started && meta.len() < offset {
+                // Truncated: the game was restarted by hand and BepInEx started a new log.
+                offset = 0;
+                records.extend(parser.finish());
+            }
+            if started && meta.len() > offset {
+                let mut file = std::fs::File::open(source).map_err(|e| e.to_string())?;
+                file.seek(SeekFrom::Start(offset))
+                    .map_err(|e| e.to_string())?;
+                let mut bytes = vec![];
+                file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+                offset += bytes.len() as u64;
+                copy.write_all(&bytes).map_err(|e| e.to_string())?;
+                records.extend(parser.feed(&bytes));
+            }
+        }
+        if stopping {
+            records.extend(parser.finish());
+        }
+        if !records.is_empty() {
+            for record in &records {
+                info.summary.add(record);
+            }
+            let _ = app.emit(
+                RECORDS_EVENT,
+                RecordsPayload {
+                    session_id: &info.id,
+                    version: info.version,
+                    records: &records,
+                    summary: &info.summary,
+                },
+            );
+        }
+        if stopping {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    info.finished_at = Some(crate::now_unix_secs());
+    write_info(dir, &info)?;
+    let _ = app.emit(FINISHED_EVENT, &info);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "[Message:   BepInEx] BepInEx 5.4.21.0 - Lethal Company\r\n\
+[Info   :   BepInEx] Loading [HQoL 1.2.3]\n\
+[Error  :   BepInEx] Could not load [LethalLib 0.16.1] because it has missing dependencies: evaisa.lethalthings\n\
+[Warning:  HQoL] Config value out of range\n\
+[Error  : Unity Log] NullReferenceException: Object reference not set to an instance of an object\n\
+Stack trace:\n\
+HQoL.Patches.Update () (at <abc>:0)\n\
+[Info   :  HQoL] ready";
+
+    #[test]
+    fn records_keep_continuation_lines_and_flag_problems() {
+        let records = parse(LOG.as_bytes());
+        assert_eq!(records.len(), 6);
+        assert_eq!(records[0].level, LogLevel::Message);
+        assert_eq!(records[0].message, "BepInEx 5.4.21.0 - Lethal Company");
+        assert_eq!(records[1].source, "BepInEx");
+        assert!(!records[1].is_problem());
+        assert_eq!(records[2].failed_plugin.as_deref(), Some("LethalLib"));
+        let exception = &records[4];
+        assert_eq!(
+            (exception.line, exception.source.as_str()),
+            (5, "Unity Log")
+        );
+        assert!(exception.exception);
+        assert!(exception
+            .message
+            .ends_with("HQoL.Patches.Update () (at <abc>:0)"));
+        assert_eq!(records[5].line, 8);
+
+        // The same log fed in arbitrary chunks parses the same way.
+        let mut parser = LogParser::default();
+        let mut chunked = vec![];
+        for chunk in LOG.as_bytes().chunks(7) {
+            chunked.extend(parser.feed(chunk));
+        }
+        chunked.extend(parser.finish());
+        assert_eq!(chunked, records);
+    }
+
+    #[test]
+    fn summary_and_filter_group_by_plugin() {
+        let records = parse(LOG.as_bytes());
+        let mut summary = LogSummary::default();
+        records.iter().for_each(|record| summary.add(record));
+        assert_eq!(
+            (summary.errors, summary.warnings, summary.exceptions),
+            (2, 1, 1)
+        );
+        assert_eq!(summary.plugin_load_failures, vec!["LethalLib".to_string()]);
+        assert_eq!(summary.plugins["HQoL"].warnings, 1);
+        assert_eq!(summary.plugins["Unity Log"].exceptions, 1);
+
+        let lethal_lib = RecordFilter {
+            plugin: Some("lethallib".to_string()),
+            ..RecordFilter::default()
+        };
+        assert_eq!(records.iter().filter(|r| lethal_lib.matches(r)).count(), 1);
+        let hqol_problems = RecordFilter {
+            plugin: Some("HQoL".to_string()),
+            problems_only: true,
+            ..RecordFilter::default()
+        };
+        assert_eq!(
+            records.iter().filter(|r| hqol_problems.matches(r)).count(),
+            0
+        );
+        let warnings = RecordFilter {
+            min_level: Some(LogLevel::Warning),
+            ..RecordFilter::default()
+        };
+        assert_eq!(records.iter().filter(|r| warnings.matches(r)).count(), 3);
+    }
+}
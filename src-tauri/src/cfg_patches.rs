@@ -0,0 +1,1346 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::bepinex_cfg::{self, Entry, FileData, Section};
+use crate::mod_config::ModEntry;
+
+/// Declarative cfg edit, built in (see [`builtin`]) or shipped in the remote
+/// manifest under `cfg_patches`.
+///
+/// Example:
+/// {
+///   "id": "weather-registry-first-day",
+///   "file": "mrov.WeatherRegistry.cfg",
+///   "section": "|WeatherSelection",
+///   "entry": "First Day Clear Weather",
+///   "value": "true",
+///   "tags": ["Wesley"],
+///   "low_cap": 69
+/// }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CfgPatch {
+    pub id: String,
+    /// Path relative to `BepInEx/config`.
+    pub file: String,
+    pub section: String,
+    pub entry: String,
+    /// Desired value in cfg text form. Missing => the patch only creates the file from `baseline`.
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Full file contents written when the cfg does not exist yet.
+    #[serde(default)]
+    pub baseline: Option<String>,
+
+    /// Inclusive game version range.
+    #[serde(default)]
+    pub low_cap: Option<u32>,
+    #[serde(default)]
+    pub high_cap: Option<u32>,
+    /// Run modes (`hq`, `wesley`, `brutal_practice`, ...). Empty => every run mode.
+    #[serde(default)]
+    pub run_modes: Vec<String>,
+    /// Preset tags (`Wesley`, `SMHQ`, ...). Empty => no tag requirement.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Run is skipped when any of these tags is active.
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+    /// Run modes the patch is skipped for.
+    #[serde(default)]
+    pub exclude_run_modes: Vec<String>,
+    #[serde(default)]
+    pub practice: Option<bool>,
+    /// Only applied when this mod (`Dev-Name`) is installed.
+    #[serde(default)]
+    pub requires_mod: Option<String>,
+    /// Keep the value when the run mode changes instead of reverting it.
+    #[serde(default)]
+    pub persistent: bool,
+    /// Revert once the game exits instead of on the next run.
+    #[serde(default)]
+    pub until_exit: bool,
+}
+
+/// The run a set of patches is evaluated against.
+#[derive(Debug, Clone)]
+pub struct CfgPatchContext {
+    pub version: u32,
+    pub run_mode: String,
+    pub tags: Vec<String>,
+    pub practice: bool,
+    /// `Dev-Name` of the installed mods named by `requires_mod`.
+    pub installed_mods: Vec<String>,
+    /// `false` when preparing a preset: patches that only last for the run are skipped.
+    pub launching: bool,
+}
+
+impl CfgPatch {
+    pub fn matches(&self, ctx: &CfgPatchContext) -> bool {
+        let has_tag = |tag: &String| ctx.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+        let is_run_mode = |m: &String| m.trim().eq_ignore_ascii_case(ctx.run_mode.trim());
+
+        ModEntry::matches_caps(ctx.version, self.low_cap, self.high_cap)
+            && (self.run_modes.is_empty() || self.run_modes.iter().any(is_run_mode))
+            && !self.exclude_run_modes.iter().any(is_run_mode)
+            && (self.tags.is_empty() || self.tags.iter().any(has_tag))
+            && !self.exclude_tags.iter().any(has_tag)
+            && self.practice.is_none_or(|p| p == ctx.practice)
+            && self.requires_mod.as_ref().is_none_or(|m| {
+                ctx.installed_mods
+                    .iter()
+                    .any(|installed| installed.eq_ignore_ascii_case(m))
+            })
+            && (ctx.launching || !self.until_exit)
+    }
+
+    fn same_entry(&self, other: &CfgPatch) -> bool {
+        self.file.eq_ignore_ascii_case(&other.file)
+            && self.section == other.section
+            && self.entry == other.entry
+    }
+}
+
+/// A patch that was applied for a run and has to be reverted once it no longer matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedCfgPatch {
+    pub id: String,
+    pub version: u32,
+    pub run_mode: String,
+    pub file: String,
+    pub section: String,
+    pub entry: String,
+    /// Value written by the patch, used to detect later manual edits.
+    pub applied: String,
+    /// Value before the patch; `None` if the entry did not exist.
+    pub previous: Option<String>,
+    #[serde(default)]
+    pub until_exit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct CfgPatchState {
+    #[serde(default)]
+    applied: Vec<AppliedCfgPatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct CfgPatchReport {
+    pub applied: Vec<String>,
+    pub created: Vec<String>,
+    pub reverted: Vec<String>,
+}
+
+fn state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("config")
+        .join("cfg_patch_state.json"))
+}
+
+fn read_state(app: &tauri::AppHandle) -> Result<CfgPatchState, String> {
+    let path = state_path(app)?;
+    if !path.exists() {
+        return Ok(CfgPatchState::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    match serde_json::from_str::<CfgPatchState>(&text) {
+        Ok(state) => Ok(state),
+        Err(e) => {
+            log::warn!("Failed to parse cfg patch state, starting fresh: {e}");
+            Ok(CfgPatchState::default())
+        }
+    }
+}
+
+fn write_state(app: &tauri::AppHandle, state: &CfgPatchState) -> Result<(), String> {
+    let path = state_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+fn safe_rel_path(rel: &str) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for c in Path::new(rel).components() {
+        match c {
+            Component::CurDir => continue,
+            Component::Normal(s) => out.push(s),
+            _ => return None,
+        }
+    }
+    if out.as_os_str().is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+fn find_entry<'a>(file: &'a FileData, section: &str, entry: &str) -> Option<&'a Entry> {
+    file.sections
+        .iter()
+        .find(|s| s.name == section)?
+        .entries
+        .iter()
+        .find(|e| e.name == entry)
+}
+
+/// Set `section.entry` to `raw`, creating the section/entry when missing.
+///
+/// Returns the previous value in text form (`None` if the entry was added) and
+/// whether anything changed.
+pub fn set_entry_text(
+    file: &mut FileData,
+    section: &str,
+    entry: &str,
+    raw: &str,
+) -> Result<(Option<String>, bool), String> {
+    let section_idx = match file.sections.iter().position(|s| s.name == section) {
+        Some(idx) => idx,
+        None => {
+            file.sections.push(Section {
+                name: section.to_string(),
+                entries: vec![],
+            });
+            file.sections.len() - 1
+        }
+    };
+    let entries = &mut file.sections[section_idx].entries;
+
+    match entries.iter_mut().find(|e| e.name == entry) {
+        Some(e) => {
+            let previous = bepinex_cfg::value_to_string(&e.value);
+            let value = bepinex_cfg::value_from_text(Some(&e.value), raw)?;
+            let changed = bepinex_cfg::value_to_string(&value) != previous;
+            e.value = value;
+            Ok((Some(previous), changed))
+        }
+        None => {
+            entries.push(Entry {
+                name: entry.to_string(),
+                description: None,
+                default: None,
+                value: bepinex_cfg::value_from_text(None, raw)?,
+            });
+            Ok((None, true))
+        }
+    }
+}
+
+/// Undo a recorded patch. Entries the user changed after the patch are left alone.
+pub fn revert_entry_text(file: &mut FileData, record: &AppliedCfgPatch) -> Result<bool, String> {
+    let Some(current) = find_entry(file, &record.section, &record.entry) else {
+        return Ok(false);
+    };
+    if bepinex_cfg::value_to_string(&current.value).trim() != record.applied.trim() {
+        return Ok(false);
+    }
+
+    match &record.previous {
+        Some(previous) => {
+            let (_, changed) = set_entry_text(file, &record.section, &record.entry, previous)?;
+            Ok(changed)
+        }
+        None => {
+            let Some(section) = file.sections.iter_mut().find(|s| s.name == record.section) else {
+                return Ok(false);
+            };
+            section.entries.retain(|e| e.name != record.entry);
+            if section.entries.is_empty() {
+                file.sections.retain(|s| s.name != record.section);
+            }
+            Ok(true)
+        }
+    }
+}
+
+fn read_cfg(path: &Path) -> Result<FileData, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    bepinex_cfg::parse(text)
+}
+
+fn write_cfg(path: &Path, file: &FileData) -> Result<(), String> {
+    let text = bepinex_cfg::write(file)?;
+    std::fs::write(path, text).map_err(|e| e.to_string())
+}
+
+fn revert_record(app: &tauri::AppHandle, record: &AppliedCfgPatch) -> Result<bool, String> {
+    let Some(rel) = safe_rel_path(&record.file) else {
+        return Ok(false);
+    };
+    let path = crate::version_config_dir(app, record.version)?.join(rel);
+    if !path.is_file() {
+        return Ok(false);
+    }
+    let mut file = read_cfg(&path)?;
+    let changed = revert_entry_text(&mut file, record)?;
+    if changed {
+        write_cfg(&path, &file)?;
+    }
+    Ok(changed)
+}
+
+#[derive(Debug, Default)]
+struct PatchOutcome {
+    created: bool,
+    changed: bool,
+    /// Value in the file after the patch; `None` when the patch has no value.
+    applied: Option<String>,
+    previous: Option<String>,
+}
+
+fn apply_patch(cfg_dir: &Path, patch: &CfgPatch) -> Result<PatchOutcome, String> {
+    let rel = safe_rel_path(&patch.file).ok_or_else(|| format!("unsafe path: {}", patch.file))?;
+    let path = cfg_dir.join(rel);
+    let mut outcome = PatchOutcome::default();
+
+    if !path.exists() {
+        let Some(baseline) = patch.baseline.as_deref() else {
+            return Ok(outcome);
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, baseline).map_err(|e| e.to_string())?;
+        outcome.created = true;
+    }
+
+    let Some(value) = patch.value.as_deref() else {
+        return Ok(outcome);
+    };
+    let mut file = read_cfg(&path).map_err(|e| format!("failed to parse {}: {e}", patch.file))?;
+    let (previous, changed) = set_entry_text(&mut file, &patch.section, &patch.entry, value)?;
+    if changed {
+        write_cfg(&path, &file)?;
+    }
+    outcome.changed = changed;
+    outcome.previous = previous;
+    outcome.applied = Some(
+        find_entry(&file, &patch.section, &patch.entry)
+            .map(|e| bepinex_cfg::value_to_string(&e.value))
+            .unwrap_or_else(|| value.to_string()),
+    );
+    Ok(outcome)
+}
+
+/// Apply one patch outside of a run (installs, migrations). Its conditions are
+/// not checked and nothing is recorded. Returns whether the file changed.
+pub fn apply_in_dir(cfg_dir: &Path, patch: &CfgPatch) -> Result<bool, String> {
+    let outcome = apply_patch(cfg_dir, patch)?;
+    Ok(outcome.created || outcome.changed)
+}
+
+/// The patches matching `ctx`. A later patch on the same entry wins, so
+/// manifest patches override the built-in ones.
+fn active_patches<'a>(ctx: &CfgPatchContext, patches: &'a [CfgPatch]) -> Vec<&'a CfgPatch> {
+    let matching: Vec<&CfgPatch> = patches.iter().filter(|p| p.matches(ctx)).collect();
+    matching
+        .iter()
+        .enumerate()
+        .filter(|(idx, patch)| {
+            patch.value.is_none()
+                || !matching[idx + 1..]
+                    .iter()
+                    .any(|later| later.value.is_some() && later.same_entry(patch))
+        })
+        .map(|(_, patch)| *patch)
+        .collect()
+}
+
+/// Apply every patch that matches `ctx` and revert recorded patches of this
+/// version that no longer match.
+pub fn apply_for_run(
+    app: &tauri::AppHandle,
+    ctx: &CfgPatchContext,
+    patches: &[CfgPatch],
+) -> Result<CfgPatchReport, String> {
+    let mut state = read_state(app)?;
+    let mut report = CfgPatchReport::default();
+
+    let active = active_patches(ctx, patches);
+    let still_active = |record: &AppliedCfgPatch| {
+        record.version != ctx.version
+            || active
+                .iter()
+                .any(|p| p.id == record.id && !p.persistent && p.value.is_some())
+    };
+
+    let mut kept: Vec<AppliedCfgPatch> = vec![];
+    for record in std::mem::take(&mut state.applied) {
+        if still_active(&record) {
+            kept.push(record);
+            continue;
+        }
+        match revert_record(app, &record) {
+            Ok(true) => report.reverted.push(record.id.clone()),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to revert cfg patch {}: {e}", record.id),
+        }
+    }
+    state.applied = kept;
+
+    let cfg_dir = crate::version_config_dir(app, ctx.version)?;
+    for patch in active {
+        let outcome = match apply_patch(&cfg_dir, patch) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                log::warn!("Skipping cfg patch {}: {e}", patch.id);
+                continue;
+            }
+        };
+        if outcome.created {
+            report.created.push(patch.id.clone());
+        }
+        if outcome.changed {
+            report.applied.push(patch.id.clone());
+        }
+
+        let Some(applied) = outcome.applied else {
+            continue;
+        };
+        if patch.persistent || !outcome.changed {
+            continue;
+        }
+        let already_recorded = state
+            .applied
+            .iter()
+            .any(|r| r.id == patch.id && r.version == ctx.version);
+        if !already_recorded {
+            state.applied.push(AppliedCfgPatch {
+                id: patch.id.clone(),
+                version: ctx.version,
+                run_mode: ctx.run_mode.clone(),
+                file: patch.file.clone(),
+                section: patch.section.clone(),
+                entry: patch.entry.clone(),
+                applied,
+                previous: outcome.previous,
+                until_exit: patch.until_exit,
+            });
+        }
+    }
+
+    write_state(app, &state)?;
+
+    if !report.applied.is_empty() || !report.created.is_empty() || !report.reverted.is_empty() {
+        log::info!(
+            "cfg patches for v{} ({}): applied={:?} created={:?} reverted={:?}",
+            ctx.version,
+            ctx.run_mode,
+            report.applied,
+            report.created,
+            report.reverted
+        );
+    }
+    Ok(report)
+}
+
+fn revert_where(
+    app: &tauri::AppHandle,
+    revert: impl Fn(&AppliedCfgPatch) -> bool,
+) -> Result<Vec<String>, String> {
+    let mut state = read_state(app)?;
+    let mut reverted = vec![];
+    let mut kept = vec![];
+    for record in std::mem::take(&mut state.applied) {
+        if !revert(&record) {
+            kept.push(record);
+            continue;
+        }
+        match revert_record(app, &record) {
+            Ok(true) => reverted.push(record.id),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to revert cfg patch {}: {e}", record.id),
+        }
+    }
+    state.applied = kept;
+    write_state(app, &state)?;
+    Ok(reverted)
+}
+
+/// Revert every recorded patch of `version` (e.g. before a Vanilla run).
+pub fn revert_all(app: &tauri::AppHandle, version: u32) -> Result<Vec<String>, String> {
+    revert_where(app, |record| record.version == version)
+}
+
+/// Revert the run-only patches of every version not in `running`.
+pub fn revert_until_exit(app: &tauri::AppHandle, running: &[u32]) -> Result<Vec<String>, String> {
+    revert_where(app, |record| {
+        record.until_exit && !running.contains(&record.version)
+    })
+}
+
+pub fn applied_patches(app: &tauri::AppHandle) -> Result<Vec<AppliedCfgPatch>, String> {
+    Ok(read_state(app)?.applied)
+}
+
+const HQOL_DONT_STORE_CFG_FILES: [&str; 2] = ["OreoM.HQoL.72.cfg", "OreoM.HQoL.73.cfg"];
+
+const WESLEY_HQOL_DONT_STORE_ITEMS: [&str; 18] = [
+    "Royal apparatus",
+    "Bloody apparatus",
+    "Cosmic apparatus",
+    "Atlantica videotape",
+    "Acidir videotape",
+    "Asteroid-13 videotape",
+    "Junic videotape",
+    "Hyx videotape",
+    "Floppy disk",
+    "Infernis videotape",
+    "Etern videotape",
+    "Empra videotape",
+    "Filitrios videotape",
+    "Motra videotape",
+    "Hyve videotape",
+    "Utril videotape",
+    "Gratar videotape",
+    "Gloom videotape",
+];
+
+const WESLEY_MOON_SCRIPTS_BASELINE: &str = "## Settings file was created by plugin WesleyMoonScripts v1.1.6\n## Plugin GUID: JacobG5.WesleyMoonScripts\n\n[Core]\n\n## Locks moons that have progression integration set up to enable playing the campaign.\n# Setting type: Boolean\n# Default value: true\nLockMoons = true\n\n";
+
+const REVERB_TRIGGER_FIX_BASELINE: &str = "## Settings file was created by plugin ReverbTriggerFix v0.3.0\n## Plugin GUID: JacobG5.ReverbTriggerFix\n\n[Core]\n\n## Disables all reverb trigger modifications.\n## Requires a lobby restart to apply.\n## Game restart *not* required.\n# Setting type: Boolean\n# Default value: false\ndisableMod = false\n\n[Debug]\n\n## Logs more info to the console when enabled.\n## \n## *THIS WILL SPAM YOUR CONSOLE DEPENDING ON YOUR OTHER SETTINGS*\n# Setting type: Boolean\n# Default value: false\nextendedLogging = false\n\n[Experimental]\n\n## I'm not sure why reverb triggers run their calculations every frame when as far as I can tell they only need to run their changes when something enters their collider.\n## I'm leaving this as an experimental toggle because it seems to be very buggy atm.\n## \n## Feel free to try it if you wish. If you're experiencing problems then turn it back off.\n# Setting type: Boolean\n# Default value: false\nTriggerOnEnter = true\n";
+
+fn builtin_patch(id: &str, file: &str, section: &str, entry: &str, value: &str) -> CfgPatch {
+    CfgPatch {
+        id: id.to_string(),
+        file: file.to_string(),
+        section: section.to_string(),
+        entry: entry.to_string(),
+        value: Some(value.to_string()),
+        baseline: None,
+        low_cap: None,
+        high_cap: None,
+        run_modes: vec![],
+        tags: vec![],
+        exclude_tags: vec![],
+        exclude_run_modes: vec![],
+        practice: None,
+        requires_mod: None,
+        persistent: true,
+        until_exit: false,
+    }
+}
+
+/// `TriggerOnEnter` for ReverbTriggerFix; also applied whenever the mod is installed.
+pub fn reverb_trigger_fix() -> CfgPatch {
+    CfgPatch {
+        baseline: Some(REVERB_TRIGGER_FIX_BASELINE.to_string()),
+        // Plain practice runs never touched the ReverbTriggerFix config.
+        exclude_run_modes: vec!["practice".to_string()],
+        requires_mod: Some("JacobG5-ReverbTriggerFix".to_string()),
+        ..builtin_patch(
+            "reverb-trigger-on-enter",
+            "JacobG5.ReverbTriggerFix.cfg",
+            "Experimental",
+            "TriggerOnEnter",
+            "true",
+        )
+    }
+}
+
+/// Applied once by the disablemod v6 migration, not per run.
+pub fn unity_explorer_event_system() -> CfgPatch {
+    builtin_patch(
+        "unity-explorer-event-system",
+        "com.sinai.unityexplorer.cfg",
+        "UnityExplorer",
+        "Disable EventSystem override",
+        "true",
+    )
+}
+
+/// The launcher's own per-run cfg edits, applied before the manifest's.
+pub fn builtin() -> Vec<CfgPatch> {
+    let wesley = |patch: CfgPatch| CfgPatch {
+        tags: vec!["Wesley".to_string()],
+        low_cap: Some(69),
+        ..patch
+    };
+    let weather = |id: &str, entry: &str, value: &str| {
+        wesley(CfgPatch {
+            baseline: Some(WEATHER_REGISTRY_BASELINE.to_string()),
+            ..builtin_patch(
+                id,
+                "mrov.WeatherRegistry.cfg",
+                "|WeatherSelection",
+                entry,
+                value,
+            )
+        })
+    };
+    let lock_moons = |id: &str, value: &str| {
+        wesley(CfgPatch {
+            baseline: Some(WESLEY_MOON_SCRIPTS_BASELINE.to_string()),
+            ..builtin_patch(
+                id,
+                "JacobG5.WesleyMoonScripts.cfg",
+                "Core",
+                "LockMoons",
+                value,
+            )
+        })
+    };
+
+    let mut patches = vec![
+        weather(
+            "weather-registry-first-day-clear",
+            "First Day Clear Weather",
+            "true",
+        ),
+        weather(
+            "weather-registry-hybrid-selection",
+            "Weather Selection Algorithm",
+            "Hybrid",
+        ),
+        // Moons stay unlocked for practice and SMHQ; the lock below wins otherwise.
+        lock_moons("wesley-moons-unlocked", "false"),
+        CfgPatch {
+            exclude_tags: vec!["SMHQ".to_string()],
+            practice: Some(false),
+            ..lock_moons("wesley-moons-locked", "true")
+        },
+        reverb_trigger_fix(),
+    ];
+    patches.extend(HQOL_DONT_STORE_CFG_FILES.map(wesley_hqol_dont_store));
+    patches
+}
+
+/// Wesley's dont-store list in one of the HQoL configs, for the run only.
+pub fn wesley_hqol_dont_store(file: &str) -> CfgPatch {
+    CfgPatch {
+        tags: vec!["Wesley".to_string()],
+        exclude_tags: vec!["SMHQ".to_string()],
+        practice: Some(false),
+        persistent: false,
+        until_exit: true,
+        ..builtin_patch(
+            &format!("wesley-hqol-dont-store-{}", file.trim_end_matches(".cfg")),
+            file,
+            "General",
+            "Dont store list",
+            &WESLEY_HQOL_DONT_STORE_ITEMS.join(", "),
+        )
+    }
+}
+
+/// The built-in patches followed by the manifest's, which replace built-ins with the same id.
+pub fn with_builtin(manifest: Vec<CfgPatch>) -> Vec<CfgPatch> {
+    let mut patches: Vec<CfgPatch> = builtin()
+        .into_iter()
+        .filter(|patch| manifest.iter().all(|m| m.id != patch.id))
+        .collect();
+    patches.extend(manifest);
+    patches
+}
+
+const WEATHER_REGISTRY_BASELINE: &str = r#"## Settings file was created by plugin WeatherRegistry v0.7.5
+## Plugin GUID: mrov.WeatherRegistry
+
+[|General]
+
+## Enable colored weathers on map screen
+# Setting type: Boolean
+# Default value: true
+Colored Weathers = true
+
+## Display planet videos on map screen
+# Setting type: Boolean
+# Default value: true
+Planet Videos = true
+
+## Show weather multipliers on map screen
+# Setting type: Boolean
+# Default value: false
+Show Weather Multipliers = false
+
+## Use Registry's scrap multipliers. Disable if you prefer to use other mod's multiplier settings.
+# Setting type: Boolean
+# Default value: true
+Scrap multipliers = true
+
+[|Logging]
+
+## Select which logs to show.
+# Setting type: LoggingType
+# Default value: Basic
+# Acceptable values: Basic, Debug, Developer
+Display Log Levels = Basic
+
+[|WeatherSelection]
+
+## Select the algorithm to use during weather selection.
+# Setting type: WeatherAlgorithm
+# Default value: Registry
+# Acceptable values: Registry, Vanilla, Hybrid
+Weather Selection Algorithm = Hybrid
+
+## If enabled, the first day will always have clear weather, on all planets, regardless of the selected algorithm.
+# Setting type: Boolean
+# Default value: false
+First Day Clear Weather = true
+
+[Modded Weather: Earthquakes]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 40
+# Acceptable value range: From 0 to 10000
+Default weight = 40
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1.1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1.1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;Galetry;Calist;Berunah
+Level filter = Company;Galetry;Calist;Berunah
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50
+Level weights = MoonName@50
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Earthquakes, next day should have weights:
+# Setting type: String
+# Default value: WeatherName@50
+WeatherToWeather weights = WeatherName@50
+
+[Modded Weather: Forsaken]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 30
+# Acceptable value range: From 0 to 10000
+Default weight = 30
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1.2
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1.2
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1.2
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1.2
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;Galetry;Berunah;Calist;Repress;Cosmocos
+Level filter = Company;Galetry;Berunah;Calist;Repress;Cosmocos
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@20
+Level weights = MoonName@20
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Forsaken, next day should have weights:
+# Setting type: String
+# Default value: WeatherName@20
+WeatherToWeather weights = WeatherName@20
+
+[Modded Weather: Hallowed]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 8
+# Acceptable value range: From 0 to 10000
+Default weight = 8
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;Galetry;Calist;Berunah;Asteroid-13;Thalasso;Roart;Repress;Cosmocos
+Level filter = Company;Galetry;Calist;Berunah;Asteroid-13;Thalasso;Roart;Repress;Cosmocos
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@8
+Level weights = MoonName@8
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Hallowed, next day should have weights:
+# Setting type: String
+# Default value: WeatherName@8
+WeatherToWeather weights = WeatherName@8
+
+[Modded Weather: Hurricane]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 80
+# Acceptable value range: From 0 to 10000
+Default weight = 80
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;Galetry;Calist;Berunah;Asteroid-13,Roart;Repress;Cosmocos
+Level filter = Company;Galetry;Calist;Berunah;Asteroid-13,Roart;Repress;Cosmocos
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: WeatherName@20
+Level weights = WeatherName@20
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Hurricane, next day should have weights:
+# Setting type: String
+# Default value: WeatherName@20
+WeatherToWeather weights = WeatherName@20
+
+[Vanilla Weather: DustClouds]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Dust Clouds, next day should have weights:
+# Setting type: String
+# Default value: WeatherName@50
+WeatherToWeather weights = WeatherName@50
+
+[Vanilla Weather: Eclipsed]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Eclipsed, next day should have weights:
+# Setting type: String
+# Default value: None@300; Rainy@40; Stormy@16; Flooded@20; Foggy@60; Eclipsed@10;
+WeatherToWeather weights = None@300; Rainy@40; Stormy@16; Flooded@20; Foggy@60; Eclipsed@10;
+
+[Vanilla Weather: Flooded]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Flooded, next day should have weights:
+# Setting type: String
+# Default value: None@160; Rainy@60; Stormy@50; Flooded@10; Foggy@60; Eclipsed@40;
+WeatherToWeather weights = None@160; Rainy@60; Stormy@50; Flooded@10; Foggy@60; Eclipsed@40;
+
+[Vanilla Weather: Foggy]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Foggy, next day should have weights:
+# Setting type: String
+# Default value: None@200; Rainy@60; Stormy@50; Flooded@10; Foggy@30; Eclipsed@20;
+WeatherToWeather weights = None@200; Rainy@60; Stormy@50; Flooded@10; Foggy@30; Eclipsed@20;
+
+[Vanilla Weather: None]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was None, next day should have weights:
+# Setting type: String
+# Default value: None@160; Rainy@100; Stormy@70; Flooded@20; Foggy@40; Eclipsed@10;
+WeatherToWeather weights = None@160; Rainy@100; Stormy@70; Flooded@20; Foggy@40; Eclipsed@10;
+
+[Vanilla Weather: Rainy]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Rainy, next day should have weights:
+# Setting type: String
+# Default value: None@100; Rainy@60; Stormy@40; Flooded@30; Foggy@50; Eclipsed@20;
+WeatherToWeather weights = None@100; Rainy@60; Stormy@40; Flooded@30; Foggy@50; Eclipsed@20;
+
+[Vanilla Weather: Stormy]
+
+## The default weight of this weather
+# Setting type: Int32
+# Default value: 100
+# Acceptable value range: From 0 to 10000
+Default weight = 100
+
+## Multiplier for the amount of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap amount multiplier = 1
+
+## Multiplier for the value of scrap spawned
+# Setting type: Single
+# Default value: 1
+# Acceptable value range: From 0 to 100
+Scrap value multiplier = 1
+
+## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
+# Setting type: Boolean
+# Default value: false
+Filtering option = false
+
+## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
+# Setting type: String
+# Default value: Company;
+Level filter = Company;
+
+## Semicolon-separated list of level weights
+# Setting type: String
+# Default value: MoonName@50;
+Level weights = MoonName@50;
+
+## Semicolon-separated list of weather-to-weather weights - if previous day was Stormy, next day should have weights:
+# Setting type: String
+# Default value: None@160; Rainy@110; Stormy@10; Flooded@120; Foggy@20; Eclipsed@80;
+WeatherToWeather weights = None@160; Rainy@110; Stormy@10; Flooded@120; Foggy@20; Eclipsed@80;
+
+
+
+
+[|WeatherSelection]
+First Day Clear Weather = true
+Weather Selection Algorithm = Hybrid
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEATHER_CFG: &str = "## Settings file was created by plugin WeatherRegistry v0.7.5\n## Plugin GUID: mrov.WeatherRegistry\n\n[|WeatherSelection]\n\n## Select the algorithm to use during weather selection.\n# Setting type: WeatherAlgorithm\n# Default value: Registry\n# Acceptable values: Registry, Vanilla, Hybrid\nWeather Selection Algorithm = Registry\n\n# Setting type: Boolean\n# Default value: false\nFirst Day Clear Weather = false\n";
+
+    fn patch(json: serde_json::Value) -> CfgPatch {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn ctx(version: u32, run_mode: &str, tags: &[&str], practice: bool) -> CfgPatchContext {
+        CfgPatchContext {
+            version,
+            run_mode: run_mode.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            practice,
+            installed_mods: vec!["JacobG5-ReverbTriggerFix".to_string()],
+            launching: true,
+        }
+    }
+
+    fn active_ids(ctx: &CfgPatchContext) -> Vec<String> {
+        let patches = with_builtin(vec![]);
+        active_patches(ctx, &patches)
+            .into_iter()
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn conditions_cover_version_tags_and_practice() {
+        let p = patch(serde_json::json!({
+            "id": "lock-moons",
+            "file": "JacobG5.WesleyMoonScripts.cfg",
+            "section": "Core",
+            "entry": "LockMoons",
+            "value": "true",
+            "tags": ["Wesley"],
+            "exclude_tags": ["SMHQ"],
+            "practice": false,
+            "low_cap": 69
+        }));
+
+        assert!(p.matches(&ctx(73, "wesley", &["Wesley"], false)));
+        assert!(!p.matches(&ctx(64, "wesley", &["Wesley"], false)));
+        assert!(!p.matches(&ctx(73, "wesley_smhq", &["Wesley", "SMHQ"], false)));
+        assert!(!p.matches(&ctx(73, "wesley_practice", &["Wesley"], true)));
+        assert!(!p.matches(&ctx(73, "hq", &[], false)));
+    }
+
+    #[test]
+    fn run_modes_are_matched_case_insensitively() {
+        let p = patch(serde_json::json!({
+            "id": "x",
+            "file": "a.cfg",
+            "section": "S",
+            "entry": "E",
+            "run_modes": ["Brutal_Practice"]
+        }));
+        assert!(p.matches(&ctx(73, "brutal_practice", &["Brutal"], true)));
+        assert!(!p.matches(&ctx(73, "brutal", &["Brutal"], false)));
+    }
+
+    #[test]
+    fn builtin_patches_follow_the_launch_rules() {
+        let wesley = active_ids(&ctx(73, "wesley", &["Wesley"], false));
+        assert!(wesley.contains(&"wesley-moons-locked".to_string()));
+        assert!(!wesley.contains(&"wesley-moons-unlocked".to_string()));
+        assert!(wesley.contains(&"wesley-hqol-dont-store-OreoM.HQoL.73".to_string()));
+        assert!(wesley.contains(&"reverb-trigger-on-enter".to_string()));
+
+        let smhq = active_ids(&ctx(73, "wesley_smhq", &["Wesley", "SMHQ"], false));
+        assert!(smhq.contains(&"wesley-moons-unlocked".to_string()));
+        assert!(!smhq
+            .iter()
+            .any(|id| id.starts_with("wesley-hqol-dont-store")));
+
+        let practice = active_ids(&ctx(73, "practice", &[], true));
+        assert!(practice.is_empty());
+        let wesley_practice = active_ids(&ctx(73, "wesley_practice", &["Wesley"], true));
+        assert!(wesley_practice.contains(&"reverb-trigger-on-enter".to_string()));
+
+        // Preparing a preset leaves the run-only dont-store list alone.
+        let mut prepare = ctx(73, "wesley", &["Wesley"], false);
+        prepare.launching = false;
+        prepare.installed_mods.clear();
+        let prepare = active_ids(&prepare);
+        assert!(!prepare
+            .iter()
+            .any(|id| id.starts_with("wesley-hqol-dont-store")));
+        assert!(!prepare.contains(&"reverb-trigger-on-enter".to_string()));
+    }
+
+    #[test]
+    fn manifest_patches_override_builtins_on_the_same_entry() {
+        let manifest = patch(serde_json::json!({
+            "id": "algo-registry",
+            "file": "mrov.weatherregistry.cfg",
+            "section": "|WeatherSelection",
+            "entry": "Weather Selection Algorithm",
+            "value": "Registry"
+        }));
+        let patches = with_builtin(vec![manifest]);
+        let active: Vec<&str> = active_patches(&ctx(73, "wesley", &["Wesley"], false), &patches)
+            .into_iter()
+            .map(|p| p.id.as_str())
+            .collect();
+        assert!(active.contains(&"algo-registry"));
+        assert!(!active.contains(&"weather-registry-hybrid-selection"));
+        assert!(active.contains(&"weather-registry-first-day-clear"));
+    }
+
+    #[test]
+    fn set_and_revert_restore_the_previous_value() {
+        let mut file = bepinex_cfg::parse(WEATHER_CFG).unwrap();
+        let (previous, changed) = set_entry_text(
+            &mut file,
+            "|WeatherSelection",
+            "Weather Selection Algorithm",
+            "Hybrid",
+        )
+        .unwrap();
+        assert!(changed);
+        assert_eq!(previous.as_deref(), Some("Registry"));
+
+        let record = AppliedCfgPatch {
+            id: "algo".to_string(),
+            version: 73,
+            run_mode: "wesley".to_string(),
+            file: "mrov.WeatherRegistry.cfg".to_string(),
+            section: "|WeatherSelection".to_string(),
+            entry: "Weather Selection Algorithm".to_string(),
+            applied: "Hybrid".to_string(),
+            previous,
+            until_exit: false,
+        };
+        assert!(revert_entry_text(&mut file, &record).unwrap());
+        let entry = find_entry(&file, "|WeatherSelection", "Weather Selection Algorithm").unwrap();
+        assert_eq!(bepinex_cfg::value_to_string(&entry.value), "Registry");
+    }
+
+    #[test]
+    fn enum_values_outside_the_options_are_rejected() {
+        let mut file = bepinex_cfg::parse(WEATHER_CFG).unwrap();
+        assert!(set_entry_text(
+            &mut file,
+            "|WeatherSelection",
+            "Weather Selection Algorithm",
+            "Random"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn revert_keeps_manual_edits_and_removes_added_entries() {
+        let mut file = bepinex_cfg::parse(WEATHER_CFG).unwrap();
+        let (previous, _) = set_entry_text(
+            &mut file,
+            "|WeatherSelection",
+            "First Day Clear Weather",
+            "true",
+        )
+        .unwrap();
+        let record = AppliedCfgPatch {
+            id: "first-day".to_string(),
+            version: 73,
+            run_mode: "wesley".to_string(),
+            file: "mrov.WeatherRegistry.cfg".to_string(),
+            section: "|WeatherSelection".to_string(),
+            entry: "First Day Clear Weather".to_string(),
+            applied: "true".to_string(),
+            previous,
+            until_exit: false,
+        };
+
+        // The user flipped it back by hand; reverting must not touch it.
+        set_entry_text(
+            &mut file,
+            "|WeatherSelection",
+            "First Day Clear Weather",
+            "false",
+        )
+        .unwrap();
+        assert!(!revert_entry_text(&mut file, &record).unwrap());
+
+        let (previous, changed) = set_entry_text(&mut file, "Extra", "Added", "5").unwrap();
+        assert!(changed);
+        assert!(previous.is_none());
+        let added = AppliedCfgPatch {
+            section: "Extra".to_string(),
+            entry: "Added".to_string(),
+            applied: "5".to_string(),
+            previous: None,
+            ..record
+        };
+        assert!(revert_entry_text(&mut file, &added).unwrap());
+        assert!(file.sections.iter().all(|s| s.name != "Extra"));
+    }
+
+    #[test]
+    fn unsafe_patch_paths_are_rejected() {
+        assert!(safe_rel_path("../evil.cfg").is_none());
+        assert!(safe_rel_path("/etc/passwd").is_none());
+        assert_eq!(
+            safe_rel_path("./sub/a.cfg"),
+            Some(PathBuf::from("sub").join("a.cfg"))
+        );
+    }
+}
@@ -0,0 +1,729 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::bepinex_log::{self, LogLevel, LogRecord};
+
+// Every game the launcher started is classified when it leaves `GameState.active`:
+// stopped from the launcher, crashed (abnormal exit status, or a crash signature in
+// Unity's Player.log or a fatal BepInEx record), or a normal exit. A crash gets a
+// diagnostic bundle in `crash_reports/`:
+//
+//   report.json          ExitReport
+//   launch_plan.json     the plan the game was started from
+//   mods.json            installed plugins with version and enabled state
+//   disablemod.json      the launcher's disabled-mod list
+//   LogOutput.log        BepInEx log of the run
+//   Player.log           Unity log of the run
+//   hq-launcher.log      end of the launcher log
+//   config/...           BepInEx cfg files of the version
+//
+// Text files are redacted: the app data folder, the home folder and the account
+// name are replaced, and so is the user folder name in any `/home/<name>` or
+// `C:\Users\<name>` path, which covers paths seen from inside a Wine prefix.
+// Saved Steam account names and SteamIDs go too, as do names from login lines of
+// the launcher log.
+
+pub const EXIT_EVENT: &str = "game://exited";
+
+const BUNDLES_DIR: &str = "crash_reports";
+const REPORT_FILE: &str = "report.json";
+const MAX_BUNDLES: usize = 20;
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+const MAX_LAUNCHER_LOG_BYTES: u64 = 1024 * 1024;
+const MAX_CFG_BYTES: u64 = 512 * 1024;
+
+/// Lines Unity writes to Player.log when the player itself goes down.
+const PLAYER_LOG_CRASH_MARKERS: [&str; 7] = [
+    "Crash!!!",
+    "Native Crash Reporting",
+    "A crash has been intercepted by the crash handler",
+    "Received signal SIG",
+    "Caught fatal signal",
+    "Fatal error in GC",
+    "Could not allocate memory",
+];
+
+/// User folders that say nothing about who is logged in.
+const GENERIC_USER_FOLDERS: [&str; 4] = ["public", "default", "shared", "steamuser"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitKind {
+    Normal,
+    Crash,
+    KilledByLauncher,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitOutcome {
+    Code(i32),
+    Signal(i32),
+    Unknown,
+}
+
+impl ExitOutcome {
+    pub fn from_status(status: Option<ExitStatus>) -> Self {
+        let Some(status) = status else {
+            return ExitOutcome::Unknown;
+        };
+        if let Some(code) = status.code() {
+            return ExitOutcome::Code(code);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = status.signal() {
+                return ExitOutcome::Signal(signal);
+            }
+        }
+        ExitOutcome::Unknown
+    }
+
+    fn code(self) -> Option<i32> {
+        match self {
+            ExitOutcome::Code(code) => Some(code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitReport {
+    pub game_id: u64,
+    pub version: u32,
+    pub mode_label: String,
+    pub run_mode: String,
+    /// Unix seconds.
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub kind: ExitKind,
+    pub exit_code: Option<i32>,
+    /// Why the exit counts as a crash.
+    pub reasons: Vec<String>,
+    #[serde(default)]
+    pub bepinex_log_session: Option<String>,
+    /// Diagnostic bundle, crashes only.
+    #[serde(default)]
+    pub bundle: Option<String>,
+}
+
+fn first_line(text: &str) -> &str {
+    let line = text.lines().next().unwrap_or_default().trim();
+    match line.char_indices().nth(200) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
+fn player_log_crash_lines(player_log: &str) -> Vec<String> {
+    let mut lines = vec![];
+    for marker in PLAYER_LOG_CRASH_MARKERS {
+        if let Some(line) = player_log.lines().find(|line| line.contains(marker)) {
+            lines.push(format!("Player.log: {}", first_line(line)));
+        }
+    }
+    lines
+}
+
+pub fn classify(
+    outcome: ExitOutcome,
+    stopped_by_launcher: bool,
+    bepinex: &[LogRecord],
+    player_log: &str,
+) -> (ExitKind, Vec<String>) {
+    if stopped_by_launcher {
+        return (ExitKind::KilledByLauncher, vec![]);
+    }
+    let mut reasons = vec![];
+    match outcome {
+        ExitOutcome::Code(0) | ExitOutcome::Unknown => {}
+        ExitOutcome::Code(code) => reasons.push(format!("exit code {code} ({code:#010X})")),
+        ExitOutcome::Signal(signal) => reasons.push(format!("terminated by signal {signal}")),
+    }
+    reasons.extend(
+        bepinex
+            .iter()
+            .filter(|record| record.level == LogLevel::Fatal)
+            .map(|record| {
+                format!(
+                    "BepInEx fatal from {}: {}",
+                    record.source,
+                    first_line(&record.message)
+                )
+            }),
+    );
+    reasons.extend(player_log_crash_lines(player_log));
+    let kind = if reasons.is_empty() {
+        ExitKind::Normal
+    } else {
+        ExitKind::Crash
+    };
+    (kind, reasons)
+}
+
+/// Replaces user-identifying values in text that goes into a bundle.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    replacements: Vec<(String, String)>,
+}
+
+impl Redactor {
+    pub fn for_host(app: &tauri::AppHandle) -> Self {
+        let mut redactor = Redactor::default();
+        if let Ok(dir) = app.path().app_data_dir() {
+            redactor.add(&dir.to_string_lossy(), "<app_data>");
+        }
+        if let Some(home) = dirs::home_dir() {
+            redactor.add(&home.to_string_lossy(), "~");
+        }
+        for var in ["USERNAME", "USER"] {
+            if let Ok(name) = std::env::var(var) {
+                redactor.add(&name, "<user>");
+            }
+        }
+        for status in crate::downloader::account_statuses(app).unwrap_or_default() {
+            if let Some(name) = status.login.username {
+                redactor.add(&name, "<steam_user>");
+            }
+            if let Some(steam_id) = status.login.steam_id {
+                redactor.add(&steam_id, "<steam_id>");
+            }
+        }
+        redactor
+    }
+
+    /// Too short a value would eat unrelated text, so those are ignored.
+    pub fn add(&mut self, value: &str, replacement: &str) {
+        let value = value.trim_end_matches(['/', '\\']);
+        if value.chars().count() < 3 {
+            return;
+        }
+        // Paths also show up with the other separator, e.g. in Wine's `Z:\home\...`.
+        for variant in [
+            value.to_string(),
+            value.replace('\\', "/"),
+            value.replace('/', "\\"),
+        ] {
+            if !self.replacements.iter().any(|(known, _)| *known == variant) {
+                self.replacements.push((variant, replacement.to_string()));
+            }
+        }
+        self.replacements
+            .sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut redactor = self.clone();
+        for name in login_account_names(text) {
+            redactor.add(&name, "<steam_user>");
+        }
+        let mut text = text.to_string();
+        for (value, replacement) in &redactor.replacements {
+            text = replace_whole(&text, value, replacement);
+        }
+        redact_user_folders(&redact_steam_ids(&text))
+    }
+}
+
+/// Account names from `Attempting login for user: <name>` and DepotDownloader's
+/// `Logging '<name>' into Steam3...` lines.
+fn login_account_names(text: &str) -> Vec<String> {
+    let mut names = vec![];
+    for line in text.lines() {
+        if let Some((_, rest)) = line.split_once("Attempting login for user: ") {
+            names.extend(rest.split_whitespace().next().map(str::to_string));
+        }
+        if let Some((_, rest)) = line.split_once("Logging '") {
+            if let Some((name, tail)) = rest.split_once('\'') {
+                if tail.trim_start().starts_with("into Steam") {
+                    names.push(name.to_string());
+                }
+            }
+        }
+    }
+    names
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-')
+}
+
+/// Replace `value` only where it is not part of a longer name: the user "asta"
+/// leaves the mod `asta-EVlog` and the GUID `asta.EVlog` alone.
+fn replace_whole(text: &str, value: &str, replacement: &str) -> String {
+    let continues = |c: Option<char>, next: Option<char>| {
+        c.is_some_and(is_name_char) || (c == Some('.') && next.is_some_and(is_name_char))
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    for (at, _) in text.match_indices(value) {
+        let end = at + value.len();
+        let mut before = text[..at].chars().rev();
+        let mut after = text[end..].chars();
+        if continues(before.next(), before.next()) || continues(after.next(), after.next()) {
+            continue;
+        }
+        out.push_str(&text[copied..at]);
+        out.push_str(replacement);
+        copied = end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// 64-bit SteamIDs: 17 digits starting with `7656119`.
+fn redact_steam_ids(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let bytes = text.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if !bytes[idx].is_ascii_digit() {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+            idx += 1;
+        }
+        if idx - start == 17 && text[start..idx].starts_with("7656119") {
+            out.push_str(&text[copied..start]);
+            out.push_str("<steam_id>");
+            copied = idx;
+        }
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+fn redact_user_folders(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut search = 0;
+    while let Some(found) = ["/home/", "\\home\\", "/users/", "\\users\\"]
+        .iter()
+        .filter_map(|marker| {
+            lower[search..]
+                .find(marker)
+                .map(|at| search + at + marker.len())
+        })
+        .min()
+    {
+        let end = text[found..]
+            .find(['/', '\\', '"', '\'', '\n', '\r', ',', ';', ':', ')', ']'])
+            .map_or(text.len(), |len| found + len);
+        let folder = &text[found..end];
+        if !folder.is_empty()
+            && !folder.starts_with('<')
+            && !GENERIC_USER_FOLDERS.contains(&folder.to_ascii_lowercase().as_str())
+        {
+            out.push_str(&text[copied..found]);
+            out.push_str("<user>");
+            copied = end;
+        }
+        search = end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Everything the exit of one game needs, captured at launch.
+pub struct ExitWatch {
+    pub app: tauri::AppHandle,
+    pub game_id: u64,
+    pub version: u32,
+    pub mode_label: String,
+    pub run_mode: String,
+    pub started_at: u64,
+    pub game_dir: PathBuf,
+    pub wine_prefix: Option<PathBuf>,
+    pub launch_plan: String,
+    pub history: Option<crate::launch_history::PendingLaunch>,
+}
+
+impl ExitWatch {
+    /// Classify the exit in the background, after the BepInEx log copy is final.
+    pub fn spawn(
+        mut self,
+        status: Option<ExitStatus>,
+        stopped_by_launcher: bool,
+        bepinex_tail: Option<bepinex_log::TailHandle>,
+    ) {
+        std::thread::spawn(move || {
+            let session = bepinex_tail.and_then(bepinex_log::TailHandle::finish);
+            let report = self.report(status, stopped_by_launcher, session);
+            match report.kind {
+                ExitKind::Crash => log::warn!(
+                    "v{} ({}) crashed: {}",
+                    report.version,
+                    report.mode_label,
+                    report.reasons.join("; ")
+                ),
+                kind => log::info!("v{} exited: {kind:?}", report.version),
+            }
+            let peaks = self
+                .app
+                .state::<crate::resource_monitor::ResourceMonitorState>()
+                .take_peaks(self.game_id);
+            if let Some(history) = self.history.take() {
+                history.finish(&self.app, &report, peaks);
+            }
+            let _ = self.app.emit(EXIT_EVENT, &report);
+        });
+    }
+
+    fn report(
+        &self,
+        status: Option<ExitStatus>,
+        stopped_by_launcher: bool,
+        session: Option<bepinex_log::LogSessionInfo>,
+    ) -> ExitReport {
+        let outcome = ExitOutcome::from_status(status);
+        let bepinex_copy = session
+            .as_ref()
+            .and_then(|session| bepinex_log::session_log_path(&self.app, &session.id).ok());
+        let bepinex = bepinex_copy
+            .as_deref()
+            .and_then(|path| std::fs::read(path).ok())
+            .map(|bytes| bepinex_log::parse(&bytes))
+            .unwrap_or_default();
+        let player_log_path = self.player_log();
+        let player_log = player_log_path
+            .as_deref()
+            .and_then(|path| read_tail(path, MAX_LOG_BYTES))
+            .unwrap_or_default();
+        let (kind, reasons) = classify(outcome, stopped_by_launcher, &bepinex, &player_log);
+        let mut report = ExitReport {
+            game_id: self.game_id,
+            version: self.version,
+            mode_label: self.mode_label.clone(),
+            run_mode: self.run_mode.clone(),
+            started_at: self.started_at,
+            finished_at: crate::now_unix_secs(),
+            kind,
+            exit_code: outcome.code(),
+            reasons,
+            bepinex_log_session: session.map(|session| session.id),
+            bundle: None,
+        };
+        if kind == ExitKind::Crash {
+            match self.write_bundle(&report, bepinex_copy.as_deref(), &player_log) {
+                Ok(path) => report.bundle = Some(path.to_string_lossy().to_string()),
+                Err(e) => log::warn!("Failed to write crash bundle for v{}: {e}", self.version),
+            }
+        }
+        report
+    }
+
+    /// Player.log of this run: written after the launch, in the Windows profile or
+    /// in the Wine prefix the game ran in.
+    fn player_log(&self) -> Option<PathBuf> {
+        let unity_dir = |users_dir: &Path| {
+            users_dir
+                .join("AppData")
+                .join("LocalLow")
+                .join("ZeekerssRBLX")
+                .join("Lethal Company")
+                .join("Player.log")
+        };
+        let mut candidates: Vec<PathBuf> = dirs::home_dir()
+            .map(|home| unity_dir(&home))
+            .into_iter()
+            .collect();
+        if let Some(prefix) = &self.wine_prefix {
+            // Wine uses the prefix itself, Proton a `pfx` folder inside it.
+            for drive_c in [prefix.join("drive_c"), prefix.join("pfx").join("drive_c")] {
+                let Ok(users) = std::fs::read_dir(drive_c.join("users")) else {
+                    continue;
+                };
+                candidates.extend(users.flatten().map(|user| unity_dir(&user.path())));
+            }
+        }
+        let started = std::time::UNIX_EPOCH + std::time::Duration::from_secs(self.started_at);
+        candidates
+            .into_iter()
+            .filter_map(|path| {
+                let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+                (modified >= started).then_some((modified, path))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+    }
+
+    fn write_bundle(
+        &self,
+        report: &ExitReport,
+        bepinex_copy: Option<&Path>,
+        player_log: &str,
+    ) -> Result<PathBuf, String> {
+        let dir = bundles_dir(&self.app)?;
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        prune_bundles(&dir);
+        let dest = dir.join(format!(
+            "v{}-{}-{}.zip",
+            self.version, report.finished_at, self.game_id
+        ));
+        let redactor = Redactor::for_host(&self.app);
+        let mut files: Vec<(String, String)> = vec![
+            (
+                REPORT_FILE.to_string(),
+                serde_json::to_string_pretty(report).map_err(|e| e.to_string())?,
+            ),
+            ("launch_plan.json".to_string(), self.launch_plan.clone()),
+            (
+                "mods.json".to_string(),
+                serde_json::to_string_pretty(&crate::instance_archive::locked_mods(
+                    &self.game_dir.join("BepInEx").join("plugins"),
+                ))
+                .map_err(|e| e.to_string())?,
+            ),
+        ];
+        if !player_log.is_empty() {
+            files.push(("Player.log".to_string(), player_log.to_string()));
+        }
+        let mut add_file = |name: &str, path: Option<PathBuf>, max: u64| {
+            if let Some(text) = path.and_then(|path| read_tail(&path, max)) {
+                files.push((name.to_string(), text));
+            }
+        };
+        add_file(
+            "disablemod.json",
+            crate::disablemod_path(&self.app).ok(),
+            MAX_CFG_BYTES,
+        );
+        add_file(
+            "LogOutput.log",
+            bepinex_copy.map(Path::to_path_buf),
+            MAX_LOG_BYTES,
+        );
+        add_file(
+            "hq-launcher.log",
+            crate::logger::log_file(&self.app).ok(),
+            MAX_LAUNCHER_LOG_BYTES,
+        );
+        let config_dir = self.game_dir.join("BepInEx").join("config");
+        for rel in cfg_files(&config_dir) {
+            let name = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            add_file(
+                &format!("config/{name}"),
+                Some(config_dir.join(rel)),
+                MAX_CFG_BYTES,
+            );
+        }
+
+        let tmp = dest.with_extension("zip.partial");
+        let res = (|| -> Result<(), String> {
+            let mut zip = ZipWriter::new(File::create(&tmp).map_err(|e| e.to_string())?);
+            let options =
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+            for (name, text) in &files {
+                zip.start_file(name.as_str(), options)
+                    .map_err(|e| e.to_string())?;
+                zip.write_all(redactor.redact(text).as_bytes())
+                    .map_err(|e| e.to_string())?;
+            }
+            zip.finish().map_err(|e| e.to_string())?;
+            Ok(())
+        })();
+        if let Err(e) = res {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e);
+        }
+        std::fs::rename(&tmp, &dest).map_err(|e| e.to_string())?;
+        Ok(dest)
+    }
+}
+
+/// The last `max` bytes of a text file, starting at a whole line.
+fn read_tail(path: &Path, max: u64) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let start = len.saturating_sub(max);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut bytes = vec![];
+    file.read_to_end(&mut bytes).ok()?;
+    let text = String::from_utf8_lossy(&bytes).to_string();
+    if start == 0 {
+        return Some(text);
+    }
+    Some(
+        text.split_once('\n')
+            .map_or(text.clone(), |(_, rest)| rest.to_string()),
+    )
+}
+
+fn cfg_files(config_dir: &Path) -> Vec<PathBuf> {
+    let mut out = vec![];
+    let mut stack = vec![PathBuf::new()];
+    while let Some(rel_dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(config_dir.join(&rel_dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let rel = rel_dir.join(entry.file_name());
+            if entry.path().is_dir() {
+                stack.push(rel);
+            } else if rel
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cfg"))
+            {
+                out.push(rel);
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+fn bundles_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join(BUNDLES_DIR))
+}
+
+fn bundle_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+        .map(|path| {
+            let modified = std::fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .unwrap_or(std::time::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+    paths.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    paths.into_iter().map(|(_, path)| path).collect()
+}
+
+fn prune_bundles(dir: &Path) {
+    for old in bundle_paths(dir).iter().skip(MAX_BUNDLES.saturating_sub(1)) {
+        let _ = std::fs::remove_file(old);
+    }
+}
+
+/// Reports of the saved crash bundles, newest first.
+pub fn list_bundles(app: &tauri::AppHandle) -> Result<Vec<ExitReport>, String> {
+    let mut reports = vec![];
+    for path in bundle_paths(&bundles_dir(app)?) {
+        let read = || -> Result<ExitReport, String> {
+            let mut archive = ZipArchive::new(File::open(&path).map_err(|e| e.to_string())?)
+                .map_err(|e| e.to_string())?;
+            let mut text = String::new();
+            archive
+                .by_name(REPORT_FILE)
+                .map_err(|e| e.to_string())?
+                .read_to_string(&mut text)
+                .map_err(|e| e.to_string())?;
+            serde_json::from_str(&text).map_err(|e| e.to_string())
+        };
+        match read() {
+            Ok(mut report) => {
+                report.bundle = Some(path.to_string_lossy().to_string());
+                reports.push(report);
+            }
+            Err(e) => log::warn!("Skipping crash bundle {}: {e}", path.display()),
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: LogLevel, message: &str) -> LogRecord {
+        LogRecord {
+            line: 1,
+            level,
+            source: "BepInEx".to_string(),
+            message: message.to_string(),
+            exception: false,
+            failed_plugin: None,
+        }
+    }
+
+    #[test]
+    fn exits_are_classified_from_status_and_logs() {
+        let load_error = [record(LogLevel::Error, "Could not load [LethalLib 0.16.1]")];
+        assert_eq!(
+            classify(ExitOutcome::Code(0), false, &load_error, "Quitting"),
+            (ExitKind::Normal, vec![])
+        );
+        assert_eq!(
+            classify(ExitOutcome::Code(1), true, &[], "Crash!!!").0,
+            ExitKind::KilledByLauncher
+        );
+
+        let (kind, reasons) = classify(ExitOutcome::Code(-1073741819), false, &[], "");
+        assert_eq!(kind, ExitKind::Crash);
+        assert_eq!(reasons, vec!["exit code -1073741819 (0xC0000005)"]);
+
+        let fatal = [record(LogLevel::Fatal, "Chainloader failed\n  at Foo ()")];
+        let player_log = "Loading...\n  Crash!!!\nSymInit: done\n";
+        let (kind, reasons) = classify(ExitOutcome::Unknown, false, &fatal, player_log);
+        assert_eq!(kind, ExitKind::Crash);
+        assert_eq!(
+            reasons,
+            vec![
+                "BepInEx fatal from BepInEx: Chainloader failed",
+                "Player.log: Crash!!!",
+            ]
+        );
+    }
+
+    #[test]
+    fn redaction_covers_host_and_wine_paths() {
+        let mut redactor = Redactor::default();
+        redactor.add("/home/alice/.local/share/hq-launcher/", "<app_data>");
+        redactor.add("/home/alice", "~");
+        redactor.add("alice", "<user>");
+        redactor.add("al", "<user>");
+        let text = "data /home/alice/.local/share/hq-launcher/versions/v73\n\
+            home /home/alice/Games and Z:\\home\\alice\\Games\n\
+            wine C:\\users\\bob\\AppData and C:\\users\\steamuser\\AppData\n\
+            mac /Users/Carol Smith/Library, alice joined, totally";
+        assert_eq!(
+            redactor.redact(text),
+            "data <app_data>/versions/v73\n\
+            home ~/Games and Z:~\\Games\n\
+            wine C:\\users\\<user>\\AppData and C:\\users\\steamuser\\AppData\n\
+            mac /Users/<user>/Library, <user> joined, totally"
+        );
+    }
+
+    #[test]
+    fn redaction_keeps_longer_names_and_covers_steam_logins() {
+        let mut redactor = Redactor::default();
+        redactor.add("asta", "<user>");
+        let text = "plugins/asta-EVlog/asta.EVlog.dll by asta.\n\
+            Attempting login for user: player_one\n\
+            DepotDownloader: Logging 'player_one' into Steam3...\n\
+            Got SteamID 76561198000000001 for player_one, depot 1966721";
+        assert_eq!(
+            redactor.redact(text),
+            "plugins/asta-EVlog/asta.EVlog.dll by <user>.\n\
+            Attempting login for user: <steam_user>\n\
+            DepotDownloader: Logging '<steam_user>' into Steam3...\n\
+            Got SteamID <steam_id> for <steam_user>, depot 1966721"
+        );
+    }
+}
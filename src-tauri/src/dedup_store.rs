@@ -0,0 +1,506 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+// Every `v{version}` folder is a full copy of the game, and most of the files are
+// byte-identical between versions. Identical files are linked to one copy kept in
+// `versions/.hq_store/<aa>/<sha256>`:
+// - reflinks (copy-on-write clones) where the filesystem supports them, which are
+//   safe to write to in place;
+// - hardlinks otherwise, which must be copied back out (`unshare_file`) before any
+//   in-place write such as patching or extracting BepInEx.
+pub const STORE_DIR_NAME: &str = ".hq_store";
+const INDEX_FILE_NAME: &str = "index.json";
+const TMP_SUFFIX: &str = ".hq_dedup_tmp";
+/// Linking tiny files saves nothing worth the extra inode churn.
+const MIN_DEDUP_SIZE: u64 = 64 * 1024;
+/// Launcher-managed entries at the version root that are never shared.
+pub const SKIPPED_ROOT_ENTRIES: &[&str] = &[
+    "BepInEx",
+    "winhttp.dll",
+    "version.dll",
+    "doorstop_config.ini",
+    ".doorstop_version",
+];
+
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMode {
+    Hardlink,
+    Reflink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFile {
+    pub rel_path: String,
+    pub hash: String,
+    pub size: u64,
+    pub mode: LinkMode,
+}
+
+/// Persisted at `versions/.hq_store/index.json` so it follows the storage folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StoreIndex {
+    #[serde(default)]
+    versions: BTreeMap<u32, Vec<StoredFile>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DedupReport {
+    pub versions: u32,
+    pub shared_files: u64,
+    /// What the shared files would take as independent copies.
+    pub logical_bytes: u64,
+    /// What they actually take in the store.
+    pub store_bytes: u64,
+    pub saved_bytes: u64,
+}
+
+impl StoreIndex {
+    fn report(&self) -> DedupReport {
+        let mut unique: BTreeMap<&str, u64> = BTreeMap::new();
+        let mut report = DedupReport {
+            versions: self.versions.len() as u32,
+            ..DedupReport::default()
+        };
+        for files in self.versions.values() {
+            for file in files {
+                report.shared_files += 1;
+                report.logical_bytes += file.size;
+                unique.insert(&file.hash, file.size);
+            }
+        }
+        report.store_bytes = unique.values().sum();
+        report.saved_bytes = report.logical_bytes.saturating_sub(report.store_bytes);
+        report
+    }
+
+    fn referenced_hashes(&self) -> BTreeSet<String> {
+        self.versions
+            .values()
+            .flatten()
+            .map(|f| f.hash.clone())
+            .collect()
+    }
+}
+
+fn store_dir(versions_dir: &Path) -> PathBuf {
+    versions_dir.join(STORE_DIR_NAME)
+}
+
+fn store_path(store: &Path, hash: &str) -> PathBuf {
+    store.join(&hash[..2]).join(hash)
+}
+
+fn read_index(store: &Path) -> StoreIndex {
+    std::fs::read_to_string(store.join(INDEX_FILE_NAME))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(store: &Path, index: &StoreIndex) -> Result<(), String> {
+    std::fs::create_dir_all(store).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    std::fs::write(store.join(INDEX_FILE_NAME), json).map_err(|e| e.to_string())
+}
+
+fn version_from_root(version_root: &Path) -> Option<(PathBuf, u32)> {
+    let name = version_root.file_name()?.to_str()?;
+    let version = name.strip_prefix('v')?.parse::<u32>().ok()?;
+    Some((version_root.parent()?.to_path_buf(), version))
+}
+
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+/// Regular game files under `root` (relative path, size), skipping launcher-managed
+/// entries, dot-files, symlinks and junctions.
+fn candidate_files(root: &Path) -> Result<Vec<(String, u64)>, String> {
+    let mut out = Vec::new();
+    let mut stack = vec![PathBuf::new()];
+    while let Some(rel_dir) = stack.pop() {
+        let entries = std::fs::read_dir(root.join(&rel_dir)).map_err(|e| e.to_string())?;
+        for entry in entries {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name.ends_with(TMP_SUFFIX) {
+                continue;
+            }
+            if rel_dir.as_os_str().is_empty() && SKIPPED_ROOT_ENTRIES.contains(&name.as_str()) {
+                continue;
+            }
+            let meta = std::fs::symlink_metadata(entry.path()).map_err(|e| e.to_string())?;
+            let rel = rel_dir.join(&name);
+            if meta.file_type().is_symlink() {
+                continue;
+            }
+            if meta.is_dir() {
+                stack.push(rel);
+            } else if meta.is_file() {
+                out.push((rel.to_string_lossy().replace('\\', "/"), meta.len()));
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+#[cfg(unix)]
+pub(crate) fn file_identity(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.dev(), meta.ino(), meta.nlink()))
+}
+
+#[cfg(windows)]
+pub(crate) fn file_identity(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    let file = std::fs::File::open(path).ok()?;
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    let ok = unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) };
+    if ok == 0 {
+        return None;
+    }
+    let index = ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64;
+    Some((
+        info.dwVolumeSerialNumber as u64,
+        index,
+        info.nNumberOfLinks as u64,
+    ))
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (file_identity(a), file_identity(b)) {
+        (Some((dev_a, ino_a, _)), Some((dev_b, ino_b, _))) => dev_a == dev_b && ino_a == ino_b,
+        _ => false,
+    }
+}
+
+fn link_count(path: &Path) -> u64 {
+    file_identity(path).map(|(_, _, n)| n).unwrap_or(1)
+}
+
+/// Copy-on-write clone of `src` at `dst` (which must not exist).
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    // _IOW(0x94, 9, int)
+    const FICLONE: u64 = 0x4004_9409;
+
+    let src_file = std::fs::File::open(src)?;
+    let dst_file = std::fs::File::create(dst)?;
+    let rc = unsafe { libc::ioctl(dst_file.as_raw_fd(), FICLONE as _, src_file.as_raw_fd()) };
+    if rc != 0 {
+        let err = std::io::Error::last_os_error();
+        drop(dst_file);
+        let _ = std::fs::remove_file(dst);
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(src: &Path, dst: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(src.as_os_str().as_bytes())?;
+    let dst = CString::new(dst.as_os_str().as_bytes())?;
+    let rc = unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_src: &Path, _dst: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(TMP_SUFFIX);
+    path.with_file_name(name)
+}
+
+/// Make `dst` share `src`'s data, preferring a reflink. Goes through a temp file so
+/// `dst` is never missing if linking fails halfway.
+fn link_over(src: &Path, dst: &Path) -> Result<LinkMode, String> {
+    let tmp = tmp_path_for(dst);
+    let _ = std::fs::remove_file(&tmp);
+    let mode = if reflink(src, &tmp).is_ok() {
+        LinkMode::Reflink
+    } else {
+        std::fs::hard_link(src, &tmp).map_err(|e| e.to_string())?;
+        LinkMode::Hardlink
+    };
+    if let Err(e) = std::fs::rename(&tmp, dst) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.to_string());
+    }
+    Ok(mode)
+}
+
+/// Put a copy of `file` into the store at `dst`.
+fn add_to_store(file: &Path, dst: &Path) -> Result<LinkMode, String> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if reflink(file, dst).is_ok() {
+        return Ok(LinkMode::Reflink);
+    }
+    std::fs::hard_link(file, dst).map_err(|e| e.to_string())?;
+    Ok(LinkMode::Hardlink)
+}
+
+/// Place `src` at `dst` sharing its data where possible (reflink, then hardlink),
+/// falling back to a plain copy. Returns whether the data is shared.
+pub fn link_or_copy(src: &Path, dst: &Path) -> Result<bool, String> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if dst.exists() {
+        std::fs::remove_file(dst).map_err(|e| e.to_string())?;
+    }
+    if reflink(src, dst).is_ok() || std::fs::hard_link(src, dst).is_ok() {
+        return Ok(true);
+    }
+    std::fs::copy(src, dst).map_err(|e| format!("{}: {e}", src.display()))?;
+    Ok(false)
+}
+
+/// Give `path` its own copy of the data if it is hardlinked elsewhere. Must be called
+/// before writing to a file in place; replacing it via rename is always safe.
+pub fn unshare_file(path: &Path) -> Result<bool, String> {
+    if !path.is_file() || link_count(path) <= 1 {
+        return Ok(false);
+    }
+    let tmp = tmp_path_for(path);
+    std::fs::copy(path, &tmp).map_err(|e| format!("{}: {e}", path.display()))?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("{}: {e}", path.display())
+    })?;
+    Ok(true)
+}
+
+fn remove_unreferenced(store: &Path, index: &StoreIndex) {
+    let referenced = index.referenced_hashes();
+    let Ok(shards) = std::fs::read_dir(store) else {
+        return;
+    };
+    for shard in shards.flatten() {
+        if !shard.path().is_dir() {
+            continue;
+        }
+        let Ok(files) = std::fs::read_dir(shard.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let name = file.file_name().to_string_lossy().to_string();
+            if !referenced.contains(&name) {
+                let _ = std::fs::remove_file(file.path());
+            }
+        }
+        let _ = std::fs::remove_dir(shard.path());
+    }
+}
+
+/// Replace the game files of `v{version}` that already exist in the store with links,
+/// and add the others to it. Re-running is cheap for files that are already linked.
+pub fn dedup_version(versions_dir: &Path, version: u32) -> Result<DedupReport, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let root = versions_dir.join(format!("v{version}"));
+    if !root.is_dir() {
+        return Err(format!("v{version} is not installed."));
+    }
+    let store = store_dir(versions_dir);
+    std::fs::create_dir_all(&store).map_err(|e| e.to_string())?;
+    let mut index = read_index(&store);
+    let linked: HashMap<String, StoredFile> = index
+        .versions
+        .get(&version)
+        .into_iter()
+        .flatten()
+        .filter(|file| file.mode == LinkMode::Hardlink)
+        .map(|file| (file.rel_path.clone(), file.clone()))
+        .collect();
+
+    let mut stored = Vec::new();
+    for (rel, size) in candidate_files(&root)? {
+        if size < MIN_DEDUP_SIZE {
+            continue;
+        }
+        let path = root.join(&rel);
+        // Still the store's file from the last run: nothing to hash.
+        if let Some(file) = linked.get(&rel) {
+            if file.size == size && same_file(&path, &store_path(&store, &file.hash)) {
+                stored.push(file.clone());
+                continue;
+            }
+        }
+        let hash = hash_file(&path)?;
+        let target = store_path(&store, &hash);
+
+        let mode = if target.is_file() {
+            if same_file(&path, &target) {
+                Some(LinkMode::Hardlink)
+            } else if std::fs::metadata(&target).map(|m| m.len()).ok() != Some(size) {
+                None
+            } else {
+                link_over(&target, &path)
+                    .map_err(|e| log::warn!("dedup: failed to link {rel}: {e}"))
+                    .ok()
+            }
+        } else {
+            // No link support (e.g. FAT32/exFAT): keep the file as a plain copy.
+            add_to_store(&path, &target)
+                .map_err(|e| log::warn!("dedup: failed to store {rel}: {e}"))
+                .ok()
+        };
+
+        if let Some(mode) = mode {
+            stored.push(StoredFile {
+                rel_path: rel,
+                hash,
+                size,
+                mode,
+            });
+        }
+    }
+
+    index.versions.insert(version, stored);
+    remove_unreferenced(&store, &index);
+    write_index(&store, &index)?;
+    Ok(index.report())
+}
+
+/// Copy every hardlinked file of the version root back out and drop the version from
+/// the store. Used before tools that rewrite game files in place (patcher, validation).
+pub fn unshare_version_root(version_root: &Path) -> Result<u64, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut unshared = 0u64;
+    for (rel, _) in candidate_files(version_root)? {
+        if unshare_file(&version_root.join(&rel))? {
+            unshared += 1;
+        }
+    }
+
+    if let Some((versions_dir, version)) = version_from_root(version_root) {
+        let store = store_dir(&versions_dir);
+        let mut index = read_index(&store);
+        if index.versions.remove(&version).is_some() {
+            remove_unreferenced(&store, &index);
+            write_index(&store, &index)?;
+        }
+    }
+    Ok(unshared)
+}
+
+/// Drop a deleted version from the store and free files nothing else references.
+pub fn forget_version(versions_dir: &Path, version: u32) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let store = store_dir(versions_dir);
+    let mut index = read_index(&store);
+    if index.versions.remove(&version).is_none() {
+        return Ok(());
+    }
+    remove_unreferenced(&store, &index);
+    write_index(&store, &index)
+}
+
+pub fn report(versions_dir: &Path) -> DedupReport {
+    read_index(&store_dir(versions_dir)).report()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn write(path: &Path, bytes: &[u8]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    fn big(byte: u8) -> Vec<u8> {
+        vec![byte; MIN_DEDUP_SIZE as usize]
+    }
+
+    #[test]
+    fn identical_files_are_shared_and_reported() {
+        let dir = temp_dir("dedup_shared");
+        for v in [72, 73] {
+            let root = dir.join(format!("v{v}"));
+            write(
+                &root.join("Lethal Company_Data/sharedassets0.assets"),
+                &big(1),
+            );
+            write(&root.join("UnityPlayer.dll"), &big(v as u8));
+            write(&root.join("BepInEx/core/BepInEx.dll"), &big(9));
+        }
+
+        dedup_version(&dir, 72).unwrap();
+        let report = dedup_version(&dir, 73).unwrap();
+
+        assert_eq!(report.versions, 2);
+        assert_eq!(report.shared_files, 4);
+        assert_eq!(report.saved_bytes, MIN_DEDUP_SIZE);
+        assert_eq!(
+            std::fs::read(dir.join("v73/Lethal Company_Data/sharedassets0.assets")).unwrap(),
+            big(1)
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unshare_copies_out_before_in_place_writes() {
+        let dir = temp_dir("dedup_unshare");
+        for v in [72, 73] {
+            write(&dir.join(format!("v{v}/UnityPlayer.dll")), &big(1));
+        }
+        dedup_version(&dir, 72).unwrap();
+        dedup_version(&dir, 73).unwrap();
+
+        let root = dir.join("v73");
+        unshare_version_root(&root).unwrap();
+        std::fs::write(root.join("UnityPlayer.dll"), b"patched").unwrap();
+
+        assert_eq!(
+            std::fs::read(dir.join("v72/UnityPlayer.dll")).unwrap(),
+            big(1)
+        );
+        assert_eq!(report(&dir).versions, 1);
+
+        forget_version(&dir, 72).unwrap();
+        assert_eq!(report(&dir).shared_files, 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
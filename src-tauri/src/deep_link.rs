@@ -0,0 +1,435 @@
+use serde::Serialize;
+
+use crate::launch_profiles::LaunchProfile;
+
+// Requests from outside the UI: `hq-launcher://` links the OS hands to the launcher
+// and the same requests written as command-line flags. Both arrive as process
+// arguments, of the first instance or forwarded by the single-instance plugin, and
+// are parsed here; lib.rs routes each request to the code its Tauri command runs.
+//
+//   hq-launcher://launch/73?mode=brutal&event=summer&profile=stream
+//   hq-launcher://prepare-event/73/summer
+//   hq-launcher://config/BepInEx.cfg?version=73
+//   hq-launcher://import-profile/hqp1.eyJpZCI6...
+//
+//   hq-launcher --launch 73 --mode brutal [--event summer] [--profile stream]
+//   hq-launcher --prepare-event summer --version 73
+//   hq-launcher --open-config BepInEx.cfg [--version 73]
+//   hq-launcher --import-profile hqp1.eyJpZCI6...
+//
+// Any web page can open a link, so links that launch or import a profile are held
+// until the user confirms them in the UI; command-line flags come from the local
+// user and run directly.
+
+pub const SCHEME: &str = "hq-launcher";
+pub const HANDLED_EVENT: &str = "deep-link://handled";
+pub const OPEN_CONFIG_EVENT: &str = "deep-link://open-config";
+pub const CONFIRM_EVENT: &str = "deep-link://confirm";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExternalRequest {
+    Launch {
+        version: u32,
+        /// Run mode as in `LaunchMode::run_mode`, e.g. `brutal` or `wesley_practice`.
+        mode: String,
+        event_id: Option<String>,
+        profile: Option<String>,
+    },
+    PrepareEvent {
+        version: u32,
+        event_id: String,
+    },
+    /// Relative to the version's config folder, or the shared one without a version.
+    OpenConfig {
+        version: Option<u32>,
+        path: String,
+    },
+    ImportProfile {
+        code: String,
+    },
+}
+
+impl ExternalRequest {
+    /// Launches and imports can run commands; a link asking for one waits for the user.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(
+            self,
+            ExternalRequest::Launch { .. } | ExternalRequest::ImportProfile { .. }
+        )
+    }
+}
+
+/// A link waiting for the user to confirm it.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingRequest {
+    pub id: u64,
+    pub input: String,
+    pub request: ExternalRequest,
+    /// The profile the launch would use, or the one the import would add, so the
+    /// UI can show its command, environment and options before anything runs.
+    pub profile: Option<LaunchProfile>,
+    /// Imports by link drop the profile's command, environment and options.
+    pub stripped: bool,
+}
+
+/// What became of one link or command line, for the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct HandledRequest {
+    pub input: String,
+    pub request: Option<ExternalRequest>,
+    pub error: Option<String>,
+}
+
+const FLAGS: [&str; 8] = [
+    "--launch",
+    "--mode",
+    "--event",
+    "--profile",
+    "--prepare-event",
+    "--version",
+    "--open-config",
+    "--import-profile",
+];
+
+/// Parse a process's arguments, `argv[0]` included. Every link is one request, the
+/// flags together form another; arguments that are neither are left alone.
+pub fn parse_args(argv: &[String]) -> Vec<(String, Result<ExternalRequest, String>)> {
+    let mut out = vec![];
+    let mut flags: Vec<(&str, String)> = vec![];
+    let mut flag_input = vec![];
+    let mut args = argv.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if is_link(arg) {
+            out.push((arg.clone(), parse_link(arg)));
+            continue;
+        }
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let Some(flag) = FLAGS.into_iter().find(|flag| *flag == name) else {
+            continue;
+        };
+        flag_input.push(arg.clone());
+        let value = match inline {
+            Some(value) => Some(value),
+            None => args
+                .next()
+                .inspect(|value| flag_input.push((*value).clone()))
+                .cloned(),
+        };
+        match value {
+            Some(value) => flags.push((flag, value)),
+            None => {
+                out.push((flag_input.join(" "), Err(format!("{flag} needs a value"))));
+                return out;
+            }
+        }
+    }
+    if !flags.is_empty() {
+        out.push((flag_input.join(" "), from_params(None, &flags)));
+    }
+    out
+}
+
+pub fn is_link(arg: &str) -> bool {
+    arg.get(..SCHEME.len() + 3)
+        .is_some_and(|start| start.eq_ignore_ascii_case(&format!("{SCHEME}://")))
+}
+
+/// Parse one `hq-launcher://` link.
+pub fn parse_link(link: &str) -> Result<ExternalRequest, String> {
+    if !is_link(link) {
+        return Err(format!("not an {SCHEME}:// link"));
+    }
+    let rest = &link[SCHEME.len() + 3..];
+    let rest = rest.split_once('#').map_or(rest, |(rest, _fragment)| rest);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode(segment, false))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut params = vec![];
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let name = percent_decode(name, true)?;
+        let flag = match name.as_str() {
+            "mode" => "--mode",
+            "event" => "--event",
+            "profile" => "--profile",
+            "version" => "--version",
+            "code" => "--import-profile",
+            _ => return Err(format!("unknown link parameter: {name}")),
+        };
+        params.push((flag, percent_decode(value, true)?));
+    }
+    if segments.is_empty() {
+        return Err("the link names no action".to_string());
+    }
+    let action = segments.remove(0).to_ascii_lowercase();
+    let (flag, positional): (&str, Vec<String>) = match action.as_str() {
+        "launch" => ("--launch", segments),
+        "prepare-event" => match segments.len() {
+            2 => {
+                params.push(("--version", segments.remove(0)));
+                ("--prepare-event", segments)
+            }
+            _ => ("--prepare-event", segments),
+        },
+        "config" => ("--open-config", vec![segments.join("/")]),
+        "import-profile" => ("--import-profile", segments),
+        _ => return Err(format!("unknown link action: {action}")),
+    };
+    match positional.as_slice() {
+        [] => {}
+        [value] => params.insert(0, (flag, value.clone())),
+        _ => return Err(format!("too many path segments for {action}")),
+    }
+    from_params(Some(flag), &params)
+}
+
+fn percent_decode(text: &str, plus_is_space: bool) -> Result<String, String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = text
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| format!("bad percent escape in {text}"))?;
+                out.push(hex);
+                i += 3;
+            }
+            b'+' if plus_is_space => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).map_err(|_| format!("{text} is not valid UTF-8"))
+}
+
+fn from_params(action: Option<&str>, params: &[(&str, String)]) -> Result<ExternalRequest, String> {
+    let value = |flag: &str| -> Result<Option<String>, String> {
+        let mut found = params.iter().filter(|(name, _)| *name == flag);
+        let first = found.next().map(|(_, value)| value.trim().to_string());
+        if found.next().is_some() {
+            return Err(format!("{flag} is given more than once"));
+        }
+        Ok(first)
+    };
+    let actions = [
+        "--launch",
+        "--prepare-event",
+        "--open-config",
+        "--import-profile",
+    ];
+    let action = match action {
+        Some(action) => action,
+        None => {
+            let given: Vec<&str> = actions
+                .into_iter()
+                .filter(|flag| params.iter().any(|(name, _)| name == flag))
+                .collect();
+            match given.as_slice() {
+                [action] => *action,
+                [] => return Err(format!("one of {} is required", actions.join(", "))),
+                _ => return Err(format!("only one of {} can be given", given.join(", "))),
+            }
+        }
+    };
+    let version = value("--version")?.map(|v| parse_version(&v)).transpose()?;
+    let event_id = value("--event")?;
+    let request = match action {
+        "--launch" => {
+            let launch = value("--launch")?.ok_or("--launch needs a version")?;
+            if version.is_some() {
+                return Err("--version cannot be combined with --launch".to_string());
+            }
+            let mode = value("--mode")?.unwrap_or_else(|| "hq".to_string());
+            ExternalRequest::Launch {
+                version: parse_version(&launch)?,
+                mode: check_name("mode", &mode)?.to_lowercase(),
+                event_id: event_id.map(|id| check_name("event", &id)).transpose()?,
+                profile: value("--profile")?
+                    .map(|p| check_name("profile", &p))
+                    .transpose()?,
+            }
+        }
+        "--prepare-event" => {
+            let event = value("--prepare-event")?.ok_or("--prepare-event needs an event")?;
+            ExternalRequest::PrepareEvent {
+                version: version.ok_or("--prepare-event needs --version")?,
+                event_id: check_name("event", &event)?,
+            }
+        }
+        "--open-config" => {
+            let path = value("--open-config")?
+                .unwrap_or_default()
+                .replace('\\', "/");
+            let valid = !path.is_empty()
+                && !path.starts_with('/')
+                && !path.contains(':')
+                && path
+                    .split('/')
+                    .all(|part| !part.is_empty() && part != "." && part != "..");
+            if !valid {
+                return Err(format!("invalid config path: {path}"));
+            }
+            ExternalRequest::OpenConfig { version, path }
+        }
+        _ => {
+            let code = value("--import-profile")?.unwrap_or_default();
+            if code.is_empty() {
+                return Err("--import-profile needs a profile code".to_string());
+            }
+            ExternalRequest::ImportProfile { code }
+        }
+    };
+    let used: &[&str] = match &request {
+        ExternalRequest::Launch { .. } => &["--launch", "--mode", "--event", "--profile"],
+        ExternalRequest::PrepareEvent { .. } => &["--prepare-event", "--version"],
+        ExternalRequest::OpenConfig { .. } => &["--open-config", "--version"],
+        ExternalRequest::ImportProfile { .. } => &["--import-profile"],
+    };
+    if let Some((flag, _)) = params.iter().find(|(flag, _)| !used.contains(flag)) {
+        return Err(format!("{flag} does not apply to {action}"));
+    }
+    Ok(request)
+}
+
+fn parse_version(text: &str) -> Result<u32, String> {
+    text.trim()
+        .trim_start_matches(['v', 'V'])
+        .parse::<u32>()
+        .ok()
+        .filter(|version| *version > 0)
+        .ok_or_else(|| format!("invalid game version: {text}"))
+}
+
+/// Ids, run modes and profile names are plain words; anything else is refused
+/// before it reaches a file name or a URL.
+fn check_name(what: &str, text: &str) -> Result<String, String> {
+    let ok = !text.is_empty()
+        && text.len() <= 64
+        && text
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'));
+    if ok {
+        Ok(text.to_string())
+    } else {
+        Err(format!("invalid {what}: {text}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        std::iter::once("hq-launcher")
+            .chain(args.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn parsed(args: &[&str]) -> Vec<Result<ExternalRequest, String>> {
+        parse_args(&argv(args))
+            .into_iter()
+            .map(|(_, request)| request)
+            .collect()
+    }
+
+    #[test]
+    fn links_and_flags_parse_to_the_same_requests() {
+        let launch = ExternalRequest::Launch {
+            version: 73,
+            mode: "brutal".to_string(),
+            event_id: None,
+            profile: Some("stream".to_string()),
+        };
+        assert_eq!(
+            parse_link("hq-launcher://launch/73?mode=Brutal&profile=stream"),
+            Ok(launch.clone())
+        );
+        assert_eq!(
+            parsed(&["--launch", "v73", "--mode=brutal", "--profile", "stream"]),
+            vec![Ok(launch)]
+        );
+
+        let prepare = ExternalRequest::PrepareEvent {
+            version: 73,
+            event_id: "summer".to_string(),
+        };
+        assert_eq!(
+            parse_link("HQ-Launcher://prepare-event/73/summer"),
+            Ok(prepare.clone())
+        );
+        assert_eq!(
+            parsed(&["--prepare-event", "summer", "--version", "73"]),
+            vec![Ok(prepare)]
+        );
+
+        assert_eq!(
+            parse_link("hq-launcher://config/Lethal%20Things/main.cfg?version=72"),
+            Ok(ExternalRequest::OpenConfig {
+                version: Some(72),
+                path: "Lethal Things/main.cfg".to_string(),
+            })
+        );
+        assert_eq!(
+            parsed(&["--open-config", "BepInEx.cfg"]),
+            vec![Ok(ExternalRequest::OpenConfig {
+                version: None,
+                path: "BepInEx.cfg".to_string(),
+            })]
+        );
+        assert_eq!(
+            parse_link("hq-launcher://import-profile/hqp1.eyJpZCI6InMifQ"),
+            Ok(ExternalRequest::ImportProfile {
+                code: "hqp1.eyJpZCI6InMifQ".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected_and_other_args_ignored() {
+        assert!(parsed(&["--minimized", "/tmp/x"]).is_empty());
+        assert_eq!(
+            parsed(&["--minimized", "hq-launcher://launch/73", "--launch"]),
+            vec![
+                Ok(ExternalRequest::Launch {
+                    version: 73,
+                    mode: "hq".to_string(),
+                    event_id: None,
+                    profile: None,
+                }),
+                Err("--launch needs a value".to_string()),
+            ]
+        );
+        for link in [
+            "hq-launcher://launch/0",
+            "hq-launcher://launch/73/74",
+            "hq-launcher://launch/73?mode=brutal;rm",
+            "hq-launcher://launch/73?version=72",
+            "hq-launcher://config/..%2F..%2Fsecrets.txt",
+            "hq-launcher://config/C:%5Cwindows%5Cwin.ini",
+            "hq-launcher://prepare-event/summer",
+            "hq-launcher://uninstall/73",
+            "hq-launcher://launch/73?mode=%zz",
+            "https://launch/73",
+        ] {
+            assert!(parse_link(link).is_err(), "{link}");
+        }
+        assert!(parsed(&["--launch", "73", "--import-profile", "x"])[0].is_err());
+        assert!(parsed(&["--launch", "73", "--mode", "hq", "--mode", "brutal"])[0].is_err());
+    }
+}
@@ -0,0 +1,465 @@
+// Line parser for DepotDownloader's console output.
+//
+// DepotDownloader has no machine-readable output, so lines are classified with the
+// run's phase in mind: once the depot download has started, file paths are printed
+// verbatim and must never be mistaken for login prompts.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepotPhase {
+    Connecting,
+    Authenticating,
+    Downloading,
+    Finished,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthPrompt {
+    TwoFactorCode,
+    EmailCode,
+    MobileConfirmation,
+    Password,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepotLine {
+    /// Overall progress in basis points (0..=10000) plus the file it finished.
+    Progress {
+        basis_points: u64,
+        path: Option<String>,
+    },
+    Preallocating,
+    AuthPrompt(AuthPrompt),
+    LoginFailed(String),
+    RateLimited(String),
+    LicenseMissing(String),
+    DiskFull(String),
+    ManifestNotFound(String),
+    Completed {
+        downloaded_bytes: Option<u64>,
+    },
+    Other,
+}
+
+/// Why a run failed, as far as the output tells.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepotFailure {
+    AuthRequired(AuthPrompt),
+    LoginFailed(String),
+    LicenseMissing(String),
+    DiskFull(String),
+    ManifestNotFound(String),
+}
+
+impl DepotFailure {
+    pub fn message(&self) -> String {
+        match self {
+            DepotFailure::AuthRequired(AuthPrompt::MobileConfirmation) => {
+                "Steam mobile app confirmation required. Please login and try again.".to_string()
+            }
+            DepotFailure::AuthRequired(_) => {
+                "Steam Guard / login required. Please login and try again.".to_string()
+            }
+            DepotFailure::LoginFailed(line) => format!("Steam login failed: {line}"),
+            DepotFailure::LicenseMissing(line) => {
+                format!("This Steam account cannot download Lethal Company: {line}")
+            }
+            DepotFailure::DiskFull(line) => {
+                format!("Not enough disk space to finish the download: {line}")
+            }
+            DepotFailure::ManifestNotFound(line) => {
+                format!("Steam could not find the depot manifest for this game version: {line}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DepotOutputParser {
+    phase: DepotPhase,
+    failure: Option<DepotFailure>,
+    rate_limited: bool,
+}
+
+impl Default for DepotOutputParser {
+    fn default() -> Self {
+        Self {
+            phase: DepotPhase::Connecting,
+            failure: None,
+            rate_limited: false,
+        }
+    }
+}
+
+fn parse_percent(line: &str) -> Option<(u64, Option<String>)> {
+    let s = line.trim_start();
+    let pct_part = s.split_whitespace().next()?;
+    let pct: f64 = pct_part.strip_suffix('%')?.parse().ok()?;
+    if !pct.is_finite() {
+        return None;
+    }
+    let basis_points = (pct.clamp(0.0, 100.0) * 100.0).round() as u64;
+    let path = s
+        .get(pct_part.len()..)
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string);
+    Some((basis_points, path))
+}
+
+fn parse_total_downloaded(lower: &str) -> Option<u64> {
+    // "Total downloaded: 123456 bytes (654321 bytes uncompressed) from 1 depots"
+    lower
+        .strip_prefix("total downloaded:")?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn auth_prompt(lower: &str) -> Option<AuthPrompt> {
+    // Tokens printed by the patched IPC build.
+    if lower.contains("steam_guard_email_code_required") {
+        return Some(AuthPrompt::EmailCode);
+    }
+    if lower.contains("steam_guard_device_code_required")
+        || lower.contains("steam_guard_code_required")
+    {
+        return Some(AuthPrompt::TwoFactorCode);
+    }
+
+    if lower.contains("use the steam mobile app to confirm") {
+        return Some(AuthPrompt::MobileConfirmation);
+    }
+    if lower.contains("steam guard") || lower.contains("auth code") {
+        if lower.contains("email") {
+            return Some(AuthPrompt::EmailCode);
+        }
+        return Some(AuthPrompt::TwoFactorCode);
+    }
+    if lower.contains("two-factor") || lower.contains("2-factor") || lower.contains("2fa") {
+        return Some(AuthPrompt::TwoFactorCode);
+    }
+    if lower.contains("enter account password") || lower.starts_with("password:") {
+        return Some(AuthPrompt::Password);
+    }
+    None
+}
+
+impl DepotOutputParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn phase(&self) -> DepotPhase {
+        self.phase
+    }
+
+    pub fn failure(&self) -> Option<&DepotFailure> {
+        self.failure.as_ref()
+    }
+
+    /// Classify one line (ANSI codes already stripped).
+    pub fn feed(&mut self, line: &str) -> DepotLine {
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+
+        // File lines first: paths may contain any text.
+        if let Some((basis_points, path)) = parse_percent(trimmed) {
+            self.phase = DepotPhase::Downloading;
+            return DepotLine::Progress { basis_points, path };
+        }
+        if trimmed.starts_with("Pre-allocating ") {
+            self.phase = DepotPhase::Downloading;
+            return DepotLine::Preallocating;
+        }
+
+        if lower.contains("not enough space on the disk")
+            || lower.contains("no space left on device")
+            || lower.contains("disk full")
+        {
+            self.failure = Some(DepotFailure::DiskFull(trimmed.to_string()));
+            return DepotLine::DiskFull(trimmed.to_string());
+        }
+        if lower.contains("ratelimitexceeded")
+            || lower.contains("rate limit")
+            || lower.contains("toomanyrequests")
+            || lower.contains("too many requests")
+        {
+            self.rate_limited = true;
+            return DepotLine::RateLimited(trimmed.to_string());
+        }
+        if lower.contains("is not available from this account")
+            || lower.contains("no valid license")
+            || lower.contains("does not own")
+        {
+            self.failure = Some(DepotFailure::LicenseMissing(trimmed.to_string()));
+            return DepotLine::LicenseMissing(trimmed.to_string());
+        }
+        if lower.contains("unable to download manifest")
+            || lower.contains("manifest not found")
+            || (lower.contains("encountered 404") && lower.contains("manifest"))
+            || (lower.contains("manifest") && lower.contains("is not available"))
+        {
+            self.failure = Some(DepotFailure::ManifestNotFound(trimmed.to_string()));
+            return DepotLine::ManifestNotFound(trimmed.to_string());
+        }
+
+        if lower.starts_with("total downloaded:") {
+            self.phase = DepotPhase::Finished;
+            return DepotLine::Completed {
+                downloaded_bytes: parse_total_downloaded(&lower),
+            };
+        }
+        if lower.starts_with("downloading depot") || lower.starts_with("processing depot") {
+            self.phase = DepotPhase::Downloading;
+            return DepotLine::Other;
+        }
+
+        if matches!(
+            self.phase,
+            DepotPhase::Connecting | DepotPhase::Authenticating
+        ) {
+            if let Some(prompt) = auth_prompt(&lower) {
+                self.failure = Some(DepotFailure::AuthRequired(prompt));
+                return DepotLine::AuthPrompt(prompt);
+            }
+            if lower.contains("failed to authenticate")
+                || lower.contains("invalidpassword")
+                || lower.contains("login failed")
+                || lower.contains("unable to login")
+            {
+                self.failure = Some(DepotFailure::LoginFailed(trimmed.to_string()));
+                return DepotLine::LoginFailed(trimmed.to_string());
+            }
+            if lower.starts_with("logging") && lower.contains("into steam") {
+                self.phase = DepotPhase::Authenticating;
+            }
+        }
+
+        DepotLine::Other
+    }
+
+    /// Error for a run that exited unsuccessfully.
+    pub fn exit_error(&self, exit_code: Option<i32>) -> String {
+        if let Some(failure) = self.failure.as_ref() {
+            return failure.message();
+        }
+        match self.phase {
+            // Never got past login: the remembered session is no longer valid.
+            DepotPhase::Connecting | DepotPhase::Authenticating => {
+                DepotFailure::AuthRequired(AuthPrompt::Password).message()
+            }
+            _ if self.rate_limited => {
+                "Steam rate-limited the download. Wait a few minutes and retry.".to_string()
+            }
+            _ => match exit_code {
+                Some(code) => format!("DepotDownloader exited with code {code}. Please retry."),
+                None => "DepotDownloader was terminated. Please retry.".to_string(),
+            },
+        }
+    }
+}
+
+/// A QR login challenge for the Steam mobile app to approve. DepotDownloader only
+/// draws the code with block characters; the URL it encodes is not printed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrChallenge {
+    pub art: String,
+}
+
+/// Collects the challenges a `-qr` login prints. Steam refreshes the challenge every
+/// so often, so one run can produce several.
+#[derive(Debug, Clone, Default)]
+pub struct QrChallengeParser {
+    art: Option<Vec<String>>,
+}
+
+fn is_qr_art(line: &str) -> bool {
+    !line.trim().is_empty() && line.chars().all(|c| matches!(c, '█' | '▀' | '▄' | ' '))
+}
+
+impl QrChallengeParser {
+    /// Feed one line (ANSI codes already stripped); returns a challenge once one is complete.
+    pub fn feed(&mut self, line: &str) -> Option<QrChallenge> {
+        if line.to_lowercase().contains("sign in with this qr code") {
+            self.art = Some(vec![]);
+            return None;
+        }
+        if self.art.is_some() {
+            if is_qr_art(line) {
+                self.art.as_mut()?.push(line.trim_end().to_string());
+                return None;
+            }
+            return self.flush();
+        }
+        None
+    }
+
+    /// The drawing in progress, for when output stops right after it (the process then
+    /// waits for the approval without printing anything).
+    pub fn flush(&mut self) -> Option<QrChallenge> {
+        let art = self.art.take().filter(|rows| !rows.is_empty())?;
+        Some(QrChallenge {
+            art: art.join("\n"),
+        })
+    }
+}
+
+/// Account name from "Logging '<name>' into Steam3...".
+pub fn logon_account_name(line: &str) -> Option<String> {
+    let rest = line.trim().strip_prefix("Logging '")?;
+    let (name, tail) = rest.split_once('\'')?;
+    (!name.is_empty() && tail.trim_start().starts_with("into Steam")).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(fixture: &str) -> (DepotOutputParser, Vec<DepotLine>) {
+        let mut parser = DepotOutputParser::new();
+        let lines = fixture.lines().map(|l| parser.feed(l)).collect();
+        (parser, lines)
+    }
+
+    #[test]
+    fn successful_download_reports_files_and_total() {
+        let (parser, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/download_ok.txt"
+        ));
+        assert_eq!(parser.phase(), DepotPhase::Finished);
+        assert!(parser.failure().is_none());
+
+        let files: Vec<&str> = lines
+            .iter()
+            .filter_map(|l| match l {
+                DepotLine::Progress {
+                    path: Some(path), ..
+                } => Some(path.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(files.len(), 4);
+        assert!(files[0].ends_with("Lethal Company.exe"));
+        assert!(lines.contains(&DepotLine::Progress {
+            basis_points: 10_000,
+            path: Some(
+                "/games/v73/Lethal Company_Data/StreamingAssets/Enter Code Password.txt"
+                    .to_string()
+            ),
+        }));
+        assert!(lines.contains(&DepotLine::Completed {
+            downloaded_bytes: Some(1_048_576_000),
+        }));
+    }
+
+    #[test]
+    fn file_paths_never_look_like_auth_prompts() {
+        let (parser, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/download_ok.txt"
+        ));
+        assert!(!lines.iter().any(|l| matches!(l, DepotLine::AuthPrompt(_))));
+        assert!(parser.failure().is_none());
+    }
+
+    #[test]
+    fn steam_guard_email_prompt() {
+        let (parser, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/steam_guard_email.txt"
+        ));
+        assert!(lines.contains(&DepotLine::AuthPrompt(AuthPrompt::EmailCode)));
+        assert_eq!(
+            parser.failure(),
+            Some(&DepotFailure::AuthRequired(AuthPrompt::EmailCode))
+        );
+    }
+
+    #[test]
+    fn mobile_confirmation_prompt() {
+        let (_, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/mobile_confirmation.txt"
+        ));
+        assert!(lines.contains(&DepotLine::AuthPrompt(AuthPrompt::MobileConfirmation)));
+    }
+
+    #[test]
+    fn missing_license_is_reported() {
+        let (parser, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/license_missing.txt"
+        ));
+        assert!(lines
+            .iter()
+            .any(|l| matches!(l, DepotLine::LicenseMissing(_))));
+        assert!(parser
+            .exit_error(Some(1))
+            .contains("is not available from this account"));
+    }
+
+    #[test]
+    fn disk_full_is_reported() {
+        let (parser, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/disk_full.txt"
+        ));
+        assert!(lines.iter().any(|l| matches!(l, DepotLine::DiskFull(_))));
+        assert!(matches!(parser.failure(), Some(DepotFailure::DiskFull(_))));
+    }
+
+    #[test]
+    fn manifest_not_found_is_reported() {
+        let (parser, _) = run(include_str!(
+            "../tests/fixtures/depot_downloader/manifest_not_found.txt"
+        ));
+        assert!(matches!(
+            parser.failure(),
+            Some(DepotFailure::ManifestNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rate_limit_is_not_fatal_by_itself() {
+        let (parser, lines) = run(include_str!(
+            "../tests/fixtures/depot_downloader/rate_limited.txt"
+        ));
+        assert!(lines.iter().any(|l| matches!(l, DepotLine::RateLimited(_))));
+        assert!(parser.failure().is_none());
+        assert!(parser.exit_error(Some(1)).contains("rate-limited"));
+    }
+
+    #[test]
+    fn exit_during_login_means_login_required() {
+        let mut parser = DepotOutputParser::new();
+        parser.feed("Connecting to Steam3... Done!");
+        parser.feed("Logging 'player' into Steam3...");
+        assert_eq!(parser.phase(), DepotPhase::Authenticating);
+        assert!(parser.exit_error(Some(1)).contains("login required"));
+    }
+
+    #[test]
+    fn unknown_failure_reports_exit_code() {
+        let mut parser = DepotOutputParser::new();
+        parser.feed("Downloading depot 1966721 - Lethal Company Content");
+        assert_eq!(
+            parser.exit_error(Some(3)),
+            "DepotDownloader exited with code 3. Please retry."
+        );
+    }
+
+    #[test]
+    fn qr_login_challenges_and_account_name() {
+        let mut qr = QrChallengeParser::default();
+        let mut challenges = vec![];
+        let mut account = None;
+        for line in include_str!("../tests/fixtures/depot_downloader/qr_login.txt").lines() {
+            challenges.extend(qr.feed(line));
+            account = account.or_else(|| logon_account_name(line));
+        }
+        challenges.extend(qr.flush());
+        assert_eq!(challenges.len(), 2);
+        assert_eq!(challenges[1].art.lines().count(), 3);
+        assert_eq!(account.as_deref(), Some("hq_alt"));
+        assert_eq!(
+            logon_account_name("Logging anonymously into Steam3..."),
+            None
+        );
+    }
+}
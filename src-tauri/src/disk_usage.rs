@@ -0,0 +1,401 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiskCategory {
+    GameFiles,
+    Mods,
+    /// `v{N}/.hq-launcher/tmp` left behind by mod installs.
+    ModTemp,
+    PartialDownloads,
+    DedupStore,
+    ThunderstoreCache,
+    LauncherTemp,
+    ProtonGe,
+    DepotDownloader,
+    AppPatcher,
+    Logs,
+}
+
+impl DiskCategory {
+    /// Categories that are rebuilt or re-downloaded on demand and can be removed
+    /// without losing anything the user set up.
+    pub fn cleanable(self) -> bool {
+        !matches!(
+            self,
+            DiskCategory::GameFiles | DiskCategory::Mods | DiskCategory::DedupStore
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsageNode {
+    pub label: String,
+    pub path: Option<String>,
+    pub category: Option<DiskCategory>,
+    pub bytes: u64,
+    pub cleanable: bool,
+    pub children: Vec<DiskUsageNode>,
+}
+
+impl DiskUsageNode {
+    fn group(label: &str, children: Vec<DiskUsageNode>) -> Self {
+        Self {
+            label: label.to_string(),
+            path: None,
+            category: None,
+            bytes: children.iter().map(|c| c.bytes).sum(),
+            cleanable: false,
+            children,
+        }
+    }
+
+    fn leaf(label: &str, path: &Path, category: DiskCategory, bytes: u64) -> Self {
+        Self {
+            label: label.to_string(),
+            path: Some(path.to_string_lossy().to_string()),
+            category: Some(category),
+            bytes,
+            cleanable: category.cleanable(),
+            children: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub freed_bytes: u64,
+    pub removed: Vec<String>,
+    /// Left in place because the runtime settings still select them.
+    pub kept: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Where the launcher keeps its data. The names mirror the `app_data_dir()` joins
+/// used by the installer, downloader, logger and Thunderstore cache.
+pub struct DiskLayout {
+    pub versions_dir: PathBuf,
+    pub app_data: PathBuf,
+    /// Runtime ids from the runtime settings; their Proton builds are not cleaned.
+    pub selected_runtimes: Vec<String>,
+}
+
+/// `(version, path)` pairs.
+type VersionDirs = Vec<(u32, PathBuf)>;
+
+const MOD_TEMP_REL: [&str; 2] = [".hq-launcher", "tmp"];
+const CURRENT_LOG_FILE: &str = "hq-launcher.log";
+
+impl DiskLayout {
+    fn app_dir(&self, category: DiskCategory) -> Option<PathBuf> {
+        let name = match category {
+            DiskCategory::ThunderstoreCache => "cache",
+            DiskCategory::LauncherTemp => "temp",
+            // Only the Proton builds; `proton_env/wine_prefix` holds the game's saves.
+            DiskCategory::ProtonGe => return Some(self.app_data.join("proton_env").join("proton")),
+            DiskCategory::DepotDownloader => "downloader",
+            DiskCategory::AppPatcher => "UnityApplicationPatcher",
+            DiskCategory::Logs => "logs",
+            _ => return None,
+        };
+        Some(self.app_data.join(name))
+    }
+
+    /// `v{N}` folders and `.v{N}.partial` folders under the versions dir.
+    fn version_dirs(&self) -> (VersionDirs, VersionDirs) {
+        let mut installed = Vec::new();
+        let mut partial = Vec::new();
+        let Ok(rd) = std::fs::read_dir(&self.versions_dir) else {
+            return (installed, partial);
+        };
+        for entry in rd.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(v) = name.strip_prefix('v').and_then(|n| n.parse::<u32>().ok()) {
+                installed.push((v, path));
+            } else if let Some(v) = name
+                .strip_prefix(".v")
+                .and_then(|n| n.strip_suffix(".partial"))
+                .and_then(|n| n.parse::<u32>().ok())
+            {
+                partial.push((v, path));
+            }
+        }
+        installed.sort();
+        partial.sort();
+        (installed, partial)
+    }
+}
+
+/// Bytes under `path`, not following links (so the shared config junction is not
+/// counted per version). Hardlinked files are counted once across a whole scan.
+fn dir_size(path: &Path, skip: &[PathBuf], seen: &mut HashSet<(u64, u64)>) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if meta.file_type().is_symlink() || skip.iter().any(|s| s == path) {
+        return 0;
+    }
+    if meta.is_file() {
+        if let Some((dev, ino, links)) = crate::dedup_store::file_identity(path) {
+            if links > 1 && !seen.insert((dev, ino)) {
+                return 0;
+            }
+        }
+        return meta.len();
+    }
+    let Ok(rd) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    rd.flatten()
+        .map(|entry| dir_size(&entry.path(), skip, seen))
+        .sum()
+}
+
+fn mod_temp_dir(version_root: &Path) -> PathBuf {
+    MOD_TEMP_REL
+        .iter()
+        .fold(version_root.to_path_buf(), |p, c| p.join(c))
+}
+
+pub fn scan(layout: &DiskLayout) -> DiskUsageNode {
+    let mut seen = HashSet::new();
+    let (installed, partial) = layout.version_dirs();
+
+    let versions = installed
+        .iter()
+        .map(|(v, root)| {
+            let bepinex = root.join("BepInEx");
+            let temp = mod_temp_dir(root);
+            let game = dir_size(root, &[bepinex.clone(), temp.clone()], &mut seen);
+            let mods = dir_size(&bepinex, &[], &mut seen);
+            let temp_bytes = dir_size(&temp, &[], &mut seen);
+            DiskUsageNode::group(
+                &format!("v{v}"),
+                vec![
+                    DiskUsageNode::leaf("Game files", root, DiskCategory::GameFiles, game),
+                    DiskUsageNode::leaf("Mods", &bepinex, DiskCategory::Mods, mods),
+                    DiskUsageNode::leaf(
+                        "Mod install leftovers",
+                        &temp,
+                        DiskCategory::ModTemp,
+                        temp_bytes,
+                    ),
+                ],
+            )
+        })
+        .collect();
+
+    let partials = partial
+        .iter()
+        .map(|(v, path)| {
+            let bytes = dir_size(path, &[], &mut seen);
+            DiskUsageNode::leaf(
+                &format!("v{v}"),
+                path,
+                DiskCategory::PartialDownloads,
+                bytes,
+            )
+        })
+        .collect();
+
+    // Scanned after the versions so only store files no version links to show up here.
+    let store = layout.versions_dir.join(crate::dedup_store::STORE_DIR_NAME);
+    let store_bytes = dir_size(&store, &[], &mut seen);
+
+    let mut children = vec![
+        DiskUsageNode::group("Game versions", versions),
+        DiskUsageNode::group("Partial downloads", partials),
+        DiskUsageNode::leaf(
+            "Shared file store",
+            &store,
+            DiskCategory::DedupStore,
+            store_bytes,
+        ),
+    ];
+    for (label, category) in [
+        ("Thunderstore cache", DiskCategory::ThunderstoreCache),
+        ("Launcher temp files", DiskCategory::LauncherTemp),
+        ("Proton-GE", DiskCategory::ProtonGe),
+        ("DepotDownloader", DiskCategory::DepotDownloader),
+        ("UnityApplicationPatcher", DiskCategory::AppPatcher),
+        ("Logs", DiskCategory::Logs),
+    ] {
+        if let Some(path) = layout.app_dir(category) {
+            let bytes = dir_size(&path, &[], &mut seen);
+            children.push(DiskUsageNode::leaf(label, &path, category, bytes));
+        }
+    }
+    DiskUsageNode::group("HQ Launcher", children)
+}
+
+fn remove_path(path: &Path, report: &mut CleanupReport) {
+    let bytes = dir_size(path, &[], &mut HashSet::new());
+    let res = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match res {
+        Ok(()) => {
+            report.freed_bytes += bytes;
+            report.removed.push(path.to_string_lossy().to_string());
+        }
+        Err(e) => report.errors.push(format!("{}: {e}", path.display())),
+    }
+}
+
+/// Remove the given categories. The caller is responsible for making sure nothing
+/// is running that uses them.
+pub fn clean(layout: &DiskLayout, categories: &[DiskCategory]) -> Result<CleanupReport, String> {
+    if let Some(category) = categories.iter().find(|c| !c.cleanable()) {
+        return Err(format!("{category:?} cannot be cleaned up from here."));
+    }
+    let mut report = CleanupReport::default();
+    let (installed, partial) = layout.version_dirs();
+
+    for category in categories {
+        match category {
+            DiskCategory::ModTemp => {
+                for (_, root) in &installed {
+                    let temp = mod_temp_dir(root);
+                    if temp.exists() {
+                        remove_path(&temp, &mut report);
+                    }
+                }
+            }
+            DiskCategory::PartialDownloads => {
+                for (_, path) in &partial {
+                    remove_path(path, &mut report);
+                }
+            }
+            DiskCategory::ProtonGe => {
+                // Only the bundled build is downloaded again on demand.
+                let Some(proton) = layout.app_dir(DiskCategory::ProtonGe) else {
+                    continue;
+                };
+                let Ok(rd) = std::fs::read_dir(&proton) else {
+                    continue;
+                };
+                for entry in rd.flatten() {
+                    let id = crate::runtimes::managed_id(&entry.file_name().to_string_lossy());
+                    if layout.selected_runtimes.contains(&id) {
+                        report.kept.push(entry.path().to_string_lossy().to_string());
+                    } else {
+                        remove_path(&entry.path(), &mut report);
+                    }
+                }
+            }
+            DiskCategory::Logs => {
+                // The active log file is held open by the logger.
+                let Some(logs) = layout.app_dir(DiskCategory::Logs) else {
+                    continue;
+                };
+                let Ok(rd) = std::fs::read_dir(&logs) else {
+                    continue;
+                };
+                for entry in rd.flatten() {
+                    if entry.file_name() != CURRENT_LOG_FILE {
+                        remove_path(&entry.path(), &mut report);
+                    }
+                }
+            }
+            other => {
+                if let Some(path) = layout.app_dir(*other).filter(|p| p.exists()) {
+                    remove_path(&path, &mut report);
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn layout(name: &str) -> DiskLayout {
+        let root = temp_dir(&format!("disk_{name}"));
+        DiskLayout {
+            versions_dir: root.join("versions"),
+            app_data: root,
+            selected_runtimes: vec!["ge:GE-Proton9-27".to_string()],
+        }
+    }
+
+    fn write(path: &Path, len: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; len]).unwrap();
+    }
+
+    fn find<'a>(node: &'a DiskUsageNode, label: &str) -> &'a DiskUsageNode {
+        node.children.iter().find(|c| c.label == label).unwrap()
+    }
+
+    #[test]
+    fn scan_splits_game_mods_and_leftovers() {
+        let layout = layout("scan");
+        let v73 = layout.versions_dir.join("v73");
+        write(&v73.join("Lethal Company.exe"), 100);
+        write(&v73.join("BepInEx/plugins/a.dll"), 20);
+        write(&v73.join(".hq-launcher/tmp/mods/x.zip"), 5);
+        write(&layout.versions_dir.join(".v81.partial/file"), 7);
+        write(&layout.app_data.join("logs/hq-launcher.log"), 3);
+
+        let tree = scan(&layout);
+        let version = find(find(&tree, "Game versions"), "v73");
+        assert_eq!(find(version, "Game files").bytes, 100);
+        assert_eq!(find(version, "Mods").bytes, 20);
+        assert_eq!(find(version, "Mod install leftovers").bytes, 5);
+        assert_eq!(find(&tree, "Partial downloads").bytes, 7);
+        assert_eq!(find(&tree, "Logs").bytes, 3);
+        assert_eq!(tree.bytes, 135);
+        let _ = std::fs::remove_dir_all(&layout.app_data);
+    }
+
+    #[test]
+    fn clean_keeps_installs_and_the_active_log() {
+        let layout = layout("clean");
+        let v73 = layout.versions_dir.join("v73");
+        write(&v73.join("Lethal Company.exe"), 100);
+        write(&v73.join(".hq-launcher/tmp/mods/x.zip"), 5);
+        write(&layout.versions_dir.join(".v81.partial/file"), 7);
+        write(&layout.app_data.join("logs/hq-launcher.log"), 3);
+        write(&layout.app_data.join("logs/hq-launcher.1.log"), 4);
+        write(&layout.app_data.join("cache/thunderstore.json"), 9);
+        let proton = layout.app_data.join("proton_env/proton");
+        write(&proton.join("GE-Proton10-28/proton"), 11);
+        write(&proton.join("GE-Proton9-27/proton"), 13);
+
+        assert!(clean(&layout, &[DiskCategory::GameFiles]).is_err());
+        let report = clean(
+            &layout,
+            &[
+                DiskCategory::ModTemp,
+                DiskCategory::PartialDownloads,
+                DiskCategory::Logs,
+                DiskCategory::ThunderstoreCache,
+                DiskCategory::ProtonGe,
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(report.freed_bytes, 5 + 7 + 4 + 9 + 11);
+        assert_eq!(
+            report.kept,
+            [proton.join("GE-Proton9-27").to_string_lossy().to_string()]
+        );
+        assert!(report.errors.is_empty());
+        assert!(v73.join("Lethal Company.exe").is_file());
+        assert!(layout.app_data.join("logs/hq-launcher.log").is_file());
+        assert!(!layout.versions_dir.join(".v81.partial").exists());
+        let _ = std::fs::remove_dir_all(&layout.app_data);
+    }
+}
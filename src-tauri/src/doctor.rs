@@ -0,0 +1,642 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+// Health checks for an installed version, run on demand and before every launch.
+// Each check looks at the version folder (and the Wine prefix on Linux) and reports
+// pass, warn or fail with an explanation. A check that has an automatic fix names
+// it; fixes that only move files around live here, the ones that need the network
+// or the saved mod state are applied by the caller.
+
+pub const REPORT_EVENT: &str = "doctor://report";
+
+/// BepInEx files a modded run cannot start without.
+const BEPINEX_CORE_FILES: [&str; 6] = [
+    "BepInEx.dll",
+    "BepInEx.Preloader.dll",
+    "0Harmony.dll",
+    "Mono.Cecil.dll",
+    "MonoMod.RuntimeDetour.dll",
+    "MonoMod.Utils.dll",
+];
+const PROXY_DLLS: [&str; 2] = ["winhttp.dll", "version.dll"];
+const BACKUP_SUFFIX: &str = ".old";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fix {
+    /// Rename `winhttp.dll.old` / `version.dll.old` back.
+    RestoreProxyBackup,
+    /// Delete a `.old` proxy backup next to the DLL it was made from.
+    RemoveProxyBackup,
+    /// Download BepInExPack and add the files that are missing.
+    RestoreBepInExFiles,
+    /// Rename plugin files to match the saved enabled/disabled state.
+    ReapplyModStates,
+    /// Empty the prefix so the runtime sets it up again.
+    ResetWinePrefix,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub fix: Option<Fix>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub version: u32,
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DoctorContext {
+    pub version: u32,
+    pub game_dir: PathBuf,
+    /// A run of this version is active, so its proxy DLLs may be hidden on purpose.
+    pub game_running: bool,
+    /// Wine prefix, or Proton compat data folder, the version runs in (Linux only).
+    pub wine_prefix: Option<PathBuf>,
+}
+
+struct Finding {
+    status: CheckStatus,
+    detail: String,
+    fix: Option<Fix>,
+}
+
+impl Finding {
+    fn pass(detail: impl Into<String>) -> Self {
+        Finding {
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(status: CheckStatus, detail: impl Into<String>, fix: Option<Fix>) -> Self {
+        Finding {
+            status,
+            detail: detail.into(),
+            fix,
+        }
+    }
+}
+
+struct Check {
+    id: &'static str,
+    title: &'static str,
+    /// Only matters when BepInEx is loaded.
+    modded_only: bool,
+    run: fn(&DoctorContext) -> Finding,
+}
+
+const CHECKS: [Check; 7] = [
+    Check {
+        id: "proxy_backups",
+        title: "Proxy DLL backups",
+        modded_only: false,
+        run: check_proxy_backups,
+    },
+    Check {
+        id: "bepinex_proxy",
+        title: "BepInEx loader (winhttp.dll)",
+        modded_only: true,
+        run: check_bepinex_proxy,
+    },
+    Check {
+        id: "doorstop_config",
+        title: "Doorstop config",
+        modded_only: true,
+        run: check_doorstop_config,
+    },
+    Check {
+        id: "bepinex_core",
+        title: "BepInEx core files",
+        modded_only: true,
+        run: check_bepinex_core,
+    },
+    Check {
+        id: "plugin_files",
+        title: "Plugin enabled state",
+        modded_only: true,
+        run: check_plugin_files,
+    },
+    Check {
+        id: "plugin_dependencies",
+        title: "Plugin dependencies",
+        modded_only: true,
+        run: check_plugin_dependencies,
+    },
+    Check {
+        id: "wine_prefix",
+        title: "Wine prefix",
+        modded_only: false,
+        run: check_wine_prefix,
+    },
+];
+
+/// Run every check, or only those that matter to a vanilla run.
+pub fn run(ctx: &DoctorContext, loads_mods: bool) -> DoctorReport {
+    let checks = CHECKS
+        .iter()
+        .filter(|check| loads_mods || !check.modded_only)
+        .map(|check| {
+            let finding = (check.run)(ctx);
+            CheckResult {
+                id: check.id,
+                title: check.title,
+                status: finding.status,
+                detail: finding.detail,
+                fix: finding.fix,
+            }
+        })
+        .collect();
+    DoctorReport {
+        version: ctx.version,
+        checks,
+    }
+}
+
+fn with_suffix(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(BACKUP_SUFFIX);
+    PathBuf::from(name)
+}
+
+fn check_proxy_backups(ctx: &DoctorContext) -> Finding {
+    if ctx.game_running {
+        return Finding::pass("Skipped while the game is running.");
+    }
+    let mut stale = vec![];
+    let mut duplicated = vec![];
+    for dll in PROXY_DLLS {
+        let original = ctx.game_dir.join(dll);
+        if !with_suffix(&original).exists() {
+            continue;
+        }
+        if original.exists() {
+            duplicated.push(dll);
+        } else {
+            stale.push(dll);
+        }
+    }
+    if !stale.is_empty() {
+        return Finding::problem(
+            CheckStatus::Fail,
+            format!(
+                "{} is still renamed to .old from an earlier vanilla or overlay-less run that \
+                 did not finish cleanly.",
+                stale.join(" and ")
+            ),
+            Some(Fix::RestoreProxyBackup),
+        );
+    }
+    if !duplicated.is_empty() {
+        return Finding::problem(
+            CheckStatus::Warn,
+            format!(
+                "A leftover .old backup sits next to {}; it blocks hiding the DLL for vanilla runs.",
+                duplicated.join(" and ")
+            ),
+            Some(Fix::RemoveProxyBackup),
+        );
+    }
+    Finding::pass("No leftover backups.")
+}
+
+fn check_bepinex_proxy(ctx: &DoctorContext) -> Finding {
+    if ctx.game_dir.join("winhttp.dll").is_file() {
+        return Finding::pass("winhttp.dll is in place.");
+    }
+    if with_suffix(&ctx.game_dir.join("winhttp.dll")).is_file() {
+        return Finding::problem(
+            CheckStatus::Fail,
+            "winhttp.dll is renamed to winhttp.dll.old, so BepInEx will not load.",
+            Some(Fix::RestoreProxyBackup),
+        );
+    }
+    Finding::problem(
+        CheckStatus::Fail,
+        "winhttp.dll is missing, so the game starts without BepInEx and mods. Antivirus \
+         software often quarantines it.",
+        Some(Fix::RestoreBepInExFiles),
+    )
+}
+
+fn check_doorstop_config(ctx: &DoctorContext) -> Finding {
+    let path = ctx.game_dir.join("doorstop_config.ini");
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Finding::problem(
+            CheckStatus::Fail,
+            "doorstop_config.ini is missing, so winhttp.dll does not know to start BepInEx.",
+            Some(Fix::RestoreBepInExFiles),
+        );
+    };
+    let value = |key: &str| {
+        text.lines().find_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            name.trim()
+                .eq_ignore_ascii_case(key)
+                .then(|| value.trim().to_string())
+        })
+    };
+    if value("enabled").is_some_and(|enabled| enabled.eq_ignore_ascii_case("false")) {
+        return Finding::problem(
+            CheckStatus::Fail,
+            "doorstop_config.ini has `enabled = false`, so BepInEx never starts.",
+            None,
+        );
+    }
+    let target = value("target_assembly")
+        .or_else(|| value("targetAssembly"))
+        .unwrap_or_default();
+    if target.is_empty() {
+        return Finding::problem(
+            CheckStatus::Warn,
+            "doorstop_config.ini does not name a target assembly.",
+            None,
+        );
+    }
+    let target_path = target
+        .split(['\\', '/'])
+        .fold(ctx.game_dir.clone(), |path, part| path.join(part));
+    if !target_path.is_file() {
+        return Finding::problem(
+            CheckStatus::Fail,
+            format!("doorstop_config.ini points at {target}, which does not exist."),
+            Some(Fix::RestoreBepInExFiles),
+        );
+    }
+    Finding::pass(format!("Starts {target}."))
+}
+
+fn check_bepinex_core(ctx: &DoctorContext) -> Finding {
+    let core = ctx.game_dir.join("BepInEx").join("core");
+    let missing: Vec<&str> = BEPINEX_CORE_FILES
+        .into_iter()
+        .filter(|file| !core.join(file).is_file())
+        .collect();
+    if missing.is_empty() {
+        return Finding::pass("All core files are present.");
+    }
+    Finding::problem(
+        CheckStatus::Fail,
+        format!(
+            "BepInEx/core is missing {}. Antivirus software may have removed them.",
+            missing.join(", ")
+        ),
+        Some(Fix::RestoreBepInExFiles),
+    )
+}
+
+fn plugin_dirs(game_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut dirs = vec![];
+    for sub in ["plugins", "patchers"] {
+        let Ok(entries) = std::fs::read_dir(game_dir.join("BepInEx").join(sub)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                dirs.push((
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                ));
+            }
+        }
+    }
+    dirs.sort();
+    dirs
+}
+
+fn files_below(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+fn check_plugin_files(ctx: &DoctorContext) -> Finding {
+    let mut mixed = vec![];
+    let mut twins = vec![];
+    for (folder, dir) in plugin_dirs(&ctx.game_dir) {
+        let (mut disabled, mut enabled) = (0, 0);
+        for file in files_below(&dir) {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            // Images keep their name when a mod is disabled so the launcher can show them.
+            if crate::is_launcher_visible_mod_asset_name(&name) {
+                continue;
+            }
+            if let Some(base) = name.strip_suffix(BACKUP_SUFFIX) {
+                disabled += 1;
+                if file.with_file_name(base).exists() {
+                    twins.push(format!("{folder}/{base}"));
+                }
+            } else {
+                enabled += 1;
+            }
+        }
+        if disabled > 0 && enabled > 0 {
+            mixed.push(folder);
+        }
+    }
+    if mixed.is_empty() && twins.is_empty() {
+        return Finding::pass("Every plugin is fully enabled or fully disabled.");
+    }
+    let mut detail = vec![];
+    if !mixed.is_empty() {
+        detail.push(format!(
+            "Half enabled, half renamed to .old: {}.",
+            mixed.join(", ")
+        ));
+    }
+    if !twins.is_empty() {
+        detail.push(format!(
+            "Both a file and its .old copy exist, remove one of them: {}.",
+            twins.join(", ")
+        ));
+    }
+    Finding::problem(
+        CheckStatus::Warn,
+        detail.join(" "),
+        (!mixed.is_empty()).then_some(Fix::ReapplyModStates),
+    )
+}
+
+fn check_plugin_dependencies(ctx: &DoctorContext) -> Finding {
+    // Folder name (`Dev-Name`, lowercased) -> enabled.
+    let installed: HashMap<String, bool> = plugin_dirs(&ctx.game_dir)
+        .into_iter()
+        .map(|(folder, dir)| (folder.to_lowercase(), dir.join("manifest.json").is_file()))
+        .collect();
+    let mut problems = vec![];
+    for (folder, dir) in plugin_dirs(&ctx.game_dir) {
+        let Ok(manifest) = crate::bepinex_cfg::read_manifest(&dir.join("manifest.json")) else {
+            continue;
+        };
+        for dependency in &manifest.dependencies {
+            let key = dependency
+                .rsplit_once('-')
+                .map_or(dependency.as_str(), |(key, _version)| key);
+            if key.eq_ignore_ascii_case("BepInEx-BepInExPack") {
+                continue;
+            }
+            match installed.get(&key.to_lowercase()) {
+                Some(true) => {}
+                Some(false) => problems.push(format!("{folder} needs {key} (disabled)")),
+                None => problems.push(format!("{folder} needs {key} (not installed)")),
+            }
+        }
+    }
+    if problems.is_empty() {
+        return Finding::pass("Every enabled plugin has its dependencies.");
+    }
+    problems.sort();
+    problems.dedup();
+    Finding::problem(
+        CheckStatus::Warn,
+        format!("BepInEx will skip these plugins: {}.", problems.join("; ")),
+        None,
+    )
+}
+
+fn check_wine_prefix(ctx: &DoctorContext) -> Finding {
+    let Some(prefix) = &ctx.wine_prefix else {
+        return Finding::pass("Not used on this platform.");
+    };
+    if !prefix.exists() {
+        return Finding::pass("Created on the first launch.");
+    }
+    if ctx.game_running || crate::wine_prefixes::is_initialised(prefix) {
+        return Finding::pass(format!("{} is set up.", prefix.display()));
+    }
+    Finding::problem(
+        CheckStatus::Warn,
+        format!(
+            "{} exists but was never fully initialised (no system.reg), usually because the \
+             first launch was interrupted.",
+            prefix.display()
+        ),
+        Some(Fix::ResetWinePrefix),
+    )
+}
+
+/// Put `.old` proxy DLL backups back where the original is missing.
+pub fn restore_proxy_backups(game_dir: &Path) -> Result<(), String> {
+    for dll in PROXY_DLLS {
+        let original = game_dir.join(dll);
+        let backup = with_suffix(&original);
+        if backup.exists() && !original.exists() {
+            std::fs::rename(&backup, &original)
+                .map_err(|e| format!("failed to restore {dll}: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete `.old` proxy DLL backups the original DLL still exists for.
+pub fn remove_proxy_backups(game_dir: &Path) -> Result<(), String> {
+    for dll in PROXY_DLLS {
+        let original = game_dir.join(dll);
+        let backup = with_suffix(&original);
+        if backup.exists() && original.exists() {
+            std::fs::remove_file(&backup)
+                .map_err(|e| format!("failed to remove {}: {e}", backup.display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn write(path: &Path, text: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    fn context(game_dir: &Path) -> DoctorContext {
+        DoctorContext {
+            version: 73,
+            game_dir: game_dir.to_path_buf(),
+            game_running: false,
+            wine_prefix: None,
+        }
+    }
+
+    fn status_of(report: &DoctorReport, id: &str) -> (CheckStatus, Option<Fix>) {
+        let check = report.checks.iter().find(|check| check.id == id).unwrap();
+        (check.status, check.fix)
+    }
+
+    fn healthy_install(dir: &Path) {
+        write(&dir.join("winhttp.dll"), "");
+        write(
+            &dir.join("doorstop_config.ini"),
+            "[General]\nenabled = true\ntarget_assembly = BepInEx\\core\\BepInEx.Preloader.dll\n",
+        );
+        for file in BEPINEX_CORE_FILES {
+            write(&dir.join("BepInEx").join("core").join(file), "");
+        }
+    }
+
+    #[test]
+    fn broken_bepinex_install_fails_with_fixes() {
+        let dir = temp_dir("doctor_bepinex");
+        healthy_install(&dir);
+        let report = run(&context(&dir), true);
+        assert_eq!(report.status(), CheckStatus::Pass, "{report:?}");
+
+        std::fs::rename(dir.join("winhttp.dll"), dir.join("winhttp.dll.old")).unwrap();
+        std::fs::remove_file(dir.join("BepInEx/core/MonoMod.Utils.dll")).unwrap();
+        let report = run(&context(&dir), true);
+        assert_eq!(
+            status_of(&report, "proxy_backups"),
+            (CheckStatus::Fail, Some(Fix::RestoreProxyBackup))
+        );
+        assert_eq!(
+            status_of(&report, "bepinex_proxy"),
+            (CheckStatus::Fail, Some(Fix::RestoreProxyBackup))
+        );
+        assert_eq!(
+            status_of(&report, "bepinex_core"),
+            (CheckStatus::Fail, Some(Fix::RestoreBepInExFiles))
+        );
+        // A vanilla run only cares about the backup.
+        let vanilla = run(&context(&dir), false);
+        assert_eq!(vanilla.failures().count(), 1);
+
+        restore_proxy_backups(&dir).unwrap();
+        assert!(dir.join("winhttp.dll").is_file());
+        let running = DoctorContext {
+            game_running: true,
+            ..context(&dir)
+        };
+        write(&dir.join("version.dll"), "");
+        write(&dir.join("version.dll.old"), "");
+        assert_eq!(
+            status_of(&run(&running, true), "proxy_backups").0,
+            CheckStatus::Pass
+        );
+        remove_proxy_backups(&dir).unwrap();
+        assert!(!dir.join("version.dll.old").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn plugin_state_and_dependencies_are_checked() {
+        let dir = temp_dir("doctor_plugins");
+        healthy_install(&dir);
+        let plugins = dir.join("BepInEx").join("plugins");
+        write(
+            &plugins.join("HQ-HQoL/manifest.json"),
+            r#"{"name":"HQoL","description":"","version_number":"1.0.0","website_url":"",
+               "dependencies":["BepInEx-BepInExPack-5.4.2100","Evaisa-LethalLib-0.16.1","Rune580-LethalCompany_InputUtils-0.7.7"]}"#,
+        );
+        write(&plugins.join("HQ-HQoL/HQoL.dll"), "");
+        write(&plugins.join("Evaisa-LethalLib/manifest.json.old"), "{}");
+        write(&plugins.join("Evaisa-LethalLib/LethalLib.dll.old"), "");
+        write(&plugins.join("Evaisa-LethalLib/icon.png"), "");
+        write(&plugins.join("Sligili-More_Emotes/manifest.json.old"), "{}");
+        write(&plugins.join("Sligili-More_Emotes/MoreEmotes.dll"), "");
+
+        let report = run(&context(&dir), true);
+        let files = report
+            .checks
+            .iter()
+            .find(|c| c.id == "plugin_files")
+            .unwrap();
+        assert_eq!(
+            (files.status, files.fix),
+            (CheckStatus::Warn, Some(Fix::ReapplyModStates))
+        );
+        assert!(
+            files.detail.contains("Sligili-More_Emotes"),
+            "{}",
+            files.detail
+        );
+        assert!(!files.detail.contains("LethalLib"), "{}", files.detail);
+
+        let deps = report
+            .checks
+            .iter()
+            .find(|c| c.id == "plugin_dependencies")
+            .unwrap();
+        assert_eq!(deps.status, CheckStatus::Warn);
+        assert_eq!(
+            deps.detail,
+            "BepInEx will skip these plugins: HQ-HQoL needs Evaisa-LethalLib (disabled); \
+             HQ-HQoL needs Rune580-LethalCompany_InputUtils (not installed)."
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn half_created_prefix_needs_a_reset() {
+        let dir = temp_dir("doctor_prefix");
+        let prefix = dir.join("GE-Proton10-28");
+        let ctx = DoctorContext {
+            wine_prefix: Some(prefix.clone()),
+            ..context(&dir)
+        };
+        assert_eq!(
+            status_of(&run(&ctx, false), "wine_prefix").0,
+            CheckStatus::Pass
+        );
+
+        write(&prefix.join("pfx").join("drive_c").join("marker"), "");
+        assert_eq!(
+            status_of(&run(&ctx, false), "wine_prefix"),
+            (CheckStatus::Warn, Some(Fix::ResetWinePrefix))
+        );
+        write(&prefix.join("pfx").join("system.reg"), "");
+        assert_eq!(
+            status_of(&run(&ctx, false), "wine_prefix").0,
+            CheckStatus::Pass
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+/// Hard upper bound for parallel game downloads.
+pub const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+/// Rough size of one installed version (game + BepInEx + mods). A second download is
+/// only started when the storage volume can fit both.
+pub const ESTIMATED_INSTALL_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStatus {
+    Queued,
+    Running,
+    Paused,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry {
+    pub version: u32,
+    pub status: QueueStatus,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Unix seconds.
+    #[serde(default)]
+    pub added_at: u64,
+}
+
+/// Persisted at `config/download_queue.json`; finished versions are dropped from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadQueue {
+    #[serde(default)]
+    pub entries: Vec<QueueEntry>,
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+}
+
+fn default_max_concurrent() -> usize {
+    1
+}
+
+impl Default for DownloadQueue {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            max_concurrent: default_max_concurrent(),
+        }
+    }
+}
+
+impl DownloadQueue {
+    pub fn entry(&self, version: u32) -> Option<&QueueEntry> {
+        self.entries.iter().find(|e| e.version == version)
+    }
+
+    /// Add `version` at the end. A paused or failed entry is re-queued in place.
+    /// Returns `false` when the version is already queued or running.
+    pub fn enqueue(&mut self, version: u32, now: u64) -> bool {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.version == version) {
+            if matches!(entry.status, QueueStatus::Queued | QueueStatus::Running) {
+                return false;
+            }
+            entry.status = QueueStatus::Queued;
+            entry.error = None;
+            return true;
+        }
+        self.entries.push(QueueEntry {
+            version,
+            status: QueueStatus::Queued,
+            error: None,
+            added_at: now,
+        });
+        true
+    }
+
+    pub fn remove(&mut self, version: u32) -> Option<QueueEntry> {
+        let idx = self.entries.iter().position(|e| e.version == version)?;
+        Some(self.entries.remove(idx))
+    }
+
+    /// Move `version` to `index` (clamped to the queue length).
+    pub fn move_to(&mut self, version: u32, index: usize) -> Result<(), String> {
+        let entry = self
+            .remove(version)
+            .ok_or_else(|| format!("v{version} is not in the download queue"))?;
+        let index = index.min(self.entries.len());
+        self.entries.insert(index, entry);
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, version: u32, status: QueueStatus, error: Option<String>) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.version == version) {
+            entry.status = status;
+            entry.error = error;
+        }
+    }
+
+    pub fn set_max_concurrent(&mut self, max: usize) -> usize {
+        self.max_concurrent = max.clamp(1, MAX_CONCURRENT_DOWNLOADS);
+        self.max_concurrent
+    }
+
+    /// Downloads allowed at once, queued or started directly.
+    pub fn slots(&self) -> usize {
+        self.max_concurrent.clamp(1, MAX_CONCURRENT_DOWNLOADS)
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.status == QueueStatus::Running)
+            .count()
+    }
+
+    /// First queued version, in queue order.
+    pub fn next_queued(&self) -> Option<u32> {
+        self.entries
+            .iter()
+            .find(|e| e.status == QueueStatus::Queued)
+            .map(|e| e.version)
+    }
+
+    /// Nothing is running right after a restart; those entries go back to the queue
+    /// and resume from their partial directory.
+    pub fn restore_after_restart(&mut self) {
+        for entry in &mut self.entries {
+            if entry.status == QueueStatus::Running {
+                entry.status = QueueStatus::Queued;
+            }
+        }
+    }
+
+    /// Whether another download may start next to `running` active ones.
+    /// `network_ready` is false while Steam is logged out; nothing starts then.
+    pub fn can_start_another(
+        &self,
+        running: usize,
+        free_bytes: Option<u64>,
+        network_ready: bool,
+    ) -> bool {
+        if !network_ready || running >= self.slots() {
+            return false;
+        }
+        if running == 0 {
+            return true;
+        }
+        // Parallel downloads need room for every install in flight.
+        free_bytes.is_some_and(|free| free >= ESTIMATED_INSTALL_BYTES * (running as u64 + 1))
+    }
+}
+
+fn queue_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("config")
+        .join("download_queue.json"))
+}
+
+pub fn load(app: &tauri::AppHandle) -> Result<DownloadQueue, String> {
+    let path = queue_path(app)?;
+    if !path.exists() {
+        return Ok(DownloadQueue::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn save(app: &tauri::AppHandle, queue: &DownloadQueue) -> Result<(), String> {
+    let path = queue_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(queue).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(queue: &DownloadQueue) -> Vec<u32> {
+        queue.entries.iter().map(|e| e.version).collect()
+    }
+
+    #[test]
+    fn enqueue_skips_duplicates_and_requeues_failed_entries() {
+        let mut queue = DownloadQueue::default();
+        assert!(queue.enqueue(73, 1));
+        assert!(queue.enqueue(50, 2));
+        assert!(!queue.enqueue(73, 3));
+
+        queue.set_status(50, QueueStatus::Failed, Some("disk full".to_string()));
+        assert!(queue.enqueue(50, 4));
+        let entry = queue.entry(50).unwrap();
+        assert_eq!(entry.status, QueueStatus::Queued);
+        assert!(entry.error.is_none());
+        assert_eq!(entry.added_at, 2);
+        assert_eq!(versions(&queue), vec![73, 50]);
+    }
+
+    #[test]
+    fn move_to_reorders_and_clamps() {
+        let mut queue = DownloadQueue::default();
+        for v in [40, 45, 49, 50] {
+            queue.enqueue(v, 0);
+        }
+        queue.move_to(50, 0).unwrap();
+        assert_eq!(versions(&queue), vec![50, 40, 45, 49]);
+        queue.move_to(40, 99).unwrap();
+        assert_eq!(versions(&queue), vec![50, 45, 49, 40]);
+        assert!(queue.move_to(81, 0).is_err());
+    }
+
+    #[test]
+    fn next_queued_skips_paused_and_running() {
+        let mut queue = DownloadQueue::default();
+        for v in [40, 45, 49] {
+            queue.enqueue(v, 0);
+        }
+        queue.set_status(40, QueueStatus::Running, None);
+        queue.set_status(45, QueueStatus::Paused, None);
+        assert_eq!(queue.next_queued(), Some(49));
+
+        queue.restore_after_restart();
+        assert_eq!(queue.next_queued(), Some(40));
+        assert_eq!(queue.running_count(), 0);
+    }
+
+    #[test]
+    fn second_download_needs_setting_and_disk_space() {
+        let mut queue = DownloadQueue::default();
+        assert!(queue.can_start_another(0, None, true));
+        assert!(!queue.can_start_another(0, None, false));
+        assert!(!queue.can_start_another(1, Some(u64::MAX), true));
+
+        assert_eq!(queue.set_max_concurrent(5), MAX_CONCURRENT_DOWNLOADS);
+        assert_eq!(queue.slots(), MAX_CONCURRENT_DOWNLOADS);
+        assert!(queue.can_start_another(1, Some(ESTIMATED_INSTALL_BYTES * 2), true));
+        assert!(!queue.can_start_another(1, Some(ESTIMATED_INSTALL_BYTES * 2), false));
+        assert!(!queue.can_start_another(1, Some(ESTIMATED_INSTALL_BYTES), true));
+        assert!(!queue.can_start_another(1, None, true));
+        assert!(!queue.can_start_another(2, Some(u64::MAX), true));
+    }
+}
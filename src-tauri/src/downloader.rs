@@ -15,6 +15,9 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
 use tokio::sync::mpsc;
 
+use crate::depot_output::{
+    AuthPrompt, DepotFailure, DepotLine, DepotOutputParser, DepotPhase, QrChallengeParser,
+};
 use crate::progress::{self, TaskProgressPayload};
 
 #[cfg(windows)]
@@ -162,10 +165,24 @@ pub enum DepotDownloaderEvent {
     NeedsMobileConfirmation {
         session_id: u64,
     },
+    /// A QR login challenge to show (again, when Steam refreshes it). Only the drawn
+    /// code is available; DepotDownloader does not print the URL behind it.
+    QrChallenge {
+        session_id: u64,
+        art: String,
+    },
     LoginSuccess,
     LoginFailed(String),
     DownloadComplete,
     Error(String),
+    /// A file finished downloading (path as printed by DepotDownloader).
+    FileDownloaded(String),
+    /// Steam asked for interactive auth while downloading; carries the message to show.
+    AuthRequired(String),
+    RateLimited(String),
+    LicenseMissing(String),
+    DiskFull(String),
+    ManifestNotFound(String),
 }
 
 #[derive(Debug, Clone)]
@@ -176,6 +193,23 @@ pub struct DownloadTaskContext {
     pub step_name: String,
 }
 
+type FileStamp = (u64, Option<std::time::SystemTime>);
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()))
+}
+
+/// Size and modification time of every game file under `root`, keyed by relative path.
+fn file_stamps(root: &Path) -> Result<HashMap<PathBuf, FileStamp>, String> {
+    let mut files = vec![];
+    crate::steam_import::collect_files(root, Path::new(""), &mut files)?;
+    Ok(files
+        .into_iter()
+        .filter_map(|rel| Some((rel.clone(), file_stamp(&root.join(&rel))?)))
+        .collect())
+}
+
 fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
     let s = step.max(1).min(steps_total) as f64;
     let sp = step_progress.clamp(0.0, 1.0);
@@ -271,15 +305,10 @@ where
 
 impl DepotDownloader {
     const APP_ID: &'static str = "1966720";
-    const DEPOT_ID: &'static str = "1966721";
+    pub(crate) const DEPOT_ID: &'static str = "1966721";
     const PATCH_MARKER: &'static str = ".hq_launcher_ipc";
 
     pub fn new(app: &tauri::AppHandle) -> Result<Self, String> {
-        let app_data = app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("failed to resolve app data dir: {e}"))?;
-
         let downloader_dir = downloader_install_dir(app)?;
         let ipc_mode = downloader_dir.join(Self::PATCH_MARKER).exists();
         let executable_path = downloader_executable_path(&downloader_dir);
@@ -288,8 +317,7 @@ impl DepotDownloader {
             return Err("DepotDownloader not installed. Please install it first.".to_string());
         }
 
-        let config_dir = app_data.join("depot_config");
-        std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+        let config_dir = depot_config_dir(app)?;
 
         Ok(Self {
             app: app.clone(),
@@ -574,7 +602,7 @@ impl DepotDownloader {
             steam_id: read_saved_steam_id(&self.config_dir),
         };
         self.save_login_state(&state)?;
-        self.emit_event(DepotDownloaderEvent::LoginSuccess);
+        self.emit_login_success();
         log::info!("Login successful");
 
         // Best-effort cleanup of the temp output directory.
@@ -582,6 +610,152 @@ impl DepotDownloader {
         Ok(())
     }
 
+    /// Steam 로그인 (QR): DepotDownloader prints a challenge that the Steam mobile app
+    /// approves, so no password passes through the launcher. Dropping the sender of
+    /// `rx_cancel` stops the attempt.
+    pub async fn login_qr(
+        &self,
+        session_id: u64,
+        username_hint: Option<String>,
+        rx_cancel: &mut mpsc::UnboundedReceiver<String>,
+    ) -> Result<(), String> {
+        const QR_LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+        // Same cache dir as the password login so a remembered login is found later.
+        let login_cache_dir = self.config_dir.join("_login_cache");
+        let _ = std::fs::create_dir_all(&login_cache_dir);
+
+        let mut args = vec![
+            "-app".to_string(),
+            Self::APP_ID.to_string(),
+            "-depot".to_string(),
+            Self::DEPOT_ID.to_string(),
+            "-manifest-only".to_string(),
+            "-dir".to_string(),
+            login_cache_dir.to_string_lossy().to_string(),
+            "-qr".to_string(),
+            "-remember-password".to_string(),
+        ];
+        if self.ipc_mode {
+            args.insert(0, "-ipc".to_string());
+        }
+
+        log::info!("Attempting QR login (session_id={session_id})");
+        let mut child = hide_console_window(Command::new(&self.executable_path))
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null())
+            .current_dir(&self.config_dir)
+            .spawn()
+            .map_err(|e| format!("Failed to spawn DepotDownloader: {e}"))?;
+
+        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<(bool, String)>(); // (is_stderr, line)
+        spawn_output_reader(stdout, false, tx.clone());
+        spawn_output_reader(stderr, true, tx);
+
+        let mut qr = QrChallengeParser::default();
+        let mut account_name = username_hint
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty());
+        let mut output_open = true;
+        let started_at = Instant::now();
+        let mut last_output_at = Instant::now();
+        let mut ticks = tokio::time::interval(Duration::from_millis(250));
+
+        let status = loop {
+            tokio::select! {
+                s = child.wait() => {
+                    break s.map_err(|e| e.to_string())?;
+                }
+                msg = rx.recv(), if output_open => {
+                    let Some((is_stderr, line)) = msg else {
+                        output_open = false;
+                        continue;
+                    };
+                    last_output_at = Instant::now();
+                    if is_stderr {
+                        log::error!("DepotDownloader error: {}", line);
+                    }
+                    if let Some(name) = crate::depot_output::logon_account_name(&line) {
+                        account_name = Some(name);
+                    }
+                    if let Some(challenge) = qr.feed(&line) {
+                        self.emit_qr_challenge(session_id, challenge);
+                        continue;
+                    }
+
+                    let l = line.to_lowercase();
+                    if l.contains("failed to authenticate")
+                        || l.contains("login failed")
+                        || l.contains("unable to login")
+                    {
+                        let _ = child.kill().await;
+                        let _ = child.wait().await;
+                        return Err(format!("Steam login failed: {line}"));
+                    }
+                    if !is_stderr {
+                        self.emit_event(DepotDownloaderEvent::Output(line));
+                    }
+                }
+                cancel = rx_cancel.recv() => {
+                    // Codes submitted to this session mean nothing to a QR login.
+                    if cancel.is_none() {
+                        let _ = child.kill().await;
+                        let _ = child.wait().await;
+                        return Err("QR login cancelled.".to_string());
+                    }
+                }
+                _ = ticks.tick() => {
+                    if last_output_at.elapsed() >= Duration::from_millis(250) {
+                        if let Some(challenge) = qr.flush() {
+                            self.emit_qr_challenge(session_id, challenge);
+                        }
+                    }
+                    if started_at.elapsed() >= QR_LOGIN_TIMEOUT {
+                        let _ = child.kill().await;
+                        let _ = child.wait().await;
+                        return Err("QR login timed out. Please try again.".to_string());
+                    }
+                }
+            }
+        };
+
+        // Lines printed right before the exit may still be queued.
+        while let Ok((_, line)) = rx.try_recv() {
+            if let Some(name) = crate::depot_output::logon_account_name(&line) {
+                account_name = Some(name);
+            }
+        }
+
+        if !status.success() {
+            return Err(format!("Login failed with status: {}", status));
+        }
+        // Later runs log in with `-username` and the remembered token.
+        let username = account_name.ok_or_else(|| {
+            "Steam approved the login, but DepotDownloader did not report the account name. Enter your username and try again.".to_string()
+        })?;
+
+        let state = LoginState {
+            is_logged_in: true,
+            username: Some(username),
+            steam_id: read_saved_steam_id(&self.config_dir),
+        };
+        self.save_login_state(&state)?;
+        self.emit_login_success();
+        log::info!("QR login successful");
+        Ok(())
+    }
+
+    fn emit_qr_challenge(&self, session_id: u64, challenge: crate::depot_output::QrChallenge) {
+        self.emit_event(DepotDownloaderEvent::QrChallenge {
+            session_id,
+            art: challenge.art,
+        });
+    }
+
     /// Steam 로그인 (interactive): monitors output, emits code request, waits for code via channel, then writes to stdin.
     pub async fn login_interactive(
         &self,
@@ -790,7 +964,7 @@ impl DepotDownloader {
             "Saved login state: {}",
             self.login_state_path().to_string_lossy()
         );
-        self.emit_event(DepotDownloaderEvent::LoginSuccess);
+        self.emit_login_success();
         Ok(())
     }
 
@@ -802,6 +976,56 @@ impl DepotDownloader {
         task: Option<DownloadTaskContext>,
         cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
     ) -> Result<(), String> {
+        self.run_depot(manifest_id, output_dir, task, cancel, vec![])
+            .await
+            .map(|_| ())
+    }
+
+    /// Re-check an existing install against `manifest_id` (`-validate`).
+    /// Returns the files DepotDownloader had to fetch again, relative to `output_dir`.
+    /// Validation prints every file it checks, so only files that were missing or
+    /// changed on disk during the run count as repaired.
+    pub async fn validate_depot(
+        &self,
+        manifest_id: String,
+        output_dir: PathBuf,
+        task: Option<DownloadTaskContext>,
+        cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
+    ) -> Result<Vec<String>, String> {
+        let before = file_stamps(&output_dir)?;
+        let files = self
+            .run_depot(
+                Some(manifest_id),
+                output_dir.clone(),
+                task,
+                cancel,
+                vec!["-validate".to_string()],
+            )
+            .await?;
+        let mut repaired: Vec<String> = files
+            .iter()
+            .map(|f| {
+                let path = Path::new(f);
+                path.strip_prefix(&output_dir).unwrap_or(path).to_path_buf()
+            })
+            .filter(|rel| before.get(rel) != file_stamp(&output_dir.join(rel)).as_ref())
+            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+            .collect();
+        repaired.sort();
+        repaired.dedup();
+        Ok(repaired)
+    }
+
+    /// Run DepotDownloader with the remembered login; `extra_args` are appended.
+    /// Returns the files it wrote, as printed in its progress lines.
+    async fn run_depot(
+        &self,
+        manifest_id: Option<String>,
+        output_dir: PathBuf,
+        task: Option<DownloadTaskContext>,
+        cancel: Option<Arc<std::sync::atomic::AtomicBool>>,
+        extra_args: Vec<String>,
+    ) -> Result<Vec<String>, String> {
         let login_state = self.get_login_state();
         if !login_state.is_logged_in {
             return Err("Not logged in. Please login first.".to_string());
@@ -832,6 +1056,7 @@ impl DepotDownloader {
             args.push("-manifest".to_string());
             args.push(manifest);
         }
+        args.extend(extra_args);
 
         let mut child = hide_console_window(Command::new(&self.executable_path))
             .args(&args)
@@ -848,10 +1073,10 @@ impl DepotDownloader {
         spawn_output_reader(stdout, false, tx.clone());
         spawn_output_reader(stderr, true, tx.clone());
 
+        let mut parser = DepotOutputParser::new();
         let mut last_task_progress_bp: Option<u64> = None;
-        // If we have seen any progress >= 0.01% (basis point >= 1),
-        // do NOT treat "no output for 30s" as an auth prompt.
-        let mut last_progress_bp: u64 = 0;
+        // Files DepotDownloader wrote during this run (progress lines carry the path).
+        let mut written_files: Vec<String> = vec![];
         let mut last_output_at = Instant::now();
         let mut idle_ticks = tokio::time::interval(Duration::from_millis(500));
         let status = loop {
@@ -864,18 +1089,26 @@ impl DepotDownloader {
                         return Err("Cancelled".to_string());
                     }
                     if last_output_at.elapsed() > Duration::from_secs(30) {
-                        // After progress has started, DepotDownloader may go quiet for a while
-                        // (large files, disk I/O). Only fail if it stays silent for a long time.
-                        if last_progress_bp >= 1 {
+                        // After the depot download has started, DepotDownloader may go quiet for a
+                        // while (large files, disk I/O). Only fail if it stays silent for a long time.
+                        if matches!(parser.phase(), DepotPhase::Downloading | DepotPhase::Finished) {
                             if last_output_at.elapsed() > Duration::from_secs(300) {
                                 let _ = child.kill().await;
                                 let err = "Download stalled (no output for 5 minutes). Please retry.".to_string();
                                 self.emit_event(DepotDownloaderEvent::Error(err.clone()));
                                 return Err(err);
                             }
+                        } else if parser.phase() == DepotPhase::Authenticating {
+                            // Logged in sessions pass this quickly; silence means Steam is
+                            // waiting for a code or confirmation nobody can give here.
+                            let _ = child.kill().await;
+                            let err = DepotFailure::AuthRequired(AuthPrompt::Password).message();
+                            self.emit_event(DepotDownloaderEvent::AuthRequired(err.clone()));
+                            self.emit_event(DepotDownloaderEvent::Error(err.clone()));
+                            return Err(err);
                         } else {
                             let _ = child.kill().await;
-                            let err = "Steam Guard / login required. Please login and try again.".to_string();
+                            let err = "Could not connect to Steam (no response for 30 seconds). Check your connection and retry.".to_string();
                             self.emit_event(DepotDownloaderEvent::Error(err.clone()));
                             return Err(err);
                         }
@@ -889,36 +1122,54 @@ impl DepotDownloader {
                         return Err("Cancelled".to_string());
                     }
                     last_output_at = Instant::now();
-                    let l = line.to_lowercase();
-                    let auth_prompt =
-                        (!l.contains("pre-allocating") && !l.contains("%")) && ( //This used to just pray the user didn't have any of these search terms in the downloaded file paths.
-                        l.contains("steam guard")
-                        || l.contains("two-factor")
-                        || l.contains("two factor")
-                        || l.contains("2fa")
-                        || (l.contains("enter") && l.contains("code"))
-                        || (l.contains("enter") && l.contains("password"))
-                        || l.contains("authentication code")
-                        || l.contains("emailed")
-                        || l.contains("use the steam mobile app to confirm"));
-                    if auth_prompt {
-                        // Downloads are non-interactive. If Steam auth is required here,
-                        // instruct the UI to run an interactive login first.
-                        let _ = child.kill().await;
-                        let err = "Steam Guard / login required. Please login and try again.".to_string();
-                        self.emit_event(DepotDownloaderEvent::Error(err.clone()));
-                        return Err(err);
-                    }
+                    let line = strip_ansi(&line);
                     if is_stderr {
-                        let line = strip_ansi(&line);
                         log::error!("DepotDownloader error: {}", line);
-                        self.emit_event(DepotDownloaderEvent::Output(format!("ERROR: {}", line)));
                     } else {
-                        let line = strip_ansi(&line);
                         log::info!("DepotDownloader: {}", line);
-                        if let Some(progress) = self.parse_progress(&line) {
-                            // Track last seen progress so we can distinguish auth prompts from stalls.
-                            last_progress_bp = progress.0;
+                    }
+
+                    match parser.feed(&line) {
+                        DepotLine::AuthPrompt(prompt) => {
+                            // Downloads are non-interactive. If Steam auth is required here,
+                            // instruct the UI to run an interactive login first.
+                            let _ = child.kill().await;
+                            let err = DepotFailure::AuthRequired(prompt).message();
+                            self.emit_event(DepotDownloaderEvent::AuthRequired(err.clone()));
+                            self.emit_event(DepotDownloaderEvent::Error(err.clone()));
+                            return Err(err);
+                        }
+                        DepotLine::DiskFull(detail) => {
+                            // DepotDownloader keeps retrying chunks; there is no point waiting.
+                            let _ = child.kill().await;
+                            self.emit_event(DepotDownloaderEvent::DiskFull(detail));
+                            return Err(parser.exit_error(None));
+                        }
+                        DepotLine::RateLimited(detail) => {
+                            self.emit_event(DepotDownloaderEvent::RateLimited(detail));
+                        }
+                        DepotLine::LicenseMissing(detail) => {
+                            self.emit_event(DepotDownloaderEvent::LicenseMissing(detail));
+                        }
+                        DepotLine::ManifestNotFound(detail) => {
+                            self.emit_event(DepotDownloaderEvent::ManifestNotFound(detail));
+                        }
+                        DepotLine::LoginFailed(detail) => {
+                            self.emit_event(DepotDownloaderEvent::LoginFailed(detail));
+                        }
+                        DepotLine::Completed { downloaded_bytes } => {
+                            if let Some(bytes) = downloaded_bytes {
+                                log::info!("DepotDownloader transferred {bytes} bytes");
+                            }
+                        }
+                        DepotLine::Progress { basis_points, path } => {
+                            let progress = (basis_points, 10_000u64);
+                            if let Some(path) = path {
+                                if written_files.last() != Some(&path) {
+                                    written_files.push(path.clone());
+                                    self.emit_event(DepotDownloaderEvent::FileDownloaded(path));
+                                }
+                            }
                             self.emit_event(DepotDownloaderEvent::Progress {
                                 current: progress.0,
                                 total: progress.1,
@@ -931,14 +1182,8 @@ impl DepotDownloader {
                                     last_task_progress_bp = Some(progress.0);
                                     let step_progress = (progress.0 as f64) / (progress.1 as f64);
 
-                                    // Use remaining text after the % token as a small detail (file path).
-                                    let s = line.trim_start();
-                                    let pct_part = s.split_whitespace().next().unwrap_or("");
-                                    let detail = s
-                                        .get(pct_part.len()..)
-                                        .unwrap_or("")
-                                        .trim_start()
-                                        .to_string();
+                                    // Use the file path as a small detail.
+                                    let detail = written_files.last().cloned().unwrap_or_default();
 
                                     progress::emit_progress(
                                         &self.app,
@@ -963,18 +1208,25 @@ impl DepotDownloader {
                                 }
                             }
                         }
+                        _ => {}
+                    }
+
+                    if is_stderr {
+                        self.emit_event(DepotDownloaderEvent::Output(format!("ERROR: {}", line)));
+                    } else {
                         self.emit_event(DepotDownloaderEvent::Output(line));
                     }
                 }
             }
         };
 
-        if status.success() {
+        // Some failures (missing license, unknown manifest) still exit with 0.
+        if status.success() && parser.failure().is_none() {
             log::info!("Download completed successfully");
             self.emit_event(DepotDownloaderEvent::DownloadComplete);
-            Ok(())
+            Ok(written_files)
         } else {
-            let err = "Steam Guard / login required. Please login and try again.".to_string();
+            let err = parser.exit_error(status.code());
             self.emit_event(DepotDownloaderEvent::Error(err.clone()));
             Err(err)
         }
@@ -986,130 +1238,27 @@ impl DepotDownloader {
         file_list: Vec<String>,
         output_dir: PathBuf,
     ) -> Result<(), String> {
-        let login_state = self.get_login_state();
-        if !login_state.is_logged_in {
-            return Err("Not logged in. Please login first.".to_string());
-        }
-        let username = login_state.username.clone().ok_or_else(|| {
-            "Missing username for remembered login. Please login again.".to_string()
-        })?;
-
-        std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
-
         // 파일 목록을 임시 파일로 저장
         let filelist_path = self.config_dir.join("filelist.txt");
         std::fs::write(&filelist_path, file_list.join("\n")).map_err(|e| e.to_string())?;
 
-        let args = vec![
-            // Enable IPC mode if patched.
-            "-app".to_string(),
-            Self::APP_ID.to_string(),
-            "-depot".to_string(),
-            Self::DEPOT_ID.to_string(),
-            "-dir".to_string(),
-            output_dir.to_string_lossy().to_string(),
-            "-filelist".to_string(),
-            filelist_path.to_string_lossy().to_string(),
-            "-username".to_string(),
-            username,
-            "-remember-password".to_string(),
-        ];
-        let mut args = args;
-        if self.ipc_mode {
-            args.insert(0, "-ipc".to_string());
-        }
-
         log::info!("Downloading {} files from depot", file_list.len());
-
-        let mut child = hide_console_window(Command::new(&self.executable_path))
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(&self.config_dir)
-            .spawn()
-            .map_err(|e| format!("Failed to spawn DepotDownloader: {e}"))?;
-
-        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
-
-        let (tx, mut rx) = mpsc::unbounded_channel::<(bool, String)>(); // (is_stderr, line)
-        spawn_output_reader(stdout, false, tx.clone());
-        spawn_output_reader(stderr, true, tx.clone());
-
-        // Same logic as download(): once we've seen progress, don't treat short silence as auth.
-        let mut last_progress_bp: u64 = 0;
-        let mut last_output_at = Instant::now();
-        let mut idle_ticks = tokio::time::interval(Duration::from_millis(500));
-        let status = loop {
-            tokio::select! {
-                s = child.wait() => break s.map_err(|e| e.to_string())?,
-                _ = idle_ticks.tick() => {
-                    if last_output_at.elapsed() > Duration::from_secs(30) {
-                        if last_progress_bp >= 1 {
-                            if last_output_at.elapsed() > Duration::from_secs(300) {
-                                let _ = child.kill().await;
-                                // 임시 파일 정리
-                                let _ = std::fs::remove_file(&filelist_path);
-                                return Err("Download stalled (no output for 5 minutes). Please retry.".to_string());
-                            }
-                        } else {
-                            let _ = child.kill().await;
-                            // 임시 파일 정리
-                            let _ = std::fs::remove_file(&filelist_path);
-                            return Err("Steam Guard / login required. Please login and try again.".to_string());
-                        }
-                    }
-                }
-                msg = rx.recv() => {
-                    let Some((is_stderr, line)) = msg else { continue; };
-                    last_output_at = Instant::now();
-                    let l = line.to_lowercase();
-                    let auth_prompt =
-                        l.contains("steam guard")
-                        || l.contains("two-factor")
-                        || l.contains("two factor")
-                        || l.contains("2fa")
-                        || (l.contains("enter") && l.contains("code"))
-                        || (l.contains("enter") && l.contains("password"))
-                        || l.contains("authentication code")
-                        || l.contains("emailed")
-                        || l.contains("use the steam mobile app to confirm");
-                    if auth_prompt {
-                        let _ = child.kill().await;
-                        // 임시 파일 정리
-                        let _ = std::fs::remove_file(&filelist_path);
-                        return Err("Steam Guard / login required. Please login and try again.".to_string());
-                    }
-                    if is_stderr {
-                        let line = strip_ansi(&line);
-                        log::error!("DepotDownloader error: {}", line);
-                        self.emit_event(DepotDownloaderEvent::Output(format!("ERROR: {}", line)));
-                    } else {
-                        let line = strip_ansi(&line);
-                        log::info!("DepotDownloader: {}", line);
-                        if let Some(progress) = self.parse_progress(&line) {
-                            last_progress_bp = progress.0;
-                            self.emit_event(DepotDownloaderEvent::Progress {
-                                current: progress.0,
-                                total: progress.1,
-                            });
-                        }
-                        self.emit_event(DepotDownloaderEvent::Output(line));
-                    }
-                }
-            }
-        };
+        let res = self
+            .run_depot(
+                None,
+                output_dir,
+                None,
+                None,
+                vec![
+                    "-filelist".to_string(),
+                    filelist_path.to_string_lossy().to_string(),
+                ],
+            )
+            .await;
 
         // 임시 파일 정리
         let _ = std::fs::remove_file(&filelist_path);
-
-        if status.success() {
-            log::info!("File download completed");
-            self.emit_event(DepotDownloaderEvent::DownloadComplete);
-            Ok(())
-        } else {
-            Err("Steam Guard / login required. Please login and try again.".to_string())
-        }
+        res.map(|_| ())
     }
 
     /// 로그아웃
@@ -1145,25 +1294,10 @@ impl DepotDownloader {
         Ok(())
     }
 
-    /// 진행률 파싱 (DepotDownloader 출력 형식에 맞게 조정 필요)
-    fn parse_progress(&self, line: &str) -> Option<(u64, u64)> {
-        // DepotDownloader prints progress lines like:
-        // " 28.91% C:\path\to\file"
-        // Use basis points (0.01%) to preserve decimals:
-        // current=2891, total=10000 → UI can compute percent = current/total*100
-        let s = line.trim_start();
-        if !s.contains('%') {
-            return None;
-        }
-        let pct_part = s.split_whitespace().next()?;
-        let pct_str = pct_part.strip_suffix('%')?;
-        let pct: f64 = pct_str.parse().ok()?;
-        if !pct.is_finite() {
-            return None;
-        }
-        let clamped = pct.clamp(0.0, 100.0);
-        let basis_points = (clamped * 100.0).round() as u64;
-        Some((basis_points, 10_000))
+    /// Also resumes a download queue that was waiting for the login.
+    fn emit_login_success(&self) {
+        self.emit_event(DepotDownloaderEvent::LoginSuccess);
+        crate::pump_download_queue(&self.app);
     }
 
     /// 이벤트 발생
@@ -1191,12 +1325,9 @@ impl DepotDownloader {
     }
 }
 
+/// Config dir of the active saved Steam account (see `steam_accounts`).
 fn depot_config_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let app_data = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?;
-    let config_dir = app_data.join("depot_config");
+    let config_dir = crate::steam_accounts::active_dir(app)?;
     std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
     Ok(config_dir)
 }
@@ -1244,22 +1375,24 @@ fn read_saved_steam_id(config_dir: &Path) -> Option<String> {
     None
 }
 
-fn read_saved_login_state(app: &tauri::AppHandle) -> Result<LoginState, String> {
-    let path = depot_login_state_path(app)?;
-    let config_dir = depot_config_dir(app)?;
-    if let Ok(content) = std::fs::read_to_string(path) {
+pub(crate) fn read_saved_login_state(app: &tauri::AppHandle) -> Result<LoginState, String> {
+    Ok(login_state_in(&depot_config_dir(app)?))
+}
+
+fn login_state_in(config_dir: &Path) -> LoginState {
+    if let Ok(content) = std::fs::read_to_string(config_dir.join("login_state.json")) {
         if let Ok(mut state) = serde_json::from_str::<LoginState>(&content) {
             if state.steam_id.as_deref().is_none_or(|id| id.trim().is_empty()) {
-                state.steam_id = read_saved_steam_id(&config_dir);
+                state.steam_id = read_saved_steam_id(config_dir);
             }
-            return Ok(state);
+            return state;
         }
     }
-    Ok(LoginState {
+    LoginState {
         is_logged_in: false,
         username: None,
-        steam_id: read_saved_steam_id(&config_dir),
-    })
+        steam_id: read_saved_steam_id(config_dir),
+    }
 }
 
 fn write_saved_login_state(app: &tauri::AppHandle, state: &LoginState) -> Result<(), String> {
@@ -1559,6 +1692,68 @@ pub async fn depot_login_start(
     Ok(session_id)
 }
 
+/// Start a QR login session and return its session_id immediately. The running
+/// process emits `QrChallenge` events until the Steam mobile app approves the login,
+/// then `LoginSuccess`/`Error`. `username` is only needed when DepotDownloader does not
+/// print the account name.
+#[tauri::command]
+pub async fn depot_login_qr_start(
+    app: tauri::AppHandle,
+    login_state: tauri::State<'_, DepotLoginState>,
+    username: Option<String>,
+) -> Result<u64, String> {
+    ensure_downloader_ready(&app).await?;
+
+    let session_id = login_state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    {
+        let mut map = login_state
+            .sessions
+            .lock()
+            .map_err(|_| "login state lock poisoned".to_string())?;
+        map.insert(session_id, tx);
+    }
+
+    let app2 = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let downloader = match DepotDownloader::new(&app2) {
+            Ok(d) => d,
+            Err(e) => {
+                let _ = app2.emit("depot-downloader", DepotDownloaderEvent::Error(e));
+                return;
+            }
+        };
+
+        let res = downloader.login_qr(session_id, username, &mut rx).await;
+
+        {
+            let state = app2.state::<DepotLoginState>();
+            if let Ok(mut map) = state.sessions.lock() {
+                map.remove(&session_id);
+            };
+        }
+
+        if let Err(err) = res {
+            downloader.emit_event(DepotDownloaderEvent::Error(err));
+        }
+    });
+
+    Ok(session_id)
+}
+
+/// Stop a QR login session started with `depot_login_qr_start`.
+#[tauri::command]
+pub fn depot_login_qr_cancel(
+    login_state: tauri::State<'_, DepotLoginState>,
+    session_id: u64,
+) -> Result<bool, String> {
+    let mut map = login_state
+        .sessions
+        .lock()
+        .map_err(|_| "login state lock poisoned".to_string())?;
+    Ok(map.remove(&session_id).is_some())
+}
+
 #[tauri::command]
 pub fn depot_login_submit_code(
     login_state: tauri::State<'_, DepotLoginState>,
@@ -1616,8 +1811,12 @@ pub fn depot_logout(app: tauri::AppHandle) -> Result<(), String> {
         },
     )?;
 
-    // Best-effort cleanup of remembered files in config dir.
-    let config_dir = depot_config_dir(&app)?;
+    clear_remembered_login(&depot_config_dir(&app)?);
+    Ok(())
+}
+
+/// Best-effort cleanup of remembered files in a config dir.
+fn clear_remembered_login(config_dir: &Path) {
     let config_files = ["config.vdf", ".DepotDownloader"];
     for filename in &config_files {
         let path = config_dir.join(filename);
@@ -1627,7 +1826,7 @@ pub fn depot_logout(app: tauri::AppHandle) -> Result<(), String> {
     }
 
     // ssfn* pattern files
-    if let Ok(entries) = std::fs::read_dir(&config_dir) {
+    if let Ok(entries) = std::fs::read_dir(config_dir) {
         for entry in entries.flatten() {
             if let Ok(name) = entry.file_name().into_string() {
                 if name.starts_with("ssfn") {
@@ -1636,8 +1835,87 @@ pub fn depot_logout(app: tauri::AppHandle) -> Result<(), String> {
             }
         }
     }
+}
 
-    Ok(())
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamAccountStatus {
+    #[serde(flatten)]
+    pub account: crate::steam_accounts::SteamAccount,
+    pub active: bool,
+    pub login: LoginState,
+}
+
+pub(crate) fn account_statuses(app: &tauri::AppHandle) -> Result<Vec<SteamAccountStatus>, String> {
+    let accounts = crate::steam_accounts::load(app)?;
+    let active = accounts.active_account().map(|a| a.id.clone());
+    accounts
+        .accounts
+        .iter()
+        .map(|account| {
+            Ok(SteamAccountStatus {
+                account: account.clone(),
+                active: active.as_deref() == Some(account.id.as_str()),
+                login: login_state_in(&crate::steam_accounts::dir_for(app, &account.id)?),
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn depot_list_accounts(app: tauri::AppHandle) -> Result<Vec<SteamAccountStatus>, String> {
+    account_statuses(&app)
+}
+
+/// Add an empty account and make it active; the next `depot_login` signs in to it.
+#[tauri::command]
+pub fn depot_add_account(
+    app: tauri::AppHandle,
+    label: Option<String>,
+) -> Result<Vec<SteamAccountStatus>, String> {
+    let mut accounts = crate::steam_accounts::load(&app)?;
+    let account = accounts.add(label, crate::now_unix_secs());
+    std::fs::create_dir_all(crate::steam_accounts::dir_for(&app, &account.id)?)
+        .map_err(|e| e.to_string())?;
+    crate::steam_accounts::save(&app, &accounts)?;
+    log::info!("Added Steam account {}", account.id);
+    account_statuses(&app)
+}
+
+#[tauri::command]
+pub fn depot_switch_account(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<Vec<SteamAccountStatus>, String> {
+    let mut accounts = crate::steam_accounts::load(&app)?;
+    accounts.switch(&id)?;
+    crate::steam_accounts::save(&app, &accounts)?;
+    log::info!("Switched to Steam account {id}");
+    crate::pump_download_queue(&app);
+    account_statuses(&app)
+}
+
+/// Forget an account and its remembered login. Refused while a download runs, since
+/// DepotDownloader reads the login from the folder this deletes.
+#[tauri::command]
+pub fn depot_remove_account(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<Vec<SteamAccountStatus>, String> {
+    crate::ensure_no_download_running(&app)?;
+    let mut accounts = crate::steam_accounts::load(&app)?;
+    accounts.remove(&id)?;
+    let dir = crate::steam_accounts::dir_for(&app, &id)?;
+    if id == crate::steam_accounts::LEGACY_ACCOUNT_ID {
+        // `depot_config` may hold other launcher files; only drop the login.
+        let _ = std::fs::remove_file(dir.join("login_state.json"));
+        clear_remembered_login(&dir);
+        let _ = std::fs::remove_dir_all(dir.join("_login_cache"));
+    } else if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    crate::steam_accounts::save(&app, &accounts)?;
+    log::info!("Removed Steam account {id}");
+    account_statuses(&app)
 }
 
 #[tauri::command]
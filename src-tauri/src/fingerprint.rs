@@ -0,0 +1,326 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+// Folder names and marker files say what a folder is supposed to be; hashes of a
+// few key files say what it actually is. Known hashes come from the remote manifest
+// (`fingerprints`) and from every install the launcher completes itself.
+pub const ASSEMBLY_CSHARP: &str = "Lethal Company_Data/Managed/Assembly-CSharp.dll";
+pub const GAME_EXE: &str = "Lethal Company.exe";
+const KEY_FILES: [&str; 2] = [ASSEMBLY_CSHARP, GAME_EXE];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FingerprintSource {
+    #[default]
+    Remote,
+    Learned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionFingerprint {
+    pub version: u32,
+    #[serde(default)]
+    pub depot_manifest: Option<String>,
+    /// Key file (relative, `/`-separated) -> lowercase sha256.
+    pub files: BTreeMap<String, String>,
+    #[serde(default)]
+    pub source: FingerprintSource,
+}
+
+/// Learned fingerprints are persisted at `config/version_fingerprints.json`; remote
+/// ones are merged in at load time and never written back.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FingerprintDb {
+    #[serde(default)]
+    pub entries: Vec<VersionFingerprint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Detection {
+    Detected {
+        version: u32,
+        depot_manifest: Option<String>,
+    },
+    /// Several versions shipped the same key files.
+    Ambiguous(Vec<u32>),
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FolderStatus {
+    Match,
+    Mismatch,
+    Ambiguous,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderCheck {
+    pub path: String,
+    /// Version implied by a `v{N}` folder name.
+    pub folder_version: Option<u32>,
+    pub detected_version: Option<u32>,
+    pub depot_manifest: Option<String>,
+    pub candidates: Vec<u32>,
+    pub status: FolderStatus,
+}
+
+impl FingerprintDb {
+    pub fn with_remote(mut self, remote: Vec<VersionFingerprint>) -> Self {
+        self.entries.extend(remote.into_iter().map(|mut fp| {
+            fp.source = FingerprintSource::Remote;
+            fp
+        }));
+        self
+    }
+
+    /// Record what an install of `version` looks like, replacing an earlier learned
+    /// entry for the same version and depot manifest.
+    pub fn learn(&mut self, fingerprint: VersionFingerprint) {
+        self.entries.retain(|fp| {
+            !(fp.source == FingerprintSource::Learned
+                && fp.version == fingerprint.version
+                && fp.depot_manifest == fingerprint.depot_manifest)
+        });
+        self.entries.push(VersionFingerprint {
+            source: FingerprintSource::Learned,
+            ..fingerprint
+        });
+    }
+
+    pub fn identify(&self, hashes: &BTreeMap<String, String>) -> Detection {
+        // Assembly-CSharp changes with every game update; the executable often doesn't,
+        // so it only narrows down otherwise ambiguous matches.
+        let Some(assembly) = hashes.get(ASSEMBLY_CSHARP) else {
+            return Detection::Unknown;
+        };
+        let mut candidates: Vec<&VersionFingerprint> = self
+            .entries
+            .iter()
+            .filter(|fp| fp.files.get(ASSEMBLY_CSHARP) == Some(assembly))
+            .collect();
+        if candidates.len() > 1 {
+            let narrowed: Vec<&VersionFingerprint> = candidates
+                .iter()
+                .copied()
+                .filter(|fp| {
+                    fp.files
+                        .iter()
+                        .all(|(file, hash)| hashes.get(file).is_none_or(|h| h == hash))
+                })
+                .collect();
+            if !narrowed.is_empty() {
+                candidates = narrowed;
+            }
+        }
+
+        let versions: BTreeSet<u32> = candidates.iter().map(|fp| fp.version).collect();
+        match versions.len() {
+            0 => Detection::Unknown,
+            1 => Detection::Detected {
+                version: candidates[0].version,
+                depot_manifest: candidates.iter().find_map(|fp| fp.depot_manifest.clone()),
+            },
+            _ => Detection::Ambiguous(versions.into_iter().collect()),
+        }
+    }
+
+    pub fn check_folder(&self, path: &Path) -> Result<FolderCheck, String> {
+        let folder_version = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix('v'))
+            .and_then(|n| n.parse::<u32>().ok());
+        let hashes = hash_key_files(path)?;
+
+        let mut check = FolderCheck {
+            path: path.to_string_lossy().to_string(),
+            folder_version,
+            detected_version: None,
+            depot_manifest: None,
+            candidates: vec![],
+            status: FolderStatus::Unknown,
+        };
+        match self.identify(&hashes) {
+            Detection::Detected {
+                version,
+                depot_manifest,
+            } => {
+                check.detected_version = Some(version);
+                check.depot_manifest = depot_manifest;
+                check.candidates = vec![version];
+                check.status = match folder_version {
+                    Some(v) if v != version => FolderStatus::Mismatch,
+                    _ => FolderStatus::Match,
+                };
+            }
+            Detection::Ambiguous(versions) => {
+                check.status = match folder_version {
+                    Some(v) if versions.contains(&v) => FolderStatus::Match,
+                    Some(_) => FolderStatus::Mismatch,
+                    None => FolderStatus::Ambiguous,
+                };
+                check.candidates = versions;
+            }
+            Detection::Unknown => {}
+        }
+        Ok(check)
+    }
+}
+
+/// Hashes of the key files present under `game_root`.
+pub fn hash_key_files(game_root: &Path) -> Result<BTreeMap<String, String>, String> {
+    let mut out = BTreeMap::new();
+    for rel in KEY_FILES {
+        let path = game_root.join(rel);
+        if path.is_file() {
+            out.insert(rel.to_string(), crate::dedup_store::hash_file(&path)?);
+        }
+    }
+    Ok(out)
+}
+
+fn db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("config")
+        .join("version_fingerprints.json"))
+}
+
+/// Learned fingerprints only; see [`FingerprintDb::with_remote`].
+pub fn load(app: &tauri::AppHandle) -> Result<FingerprintDb, String> {
+    let path = db_path(app)?;
+    if !path.exists() {
+        return Ok(FingerprintDb::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+fn save(app: &tauri::AppHandle, db: &FingerprintDb) -> Result<(), String> {
+    let path = db_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let learned = FingerprintDb {
+        entries: db
+            .entries
+            .iter()
+            .filter(|fp| fp.source == FingerprintSource::Learned)
+            .cloned()
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&learned).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Remember the key files of a folder the launcher installed (or verified) itself.
+pub fn learn_installed(
+    app: &tauri::AppHandle,
+    version: u32,
+    depot_manifest: &str,
+    game_root: &Path,
+) -> Result<(), String> {
+    let files = hash_key_files(game_root)?;
+    if !files.contains_key(ASSEMBLY_CSHARP) {
+        return Err(format!("{ASSEMBLY_CSHARP} is missing"));
+    }
+    let mut db = load(app)?;
+    db.learn(VersionFingerprint {
+        version,
+        depot_manifest: Some(depot_manifest.to_string()),
+        files,
+        source: FingerprintSource::Learned,
+    });
+    save(app, &db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn fp(version: u32, assembly: &str, exe: &str) -> VersionFingerprint {
+        VersionFingerprint {
+            version,
+            depot_manifest: Some(format!("m{version}")),
+            files: BTreeMap::from([
+                (ASSEMBLY_CSHARP.to_string(), assembly.to_string()),
+                (GAME_EXE.to_string(), exe.to_string()),
+            ]),
+            source: FingerprintSource::Remote,
+        }
+    }
+
+    fn hashes(assembly: &str, exe: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            (ASSEMBLY_CSHARP.to_string(), assembly.to_string()),
+            (GAME_EXE.to_string(), exe.to_string()),
+        ])
+    }
+
+    #[test]
+    fn identifies_by_assembly_and_narrows_with_exe() {
+        let db = FingerprintDb::default().with_remote(vec![
+            fp(72, "a72", "exe1"),
+            fp(73, "a73", "exe1"),
+            // Hotfix that only rebuilt the executable.
+            fp(74, "a73", "exe2"),
+        ]);
+
+        assert_eq!(
+            db.identify(&hashes("a72", "exe1")),
+            Detection::Detected {
+                version: 72,
+                depot_manifest: Some("m72".to_string())
+            }
+        );
+        assert!(matches!(
+            db.identify(&hashes("a73", "exe2")),
+            Detection::Detected { version: 74, .. }
+        ));
+        let mut no_exe = hashes("a73", "");
+        no_exe.remove(GAME_EXE);
+        assert_eq!(db.identify(&no_exe), Detection::Ambiguous(vec![73, 74]));
+        assert_eq!(db.identify(&hashes("zzz", "exe1")), Detection::Unknown);
+    }
+
+    #[test]
+    fn learning_replaces_the_same_install() {
+        let mut db = FingerprintDb::default().with_remote(vec![fp(72, "a72", "exe1")]);
+        db.learn(fp(73, "old", "exe1"));
+        db.learn(fp(73, "new", "exe1"));
+
+        let learned: Vec<_> = db
+            .entries
+            .iter()
+            .filter(|fp| fp.source == FingerprintSource::Learned)
+            .collect();
+        assert_eq!(learned.len(), 1);
+        assert_eq!(learned[0].files[ASSEMBLY_CSHARP], "new");
+        assert_eq!(db.entries.len(), 2);
+    }
+
+    #[test]
+    fn folder_named_for_another_version_is_a_mismatch() {
+        let dir = temp_dir("fingerprint");
+        let root = dir.join("v73");
+        let managed = root.join("Lethal Company_Data/Managed");
+        std::fs::create_dir_all(&managed).unwrap();
+        std::fs::write(managed.join("Assembly-CSharp.dll"), b"v72 assembly").unwrap();
+
+        let assembly = crate::dedup_store::hash_file(&managed.join("Assembly-CSharp.dll")).unwrap();
+        let db = FingerprintDb::default().with_remote(vec![fp(72, &assembly, "exe1")]);
+        let check = db.check_folder(&root).unwrap();
+        assert_eq!(check.folder_version, Some(73));
+        assert_eq!(check.detected_version, Some(72));
+        assert_eq!(check.status, FolderStatus::Mismatch);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
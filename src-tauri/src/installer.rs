@@ -14,6 +14,7 @@ use crate::downloader::{self, DownloadTaskContext};
 use crate::mod_config::{ModEntry, ModsConfig};
 use crate::mods;
 use crate::progress::{self, TaskErrorPayload, TaskFinishedPayload, TaskProgressPayload};
+use crate::version_meta::{PatchStatus, UpdateOperation, VersionMetadata};
 use crate::zip_utils::{self, extract_zip_with_progress};
 use progress::{emit_error, emit_finished, emit_progress};
 
@@ -41,12 +42,9 @@ const UNITY_APP_PATCHER_NAME: &str = "UnityApplicationPatcherCLI.exe";
 #[cfg(any(target_os = "macos", target_os = "linux"))]
 const UNITY_APP_PATCHER_NAME: &str = "UnityApplicationPatcherCLI";
 
-// Proton-GE (Linux): download and extract into AppData/proton_env/proton/.
+// Proton-GE (Linux): download and extract into AppData/proton_env/proton/<tag>/.
 #[cfg(target_os = "linux")]
-const PROTON_GE_VERSION: &str = "GE-Proton10-28";
-#[cfg(target_os = "linux")]
-const PROTON_GE_URL: &str =
-    "https://github.com/GloriousEggroll/proton-ge-custom/releases/download/GE-Proton10-28/GE-Proton10-28.tar.gz";
+const PROTON_GE_VERSION: &str = crate::runtimes::BUNDLED_PROTON_GE;
 
 fn overall_from_step(step: u32, step_progress: f64, steps_total: u32) -> f64 {
     let s = step.max(1).min(steps_total) as f64;
@@ -104,6 +102,9 @@ fn has_legacy_complete_files(path: &Path) -> bool {
 }
 
 fn is_complete_version_dir(app: &tauri::AppHandle, version: u32, path: &Path) -> bool {
+    if let Some(meta) = crate::version_meta::read(path) {
+        return meta.is_complete();
+    }
     path.join(INSTALL_COMPLETE_MARKER).is_file()
         || (raw_manifest_state_has_version(app, version) && has_legacy_complete_files(path))
 }
@@ -136,28 +137,6 @@ fn dir_has_any_entries(path: &Path) -> bool {
         .is_some()
 }
 
-#[cfg(target_os = "linux")]
-fn list_other_proton_ge_dirs(proton_root: &Path) -> Vec<PathBuf> {
-    let mut out = vec![];
-    let Ok(rd) = std::fs::read_dir(proton_root) else {
-        return out;
-    };
-    for e in rd.flatten() {
-        let path = e.path();
-        let Ok(ty) = e.file_type() else { continue };
-        if !ty.is_dir() {
-            continue;
-        }
-        let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
-            continue;
-        };
-        if name.starts_with("GE-Proton") && name != PROTON_GE_VERSION {
-            out.push(path);
-        }
-    }
-    out
-}
-
 pub fn proton_root_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(app
         .path()
@@ -231,11 +210,7 @@ pub fn get_current_proton_dir_impl(app: &tauri::AppHandle) -> Result<Option<Path
     Ok(candidates.pop())
 }
 
-/// Install Proton-GE under `AppDataDir/proton_env/proton/` (Linux only).
-///
-/// Behavior:
-/// - If `.../proton/GE-Proton10-28/` already exists, do nothing.
-/// - Otherwise download `GE-Proton10-28.tar.gz`, extract safely, then move into place.
+/// Install the bundled Proton-GE build (Linux only); see [`install_proton_ge_release`].
 pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, String> {
     #[cfg(not(target_os = "linux"))]
     {
@@ -243,13 +218,42 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
         return Ok(false);
     }
 
+    #[cfg(target_os = "linux")]
+    {
+        let url = format!(
+            "https://github.com/GloriousEggroll/proton-ge-custom/releases/download/{PROTON_GE_VERSION}/{PROTON_GE_VERSION}.tar.gz"
+        );
+        install_proton_ge_release(app, PROTON_GE_VERSION, &url).await
+    }
+}
+
+/// Install a Proton-GE release under `AppDataDir/proton_env/proton/<tag>/` (Linux only).
+/// Other installed releases are left alone.
+///
+/// Behavior:
+/// - If `.../proton/<tag>/` already exists, do nothing.
+/// - Otherwise download the release tarball, extract safely, then move into place.
+pub async fn install_proton_ge_release(
+    app: &tauri::AppHandle,
+    tag: &str,
+    url: &str,
+) -> Result<bool, String> {
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (app, tag, url);
+        return Ok(false);
+    }
+
     #[cfg(target_os = "linux")]
     {
         use flate2::read::GzDecoder;
         use std::io::Read;
         use tar::Archive;
 
-        log::info!("Installing Proton-GE");
+        if !crate::runtimes::is_valid_tag(tag) {
+            return Err(format!("invalid Proton-GE release tag: {tag}"));
+        }
+        log::info!("Installing Proton-GE {tag}");
 
         let app_data = app
             .path()
@@ -259,7 +263,7 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
         let proton_root = app_data.join("proton_env").join("proton");
         std::fs::create_dir_all(&proton_root).map_err(|e| e.to_string())?;
 
-        let final_dir = proton_root.join(PROTON_GE_VERSION);
+        let final_dir = proton_root.join(tag);
         if final_dir.exists() && dir_has_any_entries(&final_dir) {
             // Desired version already present.
             log::info!(
@@ -278,39 +282,20 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
             let _ = std::fs::remove_dir_all(&final_dir);
         }
 
-        // If another GE-Proton version is installed, remove it and install the desired version.
-        let other_ge_dirs = list_other_proton_ge_dirs(&proton_root);
-        if !other_ge_dirs.is_empty() {
-            log::info!(
-                "Found {} other GE-Proton version(s); replacing with {}",
-                other_ge_dirs.len(),
-                PROTON_GE_VERSION
-            );
-            for d in other_ge_dirs {
-                match std::fs::remove_dir_all(&d) {
-                    Ok(()) => log::info!("Removed old Proton-GE dir: {}", d.to_string_lossy()),
-                    Err(e) => log::warn!(
-                        "Failed to remove old Proton-GE dir {}: {e}",
-                        d.to_string_lossy()
-                    ),
-                }
-            }
-        }
-
         let temp_dir = app_data.join("temp");
         std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
 
-        let tar_path = temp_dir.join(format!("{PROTON_GE_VERSION}.tar.gz"));
+        let tar_path = temp_dir.join(format!("{tag}.tar.gz"));
         log::info!(
             "Downloading Proton-GE from {} to {}",
-            PROTON_GE_URL,
+            url,
             tar_path.to_string_lossy()
         );
 
         // Stream download into file (avoid holding whole tarball in memory).
         let client = reqwest::Client::new();
         let response = client
-            .get(PROTON_GE_URL)
+            .get(url)
             .header("User-Agent", "hq-launcher/0.1 (tauri)")
             .send()
             .await
@@ -352,7 +337,7 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_millis())
             .unwrap_or(0);
-        let extract_tmp = proton_root.join(format!(".tmp_extract_{PROTON_GE_VERSION}_{ts}"));
+        let extract_tmp = proton_root.join(format!(".tmp_extract_{tag}_{ts}"));
         if extract_tmp.exists() {
             let _ = std::fs::remove_dir_all(&extract_tmp);
         }
@@ -386,14 +371,14 @@ pub async fn install_proton_ge_impl(app: &tauri::AppHandle) -> Result<bool, Stri
         .await
         .map_err(|e| e.to_string())??;
 
-        // Expect the tarball to contain a top-level folder named exactly PROTON_GE_VERSION.
-        let extracted_dir = extract_tmp.join(PROTON_GE_VERSION);
+        // Expect the tarball to contain a top-level folder named exactly tag.
+        let extracted_dir = extract_tmp.join(tag);
         if !extracted_dir.exists() {
             let _ = std::fs::remove_file(&tar_path);
             let _ = std::fs::remove_dir_all(&extract_tmp);
             return Err(format!(
                 "Proton-GE archive did not contain expected top-level folder `{}`",
-                PROTON_GE_VERSION
+                tag
             ));
         }
 
@@ -743,11 +728,19 @@ pub async fn patch_all_instances(app: &tauri::AppHandle) -> Result<(), String> {
         }
 
         patch_single_instance(app, None, &dir.1, None).await?;
+        record_patched(app, dir.0)?;
     }
 
     Ok(())
 }
 
+fn record_patched(app: &tauri::AppHandle, version: u32) -> Result<(), String> {
+    update_version_metadata(app, version, |meta| {
+        meta.patch_status = PatchStatus::Applied;
+        meta.record(crate::now_unix_secs(), UpdateOperation::Patch, None);
+    })
+}
+
 pub fn patchable_instance_count(app: &tauri::AppHandle) -> Result<usize, String> {
     Ok(installed_version_dirs(app)?
         .into_iter()
@@ -835,6 +828,7 @@ pub async fn patch_all_instances_with_progress(
             }),
         )
         .await?;
+        record_patched(app, dir.0)?;
 
         emit_progress(
             app,
@@ -870,6 +864,9 @@ pub async fn patch_single_instance(
         install_app_patcher(&app, cancel, context.clone()).await?;
     }
 
+    // The patcher rewrites the player binaries in place.
+    crate::dedup_store::unshare_version_root(version_root)?;
+
     let unity_player_path = (&version_root).join("UnityPlayer.dll");
     let Some(unity_player_location) = unity_player_path.to_str() else {
         return Err("UnityPlayer.dll not found in extracted game directory!".to_string());
@@ -1106,7 +1103,7 @@ fn is_reparse_point(path: &Path) -> Result<bool, String> {
 }
 
 #[cfg(windows)]
-fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
+pub(crate) fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
     let link_s = link.to_string_lossy().to_string();
     let target_s = target.to_string_lossy().to_string();
 
@@ -1124,7 +1121,7 @@ fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
 }
 
 #[cfg(not(windows))]
-fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
+pub(crate) fn create_dir_junction(link: &Path, target: &Path) -> Result<(), String> {
     // Prefer a directory symlink so the game config path points to the shared config dir.
     // On Linux, a bind mount would require elevated privileges; symlink is the best userland option.
     #[cfg(unix)]
@@ -1489,7 +1486,8 @@ pub fn ensure_pack_specific_configs_on_startup(app: &tauri::AppHandle) -> Result
     for (version, _) in installed_version_dirs(app)? {
         crate::ensure_reverb_trigger_fix_cfg(app, version)?;
     }
-    let _ = crate::restore_hqol_wesley_dont_store_backup_if_present(app)?;
+    crate::restore_legacy_hqol_dont_store_backup(app)?;
+    crate::cfg_patches::revert_until_exit(app, &[])?;
     Ok(())
 }
 
@@ -1522,9 +1520,11 @@ pub async fn sync_install_from_manifest_for_version(
     )?;
 
     let mut local_state = read_manifest_state(&app)?;
-    let needs_mod_sync = local_state.manifest_version != remote_manifest_version;
+    let meta = version_metadata(app, game_version)?;
+    let (local_depot_manifest, local_manifest_version) =
+        local_manifest_info(&local_state, meta.as_ref(), game_version);
+    let needs_mod_sync = local_manifest_version != remote_manifest_version;
     let remote_depot_manifest = manifests.get(&game_version).cloned();
-    let local_depot_manifest = resolve_local_depot_manifest_id(&local_state, game_version);
     let needs_depot_sync = match (
         local_depot_manifest.as_deref(),
         remote_depot_manifest.as_deref(),
@@ -1547,7 +1547,7 @@ pub async fn sync_install_from_manifest_for_version(
         "Sync required for v{}: mods_changed={} local_manifest_version={} remote_manifest_version={} local_depot_manifest={} remote_depot_manifest={}",
         game_version,
         needs_mod_sync,
-        local_state.manifest_version,
+        local_manifest_version,
         remote_manifest_version,
         local_depot_manifest.as_deref().unwrap_or("<none>"),
         remote_depot_manifest.as_deref().unwrap_or("<none>")
@@ -1586,6 +1586,15 @@ pub async fn sync_install_from_manifest_for_version(
                 },
             );
 
+            // The update rewrites files in place, which must not reach other versions
+            // through shared links. The version is deduplicated again afterwards.
+            let unshare_root = game_root.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                crate::dedup_store::unshare_version_root(&unshare_root)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+
             downloader
                 .download_depot(
                     Some(remote_depot_manifest.clone()),
@@ -1619,7 +1628,16 @@ pub async fn sync_install_from_manifest_for_version(
 
             local_state
                 .depot_manifests
-                .insert(game_version, remote_depot_manifest);
+                .insert(game_version, remote_depot_manifest.clone());
+            update_version_metadata(app, game_version, |meta| {
+                meta.depot_manifest = Some(remote_depot_manifest);
+                // The depot update restores the original player binaries.
+                if game_version < 73 {
+                    meta.patch_status = PatchStatus::NotApplied;
+                }
+                meta.record(crate::now_unix_secs(), UpdateOperation::DepotSync, None);
+            })?;
+            dedup_installed_version(app, game_version).await;
         }
 
         if needs_mod_sync {
@@ -1702,6 +1720,14 @@ pub async fn sync_install_from_manifest_for_version(
 
         local_state.manifest_version = remote_manifest_version;
         write_manifest_state(&app, &local_state)?;
+        update_version_metadata(app, game_version, |meta| {
+            meta.remote_manifest_version = Some(remote_manifest_version);
+            if needs_mod_sync {
+                let now = crate::now_unix_secs();
+                meta.last_mod_sync = Some(now);
+                meta.record(now, UpdateOperation::ModSync, None);
+            }
+        })?;
 
         Ok(())
     }
@@ -1777,9 +1803,11 @@ pub async fn check_manifest_update_for_version(
         remote;
 
     let local_state = read_manifest_state(app)?;
-    let needs_mod_sync = local_state.manifest_version != remote_manifest_version;
+    let meta = version_metadata(app, game_version)?;
+    let (local_depot_manifest, local_manifest_version) =
+        local_manifest_info(&local_state, meta.as_ref(), game_version);
+    let needs_mod_sync = local_manifest_version != remote_manifest_version;
     let remote_depot_manifest = manifests.get(&game_version).cloned();
-    let local_depot_manifest = resolve_local_depot_manifest_id(&local_state, game_version);
     let needs_depot_sync = match (
         local_depot_manifest.as_deref(),
         remote_depot_manifest.as_deref(),
@@ -1794,95 +1822,288 @@ pub async fn check_manifest_update_for_version(
         version: Some(game_version),
         needs_mod_sync,
         needs_depot_sync,
-        local_manifest_version: local_state.manifest_version,
+        local_manifest_version,
         remote_manifest_version,
         local_depot_manifest,
         remote_depot_manifest,
     })
 }
 
-pub async fn download_and_setup(
-    app: tauri::AppHandle,
+/// Depot manifest id and remote manifest revision recorded for an installed version.
+pub fn installed_manifest_info(
+    app: &tauri::AppHandle,
     version: u32,
-    cancel: Arc<AtomicBool>,
-) -> Result<bool, String> {
-    let dir = crate::storage::versions_dir(&app)?;
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    let final_dir = dir.join(format!("v{version}"));
-    let partial_dir = dir.join(format!(".v{version}.partial"));
-    let finalized = Arc::new(AtomicBool::new(false));
+) -> Result<(Option<String>, u32), String> {
+    let state = read_manifest_state(app)?;
+    let meta = version_metadata(app, version)?;
+    Ok(local_manifest_info(&state, meta.as_ref(), version))
+}
 
-    let res: Result<bool, String> = async {
-        // DepotDownloader 설치 확인
-        if let Err(e) = downloader::install_downloader(&app).await {
-            return Err(format!("Failed to install DepotDownloader: {e}"));
+/// The version's own record wins; `manifest_state.json` only covers versions that
+/// are not installed.
+fn local_manifest_info(
+    state: &ManifestState,
+    meta: Option<&VersionMetadata>,
+    version: u32,
+) -> (Option<String>, u32) {
+    (
+        meta.and_then(|m| m.depot_manifest.clone())
+            .or_else(|| resolve_local_depot_manifest_id(state, version)),
+        meta.and_then(|m| m.remote_manifest_version)
+            .unwrap_or(state.manifest_version),
+    )
+}
+
+/// Metadata record of an installed `v{version}`. Installs that predate the record
+/// get one built from `manifest_state.json`; it is only written by
+/// `migrate_version_metadata_on_startup` or the next update of the record.
+pub fn version_metadata(
+    app: &tauri::AppHandle,
+    version: u32,
+) -> Result<Option<VersionMetadata>, String> {
+    let root = version_root_dir(app, version)?;
+    if let Some(meta) = crate::version_meta::read(&root) {
+        return Ok(Some(meta));
+    }
+    if !is_complete_version_dir(app, version, &root) {
+        return Ok(None);
+    }
+
+    let state = read_manifest_state(app)?;
+    let now = crate::now_unix_secs();
+    let installed_at = std::fs::metadata(root.join(INSTALL_COMPLETE_MARKER))
+        .or_else(|_| std::fs::metadata(&root))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(now, |d| d.as_secs());
+    let mut meta = VersionMetadata::new(version);
+    meta.installed_at = Some(installed_at);
+    meta.depot_manifest = resolve_local_depot_manifest_id(&state, version);
+    meta.remote_manifest_version = Some(state.manifest_version);
+    if version >= 73 {
+        meta.patch_status = PatchStatus::NotNeeded;
+    }
+    meta.record(now, UpdateOperation::Migrated, None);
+    Ok(Some(meta))
+}
+
+/// Write a record for every installed version that predates `version.json`.
+pub fn migrate_version_metadata_on_startup(app: &tauri::AppHandle) -> Result<(), String> {
+    for (version, root) in installed_version_dirs(app)? {
+        if crate::version_meta::read(&root).is_some() {
+            continue;
+        }
+        if let Some(meta) = version_metadata(app, version)? {
+            crate::version_meta::write(&root, &meta)?;
+            log::info!("Migrated metadata of v{version}");
         }
+    }
+    Ok(())
+}
 
-        let client = reqwest::Client::new();
-        if cancel.load(Ordering::Relaxed) {
-            return Err("Cancelled".to_string());
+/// Apply `f` to the record of an installed version. Versions that are not
+/// installed have no record to update.
+fn update_version_metadata<F>(app: &tauri::AppHandle, version: u32, f: F) -> Result<(), String>
+where
+    F: FnOnce(&mut VersionMetadata),
+{
+    let mut meta =
+        version_metadata(app, version)?.ok_or_else(|| format!("v{version} is not installed."))?;
+    f(&mut meta);
+    crate::version_meta::write(&version_root_dir(app, version)?, &meta)
+}
+
+/// Install steps that are remembered in `.v{version}.partial` so a retry can skip them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStep {
+    Download,
+    Patch,
+    #[serde(rename = "bepinex")]
+    BepInEx,
+    Config,
+    Mods,
+}
+
+const PARTIAL_INSTALL_STATE_FILE: &str = ".hq_install_progress.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialInstallState {
+    pub version: u32,
+    pub depot_manifest: String,
+    #[serde(default)]
+    pub completed: Vec<InstallStep>,
+}
+
+impl PartialInstallState {
+    fn read(partial_dir: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(partial_dir.join(PARTIAL_INSTALL_STATE_FILE)).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    fn write(&self, partial_dir: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(partial_dir.join(PARTIAL_INSTALL_STATE_FILE), json)
+            .map_err(|e| e.to_string())
+    }
+
+    fn is_done(&self, step: InstallStep) -> bool {
+        self.completed.contains(&step)
+    }
+
+    fn mark_done(&mut self, partial_dir: &Path, step: InstallStep) -> Result<(), String> {
+        if !self.is_done(step) {
+            self.completed.push(step);
         }
-        let extract_dir = partial_dir.clone();
+        self.write(partial_dir)
+    }
+}
 
-        // Download -> Extract Game -> Patch Pre-v73 Instances -> Install BepInEx -> Install Config -> Install Mods
-        const STEPS_TOTAL: u32 = 6;
+fn partial_version_dir(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
+    Ok(crate::storage::versions_dir(app)?.join(format!(".v{version}.partial")))
+}
 
-        // Step 1: Steam 로그인 확인
-        emit_progress(
-            &app,
-            TaskProgressPayload {
-                version,
-                steps_total: STEPS_TOTAL,
-                step: 1,
-                step_name: "Login Check".to_string(),
-                step_progress: 0.0,
-                overall_percent: overall_from_step(1, 0.0, STEPS_TOTAL),
-                detail: Some("Checking Steam login...".to_string()),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: None,
-                total_files: None,
-            },
-        );
+/// Steps already finished by an earlier, interrupted attempt (if any).
+pub fn partial_install_state(
+    app: &tauri::AppHandle,
+    version: u32,
+) -> Result<Option<PartialInstallState>, String> {
+    let partial_dir = partial_version_dir(app, version)?;
+    Ok(PartialInstallState::read(&partial_dir).filter(|s| s.version == version))
+}
 
-        let downloader = downloader::DepotDownloader::new(&app)?;
-        let login_state = downloader.get_login_state();
+/// Throw away an interrupted download so the next attempt starts from zero.
+pub fn discard_partial_install(app: &tauri::AppHandle, version: u32) -> Result<bool, String> {
+    let partial_dir = partial_version_dir(app, version)?;
+    if !partial_dir.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_dir_all(&partial_dir).map_err(|e| e.to_string())?;
+    Ok(true)
+}
 
-        if !login_state.is_logged_in {
-            return Err("Not logged in to Steam. Please login first.".to_string());
+/// Start `.v{version}.partial` from files that are already on disk (an existing
+/// Steam install, an instance archive), so `download_and_setup` can skip the steps
+/// they cover. Returns the directory the files must be placed in.
+pub fn seed_partial_install(
+    app: &tauri::AppHandle,
+    version: u32,
+    depot_manifest: &str,
+) -> Result<PathBuf, String> {
+    let partial_dir = partial_version_dir(app, version)?;
+    if partial_dir.exists() {
+        std::fs::remove_dir_all(&partial_dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::create_dir_all(&partial_dir).map_err(|e| e.to_string())?;
+    PartialInstallState {
+        version,
+        depot_manifest: depot_manifest.to_string(),
+        completed: vec![],
+    }
+    .write(&partial_dir)?;
+    Ok(partial_dir)
+}
+
+/// Mark the steps the seeded files cover as done; until then a retry treats the
+/// partial directory like an interrupted download.
+pub fn finish_seeded_install(
+    app: &tauri::AppHandle,
+    version: u32,
+    steps: &[InstallStep],
+) -> Result<(), String> {
+    let partial_dir = partial_version_dir(app, version)?;
+    let mut state = PartialInstallState::read(&partial_dir)
+        .filter(|s| s.version == version)
+        .ok_or_else(|| format!("No partial install of v{version} to finish."))?;
+    for step in steps {
+        state.mark_done(&partial_dir, *step)?;
+    }
+    Ok(())
+}
+
+fn emit_step_resumed(
+    app: &tauri::AppHandle,
+    version: u32,
+    steps_total: u32,
+    step: u32,
+    step_name: &str,
+) {
+    emit_progress(
+        app,
+        TaskProgressPayload {
+            version,
+            steps_total,
+            step,
+            step_name: step_name.to_string(),
+            step_progress: 1.0,
+            overall_percent: overall_from_step(step, 1.0, steps_total),
+            detail: Some("Already done in a previous attempt".to_string()),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: None,
+            total_files: None,
+        },
+    );
+}
+
+/// Steps of an install: Login Check -> Download -> Patch Pre-v73 Instances ->
+/// Install BepInEx -> Install Config -> Install Mods.
+const STEPS_TOTAL: u32 = 6;
+
+impl InstallStep {
+    const ALL: [InstallStep; 5] = [
+        InstallStep::Download,
+        InstallStep::Patch,
+        InstallStep::BepInEx,
+        InstallStep::Config,
+        InstallStep::Mods,
+    ];
+
+    /// Position in the install progress; step 1 is the login check.
+    fn number(self) -> u32 {
+        match self {
+            InstallStep::Download => 2,
+            InstallStep::Patch => 3,
+            InstallStep::BepInEx => 4,
+            InstallStep::Config => 5,
+            InstallStep::Mods => 6,
         }
+    }
 
-        emit_progress(
-            &app,
-            TaskProgressPayload {
-                version,
-                steps_total: STEPS_TOTAL,
-                step: 1,
-                step_name: "Login Check".to_string(),
-                step_progress: 1.0,
-                overall_percent: overall_from_step(1, 1.0, STEPS_TOTAL),
-                detail: Some(format!(
-                    "Logged in as {}",
-                    login_state.username.unwrap_or_default()
-                )),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: None,
-                total_files: None,
-            },
-        );
+    fn label(self) -> &'static str {
+        match self {
+            InstallStep::Download => "Download Game",
+            InstallStep::Patch => "Patching",
+            InstallStep::BepInEx => "Install BepInEx",
+            InstallStep::Config => "Install Config",
+            InstallStep::Mods => "Install Mods",
+        }
+    }
+}
 
-        // Fetch remote manifest data (mods + per-game-version depots manifest ids).
-        let (remote_manifest_version, mods_cfg, _chain_config, manifests, _preset_tag_constraints) =
-            ModsConfig::fetch_manifest(&client).await?;
-        let mods_cfg = crate::select_mod_switches_for_install(
-            &app,
-            base_mods_config_for_version(mods_cfg, version),
-        )?;
+/// One `download_and_setup` run; each install step is a method so a resumed install
+/// can skip it.
+struct InstallJob {
+    app: tauri::AppHandle,
+    version: u32,
+    extract_dir: PathBuf,
+    cancel: Arc<AtomicBool>,
+}
 
+impl InstallJob {
+    async fn download_game(
+        &self,
+        downloader: &downloader::DepotDownloader,
+        manifest_id: &str,
+    ) -> Result<(), String> {
+        let app = &self.app;
+        let version = self.version;
+        let extract_dir = &self.extract_dir;
+        let cancel = &self.cancel;
         // Step 2: Lethal Company 다운로드
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -1902,24 +2123,12 @@ pub async fn download_and_setup(
             return Err("Cancelled".to_string());
         }
 
-        if extract_dir.exists() {
-            std::fs::remove_dir_all(&extract_dir).map_err(|e| e.to_string())?;
-        }
-        if final_dir.exists() {
-            std::fs::remove_dir_all(&final_dir).map_err(|e| e.to_string())?;
-        }
-        std::fs::create_dir_all(&extract_dir).map_err(|e| e.to_string())?;
-
         log::info!("Downloading Lethal Company to {}", extract_dir.display());
 
-        let manifest_id = manifests.get(&version).cloned().ok_or_else(|| {
-            format!("No depot manifest id for game version {version} in remote manifest.")
-        })?;
-
         // 게임 다운로드
         downloader
             .download_depot(
-                Some(manifest_id.clone()),
+                Some(manifest_id.to_string()),
                 extract_dir.clone(),
                 Some(downloader::DownloadTaskContext {
                     version,
@@ -1932,7 +2141,7 @@ pub async fn download_and_setup(
             .await?;
 
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -1947,9 +2156,16 @@ pub async fn download_and_setup(
                 total_files: None,
             },
         );
+        Ok(())
+    }
 
+    async fn patch(&self) -> Result<(), String> {
+        let app = &self.app;
+        let version = self.version;
+        let extract_dir = &self.extract_dir;
+        let cancel = &self.cancel;
         // Step 3: Patch security flaw (for older versions)
-        emit_progress(&app, TaskProgressPayload { 
+        emit_progress(app, TaskProgressPayload { 
             version: version, 
             steps_total: STEPS_TOTAL, 
             step: 3, 
@@ -1964,7 +2180,7 @@ pub async fn download_and_setup(
         });
 
         if version < 73 {
-            patch_single_instance(&app, Some(&cancel), &extract_dir, Some(DownloadTaskContext {
+            patch_single_instance(app, Some(cancel), extract_dir, Some(DownloadTaskContext {
                 version: version,
                 steps_total: STEPS_TOTAL,
                 step: 3,
@@ -1975,7 +2191,7 @@ pub async fn download_and_setup(
         }
 
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version: version,
                 steps_total: STEPS_TOTAL,
@@ -1990,10 +2206,17 @@ pub async fn download_and_setup(
                 total_files: None,
             },
         );
+        Ok(())
+    }
 
+    async fn install_bepinex(&self, client: &reqwest::Client) -> Result<(), String> {
+        let app = &self.app;
+        let version = self.version;
+        let extract_dir = &self.extract_dir;
+        let cancel = &self.cancel;
         // Step 4: BepInEx 다운로드 및 설치
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -2057,7 +2280,7 @@ pub async fn download_and_setup(
                 .unwrap_or(0.0);
 
             emit_progress(
-                &app,
+                app,
                 TaskProgressPayload {
                     version,
                     steps_total: STEPS_TOTAL,
@@ -2145,7 +2368,7 @@ pub async fn download_and_setup(
         }
 
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -2160,10 +2383,21 @@ pub async fn download_and_setup(
                 total_files: None,
             },
         );
+        // Stamped when this step runs, so a resumed install keeps it and an
+        // import (which skips the step) does not claim our pack.
+        crate::version_meta::update(extract_dir, version, |meta| {
+            meta.bepinex_version = Some(BEPINEXPACK_VERSION.to_string());
+        })?;
+        Ok(())
+    }
 
+    fn install_config(&self) -> Result<(), String> {
+        let app = &self.app;
+        let version = self.version;
+        let extract_dir = &self.extract_dir;
         // Step 5: Config junction 설정 (config 다운로드는 앱 시작 시 별도로 처리)
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -2180,10 +2414,10 @@ pub async fn download_and_setup(
         );
         // Config directory is a junction to AppData/config/shared.
         // Config files are downloaded separately on app startup if needed.
-        let _shared = ensure_config_junction(&app, &extract_dir)?;
+        let _shared = ensure_config_junction(app, extract_dir)?;
 
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -2198,10 +2432,17 @@ pub async fn download_and_setup(
                 total_files: None,
             },
         );
+        Ok(())
+    }
 
+    async fn install_mods(&self, mods_cfg: &ModsConfig) -> Result<(), String> {
+        let app = &self.app;
+        let version = self.version;
+        let extract_dir = &self.extract_dir;
+        let cancel = &self.cancel;
         // Step 6: Mods 설치
         emit_progress(
-            &app,
+            app,
             TaskProgressPayload {
                 version,
                 steps_total: STEPS_TOTAL,
@@ -2217,7 +2458,7 @@ pub async fn download_and_setup(
             },
         );
 
-        let plugins_dir = mods::plugins_dir(&extract_dir);
+        let plugins_dir = mods::plugins_dir(extract_dir);
         std::fs::create_dir_all(&plugins_dir).map_err(|e| e.to_string())?;
 
         if cancel.load(Ordering::Relaxed) {
@@ -2225,10 +2466,10 @@ pub async fn download_and_setup(
         }
 
         mods::install_mods_with_progress(
-            &app,
-            &extract_dir,
+            app,
+            extract_dir,
             version,
-            &mods_cfg,
+            mods_cfg,
             &[],
             Some(cancel.clone()),
             |done, total, progress_info| {
@@ -2238,7 +2479,7 @@ pub async fn download_and_setup(
                     (done as f64 / total as f64).clamp(0.0, 1.0)
                 };
                 emit_progress(
-                    &app,
+                    app,
                     TaskProgressPayload {
                         version,
                         steps_total: STEPS_TOTAL,
@@ -2257,13 +2498,159 @@ pub async fn download_and_setup(
         )
         .await?;
 
-        crate::ensure_reverb_trigger_fix_cfg(&app, version)?;
+        crate::ensure_reverb_trigger_fix_cfg(app, version)?;
+        Ok(())
+    }
+}
+
+pub async fn download_and_setup(
+    app: tauri::AppHandle,
+    version: u32,
+    cancel: Arc<AtomicBool>,
+) -> Result<bool, String> {
+    let dir = crate::storage::versions_dir(&app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let final_dir = dir.join(format!("v{version}"));
+    let partial_dir = partial_version_dir(&app, version)?;
+    let finalized = Arc::new(AtomicBool::new(false));
+
+    let res: Result<bool, String> = async {
+        // DepotDownloader 설치 확인
+        if let Err(e) = downloader::install_downloader(&app).await {
+            return Err(format!("Failed to install DepotDownloader: {e}"));
+        }
+
+        let client = reqwest::Client::new();
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+        let extract_dir = partial_dir.clone();
+
+        // Step 1: Steam 로그인 확인
+        emit_progress(
+            &app,
+            TaskProgressPayload {
+                version,
+                steps_total: STEPS_TOTAL,
+                step: 1,
+                step_name: "Login Check".to_string(),
+                step_progress: 0.0,
+                overall_percent: overall_from_step(1, 0.0, STEPS_TOTAL),
+                detail: Some("Checking Steam login...".to_string()),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: None,
+                total_files: None,
+            },
+        );
+
+        let downloader = downloader::DepotDownloader::new(&app)?;
+        let login_state = downloader.get_login_state();
+
+        // Imported installs (and resumed ones past the download) never talk to Steam.
+        let download_done = PartialInstallState::read(&extract_dir)
+            .is_some_and(|s| s.version == version && s.is_done(InstallStep::Download));
+        if !login_state.is_logged_in && !download_done {
+            return Err("Not logged in to Steam. Please login first.".to_string());
+        }
+
+        emit_progress(
+            &app,
+            TaskProgressPayload {
+                version,
+                steps_total: STEPS_TOTAL,
+                step: 1,
+                step_name: "Login Check".to_string(),
+                step_progress: 1.0,
+                overall_percent: overall_from_step(1, 1.0, STEPS_TOTAL),
+                detail: Some(format!(
+                    "Logged in as {}",
+                    login_state.username.unwrap_or_default()
+                )),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: None,
+                total_files: None,
+            },
+        );
+
+        // Fetch remote manifest data (mods + per-game-version depots manifest ids).
+        let (remote_manifest_version, mods_cfg, _chain_config, manifests, _preset_tag_constraints) =
+            ModsConfig::fetch_manifest(&client).await?;
+        let mods_cfg = crate::select_mod_switches_for_install(
+            &app,
+            base_mods_config_for_version(mods_cfg, version),
+        )?;
+
+        let manifest_id = manifests.get(&version).cloned().ok_or_else(|| {
+            format!("No depot manifest id for game version {version} in remote manifest.")
+        })?;
+
+        // Reuse whatever an earlier attempt left behind. DepotDownloader skips files that
+        // are already complete, so only the steps themselves need to be remembered.
+        std::fs::create_dir_all(&extract_dir).map_err(|e| e.to_string())?;
+        let mut install_state = match PartialInstallState::read(&extract_dir) {
+            Some(state) if state.version == version && state.depot_manifest == manifest_id => {
+                log::info!(
+                    "Resuming install of v{version}; completed steps: {:?}",
+                    state.completed
+                );
+                state
+            }
+            _ => {
+                // A record left by an attempt at another manifest no longer applies.
+                let _ = std::fs::remove_file(crate::version_meta::path(&extract_dir));
+                PartialInstallState {
+                    version,
+                    depot_manifest: manifest_id.clone(),
+                    completed: vec![],
+                }
+            }
+        };
+        install_state.write(&extract_dir)?;
+        let resumed_steps = install_state.completed.clone();
+
+        let job = InstallJob {
+            app: app.clone(),
+            version,
+            extract_dir: extract_dir.clone(),
+            cancel: cancel.clone(),
+        };
+        for step in InstallStep::ALL {
+            if install_state.is_done(step) {
+                emit_step_resumed(&app, version, STEPS_TOTAL, step.number(), step.label());
+                continue;
+            }
+            match step {
+                InstallStep::Download => job.download_game(&downloader, &manifest_id).await?,
+                InstallStep::Patch => job.patch().await?,
+                InstallStep::BepInEx => job.install_bepinex(&client).await?,
+                InstallStep::Config => job.install_config()?,
+                InstallStep::Mods => job.install_mods(&mods_cfg).await?,
+            }
+            install_state.mark_done(&extract_dir, step)?;
+        }
 
         let marker = format!(
             "version={version}\nremote_manifest_version={remote_manifest_version}\ndepot_manifest={manifest_id}\n"
         );
         std::fs::write(extract_dir.join(INSTALL_COMPLETE_MARKER), marker)
             .map_err(|e| e.to_string())?;
+        crate::version_meta::update(&extract_dir, version, |meta| {
+            let now = crate::now_unix_secs();
+            meta.installed_at = Some(now);
+            meta.depot_manifest = Some(manifest_id.clone());
+            meta.remote_manifest_version = Some(remote_manifest_version);
+            // Imported mods are whatever the source had.
+            if !resumed_steps.contains(&InstallStep::Mods) {
+                meta.last_mod_sync = Some(now);
+            }
+            meta.patch_status = PatchStatus::after_install(version);
+            let detail = (!resumed_steps.is_empty())
+                .then(|| format!("resumed past {:?}", resumed_steps));
+            meta.record(now, UpdateOperation::Install, detail);
+        })?;
+        let _ = std::fs::remove_file(extract_dir.join(PARTIAL_INSTALL_STATE_FILE));
 
         if final_dir.exists() {
             std::fs::remove_dir_all(&final_dir).map_err(|e| e.to_string())?;
@@ -2278,6 +2665,15 @@ pub async fn download_and_setup(
         manifest_state.depot_manifests.insert(version, manifest_id);
         write_manifest_state(&app, &manifest_state)?;
 
+        if let Some(depot_manifest) = manifest_state.depot_manifests.get(&version) {
+            if let Err(e) =
+                crate::fingerprint::learn_installed(&app, version, depot_manifest, &final_dir)
+            {
+                log::warn!("Failed to record fingerprint of v{version}: {e}");
+            }
+        }
+        dedup_installed_version(&app, version).await;
+
         emit_progress(
             &app,
             TaskProgressPayload {
@@ -2311,7 +2707,13 @@ pub async fn download_and_setup(
 
     if let Err(message) = &res {
         if !finalized.load(Ordering::Relaxed) {
-            let _ = std::fs::remove_dir_all(&partial_dir);
+            // Keep `.v{version}.partial` so the next attempt can resume where this one stopped.
+            if partial_dir.exists() {
+                log::info!(
+                    "Keeping partial install of v{version} for resume: {}",
+                    partial_dir.display()
+                );
+            }
             if final_dir.exists() && !is_complete_version_dir(&app, version, &final_dir) {
                 let _ = std::fs::remove_dir_all(&final_dir);
             }
@@ -2328,3 +2730,243 @@ pub async fn download_and_setup(
 
     res
 }
+
+/// Share identical game files of `v{version}` with other installed versions.
+/// Best-effort: a volume without link support just keeps full copies.
+async fn dedup_installed_version(app: &tauri::AppHandle, version: u32) {
+    let versions_dir = match crate::storage::versions_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Skipping dedup of v{version}: {e}");
+            return;
+        }
+    };
+    let res = tauri::async_runtime::spawn_blocking(move || {
+        crate::dedup_store::dedup_version(&versions_dir, version)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    match res {
+        Ok(report) => log::info!(
+            "Deduplicated v{version}: {} MB saved across {} version(s)",
+            report.saved_bytes / (1024 * 1024),
+            report.versions
+        ),
+        Err(e) => log::warn!("Failed to dedup v{version}: {e}"),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyFilesReport {
+    pub version: u32,
+    pub depot_manifest: String,
+    /// Game files DepotDownloader re-downloaded, relative to the version folder.
+    pub repaired_files: Vec<String>,
+    pub patched: bool,
+}
+
+/// Validate `v{version}` against its recorded depot manifest and fetch missing or
+/// modified game files in place. BepInEx, plugins and configs are not part of the
+/// depot, so they are left untouched.
+pub async fn verify_and_repair_version(
+    app: &tauri::AppHandle,
+    version: u32,
+    cancel: Arc<AtomicBool>,
+) -> Result<VerifyFilesReport, String> {
+    const STEPS_TOTAL: u32 = 2;
+
+    let game_root = version_root_dir(app, version)?;
+    if !is_complete_version_dir(app, version, &game_root) {
+        return Err(format!("v{version} is not installed."));
+    }
+    let manifest_state = read_manifest_state(app)?;
+    let meta = version_metadata(app, version)?;
+    let manifest_id = local_manifest_info(&manifest_state, meta.as_ref(), version)
+        .0
+        .ok_or_else(|| format!("No depot manifest id recorded for v{version}."))?;
+
+    let res: Result<VerifyFilesReport, String> = async {
+        downloader::install_downloader(app)
+            .await
+            .map_err(|e| format!("Failed to install DepotDownloader: {e}"))?;
+        let downloader = downloader::DepotDownloader::new(app)?;
+
+        emit_progress(
+            app,
+            TaskProgressPayload {
+                version,
+                steps_total: STEPS_TOTAL,
+                step: 1,
+                step_name: "Verify Files".to_string(),
+                step_progress: 0.0,
+                overall_percent: overall_from_step(1, 0.0, STEPS_TOTAL),
+                detail: Some(format!(
+                    "Validating against depot manifest {manifest_id}..."
+                )),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: None,
+                total_files: None,
+            },
+        );
+
+        // DepotDownloader repairs files in place, which must not reach other versions
+        // through shared links. The version is deduplicated again afterwards.
+        let unshare_root = game_root.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            crate::dedup_store::unshare_version_root(&unshare_root)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        let repaired_files = downloader
+            .validate_depot(
+                manifest_id.clone(),
+                game_root.clone(),
+                Some(DownloadTaskContext {
+                    version,
+                    steps_total: STEPS_TOTAL,
+                    step: 1,
+                    step_name: "Verify Files".to_string(),
+                }),
+                Some(cancel.clone()),
+            )
+            .await?;
+        log::info!(
+            "Verified v{version}: {} file(s) repaired",
+            repaired_files.len()
+        );
+
+        // Validation restores the original (unpatched) player binaries, so pre-v73
+        // installs need the security patch again.
+        let patched = version < 73 && !repaired_files.is_empty();
+        if patched {
+            patch_single_instance(
+                app,
+                Some(&cancel),
+                &game_root,
+                Some(DownloadTaskContext {
+                    version,
+                    steps_total: STEPS_TOTAL,
+                    step: 2,
+                    step_name: "Patching".to_string(),
+                }),
+            )
+            .await?;
+        }
+        update_version_metadata(app, version, |meta| {
+            if patched {
+                meta.patch_status = PatchStatus::Applied;
+            }
+            meta.record(
+                crate::now_unix_secs(),
+                UpdateOperation::Verify,
+                Some(format!("{} file(s) repaired", repaired_files.len())),
+            );
+        })?;
+        if let Err(e) = crate::fingerprint::learn_installed(app, version, &manifest_id, &game_root)
+        {
+            log::warn!("Failed to record fingerprint of v{version}: {e}");
+        }
+        dedup_installed_version(app, version).await;
+
+        emit_progress(
+            app,
+            TaskProgressPayload {
+                version,
+                steps_total: STEPS_TOTAL,
+                step: 2,
+                step_name: "Patching".to_string(),
+                step_progress: 1.0,
+                overall_percent: overall_from_step(2, 1.0, STEPS_TOTAL),
+                detail: Some(format!("{} file(s) repaired", repaired_files.len())),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: None,
+                total_files: None,
+            },
+        );
+        emit_finished(
+            app,
+            TaskFinishedPayload {
+                version,
+                run_mode: None,
+                path: game_root.to_string_lossy().to_string(),
+            },
+        );
+
+        Ok(VerifyFilesReport {
+            version,
+            depot_manifest: manifest_id.clone(),
+            repaired_files,
+            patched,
+        })
+    }
+    .await;
+
+    if let Err(message) = &res {
+        emit_error(
+            app,
+            TaskErrorPayload {
+                version,
+                run_mode: None,
+                message: message.clone(),
+            },
+        );
+    }
+    res
+}
+
+/// Put back BepInExPack files missing from `v{version}` (quarantined by antivirus,
+/// deleted by hand) without touching the files that are still there.
+pub async fn restore_missing_bepinex_files(
+    app: &tauri::AppHandle,
+    version: u32,
+) -> Result<(), String> {
+    let game_root = version_root_dir(app, version)?;
+    let temp_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join("temp");
+    std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+    log::info!(
+        "Restoring missing BepInExPack {} files for v{version}",
+        BEPINEXPACK_VERSION
+    );
+    let bytes = reqwest::Client::new()
+        .get(BEPINEXPACK_URL)
+        .header("User-Agent", "hq-launcher/0.1 (tauri)")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !bytes.starts_with(b"PK") {
+        return Err(
+            "BepInExPack download is not a valid zip (got non-zip response). Please retry."
+                .to_string(),
+        );
+    }
+    let zip_path = temp_dir.join(format!("bepinexpack_{BEPINEXPACK_VERSION}_restore.zip"));
+    let staging = temp_dir.join(format!("bepinexpack_{BEPINEXPACK_VERSION}_restore"));
+    std::fs::write(&zip_path, &bytes).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let _ = std::fs::remove_dir_all(&staging);
+        let res = zip_utils::extract_thunderstore_package_with_progress(
+            &zip_path,
+            &staging,
+            |_, _, _| {},
+        )
+        .and_then(|()| copy_dir_add_only(&staging, &game_root));
+        let _ = std::fs::remove_dir_all(&staging);
+        let _ = std::fs::remove_file(&zip_path);
+        res
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,478 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+// A version instance archive is a plain zip:
+//   hq-instance.json   InstanceMetadata
+//   mods.lock.json     installed plugins with version and enabled state
+//   disablemod.json    the launcher's disabled-mod list at export time
+//   game/...           files relative to `v{version}` (BepInEx setup, optionally the game)
+pub const FORMAT_VERSION: u32 = 1;
+const METADATA_FILE: &str = "hq-instance.json";
+const LOCKFILE: &str = "mods.lock.json";
+const DISABLED_MODS_FILE: &str = "disablemod.json";
+const GAME_PREFIX: &str = "game/";
+
+/// Version-root entries that make up the modded setup rather than the game itself.
+const SETUP_ENTRIES: &[&str] = &[
+    "BepInEx",
+    "winhttp.dll",
+    "doorstop_config.ini",
+    ".doorstop_version",
+];
+/// Regenerated by BepInEx on every run; never worth shipping.
+const SKIPPED_BEPINEX_ENTRIES: &[&str] = &["cache", "LogOutput.log", "LogOutput.log.old"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceMetadata {
+    pub format: u32,
+    pub version: u32,
+    #[serde(default)]
+    pub depot_manifest: Option<String>,
+    #[serde(default)]
+    pub remote_manifest_version: Option<u32>,
+    #[serde(default)]
+    pub launcher_version: String,
+    /// Unix seconds.
+    #[serde(default)]
+    pub exported_at: u64,
+    #[serde(default)]
+    pub includes_game_files: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedMod {
+    pub dev: String,
+    pub name: String,
+    pub version: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub files: u64,
+    pub mods: usize,
+}
+
+/// Plugins installed under `plugins_dir` (`{dev}-{name}` folders), as recorded in
+/// their Thunderstore manifest. Disabled mods keep a `manifest.json.old`.
+pub fn locked_mods(plugins_dir: &Path) -> Vec<LockedMod> {
+    let mut out = Vec::new();
+    let Ok(rd) = std::fs::read_dir(plugins_dir) else {
+        return out;
+    };
+    for entry in rd.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let folder = entry.file_name().to_string_lossy().to_string();
+        let Some((dev, name)) = folder.split_once('-') else {
+            continue;
+        };
+        let enabled = path.join("manifest.json").is_file();
+        let manifest_path = if enabled {
+            path.join("manifest.json")
+        } else {
+            path.join("manifest.json.old")
+        };
+        let Ok(manifest) = crate::bepinex_cfg::read_manifest(&manifest_path) else {
+            continue;
+        };
+        out.push(LockedMod {
+            dev: dev.to_string(),
+            name: name.to_string(),
+            version: manifest.version_number,
+            enabled,
+        });
+    }
+    out.sort_by(|a, b| a.dev.cmp(&b.dev).then(a.name.cmp(&b.name)));
+    out
+}
+
+fn is_skipped(rel: &Path) -> bool {
+    let mut comps = rel.components().map(|c| c.as_os_str().to_string_lossy());
+    let Some(first) = comps.next() else {
+        return true;
+    };
+    if first == "BepInEx" {
+        return comps
+            .next()
+            .is_some_and(|second| SKIPPED_BEPINEX_ENTRIES.contains(&second.as_ref()));
+    }
+    // Launcher markers and DepotDownloader state describe this machine's install only.
+    first.starts_with(".hq_") || first == ".hq-launcher" || first == ".DepotDownloader"
+}
+
+/// Files to export, relative to `version_root`. `BepInEx/config` is usually a
+/// junction to the shared config folder; it is followed so the archive carries the
+/// config this version actually runs with.
+fn collect_export_files(
+    version_root: &Path,
+    include_game_files: bool,
+) -> Result<Vec<PathBuf>, String> {
+    let mut out = Vec::new();
+    let mut stack = vec![PathBuf::new()];
+    while let Some(rel_dir) = stack.pop() {
+        for entry in std::fs::read_dir(version_root.join(&rel_dir)).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let name = entry.file_name();
+            let rel = rel_dir.join(&name);
+            if rel_dir.as_os_str().is_empty()
+                && !include_game_files
+                && !SETUP_ENTRIES.contains(&name.to_string_lossy().as_ref())
+            {
+                continue;
+            }
+            if is_skipped(&rel) {
+                continue;
+            }
+            let meta = std::fs::metadata(entry.path()).map_err(|e| e.to_string())?;
+            if meta.is_dir() {
+                stack.push(rel);
+            } else if meta.is_file() {
+                out.push(rel);
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn zip_name(rel: &Path) -> String {
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    format!("{GAME_PREFIX}{}", parts.join("/"))
+}
+
+/// Write `version_root` to `dest`. The archive is built next to `dest` and renamed
+/// into place, so a failed export never leaves a truncated file behind.
+pub fn export_instance<F>(
+    version_root: &Path,
+    dest: &Path,
+    metadata: &InstanceMetadata,
+    disabled_mods: Option<&[u8]>,
+    mut on_progress: F,
+) -> Result<ExportReport, String>
+where
+    F: FnMut(u64, u64),
+{
+    let files = collect_export_files(version_root, metadata.includes_game_files)?;
+    let mods = locked_mods(&version_root.join("BepInEx").join("plugins"));
+    let total = files.len() as u64;
+
+    let tmp = dest.with_extension("zip.partial");
+    let res = (|| -> Result<(), String> {
+        let mut zip = ZipWriter::new(File::create(&tmp).map_err(|e| e.to_string())?);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        let json = serde_json::to_vec_pretty(metadata).map_err(|e| e.to_string())?;
+        zip.start_file(METADATA_FILE, options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&json).map_err(|e| e.to_string())?;
+
+        let json = serde_json::to_vec_pretty(&mods).map_err(|e| e.to_string())?;
+        zip.start_file(LOCKFILE, options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&json).map_err(|e| e.to_string())?;
+
+        if let Some(bytes) = disabled_mods {
+            zip.start_file(DISABLED_MODS_FILE, options)
+                .map_err(|e| e.to_string())?;
+            zip.write_all(bytes).map_err(|e| e.to_string())?;
+        }
+
+        for (idx, rel) in files.iter().enumerate() {
+            zip.start_file(zip_name(rel), options)
+                .map_err(|e| e.to_string())?;
+            let mut src = File::open(version_root.join(rel)).map_err(|e| e.to_string())?;
+            std::io::copy(&mut src, &mut zip).map_err(|e| e.to_string())?;
+            on_progress(idx as u64 + 1, total);
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    })();
+    if let Err(e) = res {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    std::fs::rename(&tmp, dest).map_err(|e| e.to_string())?;
+
+    Ok(ExportReport {
+        path: dest.to_string_lossy().to_string(),
+        files: total,
+        mods: mods.len(),
+    })
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(Some(bytes))
+}
+
+/// Everything an import needs from the archive besides the game folder itself.
+#[derive(Debug, Clone)]
+pub struct InstanceContents {
+    pub metadata: InstanceMetadata,
+    pub mods: Vec<LockedMod>,
+    pub disabled_mods: Option<Vec<u8>>,
+}
+
+/// Check that every entry is a known file or lives under `game/`, and that none of
+/// them could escape the target folder (Zip Slip), before anything is extracted.
+pub fn inspect_archive(archive_path: &Path) -> Result<InstanceContents, String> {
+    let file = File::open(archive_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+
+    let metadata: InstanceMetadata = match read_entry(&mut archive, METADATA_FILE)? {
+        Some(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string())?,
+        None => {
+            return Err(format!(
+                "Not a version instance archive: {METADATA_FILE} is missing."
+            ))
+        }
+    };
+    if metadata.format > FORMAT_VERSION {
+        return Err(format!(
+            "This archive needs a newer launcher (format {}).",
+            metadata.format
+        ));
+    }
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let name = entry.name().to_string();
+        if entry.enclosed_name().is_none() {
+            return Err(format!("Archive entry escapes the target folder: {name}"));
+        }
+        if [METADATA_FILE, LOCKFILE, DISABLED_MODS_FILE].contains(&name.as_str()) {
+            continue;
+        }
+        let Some(rel) = name.strip_prefix(GAME_PREFIX) else {
+            return Err(format!("Unexpected archive entry: {name}"));
+        };
+        let first = rel.split('/').next().unwrap_or_default();
+        if !metadata.includes_game_files && !first.is_empty() && !SETUP_ENTRIES.contains(&first) {
+            return Err(format!(
+                "Archive says it has no game files but contains {name}"
+            ));
+        }
+    }
+
+    let mods = match read_entry(&mut archive, LOCKFILE)? {
+        Some(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string())?,
+        None => vec![],
+    };
+    let disabled_mods = read_entry(&mut archive, DISABLED_MODS_FILE)?;
+    Ok(InstanceContents {
+        metadata,
+        mods,
+        disabled_mods,
+    })
+}
+
+/// Extract the `game/` part of an archive that passed [`inspect_archive`] into
+/// `version_root`. Returns the number of files written.
+pub fn extract_instance<F>(
+    archive_path: &Path,
+    version_root: &Path,
+    mut on_progress: F,
+) -> Result<u64, String>
+where
+    F: FnMut(u64, u64),
+{
+    let file = File::open(archive_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let total = archive.len() as u64;
+    let mut written = 0u64;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let Some(enclosed) = entry.enclosed_name() else {
+            return Err(format!(
+                "Archive entry escapes the target folder: {}",
+                entry.name()
+            ));
+        };
+        let Ok(rel) = enclosed.strip_prefix(GAME_PREFIX.trim_end_matches('/')) else {
+            continue;
+        };
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+        let out_path = version_root.join(rel);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out_path).map_err(|e| e.to_string())?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            crate::dedup_store::unshare_file(&out_path)?;
+            let mut out = File::create(&out_path).map_err(|e| e.to_string())?;
+            std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+            written += 1;
+        }
+        on_progress(i as u64 + 1, total);
+    }
+    Ok(written)
+}
+
+/// Mods from the lockfile that are missing or at another version after import.
+pub fn lockfile_differences(expected: &[LockedMod], installed: &[LockedMod]) -> Vec<String> {
+    expected
+        .iter()
+        .filter_map(|want| {
+            let found = installed.iter().find(|m| {
+                m.dev.eq_ignore_ascii_case(&want.dev) && m.name.eq_ignore_ascii_case(&want.name)
+            });
+            match found {
+                None => Some(format!(
+                    "{}-{} {} is missing",
+                    want.dev, want.name, want.version
+                )),
+                Some(m) if m.version != want.version => Some(format!(
+                    "{}-{} is {} instead of {}",
+                    want.dev, want.name, m.version, want.version
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn write(path: &Path, bytes: &[u8]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    fn metadata(includes_game_files: bool) -> InstanceMetadata {
+        InstanceMetadata {
+            format: FORMAT_VERSION,
+            version: 73,
+            depot_manifest: Some("123".to_string()),
+            remote_manifest_version: Some(5),
+            launcher_version: "test".to_string(),
+            exported_at: 0,
+            includes_game_files,
+        }
+    }
+
+    #[test]
+    fn export_and_import_round_trip_without_game_files() {
+        let dir = temp_dir("instance_roundtrip");
+        let root = dir.join("v73");
+        write(&root.join("Lethal Company.exe"), b"game");
+        write(&root.join("winhttp.dll"), b"doorstop");
+        write(&root.join("BepInEx/LogOutput.log"), b"log");
+        write(&root.join("BepInEx/config/BepInEx.cfg"), b"[cfg]");
+        write(
+            &root.join("BepInEx/plugins/Dev-Mod/manifest.json.old"),
+            br#"{"name":"Mod","description":"","version_number":"1.2.3","dependencies":[],"website_url":""}"#,
+        );
+        write(&root.join(".hq_install_complete"), b"marker");
+
+        let archive = dir.join("instance.zip");
+        let report =
+            export_instance(&root, &archive, &metadata(false), Some(b"{}"), |_, _| {}).unwrap();
+        assert_eq!(report.files, 3);
+        assert_eq!(report.mods, 1);
+
+        let contents = inspect_archive(&archive).unwrap();
+        assert_eq!(contents.metadata.version, 73);
+        assert_eq!(
+            contents.mods,
+            vec![LockedMod {
+                dev: "Dev".to_string(),
+                name: "Mod".to_string(),
+                version: "1.2.3".to_string(),
+                enabled: false,
+            }]
+        );
+        assert_eq!(contents.disabled_mods.as_deref(), Some(&b"{}"[..]));
+
+        let target = dir.join("import");
+        assert_eq!(extract_instance(&archive, &target, |_, _| {}).unwrap(), 3);
+        assert!(target.join("BepInEx/config/BepInEx.cfg").is_file());
+        assert!(!target.join("Lethal Company.exe").exists());
+        assert!(!target.join("BepInEx/LogOutput.log").exists());
+        assert!(lockfile_differences(
+            &contents.mods,
+            &locked_mods(&target.join("BepInEx/plugins"))
+        )
+        .is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_traversal_and_unexpected_entries() {
+        let dir = temp_dir("instance_reject");
+        let build = |entries: &[&str]| {
+            let path = dir.join("bad.zip");
+            let mut zip = ZipWriter::new(File::create(&path).unwrap());
+            let options = SimpleFileOptions::default();
+            zip.start_file(METADATA_FILE, options).unwrap();
+            zip.write_all(&serde_json::to_vec(&metadata(false)).unwrap())
+                .unwrap();
+            for name in entries {
+                zip.start_file(*name, options).unwrap();
+                zip.write_all(b"x").unwrap();
+            }
+            zip.finish().unwrap();
+            path
+        };
+
+        let err = inspect_archive(&build(&["game/../../evil.dll"])).unwrap_err();
+        assert!(err.contains("escapes"), "{err}");
+        let err = inspect_archive(&build(&["readme.txt"])).unwrap_err();
+        assert!(err.contains("Unexpected"), "{err}");
+        let err = inspect_archive(&build(&["game/Lethal Company.exe"])).unwrap_err();
+        assert!(err.contains("no game files"), "{err}");
+        assert!(inspect_archive(&build(&["game/BepInEx/core/BepInEx.dll"])).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lockfile_differences_reports_missing_and_changed_mods() {
+        let locked = |name: &str, version: &str| LockedMod {
+            dev: "Dev".to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+            enabled: true,
+        };
+        let diff = lockfile_differences(
+            &[
+                locked("A", "1.0.0"),
+                locked("B", "2.0.0"),
+                locked("C", "1.0.0"),
+            ],
+            &[locked("A", "1.0.0"), locked("B", "2.1.0")],
+        );
+        assert_eq!(
+            diff,
+            vec![
+                "Dev-B is 2.1.0 instead of 2.0.0".to_string(),
+                "Dev-C 1.0.0 is missing".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+
+use crate::crash_report::{ExitKind, ExitReport};
+use crate::instance_archive::LockedMod;
+use crate::resource_monitor::ResourcePeaks;
+
+// Local history of every launch: `history/launches.json` holds one record per run,
+// written when the game starts and completed when its exit is classified. The mod
+// lockfile a run started with is stored once per content hash under
+// `history/lockfiles/<hash>.json`, so a record can say which mod set was active
+// without repeating it. A run the launcher lost track of (the launcher was closed
+// first) keeps `ended_at: None`.
+
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    pub id: String,
+    pub version: u32,
+    pub mode_label: String,
+    pub run_mode: String,
+    #[serde(default)]
+    pub event_id: Option<String>,
+    #[serde(default)]
+    pub launch_profile: Option<String>,
+    /// Modded runs only.
+    #[serde(default)]
+    pub lockfile_hash: Option<String>,
+    /// Unix seconds.
+    pub started_at: u64,
+    #[serde(default)]
+    pub ended_at: Option<u64>,
+    #[serde(default)]
+    pub exit: Option<ExitKind>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub crash_bundle: Option<String>,
+    #[serde(default)]
+    pub bepinex_log_session: Option<String>,
+    /// LCStatsTracker payloads received while the game ran.
+    #[serde(default)]
+    pub lcstats_payloads: u64,
+    /// Highest use the resource monitor saw; `None` for runs it never sampled.
+    #[serde(default)]
+    pub peak_resources: Option<ResourcePeaks>,
+}
+
+impl LaunchRecord {
+    pub fn duration_secs(&self) -> Option<u64> {
+        self.ended_at
+            .map(|ended_at| ended_at.saturating_sub(self.started_at))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// Mode label or run mode, e.g. `Brutal` or `brutal_practice`.
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub event_id: Option<String>,
+    #[serde(default)]
+    pub launch_profile: Option<String>,
+    #[serde(default)]
+    pub exit: Option<ExitKind>,
+    /// Unix seconds, compared with `started_at`.
+    #[serde(default)]
+    pub since: Option<u64>,
+    #[serde(default)]
+    pub until: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    pub fn matches(&self, record: &LaunchRecord) -> bool {
+        let same = |filter: &Option<String>, value: Option<&str>| {
+            filter
+                .as_deref()
+                .is_none_or(|filter| value.is_some_and(|v| v.eq_ignore_ascii_case(filter)))
+        };
+        self.version.is_none_or(|version| record.version == version)
+            && self.mode.as_deref().is_none_or(|mode| {
+                record.mode_label.eq_ignore_ascii_case(mode)
+                    || record.run_mode.eq_ignore_ascii_case(mode)
+            })
+            && same(&self.event_id, record.event_id.as_deref())
+            && same(&self.launch_profile, record.launch_profile.as_deref())
+            && self.exit.is_none_or(|exit| record.exit == Some(exit))
+            && self.since.is_none_or(|since| record.started_at >= since)
+            && self.until.is_none_or(|until| record.started_at < until)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HistorySummary {
+    pub launches: usize,
+    pub play_secs: u64,
+    pub crashes: usize,
+    pub lcstats_payloads: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    pub records: Vec<LaunchRecord>,
+    /// Over every match, not just the returned page.
+    pub summary: HistorySummary,
+}
+
+/// Matching records, newest first.
+pub fn query(records: &[LaunchRecord], query: &HistoryQuery) -> HistoryPage {
+    let mut matched: Vec<LaunchRecord> = records
+        .iter()
+        .filter(|record| query.matches(record))
+        .cloned()
+        .collect();
+    matched.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    let summary = HistorySummary {
+        launches: matched.len(),
+        play_secs: matched.iter().filter_map(LaunchRecord::duration_secs).sum(),
+        crashes: matched
+            .iter()
+            .filter(|record| record.exit == Some(ExitKind::Crash))
+            .count(),
+        lcstats_payloads: matched.iter().map(|record| record.lcstats_payloads).sum(),
+    };
+    if let Some(limit) = query.limit {
+        matched.truncate(limit);
+    }
+    HistoryPage {
+        records: matched,
+        summary,
+    }
+}
+
+fn rfc3339(secs: Option<u64>) -> String {
+    secs.and_then(|secs| chrono::DateTime::from_timestamp(secs as i64, 0))
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(records: &[LaunchRecord]) -> String {
+    let mut out = String::from(
+        "id,version,mode_label,run_mode,event_id,launch_profile,started_at,ended_at,\
+duration_secs,exit,exit_code,lockfile_hash,lcstats_payloads,crash_bundle,peak_rss_bytes,\
+peak_cpu_percent,peak_threads\n",
+    );
+    for record in records {
+        let exit = record
+            .exit
+            .and_then(|exit| serde_json::to_value(exit).ok())
+            .and_then(|exit| exit.as_str().map(str::to_string));
+        let peak = |field: fn(&ResourcePeaks) -> String| {
+            record
+                .peak_resources
+                .as_ref()
+                .map(field)
+                .unwrap_or_default()
+        };
+        let fields = [
+            record.id.clone(),
+            record.version.to_string(),
+            record.mode_label.clone(),
+            record.run_mode.clone(),
+            record.event_id.clone().unwrap_or_default(),
+            record.launch_profile.clone().unwrap_or_default(),
+            rfc3339(Some(record.started_at)),
+            rfc3339(record.ended_at),
+            record
+                .duration_secs()
+                .map(|secs| secs.to_string())
+                .unwrap_or_default(),
+            exit.unwrap_or_default(),
+            record
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_default(),
+            record.lockfile_hash.clone().unwrap_or_default(),
+            record.lcstats_payloads.to_string(),
+            record.crash_bundle.clone().unwrap_or_default(),
+            peak(|peaks| peaks.rss_bytes.to_string()),
+            peak(|peaks| peaks.cpu_percent.to_string()),
+            peak(|peaks| peaks.threads.to_string()),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn lockfile_hash(mods: &[LockedMod]) -> String {
+    let json = serde_json::to_vec(mods).unwrap_or_default();
+    Sha256::digest(&json)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn history_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("history"))
+}
+
+fn lockfile_path(app: &tauri::AppHandle, hash: &str) -> Result<PathBuf, String> {
+    if hash.is_empty() || !hash.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(format!("invalid lockfile hash: {hash}"));
+    }
+    Ok(history_dir(app)?
+        .join("lockfiles")
+        .join(format!("{hash}.json")))
+}
+
+fn read_records(path: &Path) -> Result<Vec<LaunchRecord>, String> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+fn update_records(
+    app: &tauri::AppHandle,
+    update: impl FnOnce(&mut Vec<LaunchRecord>),
+) -> Result<(), String> {
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|_| "launch history lock poisoned".to_string())?;
+    let path = history_dir(app)?.join("launches.json");
+    let mut records = read_records(&path)?;
+    update(&mut records);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+pub fn load(app: &tauri::AppHandle) -> Result<Vec<LaunchRecord>, String> {
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|_| "launch history lock poisoned".to_string())?;
+    read_records(&history_dir(app)?.join("launches.json"))
+}
+
+pub fn load_lockfile(app: &tauri::AppHandle, hash: &str) -> Result<Vec<LockedMod>, String> {
+    let text = std::fs::read_to_string(lockfile_path(app, hash)?)
+        .map_err(|e| format!("lockfile {hash} not found: {e}"))?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+/// Store `mods` under its hash unless that mod set is already known.
+pub fn store_lockfile(app: &tauri::AppHandle, mods: &[LockedMod]) -> Result<String, String> {
+    let hash = lockfile_hash(mods);
+    let path = lockfile_path(app, &hash)?;
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(mods).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| e.to_string())?;
+    }
+    Ok(hash)
+}
+
+/// A started run, completed from its exit report.
+#[derive(Debug)]
+pub struct PendingLaunch {
+    id: String,
+    lcstats_payloads_at_start: u64,
+}
+
+pub fn start(app: &tauri::AppHandle, record: LaunchRecord) -> Result<PendingLaunch, String> {
+    let pending = PendingLaunch {
+        id: record.id.clone(),
+        lcstats_payloads_at_start: lcstats_payloads(app),
+    };
+    update_records(app, |records| records.push(record))?;
+    Ok(pending)
+}
+
+fn lcstats_payloads(app: &tauri::AppHandle) -> u64 {
+    let state = app.state::<crate::lcstats_autosheet::LcStatsAutosheetState>();
+    crate::lcstats_autosheet::received_payload_count(&state)
+}
+
+impl PendingLaunch {
+    pub fn finish(
+        self,
+        app: &tauri::AppHandle,
+        report: &ExitReport,
+        peak_resources: Option<ResourcePeaks>,
+    ) {
+        let payloads = lcstats_payloads(app).saturating_sub(self.lcstats_payloads_at_start);
+        let result = update_records(app, |records| {
+            if let Some(record) = records.iter_mut().find(|record| record.id == self.id) {
+                record.ended_at = Some(report.finished_at);
+                record.exit = Some(report.kind);
+                record.exit_code = report.exit_code;
+                record.crash_bundle = report.bundle.clone();
+                record.bepinex_log_session = report.bepinex_log_session.clone();
+                record.lcstats_payloads = payloads;
+                record.peak_resources = peak_resources;
+            }
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to record the end of launch {}: {e}", self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, version: u32, mode_label: &str, started_at: u64) -> LaunchRecord {
+        LaunchRecord {
+            id: id.to_string(),
+            version,
+            mode_label: mode_label.to_string(),
+            run_mode: mode_label.to_lowercase(),
+            event_id: None,
+            launch_profile: None,
+            lockfile_hash: None,
+            started_at,
+            ended_at: Some(started_at + 600),
+            exit: Some(ExitKind::Normal),
+            exit_code: Some(0),
+            crash_bundle: None,
+            bepinex_log_session: None,
+            lcstats_payloads: 2,
+            peak_resources: None,
+        }
+    }
+
+    #[test]
+    fn query_filters_and_sums_play_time() {
+        let mut crashed = record("c", 73, "Brutal", 2_000);
+        crashed.exit = Some(ExitKind::Crash);
+        let mut running = record("r", 73, "Brutal", 3_000);
+        running.ended_at = None;
+        let records = vec![
+            record("a", 73, "Brutal", 1_000),
+            record("b", 72, "Brutal", 1_500),
+            crashed,
+            running,
+            record("d", 73, "HQ", 2_500),
+        ];
+
+        let page = query(
+            &records,
+            &HistoryQuery {
+                version: Some(73),
+                mode: Some("brutal".to_string()),
+                since: Some(1_000),
+                limit: Some(2),
+                ..HistoryQuery::default()
+            },
+        );
+        let ids: Vec<&str> = page.records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["r", "c"]);
+        assert_eq!(
+            page.summary,
+            HistorySummary {
+                launches: 3,
+                play_secs: 1_200,
+                crashes: 1,
+                lcstats_payloads: 6,
+            }
+        );
+
+        let crashes = HistoryQuery {
+            exit: Some(ExitKind::Crash),
+            ..HistoryQuery::default()
+        };
+        assert_eq!(query(&records, &crashes).records.len(), 1);
+    }
+
+    #[test]
+    fn csv_quotes_fields_and_lockfile_hash_is_stable() {
+        let mut entry = record("a", 73, "Brutal, \"Practice\"", 0);
+        entry.exit = Some(ExitKind::KilledByLauncher);
+        entry.peak_resources = Some(ResourcePeaks {
+            cpu_percent: 187.5,
+            rss_bytes: 3_221_225_472,
+            threads: 96,
+            processes: 4,
+        });
+        let csv = to_csv(&[entry]);
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "a,73,\"Brutal, \"\"Practice\"\"\",\"brutal, \"\"practice\"\"\",,,\
+1970-01-01T00:00:00+00:00,1970-01-01T00:10:00+00:00,600,killed_by_launcher,0,,2,,\
+3221225472,187.5,96"
+        );
+
+        let mods = vec![LockedMod {
+            dev: "HQ".to_string(),
+            name: "HQoL".to_string(),
+            version: "1.2.3".to_string(),
+            enabled: true,
+        }];
+        let hash = lockfile_hash(&mods);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, lockfile_hash(&mods.clone()));
+        let mut disabled = mods;
+        disabled[0].enabled = false;
+        assert_ne!(hash, lockfile_hash(&disabled));
+    }
+}
@@ -0,0 +1,327 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+// User scripts run around a game session: pre-launch hooks right before the game
+// is spawned, post-exit hooks once a game in `GameState.active` has exited. Each
+// hook is a command line (split like a shell would, not run through one) started
+// in the version folder with this environment:
+//
+//   HQ_HOOK_EVENT    `pre_launch` or `post_exit`
+//   HQ_VERSION       game version, e.g. `73`
+//   HQ_RUN_MODE      run mode, e.g. `hq`, `vanilla`, `wesley_practice`
+//   HQ_EVENT_ID      selected event id, empty without one
+//   HQ_GAME_DIR      version folder
+//   HQ_PID           game pid (post-exit only, empty before launch)
+//   HQ_EXIT_STATUS   exit code, or how the game ended when it has none (post-exit only,
+//                    empty when the game failed to start)
+//
+// Output goes to the launcher log line by line. A hook that outlives its timeout
+// is killed and counts as failed; a failed pre-launch hook blocks the launch only
+// when it is marked `block_launch`.
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hook {
+    pub name: String,
+    pub command: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Pre-launch only: do not start the game when this hook fails.
+    #[serde(default)]
+    pub block_launch: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookSettings {
+    #[serde(default)]
+    pub pre_launch: Vec<Hook>,
+    #[serde(default)]
+    pub post_exit: Vec<Hook>,
+}
+
+impl HookSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        for hook in self.pre_launch.iter().chain(&self.post_exit) {
+            if shlex::split(&hook.command).is_none_or(|words| words.is_empty()) {
+                return Err(format!("hook `{}` has no valid command", hook.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreLaunch,
+    PostExit,
+}
+
+impl HookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::PreLaunch => "pre_launch",
+            HookEvent::PostExit => "post_exit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookContext {
+    pub version: u32,
+    pub run_mode: String,
+    pub event_id: Option<String>,
+    pub game_dir: PathBuf,
+    pub pid: Option<u32>,
+}
+
+impl HookContext {
+    fn env(
+        &self,
+        event: HookEvent,
+        exit_status: Option<ExitStatus>,
+    ) -> Vec<(&'static str, String)> {
+        let exit_status = exit_status
+            .map(|status| {
+                status
+                    .code()
+                    .map_or_else(|| status.to_string(), |code| code.to_string())
+            })
+            .unwrap_or_default();
+        vec![
+            ("HQ_HOOK_EVENT", event.as_str().to_string()),
+            ("HQ_VERSION", self.version.to_string()),
+            ("HQ_RUN_MODE", self.run_mode.clone()),
+            ("HQ_EVENT_ID", self.event_id.clone().unwrap_or_default()),
+            ("HQ_GAME_DIR", self.game_dir.to_string_lossy().to_string()),
+            (
+                "HQ_PID",
+                self.pid.map(|pid| pid.to_string()).unwrap_or_default(),
+            ),
+            ("HQ_EXIT_STATUS", exit_status),
+        ]
+    }
+}
+
+fn log_output(name: String, stream: impl Read + Send + 'static, is_stderr: bool) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if is_stderr {
+                log::warn!("[hook {name}] {line}");
+            } else {
+                log::info!("[hook {name}] {line}");
+            }
+        }
+    });
+}
+
+fn run_hook(
+    hook: &Hook,
+    env: &[(&'static str, String)],
+    current_dir: &std::path::Path,
+    timeout: Duration,
+) -> Result<(), String> {
+    let words = shlex::split(&hook.command).unwrap_or_default();
+    let (program, args) = words
+        .split_first()
+        .ok_or_else(|| "no valid command".to_string())?;
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .envs(env.iter().map(|(name, value)| (*name, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if current_dir.is_dir() {
+        command.current_dir(current_dir);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("failed to start: {e}"))?;
+    if let Some(stdout) = child.stdout.take() {
+        log_output(hook.name.clone(), stdout, false);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        log_output(hook.name.clone(), stderr, true);
+    }
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(format!("exited with {status}"))
+            };
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("timed out after {}s", timeout.as_secs()));
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn run_hooks(
+    hooks: &[Hook],
+    event: HookEvent,
+    context: &HookContext,
+    exit_status: Option<ExitStatus>,
+) -> Result<(), String> {
+    let env = context.env(event, exit_status);
+    for hook in hooks.iter().filter(|hook| hook.enabled) {
+        log::info!("Running {} hook `{}`", event.as_str(), hook.name);
+        let timeout = Duration::from_secs(hook.timeout_secs.max(1));
+        if let Err(e) = run_hook(hook, &env, &context.game_dir, timeout) {
+            log::warn!("{} hook `{}` failed: {e}", event.as_str(), hook.name);
+            if event == HookEvent::PreLaunch && hook.block_launch {
+                return Err(format!("Pre-launch hook `{}` failed: {e}", hook.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the pre-launch hooks in order; `Err` means the launch must not go ahead.
+pub fn run_pre_launch(settings: &HookSettings, context: &HookContext) -> Result<(), String> {
+    run_hooks(&settings.pre_launch, HookEvent::PreLaunch, context, None)
+}
+
+/// Post-exit hooks of one running game, run when it is removed from `GameState`.
+#[derive(Debug)]
+pub struct ExitHooks {
+    hooks: Vec<Hook>,
+    context: HookContext,
+}
+
+impl ExitHooks {
+    pub fn new(settings: &HookSettings, context: HookContext) -> Option<Self> {
+        let hooks: Vec<Hook> = settings
+            .post_exit
+            .iter()
+            .filter(|hook| hook.enabled)
+            .cloned()
+            .collect();
+        (!hooks.is_empty()).then_some(Self { hooks, context })
+    }
+
+    /// Run in the background so callers holding the game state lock are not held up.
+    /// `None` when the game failed to start after the pre-launch hooks ran.
+    pub fn spawn(self, exit_status: Option<ExitStatus>) {
+        std::thread::spawn(move || {
+            let _ = run_hooks(&self.hooks, HookEvent::PostExit, &self.context, exit_status);
+        });
+    }
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("config")
+        .join("launch_hooks.json"))
+}
+
+pub fn load_settings(app: &tauri::AppHandle) -> Result<HookSettings, String> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(HookSettings::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn save_settings(app: &tauri::AppHandle, settings: &HookSettings) -> Result<(), String> {
+    settings.validate()?;
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(command: &str, block_launch: bool) -> Hook {
+        Hook {
+            name: "test".to_string(),
+            command: command.to_string(),
+            enabled: true,
+            timeout_secs: 1,
+            block_launch,
+        }
+    }
+
+    #[test]
+    fn environment_describes_the_session() {
+        let context = HookContext {
+            version: 73,
+            run_mode: "wesley_practice".to_string(),
+            event_id: None,
+            game_dir: PathBuf::from("/games/v73"),
+            pid: Some(4242),
+        };
+        let env = context.env(HookEvent::PreLaunch, None);
+        let get = |name: &str| env.iter().find(|(n, _)| *n == name).unwrap().1.clone();
+        assert_eq!(get("HQ_HOOK_EVENT"), "pre_launch");
+        assert_eq!(get("HQ_VERSION"), "73");
+        assert_eq!(get("HQ_RUN_MODE"), "wesley_practice");
+        assert_eq!(get("HQ_EVENT_ID"), "");
+        assert_eq!(get("HQ_GAME_DIR"), "/games/v73");
+        assert_eq!(get("HQ_PID"), "4242");
+        assert_eq!(get("HQ_EXIT_STATUS"), "");
+
+        let settings = HookSettings {
+            post_exit: vec![hook("   ", false)],
+            ..HookSettings::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_hooks_block_only_when_marked() {
+        let context = HookContext {
+            game_dir: std::env::temp_dir(),
+            ..HookContext::default()
+        };
+        let mut settings = HookSettings {
+            pre_launch: vec![
+                hook("sh -c 'test \"$HQ_HOOK_EVENT\" = pre_launch'", true),
+                hook("sh -c 'exit 3'", false),
+            ],
+            ..HookSettings::default()
+        };
+        assert!(run_pre_launch(&settings, &context).is_ok());
+
+        settings.pre_launch[1].block_launch = true;
+        let err = run_pre_launch(&settings, &context).unwrap_err();
+        assert!(err.contains("exit status: 3"), "{err}");
+
+        settings.pre_launch = vec![hook("sleep 5", true)];
+        let started = Instant::now();
+        let err = run_pre_launch(&settings, &context).unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use serde::{Deserialize, Serialize};
+
+// Every launch is decided before anything on disk changes. `build_launch_plan` (lib.rs)
+// resolves the mods, config files, proxy DLLs and command line into a `LaunchPlan`,
+// `preview_launch` hands it to the UI as is and `execute_launch_plan` carries it out.
+// The per-mode rules live in `LaunchPlan::compose` so the launch commands cannot
+// drift apart again.
+
+pub type ModId = (String, String);
+
+pub const BASE_VLOG: (&str, &str) = ("HQHQTeam", "VLog");
+pub const EVENT_VLOG: (&str, &str) = ("asta", "EVlog");
+
+fn mod_id((dev, name): (&str, &str)) -> ModId {
+    (dev.to_string(), name.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LaunchMode {
+    Hq,
+    /// No BepInEx and no HQ overlay.
+    Vanilla,
+    Practice,
+    Preset {
+        preset: String,
+        practice: bool,
+    },
+}
+
+impl LaunchMode {
+    pub fn loads_mods(&self) -> bool {
+        !matches!(self, LaunchMode::Vanilla)
+    }
+
+    pub fn is_practice(&self) -> bool {
+        matches!(
+            self,
+            LaunchMode::Practice | LaunchMode::Preset { practice: true, .. }
+        )
+    }
+
+    fn takes_event(&self) -> bool {
+        matches!(self, LaunchMode::Hq | LaunchMode::Preset { .. })
+    }
+
+    /// Shown for the running game.
+    pub fn label(&self) -> String {
+        match self {
+            LaunchMode::Hq => "HQ".to_string(),
+            LaunchMode::Vanilla => "Vanilla".to_string(),
+            LaunchMode::Practice => "Practice".to_string(),
+            LaunchMode::Preset { preset, practice } if *practice => format!("{preset} Practice"),
+            LaunchMode::Preset { preset, .. } => preset.clone(),
+        }
+    }
+
+    /// Run mode name as the UI and the manifest know it, e.g. `wesley_practice`.
+    pub fn run_mode(&self) -> String {
+        match self {
+            LaunchMode::Hq => "hq".to_string(),
+            LaunchMode::Vanilla => "vanilla".to_string(),
+            LaunchMode::Practice => "practice".to_string(),
+            LaunchMode::Preset { preset, practice } if *practice => {
+                format!("{}_practice", preset.trim().to_lowercase())
+            }
+            LaunchMode::Preset { preset, .. } => preset.trim().to_lowercase(),
+        }
+    }
+
+    /// Name the manifest's cfg patches are selected by.
+    pub fn cfg_patch_preset(&self) -> &str {
+        match self {
+            LaunchMode::Preset { preset, .. } => preset,
+            _ => "hq",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchRequest {
+    pub version: u32,
+    pub mode: LaunchMode,
+    #[serde(default)]
+    pub event_id: Option<String>,
+    #[serde(default)]
+    pub launch_options: Vec<String>,
+    #[serde(default)]
+    pub launch_command_template: Option<String>,
+    #[serde(default)]
+    pub allow_multiple: bool,
+    /// Launch profile to use instead of the one selected for the version or run mode.
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+impl LaunchRequest {
+    /// The selected event, when the mode runs events at all.
+    pub fn event_id(&self) -> Option<&str> {
+        self.event_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && self.mode.takes_event())
+    }
+}
+
+/// Config files a launch writes, in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CfgStep {
+    EventVlog {
+        event_id: String,
+    },
+    /// Built-in and manifest `cfg_patches` for this run mode.
+    CfgPatches {
+        preset: String,
+        practice: bool,
+    },
+    /// Undo the recorded patches; Vanilla runs get the configs as the user left them.
+    RevertCfgPatches,
+}
+
+/// Proxy DLLs the game may load; the others are hidden until it exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ProxyDlls {
+    /// BepInEx's `winhttp.dll`.
+    pub winhttp: bool,
+    /// The native HQ overlay's `version.dll`.
+    pub version_dll: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GameCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Set for the game; `None` removes a variable inherited from the launcher.
+    pub env: BTreeMap<String, Option<String>>,
+    pub current_dir: String,
+    /// Wine/Proton prefix the game runs in (Linux).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wine_prefix: Option<String>,
+}
+
+fn parse_launch_env_assignment(entry: &str) -> Option<(&str, &str)> {
+    let trimmed = entry.trim();
+    let (name, value) = trimmed.split_once('=')?;
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    if !(first == '_' || first.is_ascii_alphabetic()) {
+        return None;
+    }
+    if !chars.all(|ch| ch == '_' || ch.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    Some((name, value))
+}
+
+impl GameCommand {
+    pub fn new(program: OsString, args: Vec<OsString>) -> Self {
+        Self {
+            program: program.to_string_lossy().to_string(),
+            args: args
+                .iter()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn set_env(&mut self, name: &str, value: impl Into<String>) {
+        self.env.insert(name.to_string(), Some(value.into()));
+    }
+
+    pub fn remove_env(&mut self, name: &str) {
+        self.env.insert(name.to_string(), None);
+    }
+
+    /// User launch options: `NAME=value` entries set variables, the rest are arguments.
+    pub fn push_launch_options(&mut self, launch_options: &[String]) {
+        for raw_entry in launch_options {
+            let entry = raw_entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+
+            if let Some((name, value)) = parse_launch_env_assignment(entry) {
+                self.set_env(name, value);
+            } else {
+                self.args.push(entry.to_string());
+            }
+        }
+    }
+
+    pub fn to_command(&self) -> std::process::Command {
+        let mut command = std::process::Command::new(&self.program);
+        command.args(&self.args);
+        for (name, value) in &self.env {
+            match value {
+                Some(value) => command.env(name, value),
+                None => command.env_remove(name),
+            };
+        }
+        command.current_dir(&self.current_dir);
+        command
+    }
+}
+
+/// What `compose` needs besides the request, resolved by the caller.
+#[derive(Debug, Clone, Default)]
+pub struct ModInputs {
+    /// Mods the selected event requires.
+    pub event_mods: Vec<ModId>,
+    /// Every practice mod.
+    pub practice_list: Vec<ModId>,
+    /// Practice mods this run enables (practice runs only).
+    pub practice_mods: Vec<ModId>,
+    pub preset_tags: Vec<String>,
+    /// Mods carrying the preset's tags (preset runs only).
+    pub preset_mods: Vec<ModId>,
+    /// Mods tied to another run mode, already narrowed for the preset.
+    pub run_mode_mods: Vec<ModId>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchPlan {
+    #[serde(flatten)]
+    pub request: LaunchRequest,
+    pub mode_label: String,
+    /// Id of the launch profile applied to `command`.
+    pub launch_profile: Option<String>,
+    pub preset_tags: Vec<String>,
+    /// Drop the mods of an event selected for an earlier run.
+    pub clear_event_mods: bool,
+    /// Installed first: the run needs them and they are missing.
+    pub install_mods: Vec<ModId>,
+    /// Taken off the saved disabled list for good.
+    pub persist_enabled: Vec<ModId>,
+    /// Enabled for this run whatever the saved list says; wins over `force_disabled`.
+    pub force_enabled: Vec<ModId>,
+    pub force_disabled: Vec<ModId>,
+    pub cfg_steps: Vec<CfgStep>,
+    pub proxy_dlls: ProxyDlls,
+    pub start_lcstats: bool,
+    pub command: GameCommand,
+}
+
+impl LaunchPlan {
+    /// Apply the per-mode rules. `install_mods`, `proxy_dlls.version_dll`,
+    /// `start_lcstats`, `launch_profile` and `command` are left for the caller.
+    pub fn compose(request: LaunchRequest, inputs: ModInputs) -> Self {
+        let loads_mods = request.mode.loads_mods();
+        let practice = request.mode.is_practice();
+        let event_id = request.event_id().map(str::to_string);
+        let mut plan = LaunchPlan {
+            mode_label: request.mode.label(),
+            launch_profile: None,
+            preset_tags: inputs.preset_tags.clone(),
+            clear_event_mods: loads_mods && event_id.is_none(),
+            install_mods: vec![],
+            persist_enabled: vec![],
+            force_enabled: vec![],
+            force_disabled: vec![],
+            cfg_steps: vec![],
+            proxy_dlls: ProxyDlls {
+                winhttp: loads_mods,
+                version_dll: false,
+            },
+            start_lcstats: false,
+            command: GameCommand::default(),
+            request,
+        };
+        if !loads_mods {
+            plan.cfg_steps.push(CfgStep::RevertCfgPatches);
+            return plan;
+        }
+
+        plan.persist_enabled = inputs.preset_mods.clone();
+        plan.force_enabled = inputs.preset_mods;
+        plan.force_enabled.extend(inputs.practice_mods);
+        plan.force_enabled.extend(inputs.event_mods);
+
+        plan.force_disabled = inputs.practice_list;
+        if practice {
+            plan.force_disabled.push(mod_id(BASE_VLOG));
+            plan.force_disabled.push(mod_id(EVENT_VLOG));
+        } else if event_id.is_some() {
+            plan.force_disabled.push(mod_id(BASE_VLOG));
+        } else {
+            plan.force_disabled.push(mod_id(EVENT_VLOG));
+        }
+        plan.force_disabled.extend(inputs.run_mode_mods);
+
+        if let Some(event_id) = event_id {
+            plan.cfg_steps.push(CfgStep::EventVlog { event_id });
+        }
+        plan.cfg_steps.push(CfgStep::CfgPatches {
+            preset: plan.request.mode.cfg_patch_preset().to_string(),
+            practice,
+        });
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(dev: &str, name: &str) -> ModId {
+        (dev.to_string(), name.to_string())
+    }
+
+    fn request(mode: LaunchMode, event_id: Option<&str>) -> LaunchRequest {
+        LaunchRequest {
+            version: 73,
+            mode,
+            event_id: event_id.map(str::to_string),
+            launch_options: vec![],
+            launch_command_template: None,
+            allow_multiple: false,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn hq_event_run_swaps_vlog_and_enables_event_mods() {
+        let inputs = ModInputs {
+            event_mods: vec![id("asta", "EVlog"), id("Zeekerss", "EventMoon")],
+            practice_list: vec![id("HQHQTeam", "PracticeTools")],
+            run_mode_mods: vec![id("Someone", "SoloOnly")],
+            ..ModInputs::default()
+        };
+        let plan = LaunchPlan::compose(request(LaunchMode::Hq, Some(" halloween ")), inputs);
+        assert_eq!(plan.mode_label, "HQ");
+        assert!(!plan.clear_event_mods);
+        assert!(plan.force_enabled.contains(&id("Zeekerss", "EventMoon")));
+        assert!(plan.force_disabled.contains(&id("HQHQTeam", "VLog")));
+        assert!(!plan.force_disabled.contains(&id("asta", "EVlog")));
+        assert!(plan
+            .force_disabled
+            .contains(&id("HQHQTeam", "PracticeTools")));
+        assert_eq!(
+            plan.cfg_steps.first(),
+            Some(&CfgStep::EventVlog {
+                event_id: "halloween".to_string()
+            })
+        );
+        assert_eq!(
+            plan.cfg_steps.last(),
+            Some(&CfgStep::CfgPatches {
+                preset: "hq".to_string(),
+                practice: false
+            })
+        );
+    }
+
+    #[test]
+    fn presets_and_practice_follow_the_same_rules() {
+        let wesley = LaunchPlan::compose(
+            request(
+                LaunchMode::Preset {
+                    preset: "wesley".to_string(),
+                    practice: false,
+                },
+                None,
+            ),
+            ModInputs {
+                preset_tags: vec!["Wesley".to_string()],
+                preset_mods: vec![id("mrov", "LethalNetworkAPI")],
+                practice_list: vec![id("mrov", "LethalNetworkAPI")],
+                ..ModInputs::default()
+            },
+        );
+        assert_eq!(wesley.request.mode.run_mode(), "wesley");
+        let wesley_practice = LaunchMode::Preset {
+            preset: "Wesley".to_string(),
+            practice: true,
+        };
+        assert_eq!(wesley_practice.run_mode(), "wesley_practice");
+        assert!(wesley.clear_event_mods);
+        assert_eq!(wesley.persist_enabled, vec![id("mrov", "LethalNetworkAPI")]);
+        assert!(wesley
+            .force_enabled
+            .contains(&id("mrov", "LethalNetworkAPI")));
+        assert_eq!(
+            wesley.cfg_steps,
+            vec![CfgStep::CfgPatches {
+                preset: "wesley".to_string(),
+                practice: false
+            }]
+        );
+
+        // A practice run ignores events and keeps both VLogs off.
+        let practice = LaunchPlan::compose(request(LaunchMode::Practice, Some("halloween")), {
+            ModInputs {
+                practice_mods: vec![id("HQHQTeam", "PracticeTools")],
+                ..ModInputs::default()
+            }
+        });
+        assert!(practice.clear_event_mods);
+        assert!(practice.force_disabled.contains(&id("HQHQTeam", "VLog")));
+        assert!(practice.force_disabled.contains(&id("asta", "EVlog")));
+
+        let vanilla = LaunchPlan::compose(request(LaunchMode::Vanilla, None), ModInputs::default());
+        assert_eq!(vanilla.mode_label, "Vanilla");
+        assert!(!vanilla.proxy_dlls.winhttp);
+        assert!(!vanilla.clear_event_mods);
+        assert_eq!(vanilla.cfg_steps, vec![CfgStep::RevertCfgPatches]);
+    }
+
+    #[test]
+    fn launch_options_split_into_env_and_args() {
+        let mut command = GameCommand::new("game.exe".into(), vec!["-screen-fullscreen".into()]);
+        command.push_launch_options(&[
+            " DXVK_HUD=fps ".to_string(),
+            "-logFile".to_string(),
+            "1BAD=x".to_string(),
+            "  ".to_string(),
+        ]);
+        assert_eq!(
+            command.args,
+            vec!["-screen-fullscreen", "-logFile", "1BAD=x"]
+        );
+        assert_eq!(command.env.get("DXVK_HUD"), Some(&Some("fps".to_string())));
+    }
+}
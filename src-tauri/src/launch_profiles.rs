@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+// Saved launch profiles: launch options, environment and a wrapper template the
+// launcher applies without the UI passing them in on every launch. A profile is
+// picked per version first, then per run mode. Templates are split like a shell
+// command line and `%name%` variables are expanded per word afterwards, so a value
+// containing spaces or quotes stays a single argument.
+
+/// Placeholder for the game command itself; the caller substitutes it.
+pub const COMMAND_PLACEHOLDER: &str = "%command%";
+
+pub const VARIABLES: [&str; 5] = ["version", "game_dir", "run_mode", "bepinex_log", "pid_file"];
+
+/// Start of a shared profile code; the number changes if the encoding does.
+const CODE_PREFIX: &str = "hqp1.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchProfile {
+    pub id: String,
+    pub name: String,
+    /// Same format as the per-launch options: `NAME=value` or an argument.
+    #[serde(default)]
+    pub launch_options: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Used when the launch itself passes no template.
+    #[serde(default)]
+    pub command_template: Option<String>,
+    /// Wine prefix to run in on Linux, instead of the version's.
+    #[serde(default)]
+    pub wine_prefix: Option<String>,
+}
+
+impl LaunchProfile {
+    /// Drop everything that runs code: the command template, environment and
+    /// options. Returns whether there was any.
+    pub fn strip_commands(&mut self) -> bool {
+        let had_commands = !self.launch_options.is_empty()
+            || !self.env.is_empty()
+            || self.command_template.is_some();
+        self.launch_options.clear();
+        self.env.clear();
+        self.command_template = None;
+        had_commands
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchProfiles {
+    #[serde(default)]
+    pub profiles: Vec<LaunchProfile>,
+    #[serde(default)]
+    pub per_version: BTreeMap<u32, String>,
+    /// Keyed by run mode as in `LaunchMode::run_mode`, e.g. `wesley_practice`.
+    #[serde(default)]
+    pub per_run_mode: BTreeMap<String, String>,
+}
+
+impl LaunchProfiles {
+    pub fn get(&self, id: &str) -> Option<&LaunchProfile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// The profile a launch uses: `explicit` when given, else the one selected for
+    /// the version, else the one for the run mode.
+    pub fn resolve(
+        &self,
+        explicit: Option<&str>,
+        version: u32,
+        run_mode: &str,
+    ) -> Result<Option<&LaunchProfile>, String> {
+        let id = explicit
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .or_else(|| self.per_version.get(&version).map(String::as_str))
+            .or_else(|| {
+                self.per_run_mode
+                    .get(&run_mode.to_lowercase())
+                    .map(String::as_str)
+            });
+        match id {
+            Some(id) => self
+                .get(id)
+                .map(Some)
+                .ok_or_else(|| format!("launch profile `{id}` not found")),
+            None => Ok(None),
+        }
+    }
+
+    /// Add a profile shared by someone else. Its id gets a `-2`, `-3`, ... suffix
+    /// when a profile with that id already exists.
+    pub fn import(&mut self, mut profile: LaunchProfile) -> Result<&LaunchProfile, String> {
+        let base = profile.id.trim().to_string();
+        profile.id = base.clone();
+        let mut n = 1;
+        while self.get(&profile.id).is_some() {
+            n += 1;
+            profile.id = format!("{base}-{n}");
+        }
+        self.profiles.push(profile);
+        if let Err(e) = self.validate() {
+            self.profiles.pop();
+            return Err(e);
+        }
+        Ok(&self.profiles[self.profiles.len() - 1])
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for profile in &self.profiles {
+            if profile.id.trim().is_empty() {
+                return Err(format!("launch profile `{}` has no id", profile.name));
+            }
+            if !seen.insert(profile.id.as_str()) {
+                return Err(format!("duplicate launch profile id `{}`", profile.id));
+            }
+            if let Some(name) = profile.env.keys().find(|name| !is_env_name(name)) {
+                return Err(format!(
+                    "launch profile `{}`: invalid environment variable name `{name}`",
+                    profile.name
+                ));
+            }
+            if let Some(template) = profile.command_template.as_deref() {
+                expand_template(template, &TemplateVars::default())
+                    .map_err(|e| format!("launch profile `{}`: {e}", profile.name))?;
+            }
+            if let Some(prefix) = profile.wine_prefix.as_deref() {
+                crate::wine_prefixes::check_id(prefix)
+                    .map_err(|e| format!("launch profile `{}`: {e}", profile.name))?;
+            }
+        }
+        let mut selections = self.per_version.values().chain(self.per_run_mode.values());
+        if let Some(id) = selections.find(|id| self.get(id).is_none()) {
+            return Err(format!("launch profile `{id}` not found"));
+        }
+        Ok(())
+    }
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|ch| ch == '_' || ch.is_ascii_alphanumeric())
+}
+
+/// Values for the `%name%` variables of a command template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateVars {
+    pub version: u32,
+    pub game_dir: String,
+    pub run_mode: String,
+    pub bepinex_log: String,
+    /// The launcher writes the game's pid here once it has started.
+    pub pid_file: String,
+}
+
+impl TemplateVars {
+    fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "version" => Some(self.version.to_string()),
+            "game_dir" => Some(self.game_dir.clone()),
+            "run_mode" => Some(self.run_mode.clone()),
+            "bepinex_log" => Some(self.bepinex_log.clone()),
+            "pid_file" => Some(self.pid_file.clone()),
+            _ => None,
+        }
+    }
+
+    /// Expand the variables in one template word. `%%` is a literal `%`, and a `%`
+    /// that does not start a `%name%` is kept as is.
+    pub fn expand(&self, word: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(word.len());
+        let mut rest = word;
+        while let Some(start) = rest.find('%') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let name_len = after
+                .find(|ch: char| !(ch == '_' || ch.is_ascii_alphanumeric()))
+                .unwrap_or(after.len());
+            if !after[name_len..].starts_with('%') {
+                out.push('%');
+                rest = after;
+                continue;
+            }
+            let name = &after[..name_len];
+            if name.is_empty() {
+                out.push('%');
+            } else if name == "command" {
+                return Err(format!(
+                    "{COMMAND_PLACEHOLDER} must be a word of its own in the launch command template"
+                ));
+            } else {
+                let value = self.get(name).ok_or_else(|| {
+                    format!(
+                        "unknown launch template variable %{name}% (known: {})",
+                        VARIABLES.map(|v| format!("%{v}%")).join(", ")
+                    )
+                })?;
+                out.push_str(&value);
+            }
+            rest = &after[name_len + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// Split `template` into words and expand their variables. `%command%` words are
+/// left for the caller.
+pub fn expand_template(template: &str, vars: &TemplateVars) -> Result<Vec<String>, String> {
+    let words = shlex::split(template)
+        .ok_or_else(|| "failed to parse launch command template".to_string())?;
+    words
+        .into_iter()
+        .map(|word| {
+            if word == COMMAND_PLACEHOLDER {
+                Ok(word)
+            } else {
+                vars.expand(&word)
+            }
+        })
+        .collect()
+}
+
+/// Text form of a profile for sharing: its JSON, base64url encoded.
+pub fn to_code(profile: &LaunchProfile) -> Result<String, String> {
+    use base64::Engine as _;
+    let json = serde_json::to_vec(profile).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "{CODE_PREFIX}{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json)
+    ))
+}
+
+pub fn from_code(code: &str) -> Result<LaunchProfile, String> {
+    use base64::Engine as _;
+    let body = code
+        .trim()
+        .strip_prefix(CODE_PREFIX)
+        .ok_or_else(|| "not a launch profile code".to_string())?;
+    let json = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(body.trim_end_matches('='))
+        .map_err(|_| "launch profile code is damaged".to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("launch profile code is damaged: {e}"))
+}
+
+fn app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))
+}
+
+fn profiles_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?
+        .join("config")
+        .join("launch_profiles.json"))
+}
+
+/// Where `%pid_file%` points for `version`.
+pub fn pid_file(app: &tauri::AppHandle, version: u32) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?
+        .join("run")
+        .join(format!("v{version}.pid")))
+}
+
+pub fn load(app: &tauri::AppHandle) -> Result<LaunchProfiles, String> {
+    let path = profiles_path(app)?;
+    if !path.exists() {
+        return Ok(LaunchProfiles::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn save(app: &tauri::AppHandle, profiles: &LaunchProfiles) -> Result<(), String> {
+    profiles.validate()?;
+    let path = profiles_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars {
+        TemplateVars {
+            version: 73,
+            game_dir: "/games/HQ Launcher/v73".to_string(),
+            run_mode: "wesley_practice".to_string(),
+            bepinex_log: "/games/HQ Launcher/v73/BepInEx/LogOutput.log".to_string(),
+            pid_file: "/data/run/v73.pid".to_string(),
+        }
+    }
+
+    #[test]
+    fn expanded_values_stay_single_words() {
+        let words = expand_template(
+            r#"obs-wrap --dir=%game_dir% "--title=v%version% %run_mode%" '%pid_file%' %command% 100%"#,
+            &vars(),
+        )
+        .unwrap();
+        assert_eq!(
+            words,
+            vec![
+                "obs-wrap",
+                "--dir=/games/HQ Launcher/v73",
+                "--title=v73 wesley_practice",
+                "/data/run/v73.pid",
+                "%command%",
+                "100%",
+            ]
+        );
+        assert_eq!(
+            vars().expand("50%% of %bepinex_log").unwrap(),
+            "50% of %bepinex_log"
+        );
+    }
+
+    #[test]
+    fn unknown_variables_and_bad_templates_are_rejected() {
+        let err = expand_template("wrap %gamedir% %command%", &vars()).unwrap_err();
+        assert!(err.contains("%gamedir%"), "{err}");
+        assert!(expand_template("wrap --run=%command%", &vars()).is_err());
+        assert!(expand_template("wrap \"unterminated", &vars()).is_err());
+
+        let mut profiles = LaunchProfiles {
+            profiles: vec![LaunchProfile {
+                id: "stream".to_string(),
+                name: "Stream".to_string(),
+                launch_options: vec![],
+                env: BTreeMap::from([("OBS SCENE".to_string(), "game".to_string())]),
+                command_template: None,
+                wine_prefix: None,
+            }],
+            ..LaunchProfiles::default()
+        };
+        assert!(profiles.validate().is_err());
+        profiles.profiles[0].env.clear();
+        profiles
+            .per_run_mode
+            .insert("hq".to_string(), "missing".to_string());
+        assert!(profiles.validate().is_err());
+    }
+
+    #[test]
+    fn codes_round_trip_and_imports_get_free_ids() {
+        let profile = LaunchProfile {
+            id: "stream".to_string(),
+            name: "Stream".to_string(),
+            launch_options: vec!["-screen-fullscreen 0".to_string()],
+            env: BTreeMap::from([("OBS_SCENE".to_string(), "game".to_string())]),
+            command_template: Some("obs-wrap %command%".to_string()),
+            wine_prefix: Some("stream".to_string()),
+        };
+        let code = to_code(&profile).unwrap();
+        assert!(code.starts_with("hqp1."), "{code}");
+        assert_eq!(from_code(&format!(" {code}\n")).unwrap(), profile);
+        assert!(from_code("hqp1.!!!").is_err());
+        assert!(from_code(&code[5..]).is_err());
+
+        let mut profiles = LaunchProfiles::default();
+        profiles.import(profile.clone()).unwrap();
+        assert_eq!(profiles.import(profile.clone()).unwrap().id, "stream-2");
+        let broken = LaunchProfile {
+            command_template: Some("wrap --run=%command%".to_string()),
+            ..profile
+        };
+        assert!(profiles.import(broken.clone()).is_err());
+        assert_eq!(profiles.profiles.len(), 2);
+
+        let mut linked = broken;
+        assert!(linked.strip_commands());
+        assert_eq!((linked.launch_options.len(), linked.env.len()), (0, 0));
+        assert_eq!(linked.command_template, None);
+        assert_eq!(linked.wine_prefix.as_deref(), Some("stream"));
+        assert!(!linked.strip_commands());
+    }
+
+    #[test]
+    fn version_selection_wins_over_run_mode() {
+        let profile = |id: &str| LaunchProfile {
+            id: id.to_string(),
+            name: id.to_string(),
+            launch_options: vec![],
+            env: BTreeMap::new(),
+            command_template: None,
+            wine_prefix: None,
+        };
+        let profiles = LaunchProfiles {
+            profiles: vec![profile("stream"), profile("gamemode")],
+            per_version: BTreeMap::from([(73, "gamemode".to_string())]),
+            per_run_mode: BTreeMap::from([("practice".to_string(), "stream".to_string())]),
+        };
+        profiles.validate().unwrap();
+        let id = |p: Option<&LaunchProfile>| p.map(|p| p.id.clone());
+        assert_eq!(
+            id(profiles.resolve(None, 73, "practice").unwrap()),
+            Some("gamemode".to_string())
+        );
+        assert_eq!(
+            id(profiles.resolve(None, 72, "Practice").unwrap()),
+            Some("stream".to_string())
+        );
+        assert_eq!(id(profiles.resolve(None, 72, "hq").unwrap()), None);
+        assert_eq!(
+            id(profiles.resolve(Some("stream"), 73, "hq").unwrap()),
+            Some("stream".to_string())
+        );
+        assert!(profiles.resolve(Some("nope"), 73, "hq").is_err());
+    }
+}
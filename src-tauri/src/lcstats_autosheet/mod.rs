@@ -35,6 +35,7 @@ pub struct LcStatsAutosheetState {
     pending_stats: Arc<Mutex<Vec<PendingStatsEntry>>>,
     latest_payload: Arc<Mutex<Option<LatestLcStatsPayload>>>,
     recent_written_payloads: Arc<Mutex<VecDeque<u64>>>,
+    received_payloads: Arc<AtomicU64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +133,11 @@ pub fn is_running(state: &tauri::State<'_, LcStatsAutosheetState>) -> bool {
     state.running.load(Ordering::Acquire)
 }
 
+/// Payloads accepted while tracking since the launcher started.
+pub fn received_payload_count(state: &tauri::State<'_, LcStatsAutosheetState>) -> u64 {
+    state.received_payloads.load(Ordering::Acquire)
+}
+
 pub fn latest_payload(
     state: &tauri::State<'_, LcStatsAutosheetState>,
 ) -> Result<Option<LatestLcStatsPayload>, String> {
@@ -251,6 +257,7 @@ async fn run_listener(app: tauri::AppHandle, state: LcStatsAutosheetState) -> Re
             summary.seed_text(),
             summary.moon_name()
         );
+        state.received_payloads.fetch_add(1, Ordering::AcqRel);
         remember_latest_payload(&state, payload.clone(), stats.clone())?;
         write_overlay_lcstats_file(&app, &payload);
         emit_overlay_lcstats_update(&app, payload.clone(), stats.clone());
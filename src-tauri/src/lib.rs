@@ -1,18 +1,42 @@
 mod bepinex_cfg;
+mod bepinex_log;
+mod cfg_patches;
+mod crash_report;
+mod dedup_store;
+mod deep_link;
+mod depot_output;
 mod discord_presence;
+mod disk_usage;
+mod doctor;
+mod download_queue;
 mod downloader;
 mod event_config;
+mod fingerprint;
 mod google_oauth;
 mod installer;
+mod instance_archive;
+mod launch_history;
+mod launch_hooks;
+mod launch_plan;
+mod launch_profiles;
 mod lcstats_autosheet;
 mod logger;
 mod mod_config;
 mod mods;
 mod progress;
 mod release_channel;
+mod relocation;
+mod resource_monitor;
+mod runtimes;
+mod steam_accounts;
+mod steam_import;
 mod storage;
+#[cfg(test)]
+mod test_util;
 mod thunderstore;
 mod variable;
+mod version_meta;
+mod wine_prefixes;
 mod zip_utils;
 
 use serde::de::DeserializeOwned;
@@ -156,6 +180,9 @@ fn has_legacy_complete_files(path: &Path) -> bool {
 }
 
 fn is_complete_version_dir(app: &tauri::AppHandle, version: u32, path: &Path) -> bool {
+    if let Some(meta) = version_meta::read(path) {
+        return meta.is_complete();
+    }
     path.join(INSTALL_COMPLETE_MARKER).is_file()
         || (manifest_state_has_version(app, version) && has_legacy_complete_files(path))
 }
@@ -242,35 +269,6 @@ fn allow_eclipsed_hq_optional_mods(preset: &str, forced_disabled_ids: &mut Vec<(
     forced_disabled_ids.retain(|(dev, name)| !is_eclipsed_hq_optional_mod(dev, name));
 }
 
-fn is_wesley_base_run(tags: &[String], practice: bool) -> bool {
-    !practice
-        && tags.iter().any(|t| t.eq_ignore_ascii_case("wesley"))
-        && !tags.iter().any(|t| t.eq_ignore_ascii_case("smhq"))
-}
-
-const HQOL_DONT_STORE_CFG_FILES: [&str; 2] = ["OreoM.HQoL.72.cfg", "OreoM.HQoL.73.cfg"];
-
-const WESLEY_HQOL_DONT_STORE_ITEMS: [&str; 18] = [
-    "Royal apparatus",
-    "Bloody apparatus",
-    "Cosmic apparatus",
-    "Atlantica videotape",
-    "Acidir videotape",
-    "Asteroid-13 videotape",
-    "Junic videotape",
-    "Hyx videotape",
-    "Floppy disk",
-    "Infernis videotape",
-    "Etern videotape",
-    "Empra videotape",
-    "Filitrios videotape",
-    "Motra videotape",
-    "Hyve videotape",
-    "Utril videotape",
-    "Gratar videotape",
-    "Gloom videotape",
-];
-
 const LETHAL_COMPANY_STEAM_APP_ID: &str = "1966720";
 
 #[cfg(target_os = "windows")]
@@ -1641,9 +1639,38 @@ async fn prepare_tagged_mods_for_version(
         ));
     }
 
+    let tagged = tagged_mods_for_version(version, tags, cancel.as_ref()).await?;
+    // Return ids so the caller can force-enable these mods for this run (even if they overlap practice-disable rules).
+    let tagged_ids = mod_entry_pairs(&tagged);
+
+    let missing_tagged_mods = filter_missing_mods_for_version(app, version, &tagged)?;
+    install_missing_mods_with_progress(
+        app,
+        version,
+        missing_tagged_mods,
+        tags,
+        step_name,
+        &format!("Installing missing tagged mods: {}", tags.join(", ")),
+        cancel,
+    )
+    .await?;
+
+    Ok(tagged_ids)
+}
+
+/// Manifest mods carrying one of `tags` that are compatible with `version`, enabled
+/// even when the manifest marks them optional.
+async fn tagged_mods_for_version(
+    version: u32,
+    tags: &[String],
+    cancel: Option<&Arc<AtomicBool>>,
+) -> Result<Vec<mod_config::ModEntry>, String> {
+    if tags.is_empty() {
+        return Ok(vec![]);
+    }
     let client = reqwest::Client::new();
     let (_remote_manifest_version, mods_cfg, _chain_config, _manifests, preset_tag_constraints) =
-        ModsConfig::fetch_manifest_with_cancel(&client, cancel.as_ref()).await?;
+        ModsConfig::fetch_manifest_with_cancel(&client, cancel).await?;
     validate_preset_tags_for_version(version, tags, &preset_tag_constraints)?;
 
     let want: Vec<String> = tags.iter().map(|t| t.to_lowercase()).collect();
@@ -1660,81 +1687,78 @@ async fn prepare_tagged_mods_for_version(
     }
 
     // Only install compatible subset (same semantics as practice list).
-    let tagged: Vec<mod_config::ModEntry> = tagged
+    Ok(tagged
         .into_iter()
         .filter(|m| m.is_compatible_for_tags(version, tags))
-        .collect();
-    if tagged.is_empty() {
-        return Ok(vec![]);
-    }
-
-    // Return ids so the caller can force-enable these mods for this run (even if they overlap practice-disable rules).
-    let tagged_ids: Vec<(String, String)> = tagged
-        .iter()
-        .map(|m| (m.dev.clone(), m.name.clone()))
-        .collect();
+        .collect())
+}
 
-    let missing_tagged_mods = filter_missing_mods_for_version(app, version, &tagged)?;
-    if !missing_tagged_mods.is_empty() {
-        const STEPS_TOTAL: u32 = 1;
-        progress::emit_progress(
-            app,
-            TaskProgressPayload {
-                version,
-                steps_total: STEPS_TOTAL,
-                step: 1,
-                step_name: step_name.to_string(),
-                step_progress: 0.0,
-                overall_percent: 0.0,
-                detail: Some(format!(
-                    "Installing missing tagged mods: {}",
-                    tags.join(", ")
-                )),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: Some(0),
-                total_files: Some(missing_tagged_mods.len() as u64),
-            },
-        );
+/// Install `mods` (already narrowed to the missing ones) as a one-step task.
+async fn install_missing_mods_with_progress(
+    app: &tauri::AppHandle,
+    version: u32,
+    mods: Vec<mod_config::ModEntry>,
+    tags: &[String],
+    step_name: &str,
+    detail: &str,
+    cancel: Option<Arc<AtomicBool>>,
+) -> Result<(), String> {
+    if mods.is_empty() {
+        return Ok(());
+    }
+    let game_root = version_dir(app, version)?;
 
-        let cfg = ModsConfig {
-            mods: missing_tagged_mods,
-        };
-        mods::install_mods_with_progress(
-            app,
-            &game_root,
+    const STEPS_TOTAL: u32 = 1;
+    progress::emit_progress(
+        app,
+        TaskProgressPayload {
             version,
-            &cfg,
-            tags,
-            cancel,
-            |done, total, progress_info| {
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (done as f64 / total as f64).clamp(0.0, 1.0)
-                };
-                progress::emit_progress(
-                    app,
-                    TaskProgressPayload {
-                        version,
-                        steps_total: STEPS_TOTAL,
-                        step: 1,
-                        step_name: step_name.to_string(),
-                        step_progress,
-                        overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
-                        detail: progress_info.detail,
-                        downloaded_bytes: progress_info.downloaded_bytes,
-                        total_bytes: progress_info.total_bytes,
-                        extracted_files: progress_info.extracted_files.or(Some(done)),
-                        total_files: progress_info.total_files.or(Some(total)),
-                    },
-                );
-            },
-        )
-        .await?;
-    }
+            steps_total: STEPS_TOTAL,
+            step: 1,
+            step_name: step_name.to_string(),
+            step_progress: 0.0,
+            overall_percent: 0.0,
+            detail: Some(detail.to_string()),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: Some(0),
+            total_files: Some(mods.len() as u64),
+        },
+    );
 
-    Ok(tagged_ids)
+    let cfg = ModsConfig { mods };
+    mods::install_mods_with_progress(
+        app,
+        &game_root,
+        version,
+        &cfg,
+        tags,
+        cancel,
+        |done, total, progress_info| {
+            let step_progress = if total == 0 {
+                1.0
+            } else {
+                (done as f64 / total as f64).clamp(0.0, 1.0)
+            };
+            progress::emit_progress(
+                app,
+                TaskProgressPayload {
+                    version,
+                    steps_total: STEPS_TOTAL,
+                    step: 1,
+                    step_name: step_name.to_string(),
+                    step_progress,
+                    overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
+                    detail: progress_info.detail,
+                    downloaded_bytes: progress_info.downloaded_bytes,
+                    total_bytes: progress_info.total_bytes,
+                    extracted_files: progress_info.extracted_files.or(Some(done)),
+                    total_files: progress_info.total_files.or(Some(total)),
+                },
+            );
+        },
+    )
+    .await
 }
 
 async fn run_mode_tagged_mod_ids(
@@ -2058,23 +2082,19 @@ fn event_allows_current_tester(
     )
 }
 
-async fn event_forced_enabled_mods_for_launch(
+/// Mods `event_id` enables for a launch of `version`, once the event is confirmed to
+/// be open to this version and account.
+async fn event_mods_for_launch(
     app: &tauri::AppHandle,
     version: u32,
-    event_id: Option<String>,
+    event_id: &str,
 ) -> Result<Vec<(String, String)>, String> {
-    let event_id = event_id.unwrap_or_default().trim().to_string();
-    if event_id.is_empty() {
-        clear_event_mods_for_version(app, version)?;
-        return Ok(vec![]);
-    }
-
     let client = reqwest::Client::new();
     let events = event_config::fetch_events(&client).await?;
     let event = events
         .events
         .into_iter()
-        .find(|entry| entry.id.eq_ignore_ascii_case(&event_id))
+        .find(|entry| entry.id.eq_ignore_ascii_case(event_id))
         .ok_or_else(|| format!("event not found: {event_id}"))?;
 
     if !event.versions.is_empty() && !event.versions.contains(&version) {
@@ -2399,7 +2419,10 @@ fn version_dir(app: &tauri::AppHandle, version: u32) -> Result<std::path::PathBu
     Ok(storage::versions_dir(app)?.join(format!("v{version}")))
 }
 
-fn version_config_dir(app: &tauri::AppHandle, version: u32) -> Result<std::path::PathBuf, String> {
+pub(crate) fn version_config_dir(
+    app: &tauri::AppHandle,
+    version: u32,
+) -> Result<std::path::PathBuf, String> {
     Ok(version_dir(app, version)?.join("BepInEx").join("config"))
 }
 
@@ -2498,244 +2521,36 @@ fn sync_fontpatcher_with_assets_for_version(
     Ok(())
 }
 
-fn ensure_wesley_moonscripts_cfg(
+pub(crate) fn ensure_reverb_trigger_fix_cfg(
     app: &tauri::AppHandle,
     version: u32,
-    lock_moons: bool,
 ) -> Result<(), String> {
-    // Only meaningful for the Wesley preset (v69+).
-    if version < 69 {
-        return Ok(());
-    }
-
-    let cfg_dir = version_config_dir(app, version)?;
-    std::fs::create_dir_all(&cfg_dir).map_err(|e| e.to_string())?;
-
-    let cfg_path = cfg_dir.join("JacobG5.WesleyMoonScripts.cfg");
-    if cfg_path.exists() {
-        // Preserve everything else in the file and only update LockMoons.
-        let bytes = std::fs::read(&cfg_path).map_err(|e| e.to_string())?;
-        let text = String::from_utf8_lossy(&bytes);
-
-        let mut changed = false;
-        let mut out = String::with_capacity(text.len());
-        let desired_value = if lock_moons { "true" } else { "false" };
-
-        for seg in text.split_inclusive(['\n', '\r']) {
-            // Preserve original newline sequence; only edit the assignment line.
-            let (line, nl) = if seg.ends_with("\r\n") {
-                (seg.trim_end_matches("\r\n"), "\r\n")
-            } else if seg.ends_with('\n') {
-                (seg.trim_end_matches('\n'), "\n")
-            } else if seg.ends_with('\r') {
-                (seg.trim_end_matches('\r'), "\r")
-            } else {
-                (seg, "")
-            };
-
-            let trimmed = line.trim_start();
-            let indent_len = line.len().saturating_sub(trimmed.len());
-            let indent = &line[..indent_len];
-
-            if trimmed.starts_with("LockMoons") {
-                if let Some(eq_idx) = trimmed.find('=') {
-                    let (left, right_all) = trimmed.split_at(eq_idx);
-                    let right_all = right_all.trim_start_matches('=');
-
-                    // Keep inline comments if any.
-                    let (right, comment) = match right_all.find('#') {
-                        Some(i) => (&right_all[..i], &right_all[i..]),
-                        None => (right_all, ""),
-                    };
-
-                    if left.trim() == "LockMoons" {
-                        if !right.trim().eq_ignore_ascii_case(desired_value) {
-                            out.push_str(indent);
-                            out.push_str("LockMoons = ");
-                            out.push_str(desired_value);
-                            out.push_str(comment);
-                            out.push_str(nl);
-                            changed = true;
-                            continue;
-                        }
-                    }
-                }
-            }
-
-            out.push_str(line);
-            out.push_str(nl);
-        }
-
-        if changed {
-            std::fs::write(&cfg_path, out).map_err(|e| e.to_string())?;
-        }
+    let patch = cfg_patches::reverb_trigger_fix();
+    if installed_patch_mods(app, version, std::slice::from_ref(&patch)).is_empty() {
         return Ok(());
     }
-
-    let content = format!(
-        "## Settings file was created by plugin WesleyMoonScripts v1.1.6\n## Plugin GUID: JacobG5.WesleyMoonScripts\n\n[Core]\n\n## Locks moons that have progression integration set up to enable playing the campaign.\n# Setting type: Boolean\n# Default value: true\nLockMoons = {}\n\n",
-        if lock_moons { "true" } else { "false" }
-    );
-
-    std::fs::write(&cfg_path, content).map_err(|e| e.to_string())?;
+    cfg_patches::apply_in_dir(&version_config_dir(app, version)?, &patch)?;
     Ok(())
 }
 
-pub(crate) fn ensure_reverb_trigger_fix_cfg(
+/// `Dev-Name` of the mods named by `requires_mod` that are installed for `version`.
+fn installed_patch_mods(
     app: &tauri::AppHandle,
     version: u32,
-) -> Result<(), String> {
-    let plugins = plugins_dir(app, version)?;
-    if mod_dir_for(&plugins, "JacobG5", "ReverbTriggerFix").is_none() {
-        return Ok(());
-    }
-
-    let cfg_dir = version_config_dir(app, version)?;
-    std::fs::create_dir_all(&cfg_dir).map_err(|e| e.to_string())?;
-
-    let cfg_path = cfg_dir.join("JacobG5.ReverbTriggerFix.cfg");
-    let baseline = "## Settings file was created by plugin ReverbTriggerFix v0.3.0\n## Plugin GUID: JacobG5.ReverbTriggerFix\n\n[Core]\n\n## Disables all reverb trigger modifications.\n## Requires a lobby restart to apply.\n## Game restart *not* required.\n# Setting type: Boolean\n# Default value: false\ndisableMod = false\n\n[Debug]\n\n## Logs more info to the console when enabled.\n## \n## *THIS WILL SPAM YOUR CONSOLE DEPENDING ON YOUR OTHER SETTINGS*\n# Setting type: Boolean\n# Default value: false\nextendedLogging = false\n\n[Experimental]\n\n## I'm not sure why reverb triggers run their calculations every frame when as far as I can tell they only need to run their changes when something enters their collider.\n## I'm leaving this as an experimental toggle because it seems to be very buggy atm.\n## \n## Feel free to try it if you wish. If you're experiencing problems then turn it back off.\n# Setting type: Boolean\n# Default value: false\nTriggerOnEnter = true\n";
-
-    if !cfg_path.exists() {
-        std::fs::write(&cfg_path, baseline).map_err(|e| e.to_string())?;
-        return Ok(());
-    }
-
-    let bytes = std::fs::read(&cfg_path).map_err(|e| e.to_string())?;
-    let text = String::from_utf8_lossy(&bytes);
-
-    let mut changed = false;
-    let mut saw_key = false;
-    let mut out = String::with_capacity(text.len() + 128);
-
-    for seg in text.split_inclusive(['\n', '\r']) {
-        let (line, nl) = if seg.ends_with("\r\n") {
-            (seg.trim_end_matches("\r\n"), "\r\n")
-        } else if seg.ends_with('\n') {
-            (seg.trim_end_matches('\n'), "\n")
-        } else if seg.ends_with('\r') {
-            (seg.trim_end_matches('\r'), "\r")
-        } else {
-            (seg, "")
-        };
-
-        let trimmed = line.trim_start();
-        let indent_len = line.len().saturating_sub(trimmed.len());
-        let indent = &line[..indent_len];
-
-        if let Some(eq_idx) = trimmed.find('=') {
-            let (left, right_all) = trimmed.split_at(eq_idx);
-            if left.trim().eq_ignore_ascii_case("triggerOnEnter") {
-                saw_key = true;
-                let right_all = right_all.trim_start_matches('=');
-                let (right, comment) = match right_all.find('#') {
-                    Some(i) => (&right_all[..i], &right_all[i..]),
-                    None => (right_all, ""),
-                };
-
-                if !left.trim().eq("TriggerOnEnter") || !right.trim().eq_ignore_ascii_case("true") {
-                    out.push_str(indent);
-                    out.push_str("TriggerOnEnter = true");
-                    out.push_str(comment);
-                    out.push_str(nl);
-                    changed = true;
-                    continue;
-                }
-            }
-        }
-
-        out.push_str(line);
-        out.push_str(nl);
-    }
-
-    if !saw_key {
-        if !out.ends_with('\n') && !out.ends_with("\r\n") && !out.ends_with('\r') {
-            out.push('\n');
-        }
-        out.push_str("\n[Experimental]\n\n");
-        out.push_str("## I'm not sure why reverb triggers run their calculations every frame when as far as I can tell they only need to run their changes when something enters their collider.\n");
-        out.push_str("## I'm leaving this as an experimental toggle because it seems to be very buggy atm.\n");
-        out.push_str("## \n");
-        out.push_str("## Feel free to try it if you wish. If you're experiencing problems then turn it back off.\n");
-        out.push_str("# Setting type: Boolean\n");
-        out.push_str("# Default value: false\n");
-        out.push_str("TriggerOnEnter = true\n");
-        changed = true;
-    }
-
-    if changed {
-        std::fs::write(&cfg_path, out).map_err(|e| e.to_string())?;
-    }
-
-    Ok(())
-}
-
-fn replace_cfg_bool_assignment(text: &str, key: &str, desired_value: bool) -> (String, bool) {
-    let mut changed = false;
-    let mut out = String::with_capacity(text.len());
-    let desired = if desired_value { "true" } else { "false" };
-
-    for seg in text.split_inclusive(['\n', '\r']) {
-        let (line, nl) = if seg.ends_with("\r\n") {
-            (seg.trim_end_matches("\r\n"), "\r\n")
-        } else if seg.ends_with('\n') {
-            (seg.trim_end_matches('\n'), "\n")
-        } else if seg.ends_with('\r') {
-            (seg.trim_end_matches('\r'), "\r")
-        } else {
-            (seg, "")
-        };
-
-        let trimmed = line.trim_start();
-        let indent_len = line.len().saturating_sub(trimmed.len());
-        let indent = &line[..indent_len];
-
-        if let Some(eq_idx) = trimmed.find('=') {
-            let (left, right_all) = trimmed.split_at(eq_idx);
-            if left.trim().eq_ignore_ascii_case(key) {
-                let right_all = right_all.trim_start_matches('=');
-                let (right, comment) = match right_all.find('#') {
-                    Some(i) => (&right_all[..i], &right_all[i..]),
-                    None => (right_all, ""),
-                };
-
-                if !right.trim().eq_ignore_ascii_case(desired) {
-                    out.push_str(indent);
-                    out.push_str(left.trim());
-                    out.push_str(" = ");
-                    out.push_str(desired);
-                    out.push_str(comment);
-                    out.push_str(nl);
-                    changed = true;
-                    continue;
-                }
-            }
-        }
-
-        out.push_str(line);
-        out.push_str(nl);
-    }
-
-    (out, changed)
-}
-
-fn ensure_unity_explorer_eventsystem_override_in_dir(
-    config_dir: &std::path::Path,
-) -> Result<bool, String> {
-    let cfg_path = config_dir.join("com.sinai.unityexplorer.cfg");
-    if !cfg_path.exists() {
-        return Ok(false);
-    }
-
-    let bytes = std::fs::read(&cfg_path).map_err(|e| e.to_string())?;
-    let text = String::from_utf8_lossy(&bytes);
-    let (updated, changed) =
-        replace_cfg_bool_assignment(&text, "Disable EventSystem override", true);
-    if changed {
-        std::fs::write(&cfg_path, updated).map_err(|e| e.to_string())?;
-    }
-
-    Ok(changed)
+    patches: &[cfg_patches::CfgPatch],
+) -> Vec<String> {
+    let Ok(plugins) = plugins_dir(app, version) else {
+        return vec![];
+    };
+    patches
+        .iter()
+        .filter_map(|patch| patch.requires_mod.as_deref())
+        .filter(|key| {
+            key.split_once('-')
+                .is_some_and(|(dev, name)| mod_dir_for(&plugins, dev, name).is_some())
+        })
+        .map(str::to_string)
+        .collect()
 }
 
 fn migrate_disablemod_v6_unity_explorer_cfg(app: &tauri::AppHandle) -> Result<(), String> {
@@ -2755,7 +2570,8 @@ fn migrate_disablemod_v6_unity_explorer_cfg(app: &tauri::AppHandle) -> Result<()
             continue;
         }
 
-        if let Err(e) = ensure_unity_explorer_eventsystem_override_in_dir(&config_dir) {
+        let patch = cfg_patches::unity_explorer_event_system();
+        if let Err(e) = cfg_patches::apply_in_dir(&config_dir, &patch) {
             log::warn!(
                 "Failed to migrate UnityExplorer config in {}: {e}",
                 config_dir.to_string_lossy()
@@ -2766,845 +2582,88 @@ fn migrate_disablemod_v6_unity_explorer_cfg(app: &tauri::AppHandle) -> Result<()
     Ok(())
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct HqolDontStoreBackupEntry {
-    file_name: String,
-    rhs: String,
+fn run_mode_for_preset(preset: &str, practice: bool) -> String {
+    let preset = preset.trim().to_lowercase();
+    match (preset.as_str(), practice) {
+        ("" | "hq", false) => "hq".to_string(),
+        ("" | "hq", true) => "practice".to_string(),
+        (p, false) => p.to_string(),
+        (p, true) => format!("{p}_practice"),
+    }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct HqolDontStoreBackup {
+/// Apply the built-in and manifest `cfg_patches` for this run and revert the ones
+/// left over from a previous run mode.
+async fn apply_cfg_patches(
+    app: &tauri::AppHandle,
     version: u32,
-    files: Vec<HqolDontStoreBackupEntry>,
+    preset: &str,
+    practice: bool,
+    launching: bool,
+) {
+    let client = reqwest::Client::new();
+    let manifest = match ModsConfig::cached_cfg_patches(&client).await {
+        Ok(patches) => patches,
+        Err(e) => {
+            log::warn!("Applying only the built-in cfg patches for v{version}: {e}");
+            vec![]
+        }
+    };
+    let patches = cfg_patches::with_builtin(manifest);
+    let ctx = cfg_patches::CfgPatchContext {
+        version,
+        run_mode: run_mode_for_preset(preset, practice),
+        tags: preset_tags_for_name(preset),
+        practice,
+        installed_mods: installed_patch_mods(app, version, &patches),
+        launching,
+    };
+    if let Err(e) = cfg_patches::apply_for_run(app, &ctx, &patches) {
+        log::warn!("Failed to apply cfg patches for v{version}: {e}");
+    }
 }
 
-fn hqol_dont_store_backup_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
-    Ok(app
+/// Revert the run-only cfg patches of versions with no game left running.
+fn revert_run_only_cfg_patches(app: &tauri::AppHandle, active: &[ActiveGame]) {
+    let running: Vec<u32> = active.iter().map(|game| game.version).collect();
+    if let Err(e) = cfg_patches::revert_until_exit(app, &running) {
+        log::warn!("Failed to revert run-only cfg patches: {e}");
+    }
+}
+
+/// Put back the dont-store list older launchers backed up before a Wesley run.
+pub(crate) fn restore_legacy_hqol_dont_store_backup(app: &tauri::AppHandle) -> Result<(), String> {
+    #[derive(Deserialize)]
+    struct BackupEntry {
+        file_name: String,
+        rhs: String,
+    }
+    #[derive(Deserialize)]
+    struct Backup {
+        version: u32,
+        files: Vec<BackupEntry>,
+    }
+
+    let path = app
         .path()
         .app_data_dir()
         .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("hqol_wesley_dont_store_backup.json"))
-}
-
-fn read_hqol_dont_store_backup(
-    app: &tauri::AppHandle,
-) -> Result<Option<HqolDontStoreBackup>, String> {
-    let path = hqol_dont_store_backup_path(app)?;
+        .join("hqol_wesley_dont_store_backup.json");
     if !path.exists() {
-        return Ok(None);
+        return Ok(());
     }
-
     let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    match serde_json::from_str::<HqolDontStoreBackup>(&text) {
-        Ok(backup) => Ok(Some(backup)),
-        Err(e) => {
-            log::warn!("Failed to parse HQoL Wesley dont-store backup, clearing stale file: {e}");
-            let _ = std::fs::remove_file(&path);
-            Ok(None)
+    if let Ok(backup) = serde_json::from_str::<Backup>(&text) {
+        let cfg_dir = version_config_dir(app, backup.version)?;
+        for entry in backup.files {
+            let patch = cfg_patches::CfgPatch {
+                value: Some(entry.rhs),
+                ..cfg_patches::wesley_hqol_dont_store(&entry.file_name)
+            };
+            cfg_patches::apply_in_dir(&cfg_dir, &patch)?;
         }
     }
-}
-
-fn write_hqol_dont_store_backup(
-    app: &tauri::AppHandle,
-    backup: Option<&HqolDontStoreBackup>,
-) -> Result<(), String> {
-    let path = hqol_dont_store_backup_path(app)?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-
-    if let Some(backup) = backup {
-        let json = serde_json::to_string_pretty(backup).map_err(|e| e.to_string())?;
-        std::fs::write(&path, json).map_err(|e| e.to_string())?;
-    } else if path.exists() {
-        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
-    }
-
-    Ok(())
-}
-
-fn extract_hqol_dont_store_rhs(text: &str) -> Option<String> {
-    let mut in_general = false;
-
-    for seg in text.split_inclusive(['\n', '\r']) {
-        let line = if seg.ends_with("\r\n") {
-            seg.trim_end_matches("\r\n")
-        } else if seg.ends_with('\n') {
-            seg.trim_end_matches('\n')
-        } else if seg.ends_with('\r') {
-            seg.trim_end_matches('\r')
-        } else {
-            seg
-        };
-
-        let trimmed = line.trim_start();
-        let trimmed_all = line.trim();
-
-        if trimmed_all.starts_with('[') && trimmed_all.ends_with(']') {
-            in_general = trimmed_all.eq_ignore_ascii_case("[General]");
-        }
-
-        if !in_general {
-            continue;
-        }
-
-        let Some(eq_idx) = trimmed.find('=') else {
-            continue;
-        };
-        let (left, _) = trimmed.split_at(eq_idx);
-        if left.trim() == "Dont store list" {
-            return Some(trimmed[eq_idx + 1..].to_string());
-        }
-    }
-
-    None
-}
-
-fn replace_hqol_dont_store_rhs(text: &str, replacement_rhs: &str) -> (String, bool) {
-    let mut changed = false;
-    let mut in_general = false;
-    let mut out = String::with_capacity(text.len() + replacement_rhs.len());
-
-    for seg in text.split_inclusive(['\n', '\r']) {
-        let (line, nl) = if seg.ends_with("\r\n") {
-            (seg.trim_end_matches("\r\n"), "\r\n")
-        } else if seg.ends_with('\n') {
-            (seg.trim_end_matches('\n'), "\n")
-        } else if seg.ends_with('\r') {
-            (seg.trim_end_matches('\r'), "\r")
-        } else {
-            (seg, "")
-        };
-
-        let trimmed = line.trim_start();
-        let trimmed_all = line.trim();
-
-        if trimmed_all.starts_with('[') && trimmed_all.ends_with(']') {
-            in_general = trimmed_all.eq_ignore_ascii_case("[General]");
-        }
-
-        if in_general {
-            if let Some(eq_idx) = trimmed.find('=') {
-                let (left, _) = trimmed.split_at(eq_idx);
-                if left.trim() == "Dont store list" {
-                    let indent_len = line.len().saturating_sub(trimmed.len());
-                    let indent = &line[..indent_len];
-                    let current_rhs = &trimmed[eq_idx + 1..];
-                    changed |= current_rhs != replacement_rhs;
-                    out.push_str(indent);
-                    out.push_str("Dont store list =");
-                    out.push_str(replacement_rhs);
-                    out.push_str(nl);
-                    continue;
-                }
-            }
-        }
-
-        out.push_str(line);
-        out.push_str(nl);
-    }
-
-    (out, changed)
-}
-
-fn wesley_hqol_dont_store_rhs(current_rhs: &str) -> String {
-    let comment = current_rhs
-        .find('#')
-        .map(|idx| &current_rhs[idx..])
-        .unwrap_or("");
-    let leading_ws_len = current_rhs.len() - current_rhs.trim_start().len();
-    let leading_ws = &current_rhs[..leading_ws_len];
-    let prefix = if leading_ws.is_empty() {
-        " "
-    } else {
-        leading_ws
-    };
-
-    format!(
-        "{}{}{}",
-        prefix,
-        WESLEY_HQOL_DONT_STORE_ITEMS.join(", "),
-        comment
-    )
-}
-
-fn apply_wesley_hqol_dont_store_override(
-    app: &tauri::AppHandle,
-    version: u32,
-) -> Result<(), String> {
-    let _ = restore_hqol_wesley_dont_store_backup_if_present(app)?;
-
-    let cfg_dir = version_config_dir(app, version)?;
-    let mut backup = HqolDontStoreBackup {
-        version,
-        files: vec![],
-    };
-
-    for file_name in HQOL_DONT_STORE_CFG_FILES {
-        let cfg_path = cfg_dir.join(file_name);
-        if !cfg_path.exists() {
-            continue;
-        }
-
-        let bytes = std::fs::read(&cfg_path).map_err(|e| e.to_string())?;
-        let text = String::from_utf8_lossy(&bytes);
-        let Some(current_rhs) = extract_hqol_dont_store_rhs(&text) else {
-            continue;
-        };
-
-        backup.files.push(HqolDontStoreBackupEntry {
-            file_name: file_name.to_string(),
-            rhs: current_rhs.clone(),
-        });
-
-        let override_rhs = wesley_hqol_dont_store_rhs(&current_rhs);
-        let (updated, changed) = replace_hqol_dont_store_rhs(&text, &override_rhs);
-        if changed {
-            std::fs::write(&cfg_path, updated).map_err(|e| e.to_string())?;
-        }
-    }
-
-    if backup.files.is_empty() {
-        write_hqol_dont_store_backup(app, None)?;
-    } else {
-        write_hqol_dont_store_backup(app, Some(&backup))?;
-    }
-
-    Ok(())
-}
-
-pub(crate) fn restore_hqol_wesley_dont_store_backup_if_present(
-    app: &tauri::AppHandle,
-) -> Result<bool, String> {
-    let Some(backup) = read_hqol_dont_store_backup(app)? else {
-        return Ok(false);
-    };
-
-    let cfg_dir = version_config_dir(app, backup.version)?;
-    let mut restored = false;
-
-    for entry in &backup.files {
-        let cfg_path = cfg_dir.join(&entry.file_name);
-        if !cfg_path.exists() {
-            continue;
-        }
-
-        let bytes = std::fs::read(&cfg_path).map_err(|e| e.to_string())?;
-        let text = String::from_utf8_lossy(&bytes);
-        let (updated, changed) = replace_hqol_dont_store_rhs(&text, &entry.rhs);
-        if changed {
-            std::fs::write(&cfg_path, updated).map_err(|e| e.to_string())?;
-            restored = true;
-        }
-    }
-
-    write_hqol_dont_store_backup(app, None)?;
-    Ok(restored)
-}
-
-fn ensure_weather_registry_cfg(app: &tauri::AppHandle, version: u32) -> Result<(), String> {
-    // Only meaningful for the Wesley preset (v69+). Never overwrite entire configs.
-    if version < 69 {
-        return Ok(());
-    }
-
-    let cfg_dir = version_config_dir(app, version)?;
-    std::fs::create_dir_all(&cfg_dir).map_err(|e| e.to_string())?;
-
-    let cfg_path = cfg_dir.join("mrov.WeatherRegistry.cfg");
-    let desired_first_day = "true";
-    let desired_algo = "Hybrid";
-
-    // If missing, create using the provided baseline.
-    if !cfg_path.exists() {
-        let content = r#"## Settings file was created by plugin WeatherRegistry v0.7.5
-## Plugin GUID: mrov.WeatherRegistry
-
-[|General]
-
-## Enable colored weathers on map screen
-# Setting type: Boolean
-# Default value: true
-Colored Weathers = true
-
-## Display planet videos on map screen
-# Setting type: Boolean
-# Default value: true
-Planet Videos = true
-
-## Show weather multipliers on map screen
-# Setting type: Boolean
-# Default value: false
-Show Weather Multipliers = false
-
-## Use Registry's scrap multipliers. Disable if you prefer to use other mod's multiplier settings.
-# Setting type: Boolean
-# Default value: true
-Scrap multipliers = true
-
-[|Logging]
-
-## Select which logs to show.
-# Setting type: LoggingType
-# Default value: Basic
-# Acceptable values: Basic, Debug, Developer
-Display Log Levels = Basic
-
-[|WeatherSelection]
-
-## Select the algorithm to use during weather selection.
-# Setting type: WeatherAlgorithm
-# Default value: Registry
-# Acceptable values: Registry, Vanilla, Hybrid
-Weather Selection Algorithm = Hybrid
-
-## If enabled, the first day will always have clear weather, on all planets, regardless of the selected algorithm.
-# Setting type: Boolean
-# Default value: false
-First Day Clear Weather = true
-
-[Modded Weather: Earthquakes]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 40
-# Acceptable value range: From 0 to 10000
-Default weight = 40
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1.1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1.1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;Galetry;Calist;Berunah
-Level filter = Company;Galetry;Calist;Berunah
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50
-Level weights = MoonName@50
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Earthquakes, next day should have weights:
-# Setting type: String
-# Default value: WeatherName@50
-WeatherToWeather weights = WeatherName@50
-
-[Modded Weather: Forsaken]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 30
-# Acceptable value range: From 0 to 10000
-Default weight = 30
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1.2
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1.2
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1.2
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1.2
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;Galetry;Berunah;Calist;Repress;Cosmocos
-Level filter = Company;Galetry;Berunah;Calist;Repress;Cosmocos
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@20
-Level weights = MoonName@20
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Forsaken, next day should have weights:
-# Setting type: String
-# Default value: WeatherName@20
-WeatherToWeather weights = WeatherName@20
-
-[Modded Weather: Hallowed]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 8
-# Acceptable value range: From 0 to 10000
-Default weight = 8
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;Galetry;Calist;Berunah;Asteroid-13;Thalasso;Roart;Repress;Cosmocos
-Level filter = Company;Galetry;Calist;Berunah;Asteroid-13;Thalasso;Roart;Repress;Cosmocos
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@8
-Level weights = MoonName@8
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Hallowed, next day should have weights:
-# Setting type: String
-# Default value: WeatherName@8
-WeatherToWeather weights = WeatherName@8
-
-[Modded Weather: Hurricane]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 80
-# Acceptable value range: From 0 to 10000
-Default weight = 80
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;Galetry;Calist;Berunah;Asteroid-13,Roart;Repress;Cosmocos
-Level filter = Company;Galetry;Calist;Berunah;Asteroid-13,Roart;Repress;Cosmocos
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: WeatherName@20
-Level weights = WeatherName@20
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Hurricane, next day should have weights:
-# Setting type: String
-# Default value: WeatherName@20
-WeatherToWeather weights = WeatherName@20
-
-[Vanilla Weather: DustClouds]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Dust Clouds, next day should have weights:
-# Setting type: String
-# Default value: WeatherName@50
-WeatherToWeather weights = WeatherName@50
-
-[Vanilla Weather: Eclipsed]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Eclipsed, next day should have weights:
-# Setting type: String
-# Default value: None@300; Rainy@40; Stormy@16; Flooded@20; Foggy@60; Eclipsed@10;
-WeatherToWeather weights = None@300; Rainy@40; Stormy@16; Flooded@20; Foggy@60; Eclipsed@10;
-
-[Vanilla Weather: Flooded]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Flooded, next day should have weights:
-# Setting type: String
-# Default value: None@160; Rainy@60; Stormy@50; Flooded@10; Foggy@60; Eclipsed@40;
-WeatherToWeather weights = None@160; Rainy@60; Stormy@50; Flooded@10; Foggy@60; Eclipsed@40;
-
-[Vanilla Weather: Foggy]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Foggy, next day should have weights:
-# Setting type: String
-# Default value: None@200; Rainy@60; Stormy@50; Flooded@10; Foggy@30; Eclipsed@20;
-WeatherToWeather weights = None@200; Rainy@60; Stormy@50; Flooded@10; Foggy@30; Eclipsed@20;
-
-[Vanilla Weather: None]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was None, next day should have weights:
-# Setting type: String
-# Default value: None@160; Rainy@100; Stormy@70; Flooded@20; Foggy@40; Eclipsed@10;
-WeatherToWeather weights = None@160; Rainy@100; Stormy@70; Flooded@20; Foggy@40; Eclipsed@10;
-
-[Vanilla Weather: Rainy]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Rainy, next day should have weights:
-# Setting type: String
-# Default value: None@100; Rainy@60; Stormy@40; Flooded@30; Foggy@50; Eclipsed@20;
-WeatherToWeather weights = None@100; Rainy@60; Stormy@40; Flooded@30; Foggy@50; Eclipsed@20;
-
-[Vanilla Weather: Stormy]
-
-## The default weight of this weather
-# Setting type: Int32
-# Default value: 100
-# Acceptable value range: From 0 to 10000
-Default weight = 100
-
-## Multiplier for the amount of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap amount multiplier = 1
-
-## Multiplier for the value of scrap spawned
-# Setting type: Single
-# Default value: 1
-# Acceptable value range: From 0 to 100
-Scrap value multiplier = 1
-
-## Whether to make the filter a whitelist (false is blacklist, true is whitelist)
-# Setting type: Boolean
-# Default value: false
-Filtering option = false
-
-## Semicolon-separated list of level names to filter (use `Filtering Option` config to select filter type)
-# Setting type: String
-# Default value: Company;
-Level filter = Company;
-
-## Semicolon-separated list of level weights
-# Setting type: String
-# Default value: MoonName@50;
-Level weights = MoonName@50;
-
-## Semicolon-separated list of weather-to-weather weights - if previous day was Stormy, next day should have weights:
-# Setting type: String
-# Default value: None@160; Rainy@110; Stormy@10; Flooded@120; Foggy@20; Eclipsed@80;
-WeatherToWeather weights = None@160; Rainy@110; Stormy@10; Flooded@120; Foggy@20; Eclipsed@80;
-
-
-
-
-[|WeatherSelection]
-First Day Clear Weather = true
-Weather Selection Algorithm = Hybrid
-"#;
-
-        std::fs::write(&cfg_path, content).map_err(|e| e.to_string())?;
-        return Ok(());
-    }
-
-    // If exists: enforce only these two keys (preserve everything else).
-    let bytes = std::fs::read(&cfg_path).map_err(|e| e.to_string())?;
-    let text = String::from_utf8_lossy(&bytes);
-
-    let mut changed = false;
-    let mut saw_first_day = false;
-    let mut saw_algo = false;
-    let mut out = String::with_capacity(text.len());
-
-    for seg in text.split_inclusive(['\n', '\r']) {
-        let (line, nl) = if seg.ends_with("\r\n") {
-            (seg.trim_end_matches("\r\n"), "\r\n")
-        } else if seg.ends_with('\n') {
-            (seg.trim_end_matches('\n'), "\n")
-        } else if seg.ends_with('\r') {
-            (seg.trim_end_matches('\r'), "\r")
-        } else {
-            (seg, "")
-        };
-
-        let trimmed = line.trim_start();
-        let indent_len = line.len().saturating_sub(trimmed.len());
-        let indent = &line[..indent_len];
-
-        let mut handled = false;
-        for (key, desired, flag) in [
-            (
-                "First Day Clear Weather",
-                desired_first_day,
-                &mut saw_first_day,
-            ),
-            ("Weather Selection Algorithm", desired_algo, &mut saw_algo),
-        ] {
-            if trimmed.starts_with(key) {
-                if let Some(eq_idx) = trimmed.find('=') {
-                    let (left, right_all) = trimmed.split_at(eq_idx);
-                    if left.trim() == key {
-                        let right_all = right_all.trim_start_matches('=');
-                        let (right, comment) = match right_all.find('#') {
-                            Some(i) => (&right_all[..i], &right_all[i..]),
-                            None => (right_all, ""),
-                        };
-                        *flag = true;
-                        if right.trim() != desired {
-                            out.push_str(indent);
-                            out.push_str(key);
-                            out.push_str(" = ");
-                            out.push_str(desired);
-                            out.push_str(comment);
-                            out.push_str(nl);
-                            changed = true;
-                        } else {
-                            out.push_str(line);
-                            out.push_str(nl);
-                        }
-                        handled = true;
-                        break;
-                    }
-                }
-            }
-        }
-        if handled {
-            continue;
-        }
-
-        out.push_str(line);
-        out.push_str(nl);
-    }
-
-    if !saw_first_day || !saw_algo {
-        // Append a minimal section at end if missing.
-        if !out.ends_with('\n') && !out.ends_with("\r\n") && !out.ends_with('\r') {
-            out.push('\n');
-        }
-        out.push_str("\n[|WeatherSelection]\n");
-        if !saw_first_day {
-            out.push_str("First Day Clear Weather = true\n");
-        }
-        if !saw_algo {
-            out.push_str("Weather Selection Algorithm = Hybrid\n");
-        }
-        changed = true;
-    }
-
-    if changed {
-        std::fs::write(&cfg_path, out).map_err(|e| e.to_string())?;
-    }
-
-    Ok(())
+    std::fs::remove_file(&path).map_err(|e| e.to_string())
 }
 
 fn find_file_named(
@@ -5055,6 +4114,24 @@ fn read_disablemod(app: &tauri::AppHandle) -> Result<DisableModFile, String> {
 
     let original_version = f.version;
 
+    if upgrade_disablemod_mods(&mut f) {
+        let _ = write_disablemod(app, &f);
+    }
+    if original_version <= 5 {
+        if let Err(e) = migrate_disablemod_v6_unity_explorer_cfg(app) {
+            log::warn!("Failed to migrate UnityExplorer config for disablemod v6: {e}");
+        }
+        f.version = DISABLEMOD_FILE_VERSION;
+        let _ = write_disablemod(app, &f);
+    }
+
+    Ok(f)
+}
+
+/// Mod list changes of older disablemod.json versions; returns whether `f` changed.
+fn upgrade_disablemod_mods(f: &mut DisableModFile) -> bool {
+    let original_version = f.version;
+
     // Migration: v1 -> v2
     if f.version == 1 {
         f.version = 2;
@@ -5063,7 +4140,6 @@ fn read_disablemod(app: &tauri::AppHandle) -> Result<DisableModFile, String> {
         f.mods
             .sort_by(|a, b| a.dev.cmp(&b.dev).then(a.name.cmp(&b.name)));
         f.mods.dedup();
-        let _ = write_disablemod(app, &f);
     }
     if f.version < 3 {
         f.version = 3;
@@ -5071,23 +4147,57 @@ fn read_disablemod(app: &tauri::AppHandle) -> Result<DisableModFile, String> {
         f.mods
             .sort_by(|a, b| a.dev.cmp(&b.dev).then(a.name.cmp(&b.name)));
         f.mods.dedup();
-        let _ = write_disablemod(app, &f);
     }
     if f.version == 4 {
         f.mods
             .retain(|m| m != &normalize_mod_id("MikuOreo", "LCStatsTracker"));
         f.version = DISABLEMOD_FILE_VERSION;
-        let _ = write_disablemod(app, &f);
-    }
-    if original_version <= 5 {
-        if let Err(e) = migrate_disablemod_v6_unity_explorer_cfg(app) {
-            log::warn!("Failed to migrate UnityExplorer config for disablemod v6: {e}");
-        }
-        f.version = DISABLEMOD_FILE_VERSION;
-        let _ = write_disablemod(app, &f);
     }
+    f.version != original_version
+}
 
-    Ok(f)
+/// Adds `mods` to the disabled list, keeping entries that are already there.
+fn merge_disabled_mods(f: &mut DisableModFile, mods: Vec<DisabledMod>) {
+    f.mods
+        .extend(mods.into_iter().map(|m| normalize_mod_id(&m.dev, &m.name)));
+    f.mods
+        .sort_by(|a, b| a.dev.cmp(&b.dev).then(a.name.cmp(&b.name)));
+    f.mods.dedup();
+}
+
+#[cfg(test)]
+mod disablemod_merge_tests {
+    use super::*;
+
+    #[test]
+    fn imported_mods_are_added_without_dropping_existing_ones() {
+        let mut disabled = DisableModFile {
+            version: DISABLEMOD_FILE_VERSION,
+            mods: vec![normalize_mod_id("Pooble", "LCBetterSaves")],
+        };
+
+        merge_disabled_mods(
+            &mut disabled,
+            vec![
+                DisabledMod {
+                    dev: "Zehs".to_string(),
+                    name: "SellMyScrap".to_string(),
+                },
+                DisabledMod {
+                    dev: " pooble".to_string(),
+                    name: "LCBetterSaves".to_string(),
+                },
+            ],
+        );
+
+        assert_eq!(
+            disabled.mods,
+            vec![
+                normalize_mod_id("Pooble", "LCBetterSaves"),
+                normalize_mod_id("Zehs", "SellMyScrap"),
+            ]
+        );
+    }
 }
 
 async fn migrate_disablemod_v4_on_startup(app: &tauri::AppHandle) -> Result<(), String> {
@@ -7922,7 +7032,7 @@ fn collect_mod_entries_recursive(
     Ok(out)
 }
 
-fn is_launcher_visible_mod_asset_name(name: &str) -> bool {
+pub(crate) fn is_launcher_visible_mod_asset_name(name: &str) -> bool {
     let base_name = if name.to_ascii_lowercase().ends_with(".old") {
         &name[..name.len().saturating_sub(4)]
     } else {
@@ -8381,11 +7491,13 @@ fn event_vlog_mod_entry() -> mod_config::ModEntry {
 }
 
 fn event_vlog_mod_id() -> (String, String) {
-    ("asta".to_string(), "EVlog".to_string())
+    let (dev, name) = launch_plan::EVENT_VLOG;
+    (dev.to_string(), name.to_string())
 }
 
 fn base_vlog_mod_id() -> (String, String) {
-    ("HQHQTeam".to_string(), "VLog".to_string())
+    let (dev, name) = launch_plan::BASE_VLOG;
+    (dev.to_string(), name.to_string())
 }
 
 fn with_default_event_mods(mut event_mods: Vec<mod_config::ModEntry>) -> Vec<mod_config::ModEntry> {
@@ -8425,17 +7537,6 @@ fn practice_mode_mod_ids() -> Vec<(String, String)> {
         .collect()
 }
 
-fn practice_mode_forced_disabled_ids() -> Vec<(String, String)> {
-    let mut mods = practice_mode_mod_ids();
-    mods.push(base_vlog_mod_id());
-    mods.push(event_vlog_mod_id());
-    mods
-}
-
-fn practice_mode_forced_enabled_ids() -> Vec<(String, String)> {
-    vec![]
-}
-
 fn sync_practice_locked_mods_for_version(
     version_plugins_dir: &std::path::Path,
 ) -> Result<(), String> {
@@ -8465,32 +7566,58 @@ fn filter_missing_mods_for_version(
         .collect())
 }
 
-fn ensure_practice_mods_disabled_for_version(
+async fn prepare_practice_mods_for_version(
     app: &tauri::AppHandle,
     version: u32,
-) -> Result<(), String> {
-    let practice = variable::get_practice_mod_list();
-    // Apply non-practice runtime state for this version immediately, without
-    // overwriting the user's persisted practice toggle preferences.
+    cancel: Option<Arc<AtomicBool>>,
+) -> Result<Vec<(String, String)>, String> {
+    let practice_all = variable::get_practice_mod_list();
+    let practice_enabled = practice_mods_for_launch(app, version)?;
+    let practice_ids: Vec<(String, String)> = practice_enabled
+        .iter()
+        .map(|m| (m.dev.clone(), m.name.clone()))
+        .collect();
+
+    let missing_practice_mods = filter_missing_mods_for_version(app, version, &practice_enabled)?;
+    install_missing_practice_mods(app, version, missing_practice_mods, cancel).await?;
+
     let plugins = plugins_dir(app, version)?;
     let patchers = patchers_dir(app, version)?;
-    for m in practice {
+    let mut rename_ops: Vec<(std::path::PathBuf, bool, String)> = vec![];
+    for m in &practice_all {
         if let Some(dir) = mod_dir_for(&plugins, &m.dev, &m.name) {
-            let _ = set_mod_files_old_suffix(&dir, false);
+            rename_ops.push((dir, false, format!("{}-{}", m.dev, m.name)));
         }
         if let Some(dir) = mod_dir_for(&patchers, &m.dev, &m.name) {
-            let _ = set_mod_files_old_suffix(&dir, false);
+            rename_ops.push((dir, false, format!("{}-{}", m.dev, m.name)));
+        }
+    }
+    for m in &practice_enabled {
+        if let Some(dir) = mod_dir_for(&plugins, &m.dev, &m.name) {
+            rename_ops.push((dir, true, format!("{}-{}", m.dev, m.name)));
+        }
+        if let Some(dir) = mod_dir_for(&patchers, &m.dev, &m.name) {
+            rename_ops.push((dir, true, format!("{}-{}", m.dev, m.name)));
+        }
+    }
+    for (dev, name) in [("HQHQTeam", "VLog")] {
+        if let Some(dir) = mod_dir_for(&plugins, dev, name) {
+            rename_ops.push((dir, false, format!("{dev}-{name}")));
         }
     }
+    for (dir, enabled, _label) in rename_ops {
+        let _ = set_mod_files_old_suffix(&dir, enabled);
+    }
 
-    Ok(())
+    Ok(practice_ids)
 }
 
-async fn prepare_practice_mods_for_version(
+/// Practice mods a practice run of `version` enables: the compatible ones the user
+/// has not turned off.
+fn practice_mods_for_launch(
     app: &tauri::AppHandle,
     version: u32,
-    cancel: Option<Arc<AtomicBool>>,
-) -> Result<Vec<(String, String)>, String> {
+) -> Result<Vec<mod_config::ModEntry>, String> {
     let game_root = version_dir(app, version)?;
     if !game_root.exists() {
         return Err(format!(
@@ -8499,11 +7626,9 @@ async fn prepare_practice_mods_for_version(
         ));
     }
 
-    let practice_all = variable::get_practice_mod_list();
     let disabled_list = read_disablemod(app)?;
-    let practice_enabled: Vec<mod_config::ModEntry> = practice_all
-        .iter()
-        .cloned()
+    Ok(variable::get_practice_mod_list()
+        .into_iter()
         .filter(|m| {
             m.is_compatible(version)
                 && (is_ui_hidden_mod(m)
@@ -8511,112 +7636,38 @@ async fn prepare_practice_mods_for_version(
                         .mods
                         .contains(&normalize_mod_id(&m.dev, &m.name)))
         })
-        .collect();
-    let practice_ids: Vec<(String, String)> = practice_enabled
-        .iter()
-        .map(|m| (m.dev.clone(), m.name.clone()))
-        .collect();
+        .collect())
+}
 
-    let missing_practice_mods = filter_missing_mods_for_version(app, version, &practice_enabled)?;
-    if !missing_practice_mods.is_empty() {
-        const STEPS_TOTAL: u32 = 1;
-        progress::emit_progress(
+async fn install_missing_practice_mods(
+    app: &tauri::AppHandle,
+    version: u32,
+    mods: Vec<mod_config::ModEntry>,
+    cancel: Option<Arc<AtomicBool>>,
+) -> Result<(), String> {
+    let install_res = install_missing_mods_with_progress(
+        app,
+        version,
+        mods,
+        &[],
+        "Practice Mods",
+        "Installing missing practice mods...",
+        cancel,
+    )
+    .await;
+
+    if let Err(e) = &install_res {
+        progress::emit_error(
             app,
-            TaskProgressPayload {
+            TaskErrorPayload {
                 version,
-                steps_total: STEPS_TOTAL,
-                step: 1,
-                step_name: "Practice Mods".to_string(),
-                step_progress: 0.0,
-                overall_percent: 0.0,
-                detail: Some("Installing missing practice mods...".to_string()),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: Some(0),
-                total_files: Some(missing_practice_mods.len() as u64),
+                run_mode: None,
+                message: e.clone(),
             },
         );
-
-        let cfg = ModsConfig {
-            mods: missing_practice_mods,
-        };
-
-        let install_res: Result<(), String> = mods::install_mods_with_progress(
-            app,
-            &game_root,
-            version,
-            &cfg,
-            &[],
-            cancel,
-            |done, total, progress_info| {
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (done as f64 / total as f64).clamp(0.0, 1.0)
-                };
-                progress::emit_progress(
-                    app,
-                    TaskProgressPayload {
-                        version,
-                        steps_total: STEPS_TOTAL,
-                        step: 1,
-                        step_name: "Practice Mods".to_string(),
-                        step_progress,
-                        overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
-                        detail: progress_info.detail,
-                        downloaded_bytes: progress_info.downloaded_bytes,
-                        total_bytes: progress_info.total_bytes,
-                        extracted_files: progress_info.extracted_files.or(Some(done)),
-                        total_files: progress_info.total_files.or(Some(total)),
-                    },
-                );
-            },
-        )
-        .await;
-
-        if let Err(e) = &install_res {
-            progress::emit_error(
-                app,
-                TaskErrorPayload {
-                    version,
-                    run_mode: None,
-                    message: e.clone(),
-                },
-            );
-            return Err(e.clone());
-        }
-    }
-
-    let plugins = plugins_dir(app, version)?;
-    let patchers = patchers_dir(app, version)?;
-    let mut rename_ops: Vec<(std::path::PathBuf, bool, String)> = vec![];
-    for m in &practice_all {
-        if let Some(dir) = mod_dir_for(&plugins, &m.dev, &m.name) {
-            rename_ops.push((dir, false, format!("{}-{}", m.dev, m.name)));
-        }
-        if let Some(dir) = mod_dir_for(&patchers, &m.dev, &m.name) {
-            rename_ops.push((dir, false, format!("{}-{}", m.dev, m.name)));
-        }
-    }
-    for m in &practice_enabled {
-        if let Some(dir) = mod_dir_for(&plugins, &m.dev, &m.name) {
-            rename_ops.push((dir, true, format!("{}-{}", m.dev, m.name)));
-        }
-        if let Some(dir) = mod_dir_for(&patchers, &m.dev, &m.name) {
-            rename_ops.push((dir, true, format!("{}-{}", m.dev, m.name)));
-        }
-    }
-    for (dev, name) in [("HQHQTeam", "VLog")] {
-        if let Some(dir) = mod_dir_for(&plugins, dev, name) {
-            rename_ops.push((dir, false, format!("{dev}-{name}")));
-        }
-    }
-    for (dir, enabled, _label) in rename_ops {
-        let _ = set_mod_files_old_suffix(&dir, enabled);
-    }
-
-    Ok(practice_ids)
-}
+    }
+    install_res
+}
 
 #[derive(Default)]
 struct GameState {
@@ -8632,18 +7683,52 @@ struct ActiveGame {
     mode_label: String,
     launch_options: Vec<String>,
     launch_command_template: Option<String>,
-    // Vanilla Run hides both proxy DLLs (BepInEx winhttp.dll and the HQ overlay
-    // version.dll) so neither loads in the unmodded game. Both restore on drop.
-    _vanilla_proxy_dll_guards: Option<Vec<VanillaProxyDllGuard>>,
-    // Legacy/Off launches hide only the native HQ overlay proxy. Keep the
-    // guard alive until the game exits so version.dll cannot be loaded by a
-    // child process started later in the same run.
-    _non_native_overlay_proxy_guard: Option<VanillaProxyDllGuard>,
+    // Proxy DLLs the launch plan hid: both for Vanilla Run (BepInEx winhttp.dll
+    // and the HQ overlay version.dll), only version.dll for Legacy/Off overlay
+    // runs. Keep the guards alive until the game exits so a hidden DLL cannot
+    // be loaded by a child process started later in the same run; they restore
+    // on drop.
+    _proxy_dll_guards: Vec<VanillaProxyDllGuard>,
+    exit_hooks: Option<launch_hooks::ExitHooks>,
+    // Modded runs only; the tail finishes the session copy once this drops.
+    bepinex_log_tail: Option<bepinex_log::TailHandle>,
+    exit_watch: Option<crash_report::ExitWatch>,
+    // Set by the stop commands so their kill is not reported as a crash.
+    stopped_by_launcher: bool,
+    // Linux: the prefix this run uses, which scopes its process tracking.
+    wine_prefix: Option<std::path::PathBuf>,
+    // Unix seconds.
+    started_at: u64,
+}
+
+impl Drop for ActiveGame {
+    // Every path that removes a game from `GameState.active` does so after it
+    // exited, so this is where post-exit hooks start and the exit is classified.
+    fn drop(&mut self) {
+        let status = self.child.try_wait().ok().flatten();
+        if let Some(hooks) = self.exit_hooks.take() {
+            match status {
+                Some(status) => hooks.spawn(Some(status)),
+                None => log::warn!(
+                    "Skipping post-exit hooks for v{}: exit status unknown",
+                    self.version
+                ),
+            }
+        }
+        if let Some(watch) = self.exit_watch.take() {
+            watch.spawn(
+                status,
+                self.stopped_by_launcher,
+                self.bepinex_log_tail.take(),
+            );
+        }
+    }
 }
 
 #[derive(Default)]
 struct DownloadState {
-    active: Mutex<Option<ActiveDownload>>,
+    active: Mutex<Vec<ActiveDownload>>,
+    queue: Mutex<download_queue::DownloadQueue>,
 }
 
 struct ActiveDownload {
@@ -8708,6 +7793,8 @@ struct RunningGameDto {
     mode_label: String,
     launch_options: Vec<String>,
     launch_command_template: Option<String>,
+    bepinex_log_session: Option<String>,
+    wine_prefix: Option<String>,
 }
 
 #[tauri::command]
@@ -8716,26 +7803,42 @@ async fn download(
     version: u32,
     state: State<'_, DownloadState>,
 ) -> Result<bool, String> {
-    // Only allow one active download at a time (simplifies cancel + UI state).
     let cancel = Arc::new(AtomicBool::new(false));
+    let slots = state
+        .queue
+        .lock()
+        .map_err(|_| "download queue lock poisoned".to_string())?
+        .slots();
     {
         let mut guard = state
             .active
             .lock()
             .map_err(|_| "download state lock poisoned".to_string())?;
-        if let Some(active) = guard.as_ref() {
-            if !active.cancel.load(Ordering::Relaxed) {
-                return Err(format!(
-                    "download already in progress (v{}). Please cancel it first.",
-                    active.version
-                ));
-            }
+        guard.retain(|a| !a.cancel.load(Ordering::Relaxed));
+        if guard.iter().any(|a| a.version == version) {
+            return Err(format!("v{version} is already downloading."));
         }
-        *guard = Some(ActiveDownload {
+        if guard.len() >= slots {
+            return Err(format!(
+                "download already in progress (v{}). Please cancel it first.",
+                guard[0].version
+            ));
+        }
+        guard.push(ActiveDownload {
             version,
             cancel: cancel.clone(),
         });
     }
+    // A direct download replaces a queued entry for the same version.
+    if let Ok(mut queue) = state.queue.lock() {
+        if queue
+            .entry(version)
+            .is_some_and(|e| e.status != download_queue::QueueStatus::Running)
+        {
+            queue.remove(version);
+            let _ = download_queue::save(&app, &queue);
+        }
+    }
 
     let res = installer::download_and_setup(app.clone(), version, cancel.clone()).await;
 
@@ -8745,1140 +7848,1193 @@ async fn download(
             .active
             .lock()
             .map_err(|_| "download state lock poisoned".to_string())?;
-        if guard.as_ref().is_some_and(|a| a.version == version) {
-            *guard = None;
-        }
+        guard.retain(|a| !Arc::ptr_eq(&a.cancel, &cancel));
     }
+    pump_download_queue(&app);
     res
 }
 
-#[tauri::command]
-fn cancel_download(
-    app: tauri::AppHandle,
-    version: u32,
-    state: State<'_, DownloadState>,
-) -> Result<bool, String> {
-    let mut did_signal = false;
-    {
-        let guard = state
-            .active
-            .lock()
-            .map_err(|_| "download state lock poisoned".to_string())?;
-        if let Some(active) = guard.as_ref() {
-            if active.version == version {
-                active.cancel.store(true, Ordering::Relaxed);
-                did_signal = true;
-            }
-        }
-    }
-
-    // Best-effort cleanup: delete the partial version folder.
-    // If files are still in use, the installer will retry cleanup on exit.
-    if did_signal {
-        if let Ok(dir) = version_dir(&app, version) {
-            let _ = std::fs::remove_dir_all(&dir);
+/// Learned fingerprints plus the ones shipped in the remote manifest.
+async fn load_fingerprint_db(app: &tauri::AppHandle) -> Result<fingerprint::FingerprintDb, String> {
+    let client = reqwest::Client::new();
+    let remote = match ModsConfig::fetch_fingerprints(&client).await {
+        Ok(remote) => remote,
+        Err(e) => {
+            log::warn!("Using learned version fingerprints only: {e}");
+            vec![]
         }
-    }
-
-    Ok(did_signal)
+    };
+    Ok(fingerprint::load(app)?.with_remote(remote))
 }
 
+/// Identify the game version of an arbitrary folder (e.g. a manually copied install).
 #[tauri::command]
-async fn sync_latest_install_from_manifest(
+async fn identify_game_folder(
     app: tauri::AppHandle,
-    version: Option<u32>,
-) -> Result<bool, String> {
-    if let Some(version) = version {
-        let game_root = version_dir(&app, version)?;
-        installer::sync_install_from_manifest_for_version(&app, version, game_root).await?;
-    } else {
-        installer::sync_latest_install_from_manifest(app).await?;
-    }
-    Ok(true)
+    path: String,
+) -> Result<fingerprint::FolderCheck, String> {
+    let db = load_fingerprint_db(&app).await?;
+    tauri::async_runtime::spawn_blocking(move || db.check_folder(std::path::Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
+/// Compare every installed `v{N}` folder against the fingerprint database.
 #[tauri::command]
-async fn check_latest_install_manifest_update(
+async fn check_installed_version_folders(
     app: tauri::AppHandle,
-    version: Option<u32>,
-) -> Result<installer::ManifestUpdateCheck, String> {
-    if let Some(version) = version {
-        installer::check_manifest_update_for_version(&app, version).await
-    } else {
-        installer::check_latest_install_manifest_update(&app).await
+) -> Result<Vec<fingerprint::FolderCheck>, String> {
+    let db = load_fingerprint_db(&app).await?;
+    let base = storage::versions_dir(&app)?;
+    let versions = list_installed_versions(app.clone())?;
+    let checks = tauri::async_runtime::spawn_blocking(move || {
+        versions
+            .into_iter()
+            .map(|v| db.check_folder(&base.join(format!("v{v}"))))
+            .collect::<Result<Vec<_>, String>>()
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    for check in &checks {
+        if check.status == fingerprint::FolderStatus::Mismatch {
+            log::warn!(
+                "{} does not look like v{}: its files match {:?}",
+                check.path,
+                check.folder_version.unwrap_or_default(),
+                check.candidates
+            );
+        }
     }
+    Ok(checks)
 }
 
-#[tauri::command]
-async fn open_version_folder(app: tauri::AppHandle) -> Result<bool, String> {
-    let dir = storage::versions_dir(&app)?;
-    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create versions dir: {e}"))?;
-    open_folder_path(&dir)?;
-    Ok(true)
+async fn detect_steam_installs_with_manifests(
+    app: &tauri::AppHandle,
+    steam_path: Option<String>,
+) -> Result<(Vec<steam_import::SteamInstall>, BTreeMap<u32, String>), String> {
+    let client = reqwest::Client::new();
+    let (_, _, _, manifests, _) = ModsConfig::fetch_manifest(&client).await?;
+    let db = load_fingerprint_db(app).await?;
+    let roots = steam_import::steam_roots(steam_path.as_deref());
+    let manifests_clone = manifests.clone();
+    let installs = tauri::async_runtime::spawn_blocking(move || {
+        let mut installs = steam_import::find_installs(&roots);
+        for install in &mut installs {
+            install.version = install
+                .depot_manifest
+                .as_deref()
+                .and_then(|id| steam_import::version_for_manifest(&manifests_clone, id));
+            // Builds missing from the manifests table can still be labelled by content.
+            if install.version.is_none() {
+                if let Ok(check) = db.check_folder(std::path::Path::new(&install.game_dir)) {
+                    install.version = check.detected_version;
+                }
+            }
+        }
+        installs
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok((installs, manifests))
 }
 
 #[tauri::command]
-fn get_game_storage_settings(
+async fn detect_steam_installs(
     app: tauri::AppHandle,
-) -> Result<storage::GameStorageSettings, String> {
-    storage::game_storage_settings(&app)
-}
-
-#[tauri::command]
-fn get_selected_run_mode(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    storage::selected_run_mode(&app)
+    steam_path: Option<String>,
+) -> Result<Vec<steam_import::SteamInstall>, String> {
+    let (installs, _) = detect_steam_installs_with_manifests(&app, steam_path).await?;
+    Ok(installs)
 }
 
+/// Import the Steam copy at `game_dir` as `v{version}` instead of downloading the
+/// depot, then run the remaining install steps (patch, BepInEx, config, mods).
 #[tauri::command]
-fn set_selected_run_mode(app: tauri::AppHandle, run_mode: String) -> Result<String, String> {
-    let normalized = run_mode.trim().to_lowercase();
-    if !matches!(
-        normalized.as_str(),
-        "hq" | "smhq"
-            | "c_moons"
-            | "c_moons_smhq"
-            | "c_moons_eclipsed"
-            | "c_moons_practice"
-            | "practice"
-            | "eclipsed_hq"
-            | "brutal"
-            | "brutal_smhq"
-            | "brutal_eclipsed"
-            | "brutal_practice"
-            | "wesley"
-            | "wesley_smhq"
-            | "wesley_eclipsed"
-            | "wesley_practice"
-    ) {
-        return Err(format!("invalid run mode: {run_mode}"));
+async fn import_steam_install(
+    app: tauri::AppHandle,
+    game_dir: String,
+    link_files: bool,
+    steam_path: Option<String>,
+    state: State<'_, DownloadState>,
+) -> Result<u32, String> {
+    let (installs, manifests) = detect_steam_installs_with_manifests(&app, steam_path).await?;
+    let install = installs
+        .into_iter()
+        .find(|i| std::path::Path::new(&i.game_dir) == std::path::Path::new(&game_dir))
+        .ok_or_else(|| format!("No Steam install of Lethal Company found at {game_dir}"))?;
+    if !install.fully_installed {
+        return Err(
+            "Steam has a pending update for this install. Let it finish first.".to_string(),
+        );
+    }
+    let version = install.version.ok_or_else(|| {
+        format!(
+            "Steam build {} (manifest {}) is not a known game version.",
+            install.build_id.as_deref().unwrap_or("?"),
+            install.depot_manifest.as_deref().unwrap_or("?")
+        )
+    })?;
+    // Seed with the launcher's manifest id for the version so the install resumes
+    // past the download even when the version was recognised by its files.
+    let depot_manifest = manifests
+        .get(&version)
+        .cloned()
+        .ok_or_else(|| format!("No depot manifest id for game version {version}."))?;
+    if list_installed_versions(app.clone())?.contains(&version) {
+        return Err(format!("v{version} is already installed."));
     }
-    storage::set_selected_run_mode(&app, normalized)
-}
 
-#[tauri::command]
-fn get_selected_version(app: tauri::AppHandle) -> Result<Option<u32>, String> {
-    storage::selected_version(&app)
-}
+    let cancel = register_import_download(&state, version)?;
+    let res = async {
+        let partial_dir = installer::seed_partial_install(&app, version, &depot_manifest)?;
+        record_import(&partial_dir, version, "Steam library")?;
+        let app_clone = app.clone();
+        let cancel_clone = cancel.clone();
+        let (files, shared) = tauri::async_runtime::spawn_blocking(move || {
+            steam_import::import_game_files(
+                std::path::Path::new(&install.game_dir),
+                &partial_dir,
+                link_files,
+                &cancel_clone,
+                |done, total| {
+                    emit_import_progress(&app_clone, version, "Import from Steam", done, total)
+                },
+            )
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        log::info!("Imported v{version} from Steam: {files} file(s), {shared} linked");
 
-#[tauri::command]
-fn set_selected_version(app: tauri::AppHandle, version: u32) -> Result<u32, String> {
-    if version == 0 {
-        return Err("invalid selected version: 0".to_string());
+        installer::finish_seeded_install(&app, version, &[installer::InstallStep::Download])?;
+        installer::download_and_setup(app.clone(), version, cancel.clone()).await
     }
-    storage::set_selected_version(&app, version)
-}
-
-#[tauri::command]
-fn get_events_enabled(app: tauri::AppHandle) -> Result<bool, String> {
-    storage::events_enabled(&app)
-}
+    .await;
 
-#[tauri::command]
-fn set_events_enabled(app: tauri::AppHandle, enabled: bool) -> Result<bool, String> {
-    storage::set_events_enabled(&app, enabled)
+    unregister_import_download(&app, &state, &cancel)?;
+    res.map(|_| version)
 }
 
-fn same_storage_path(a: &std::path::Path, b: &std::path::Path) -> bool {
-    if let (Ok(a), Ok(b)) = (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
-        return a == b;
+/// Imports run through `download_and_setup`, so they are registered like a download
+/// (one per version, cancellable through `cancel_download`).
+fn register_import_download(
+    state: &State<'_, DownloadState>,
+    version: u32,
+) -> Result<Arc<AtomicBool>, String> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut guard = state
+        .active
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?;
+    guard.retain(|a| !a.cancel.load(Ordering::Relaxed));
+    if guard.iter().any(|a| a.version == version) {
+        return Err(format!("v{version} is already downloading."));
     }
-    a == b
+    guard.push(ActiveDownload {
+        version,
+        cancel: cancel.clone(),
+    });
+    Ok(cancel)
 }
 
-fn ensure_storage_can_move(
+fn unregister_import_download(
     app: &tauri::AppHandle,
-    game_state: &State<'_, GameState>,
-    download_state: &State<'_, DownloadState>,
-    prepare_state: &State<'_, PrepareState>,
+    state: &State<'_, DownloadState>,
+    cancel: &Arc<AtomicBool>,
 ) -> Result<(), String> {
     {
-        let mut guard = game_state
+        let mut guard = state
             .active
             .lock()
-            .map_err(|_| "game state lock poisoned".to_string())?;
-        for active in guard.iter_mut() {
-            if active
-                .child
-                .try_wait()
-                .map_err(|e| e.to_string())?
-                .is_none()
-                || linux_lingering_game_pid(app, active.version).is_some()
-            {
-                return Err("Cannot change game storage while the game is running.".to_string());
-            }
-        }
-        guard.clear();
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        guard.retain(|a| !Arc::ptr_eq(&a.cancel, cancel));
     }
+    pump_download_queue(app);
+    Ok(())
+}
 
-    {
-        let guard = download_state
-            .active
-            .lock()
-            .map_err(|_| "download state lock poisoned".to_string())?;
-        if guard
-            .as_ref()
-            .is_some_and(|active| !active.cancel.load(Ordering::Relaxed))
-        {
-            return Err("Cannot change game storage while a download is running.".to_string());
-        }
-    }
+/// Start the metadata record of an import in its partial directory; `download_and_setup`
+/// completes it.
+fn record_import(partial_dir: &Path, version: u32, source: &str) -> Result<(), String> {
+    version_meta::update(partial_dir, version, |meta| {
+        meta.record(
+            now_unix_secs(),
+            version_meta::UpdateOperation::Import,
+            Some(source.to_string()),
+        );
+    })
+    .map(|_| ())
+}
+
+fn emit_import_progress(
+    app: &tauri::AppHandle,
+    version: u32,
+    step_name: &str,
+    done: u64,
+    total: u64,
+) {
+    let step_progress = if total == 0 {
+        1.0
+    } else {
+        done as f64 / total as f64
+    };
+    progress::emit_progress(
+        app,
+        TaskProgressPayload {
+            version,
+            steps_total: 1,
+            step: 1,
+            step_name: step_name.to_string(),
+            step_progress,
+            overall_percent: step_progress * 100.0,
+            detail: Some(format!("{done}/{total} files")),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: Some(done),
+            total_files: Some(total),
+        },
+    );
+}
 
+#[tauri::command]
+fn pick_instance_archive_path(save_as: Option<String>) -> Result<Option<String>, String> {
+    let dialog = rfd::FileDialog::new().add_filter("HQ instance", &["zip"]);
+    let picked = match save_as {
+        Some(file_name) => dialog.set_file_name(file_name).save_file(),
+        None => dialog.pick_file(),
+    };
+    Ok(picked.map(|path| path.to_string_lossy().to_string()))
+}
+
+/// Write `v{version}`'s BepInEx setup, mods, configs and launcher metadata (plus the
+/// game files when `include_game_files` is set) to a portable archive.
+#[tauri::command]
+async fn export_version_instance(
+    app: tauri::AppHandle,
+    version: u32,
+    dest_path: String,
+    include_game_files: bool,
+    state: State<'_, DownloadState>,
+) -> Result<instance_archive::ExportReport, String> {
+    if !list_installed_versions(app.clone())?.contains(&version) {
+        return Err(format!("v{version} is not installed."));
+    }
+    if state
+        .active
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?
+        .iter()
+        .any(|a| a.version == version)
     {
-        let guard = prepare_state
-            .active
-            .lock()
-            .map_err(|_| "prepare state lock poisoned".to_string())?;
-        if guard
-            .as_ref()
-            .is_some_and(|active| !active.cancel.load(Ordering::Relaxed))
-        {
-            return Err("Cannot change game storage while a preset is being prepared.".to_string());
+        return Err(format!("v{version} is downloading."));
+    }
+
+    let (depot_manifest, remote_manifest_version) =
+        installer::installed_manifest_info(&app, version)?;
+    let metadata = instance_archive::InstanceMetadata {
+        format: instance_archive::FORMAT_VERSION,
+        version,
+        depot_manifest,
+        remote_manifest_version: Some(remote_manifest_version),
+        launcher_version: app.package_info().version.to_string(),
+        exported_at: now_unix_secs(),
+        includes_game_files,
+    };
+    let disabled_mods = serde_json::to_vec_pretty(&read_disablemod(&app)?).ok();
+    let version_root = version_dir(&app, version)?;
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        instance_archive::export_instance(
+            &version_root,
+            std::path::Path::new(&dest_path),
+            &metadata,
+            disabled_mods.as_deref(),
+            |done, total| emit_import_progress(&app_clone, version, "Export Instance", done, total),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct InstanceImportReport {
+    version: u32,
+    files: u64,
+    /// Lockfile entries the imported plugins folder does not match.
+    lockfile_differences: Vec<String>,
+}
+
+/// Install a version from an instance archive. Archives without game files download
+/// them through DepotDownloader; BepInEx, configs and mods always come from the
+/// archive as-is.
+#[tauri::command]
+async fn import_version_instance(
+    app: tauri::AppHandle,
+    archive_path: String,
+    apply_disabled_mods: bool,
+    state: State<'_, DownloadState>,
+) -> Result<InstanceImportReport, String> {
+    let archive = std::path::PathBuf::from(&archive_path);
+    let archive_clone = archive.clone();
+    let contents = tauri::async_runtime::spawn_blocking(move || {
+        instance_archive::inspect_archive(&archive_clone)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let version = contents.metadata.version;
+    if list_installed_versions(app.clone())?.contains(&version) {
+        return Err(format!("v{version} is already installed."));
+    }
+
+    let client = reqwest::Client::new();
+    let (_, _, _, manifests, _) = ModsConfig::fetch_manifest(&client).await?;
+    let depot_manifest = manifests
+        .get(&version)
+        .cloned()
+        .or_else(|| contents.metadata.depot_manifest.clone())
+        .ok_or_else(|| format!("No depot manifest id for game version {version}."))?;
+    if contents.metadata.depot_manifest.as_deref() != Some(depot_manifest.as_str()) {
+        log::warn!(
+            "Instance archive for v{version} was exported from depot manifest {:?}; using {depot_manifest}",
+            contents.metadata.depot_manifest
+        );
+    }
+
+    let cancel = register_import_download(&state, version)?;
+    let res = async {
+        let partial_dir = installer::seed_partial_install(&app, version, &depot_manifest)?;
+        record_import(&partial_dir, version, "instance archive")?;
+        let app_clone = app.clone();
+        let files = tauri::async_runtime::spawn_blocking(move || {
+            instance_archive::extract_instance(&archive, &partial_dir, |done, total| {
+                emit_import_progress(&app_clone, version, "Import Instance", done, total)
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        if apply_disabled_mods {
+            if let Some(bytes) = &contents.disabled_mods {
+                let mut imported: DisableModFile =
+                    serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+                upgrade_disablemod_mods(&mut imported);
+                let mut disabled = read_disablemod(&app)?;
+                merge_disabled_mods(&mut disabled, imported.mods);
+                write_disablemod(&app, &disabled)?;
+            }
+        }
+
+        // The archive's BepInEx, configs and mods are the point of the import, so the
+        // install never replaces them; game files only skip the download when shipped.
+        let mut steps = vec![
+            installer::InstallStep::BepInEx,
+            installer::InstallStep::Config,
+            installer::InstallStep::Mods,
+        ];
+        if contents.metadata.includes_game_files {
+            steps.extend([
+                installer::InstallStep::Download,
+                installer::InstallStep::Patch,
+            ]);
         }
+        installer::finish_seeded_install(&app, version, &steps)?;
+        installer::download_and_setup(app.clone(), version, cancel.clone()).await?;
+        Ok::<u64, String>(files)
     }
+    .await;
 
-    Ok(())
+    unregister_import_download(&app, &state, &cancel)?;
+    let files = res?;
+    let installed = instance_archive::locked_mods(&plugins_dir(&app, version)?);
+    Ok(InstanceImportReport {
+        version,
+        files,
+        lockfile_differences: instance_archive::lockfile_differences(&contents.mods, &installed),
+    })
 }
 
 #[tauri::command]
-fn pick_game_storage_dir(initial_path: Option<String>) -> Result<Option<String>, String> {
-    let mut dialog = rfd::FileDialog::new();
-    if let Some(path) = initial_path
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
+fn cancel_download(
+    app: tauri::AppHandle,
+    version: u32,
+    state: State<'_, DownloadState>,
+) -> Result<bool, String> {
+    let mut did_signal = false;
     {
-        let candidate = std::path::PathBuf::from(&path);
-        if candidate.exists() {
-            dialog = dialog.set_directory(candidate);
+        let guard = state
+            .active
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        for active in guard.iter().filter(|a| a.version == version) {
+            active.cancel.store(true, Ordering::Relaxed);
+            did_signal = true;
+        }
+    }
+    {
+        let mut queue = state
+            .queue
+            .lock()
+            .map_err(|_| "download queue lock poisoned".to_string())?;
+        if queue.remove(version).is_some() {
+            download_queue::save(&app, &queue)?;
+            emit_download_queue_changed(&app, &queue);
+            did_signal = true;
         }
     }
 
-    Ok(dialog
-        .pick_folder()
-        .map(|path| path.to_string_lossy().to_string()))
+    // `.v{version}.partial` is kept so the download can resume later; only a
+    // leftover incomplete version folder is cleaned up here.
+    if did_signal {
+        if let Ok(dir) = version_dir(&app, version) {
+            if dir.exists() && !is_complete_version_dir(&app, version, &dir) {
+                let _ = std::fs::remove_dir_all(&dir);
+            }
+        }
+    }
+
+    Ok(did_signal)
 }
 
 #[tauri::command]
-fn set_game_storage_dir(
+async fn verify_version_files(
     app: tauri::AppHandle,
-    custom_dir: Option<String>,
+    version: u32,
+    state: State<'_, DownloadState>,
     game_state: State<'_, GameState>,
-    download_state: State<'_, DownloadState>,
     prepare_state: State<'_, PrepareState>,
-) -> Result<storage::GameStorageSettings, String> {
-    ensure_storage_can_move(&app, &game_state, &download_state, &prepare_state)?;
-
-    let normalized_custom = custom_dir
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-        .map(std::path::PathBuf::from);
-    let linked_versions: Vec<u32> = list_installed_versions(app.clone())?
-        .into_iter()
-        .filter(|version| {
-            installer::get_config_link_state_for_version(&app, *version)
-                .map(|state| state.is_linked)
-                .unwrap_or(false)
-        })
-        .collect();
-    let old_versions_dir = storage::versions_dir(&app)?;
-    let new_versions_dir = storage::versions_dir_for_custom(&app, normalized_custom.clone())?;
+) -> Result<installer::VerifyFilesReport, String> {
+    ensure_game_not_running(&app, &game_state)?;
+    wait_for_prepare_to_finish(&prepare_state, version, std::time::Duration::from_secs(30))?;
 
-    if !same_storage_path(&old_versions_dir, &new_versions_dir) {
-        storage::move_versions_dir(
-            &old_versions_dir,
-            &new_versions_dir,
-            |done, total, detail| {
-                let total = total.max(1);
-                let step_progress = (done as f64 / total as f64).clamp(0.0, 1.0);
-                progress::emit_progress(
-                    &app,
-                    TaskProgressPayload {
-                        version: 0,
-                        steps_total: 1,
-                        step: 1,
-                        step_name: "Move Storage".to_string(),
-                        step_progress,
-                        overall_percent: step_progress * 100.0,
-                        detail,
-                        downloaded_bytes: None,
-                        total_bytes: None,
-                        extracted_files: Some(done),
-                        total_files: Some(total),
-                    },
-                );
-            },
-        )?;
-    } else {
-        std::fs::create_dir_all(&new_versions_dir).map_err(|e| e.to_string())?;
-        progress::emit_progress(
-            &app,
-            TaskProgressPayload {
-                version: 0,
-                steps_total: 1,
-                step: 1,
-                step_name: "Move Storage".to_string(),
-                step_progress: 1.0,
-                overall_percent: 100.0,
-                detail: Some("Storage folder is ready".to_string()),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: Some(1),
-                total_files: Some(1),
-            },
-        );
+    // Registered like a download so `cancel_download` can stop it.
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut guard = state
+            .active
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        if guard.iter().any(|a| a.version == version) {
+            return Err(format!("v{version} is already downloading."));
+        }
+        guard.push(ActiveDownload {
+            version,
+            cancel: cancel.clone(),
+        });
     }
 
-    let settings = storage::set_game_storage_dir(&app, normalized_custom)?;
-    for version in linked_versions {
-        installer::link_config_for_version(&app, version)?;
+    let res = installer::verify_and_repair_version(&app, version, cancel.clone()).await;
+
+    {
+        let mut guard = state
+            .active
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        guard.retain(|a| !Arc::ptr_eq(&a.cancel, &cancel));
     }
-    let _ = app.emit("game-storage://changed", &settings);
-    Ok(settings)
+    res
 }
 
 #[tauri::command]
-async fn open_custom_layout_docs() -> Result<bool, String> {
-    opener::open("https://github.com/P-Asta/hq-launcher/blob/main/docs/CUSTOM_LAYOUT.md")
-        .map_err(|e| format!("failed to open Custom Layout docs: {e}"))?;
-    Ok(true)
+fn get_version_metadata(
+    app: tauri::AppHandle,
+    version: u32,
+) -> Result<Option<version_meta::VersionMetadata>, String> {
+    installer::version_metadata(&app, version)
 }
 
 #[tauri::command]
-async fn open_external_url(url: String) -> Result<bool, String> {
-    let trimmed = url.trim();
-    if !(trimmed.starts_with("https://") || trimmed.starts_with("http://")) {
-        return Err("only http(s) URLs can be opened".to_string());
+fn list_version_metadata(
+    app: tauri::AppHandle,
+) -> Result<Vec<version_meta::VersionMetadata>, String> {
+    let mut out = Vec::new();
+    for version in list_installed_versions(app.clone())? {
+        if let Some(meta) = installer::version_metadata(&app, version)? {
+            out.push(meta);
+        }
     }
-    opener::open(trimmed).map_err(|e| format!("failed to open URL: {e}"))?;
-    Ok(true)
-}
-
-fn open_folder_path(path: &Path) -> Result<(), String> {
-    open_path_with_fallbacks(path, true)
+    Ok(out)
 }
 
-fn open_file_path(path: &Path) -> Result<(), String> {
-    open_path_with_fallbacks(path, false)
+fn emit_download_queue_changed(app: &tauri::AppHandle, queue: &download_queue::DownloadQueue) {
+    let _ = app.emit("download-queue://changed", queue.clone());
 }
 
-#[cfg(target_os = "linux")]
-fn host_open_command(program: &str) -> std::process::Command {
-    let mut command = std::process::Command::new(program);
-    for key in [
-        "APPDIR",
-        "APPIMAGE",
-        "ARGV0",
-        "GIO_MODULE_DIR",
-        "GTK_PATH",
-        "LD_PRELOAD",
-        "PYTHONHOME",
-        "PYTHONPATH",
-        "PYTHONPLATLIBDIR",
-        "PYTHONSAFEPATH",
-        "QT_PLUGIN_PATH",
-        "QT_QPA_PLATFORM_PLUGIN_PATH",
-    ] {
-        command.env_remove(key);
-    }
-    if let Some(original_ld_library_path) = std::env::var_os("APPIMAGE_ORIGINAL_LD_LIBRARY_PATH") {
-        command.env("LD_LIBRARY_PATH", original_ld_library_path);
-    } else {
-        command.env_remove("LD_LIBRARY_PATH");
-    }
-    command
+fn now_unix_secs() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
 }
 
-#[cfg(target_os = "linux")]
-fn command_status_ok(program: &str, args: &[&std::ffi::OsStr]) -> bool {
-    let mut command = host_open_command(program);
-    for arg in args {
-        command.arg(arg);
+/// For operations that would pull files out from under a running download.
+pub(crate) fn ensure_no_download_running(app: &tauri::AppHandle) -> Result<(), String> {
+    let downloading = !app
+        .state::<DownloadState>()
+        .active
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?
+        .is_empty();
+    if downloading {
+        return Err("a download is in progress; try again once it finishes".to_string());
     }
-    command.status().is_ok_and(|status| status.success())
+    Ok(())
 }
 
-#[cfg(target_os = "linux")]
-fn spawn_open_command(program: &str, path: &Path) -> bool {
-    host_open_command(program).arg(path).spawn().is_ok()
-}
+/// Start queued downloads while there is a free slot (see `DownloadQueue::can_start_another`).
+pub(crate) fn pump_download_queue(app: &tauri::AppHandle) {
+    let state = app.state::<DownloadState>();
+    let free_bytes = storage::versions_dir(app)
+        .ok()
+        .and_then(|dir| storage::free_space_bytes(&dir));
+    // Depot downloads need a Steam session; the login success path pumps again.
+    let logged_in = downloader::read_saved_login_state(app).is_ok_and(|s| s.is_logged_in);
 
-#[cfg(target_os = "linux")]
-fn spawn_url_command(program: &str, url: &str) -> bool {
-    host_open_command(program).arg(url).spawn().is_ok()
-}
+    let Ok(mut queue) = state.queue.lock() else {
+        return;
+    };
+    let Ok(mut active) = state.active.lock() else {
+        return;
+    };
+    active.retain(|a| !a.cancel.load(Ordering::Relaxed));
 
-#[cfg(target_os = "linux")]
-fn url_command_status_ok(program: &str, args: &[&std::ffi::OsStr]) -> bool {
-    let mut command = host_open_command(program);
-    command.args(args);
-    match command.status() {
-        Ok(status) if status.success() => {
-            log::info!("Opened browser URL with {program}");
-            true
-        }
-        Ok(status) => {
-            log::warn!("Browser opener {program} exited with {status}");
-            false
+    let mut changed = false;
+    while queue.can_start_another(active.len(), free_bytes, logged_in) {
+        let Some(version) = queue.next_queued() else {
+            break;
+        };
+        if active.iter().any(|a| a.version == version) {
+            // Already running through a direct `download` call.
+            queue.remove(version);
+            changed = true;
+            continue;
         }
-        Err(error) => {
-            log::warn!("Browser opener {program} could not start: {error}");
-            false
+        if let Ok(dir) = version_dir(app, version) {
+            if is_complete_version_dir(app, version, &dir) {
+                queue.remove(version);
+                changed = true;
+                continue;
+            }
         }
-    }
-}
 
-#[cfg(target_os = "linux")]
-pub(crate) fn open_url_with_fallbacks(url: &str) -> Result<(), String> {
-    let url_arg = std::ffi::OsStr::new(url);
-    if url_command_status_ok("xdg-open", &[url_arg])
-        || url_command_status_ok("gio", &[std::ffi::OsStr::new("open"), url_arg])
-        || url_command_status_ok("kioclient6", &[std::ffi::OsStr::new("exec"), url_arg])
-        || url_command_status_ok("kioclient5", &[std::ffi::OsStr::new("exec"), url_arg])
-    {
-        return Ok(());
-    }
+        let cancel = Arc::new(AtomicBool::new(false));
+        active.push(ActiveDownload {
+            version,
+            cancel: cancel.clone(),
+        });
+        queue.set_status(version, download_queue::QueueStatus::Running, None);
+        changed = true;
 
-    if let Some(browser) = std::env::var_os("BROWSER").and_then(|value| {
-        value
-            .to_string_lossy()
-            .split(':')
-            .find_map(|command| shlex::split(command).and_then(|parts| parts.into_iter().next()))
-    }) {
-        if spawn_url_command(&browser, url) {
-            log::info!("Opened browser URL with BROWSER={browser}");
-            return Ok(());
-        }
-        log::warn!("Browser from BROWSER could not start: {browser}");
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let res = installer::download_and_setup(app.clone(), version, cancel.clone()).await;
+            finish_queued_download(&app, version, &cancel, res);
+        });
     }
 
-    for browser in [
-        "firefox",
-        "google-chrome-stable",
-        "google-chrome",
-        "chromium",
-        "chromium-browser",
-        "brave",
-        "brave-browser",
-        "vivaldi",
-        "vivaldi-stable",
-    ] {
-        if spawn_url_command(browser, url) {
-            log::info!("Opened browser URL with {browser}");
-            return Ok(());
+    if changed {
+        if let Err(e) = download_queue::save(app, &queue) {
+            log::warn!("Failed to save download queue: {e}");
         }
+        emit_download_queue_changed(app, &queue);
     }
-
-    Err("no working system browser opener was found".to_string())
-}
-
-#[cfg(not(target_os = "linux"))]
-pub(crate) fn open_url_with_fallbacks(url: &str) -> Result<(), String> {
-    opener::open(url).map_err(|e| e.to_string())
 }
 
-#[cfg(target_os = "linux")]
-fn open_path_with_fallbacks(path: &Path, is_folder: bool) -> Result<(), String> {
-    if command_status_ok("xdg-open", &[path.as_os_str()])
-        || command_status_ok("gio", &[std::ffi::OsStr::new("open"), path.as_os_str()])
-        || (is_folder
-            && (command_status_ok(
-                "kioclient6",
-                &[std::ffi::OsStr::new("exec"), path.as_os_str()],
-            ) || command_status_ok(
-                "kioclient5",
-                &[std::ffi::OsStr::new("exec"), path.as_os_str()],
-            )))
-    {
-        return Ok(());
-    }
-
-    if !is_folder {
-        if let Some(editor) = std::env::var_os("VISUAL")
-            .or_else(|| std::env::var_os("EDITOR"))
-            .and_then(|value| {
-                let value = value.to_string_lossy();
-                shlex::split(&value).and_then(|parts| parts.into_iter().next())
-            })
-        {
-            if host_open_command(&editor).arg(path).spawn().is_ok() {
-                return Ok(());
+fn finish_queued_download(
+    app: &tauri::AppHandle,
+    version: u32,
+    cancel: &Arc<AtomicBool>,
+    res: Result<bool, String>,
+) {
+    let state = app.state::<DownloadState>();
+    if let Ok(mut active) = state.active.lock() {
+        active.retain(|a| !Arc::ptr_eq(&a.cancel, cancel));
+    }
+    if let Ok(mut queue) = state.queue.lock() {
+        // Paused entries keep their status; cancelled ones are already gone.
+        let still_running = queue
+            .entry(version)
+            .is_some_and(|e| e.status == download_queue::QueueStatus::Running);
+        if still_running {
+            match res {
+                Ok(_) => {
+                    queue.remove(version);
+                }
+                Err(e) if cancel.load(Ordering::Relaxed) => {
+                    log::info!("Queued download of v{version} stopped: {e}");
+                    queue.set_status(version, download_queue::QueueStatus::Paused, None);
+                }
+                Err(e) => {
+                    log::warn!("Queued download of v{version} failed: {e}");
+                    queue.set_status(version, download_queue::QueueStatus::Failed, Some(e));
+                }
             }
         }
-
-        for program in [
-            "kate", "kwrite", "gedit", "mousepad", "xed", "code", "codium",
-        ] {
-            if spawn_open_command(program, path) {
-                return Ok(());
-            }
+        if let Err(e) = download_queue::save(app, &queue) {
+            log::warn!("Failed to save download queue: {e}");
         }
+        emit_download_queue_changed(app, &queue);
+    }
+    pump_download_queue(app);
+}
 
-        if let Some(parent) = path.parent() {
-            if open_folder_path(parent).is_ok() {
-                return Ok(());
-            }
-        }
+/// Reload the persisted queue. Interrupted downloads resume once Steam is logged in.
+fn restore_download_queue_on_startup(app: &tauri::AppHandle) -> Result<(), String> {
+    let mut restored = download_queue::load(app)?;
+    restored.restore_after_restart();
+    let has_queued = restored.next_queued().is_some();
+    {
+        let state = app.state::<DownloadState>();
+        let mut queue = state
+            .queue
+            .lock()
+            .map_err(|_| "download queue lock poisoned".to_string())?;
+        *queue = restored;
+        download_queue::save(app, &queue)?;
     }
 
-    if is_folder {
-        for program in [
-            "dolphin",
-            "nautilus",
-            "thunar",
-            "nemo",
-            "pcmanfm",
-            "pcmanfm-qt",
-            "caja",
-        ] {
-            if spawn_open_command(program, path) {
-                return Ok(());
-            }
+    if has_queued {
+        let logged_in = downloader::read_saved_login_state(app).is_ok_and(|s| s.is_logged_in);
+        if !logged_in {
+            log::info!("Download queue restored; waiting for Steam login before resuming.");
         }
+        pump_download_queue(app);
     }
-
-    opener::open(path).map_err(|e| e.to_string())
+    Ok(())
 }
 
-#[cfg(not(target_os = "linux"))]
-fn open_path_with_fallbacks(path: &Path, _is_folder: bool) -> Result<(), String> {
-    opener::open(path).map_err(|e| e.to_string())
+#[tauri::command]
+fn get_download_queue(
+    state: State<'_, DownloadState>,
+) -> Result<download_queue::DownloadQueue, String> {
+    state
+        .queue
+        .lock()
+        .map(|q| q.clone())
+        .map_err(|_| "download queue lock poisoned".to_string())
 }
 
-fn collect_delete_targets(
-    path: &std::path::Path,
-    out: &mut Vec<std::path::PathBuf>,
-) -> Result<(), String> {
-    let rd = std::fs::read_dir(path).map_err(|e| e.to_string())?;
-    for entry in rd {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let child = entry.path();
-        let ty = entry.file_type().map_err(|e| e.to_string())?;
-        if ty.is_dir() && !ty.is_symlink() {
-            collect_delete_targets(&child, out)?;
-            out.push(child);
-        } else {
-            out.push(child);
+#[tauri::command]
+fn enqueue_downloads(
+    app: tauri::AppHandle,
+    versions: Vec<u32>,
+    state: State<'_, DownloadState>,
+) -> Result<download_queue::DownloadQueue, String> {
+    let snapshot = {
+        let mut queue = state
+            .queue
+            .lock()
+            .map_err(|_| "download queue lock poisoned".to_string())?;
+        let now = now_unix_secs();
+        for version in versions {
+            queue.enqueue(version, now);
         }
-    }
-    Ok(())
+        download_queue::save(&app, &queue)?;
+        emit_download_queue_changed(&app, &queue);
+        queue.clone()
+    };
+    pump_download_queue(&app);
+    Ok(snapshot)
 }
 
-fn remove_delete_target(path: &std::path::Path) -> Result<(), String> {
-    let ty = std::fs::symlink_metadata(path)
-        .map_err(|e| e.to_string())?
-        .file_type();
+#[tauri::command]
+fn move_download_queue_entry(
+    app: tauri::AppHandle,
+    version: u32,
+    index: usize,
+    state: State<'_, DownloadState>,
+) -> Result<download_queue::DownloadQueue, String> {
+    let mut queue = state
+        .queue
+        .lock()
+        .map_err(|_| "download queue lock poisoned".to_string())?;
+    queue.move_to(version, index)?;
+    download_queue::save(&app, &queue)?;
+    emit_download_queue_changed(&app, &queue);
+    Ok(queue.clone())
+}
 
-    if ty.is_dir() && !ty.is_symlink() {
-        std::fs::remove_dir(path).map_err(|e| e.to_string())?;
-        return Ok(());
+#[tauri::command]
+fn pause_queued_download(
+    app: tauri::AppHandle,
+    version: u32,
+    state: State<'_, DownloadState>,
+) -> Result<bool, String> {
+    {
+        let mut queue = state
+            .queue
+            .lock()
+            .map_err(|_| "download queue lock poisoned".to_string())?;
+        if queue.entry(version).is_none() {
+            return Ok(false);
+        }
+        queue.set_status(version, download_queue::QueueStatus::Paused, None);
+        download_queue::save(&app, &queue)?;
+        emit_download_queue_changed(&app, &queue);
     }
-
-    if let Err(file_err) = std::fs::remove_file(path) {
-        std::fs::remove_dir(path).map_err(|dir_err| format!("{file_err}; {dir_err}"))?;
+    // The partial directory is kept, so resuming continues where this stopped.
+    let active = state
+        .active
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?;
+    for a in active.iter().filter(|a| a.version == version) {
+        a.cancel.store(true, Ordering::Relaxed);
     }
-    Ok(())
+    Ok(true)
 }
 
 #[tauri::command]
-fn delete_installed_version(
+fn resume_queued_download(
     app: tauri::AppHandle,
     version: u32,
-    game_state: State<'_, GameState>,
-    download_state: State<'_, DownloadState>,
-    prepare_state: State<'_, PrepareState>,
+    state: State<'_, DownloadState>,
 ) -> Result<bool, String> {
     {
-        let mut guard = game_state
-            .active
+        let mut queue = state
+            .queue
             .lock()
-            .map_err(|_| "game state lock poisoned".to_string())?;
-        for active in guard.iter_mut() {
-            if active.version == version
-                && active
-                    .child
-                    .try_wait()
-                    .map_err(|e| e.to_string())?
-                    .is_none()
-            {
-                return Err("Cannot delete a version while the game is running.".to_string());
-            }
-            if active.version == version && linux_lingering_game_pid(&app, version).is_some() {
-                return Err("Cannot delete a version while the game is running.".to_string());
-            }
+            .map_err(|_| "download queue lock poisoned".to_string())?;
+        if !queue.enqueue(version, now_unix_secs()) {
+            return Ok(false);
         }
-        guard.retain(|active| active.version != version);
+        download_queue::save(&app, &queue)?;
+        emit_download_queue_changed(&app, &queue);
     }
+    pump_download_queue(&app);
+    Ok(true)
+}
+
+#[tauri::command]
+fn set_download_queue_concurrency(
+    app: tauri::AppHandle,
+    max_concurrent: usize,
+    state: State<'_, DownloadState>,
+) -> Result<usize, String> {
+    let applied = {
+        let mut queue = state
+            .queue
+            .lock()
+            .map_err(|_| "download queue lock poisoned".to_string())?;
+        let applied = queue.set_max_concurrent(max_concurrent);
+        download_queue::save(&app, &queue)?;
+        emit_download_queue_changed(&app, &queue);
+        applied
+    };
+    pump_download_queue(&app);
+    Ok(applied)
+}
+
+#[tauri::command]
+fn get_partial_download_state(
+    app: tauri::AppHandle,
+    version: u32,
+) -> Result<Option<installer::PartialInstallState>, String> {
+    installer::partial_install_state(&app, version)
+}
 
+#[tauri::command]
+fn discard_partial_download(
+    app: tauri::AppHandle,
+    version: u32,
+    state: State<'_, DownloadState>,
+) -> Result<bool, String> {
     {
-        let guard = download_state
+        let guard = state
             .active
             .lock()
             .map_err(|_| "download state lock poisoned".to_string())?;
-        if let Some(active) = guard.as_ref() {
-            if active.version == version && !active.cancel.load(Ordering::Relaxed) {
-                return Err("Cannot delete a version while it is downloading.".to_string());
-            }
+        if guard.iter().any(|a| a.version == version) {
+            return Err(format!(
+                "v{version} is still downloading. Please cancel it first."
+            ));
         }
     }
+    installer::discard_partial_install(&app, version)
+}
 
-    {
-        let guard = prepare_state
-            .active
-            .lock()
-            .map_err(|_| "prepare state lock poisoned".to_string())?;
-        if let Some(active) = guard.as_ref() {
-            if active.version == version && !active.cancel.load(Ordering::Relaxed) {
-                return Err("Cannot delete a version while it is being prepared.".to_string());
-            }
-        }
-    }
-
-    let dir = version_dir(&app, version)?;
-    if !dir.exists() {
-        return Ok(false);
+#[tauri::command]
+async fn sync_latest_install_from_manifest(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> Result<bool, String> {
+    if let Some(version) = version {
+        let game_root = version_dir(&app, version)?;
+        installer::sync_install_from_manifest_for_version(&app, version, game_root).await?;
+    } else {
+        installer::sync_latest_install_from_manifest(app).await?;
     }
+    Ok(true)
+}
 
-    let mut targets = Vec::new();
-    collect_delete_targets(&dir, &mut targets)?;
-    targets.push(dir.clone());
-
-    let total = targets.len() as u64;
-    progress::emit_progress(
-        &app,
-        TaskProgressPayload {
-            version,
-            steps_total: 1,
-            step: 1,
-            step_name: "Delete Version".to_string(),
-            step_progress: 0.0,
-            overall_percent: 0.0,
-            detail: Some(format!("Preparing to delete v{version}...")),
-            downloaded_bytes: None,
-            total_bytes: None,
-            extracted_files: Some(0),
-            total_files: Some(total),
-        },
-    );
-
-    for (idx, target) in targets.iter().enumerate() {
-        remove_delete_target(target)?;
-        let done = idx as u64 + 1;
-        let step_progress = if total == 0 {
-            1.0
-        } else {
-            done as f64 / total as f64
-        };
-        let detail = target
-            .file_name()
-            .and_then(|name| name.to_str())
-            .map(|name| format!("Deleting {name}"))
-            .unwrap_or_else(|| "Deleting files...".to_string());
-        progress::emit_progress(
-            &app,
-            TaskProgressPayload {
-                version,
-                steps_total: 1,
-                step: 1,
-                step_name: "Delete Version".to_string(),
-                step_progress,
-                overall_percent: step_progress * 100.0,
-                detail: Some(detail),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: Some(done),
-                total_files: Some(total),
-            },
-        );
+#[tauri::command]
+async fn check_latest_install_manifest_update(
+    app: tauri::AppHandle,
+    version: Option<u32>,
+) -> Result<installer::ManifestUpdateCheck, String> {
+    if let Some(version) = version {
+        installer::check_manifest_update_for_version(&app, version).await
+    } else {
+        installer::check_latest_install_manifest_update(&app).await
     }
-
-    Ok(true)
 }
 
 #[tauri::command]
-async fn open_downloader_folder(app: tauri::AppHandle) -> Result<bool, String> {
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
-        .join("downloader");
-    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create downloader dir: {e}"))?;
+async fn open_version_folder(app: tauri::AppHandle) -> Result<bool, String> {
+    let dir = storage::versions_dir(&app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create versions dir: {e}"))?;
     open_folder_path(&dir)?;
     Ok(true)
 }
 
 #[tauri::command]
-async fn open_mod_folder(
+fn get_game_storage_settings(
     app: tauri::AppHandle,
-    version: u32,
-    dev: String,
-    name: String,
-) -> Result<bool, String> {
-    let plugins = plugins_dir(&app, version)?;
-    let patchers = patchers_dir(&app, version)?;
-
-    let Some(dir) =
-        mod_dir_for(&plugins, &dev, &name).or_else(|| mod_dir_for(&patchers, &dev, &name))
-    else {
-        return Err(format!(
-            "mod folder not found for {dev}-{name} on v{version}"
-        ));
-    };
+) -> Result<storage::GameStorageSettings, String> {
+    storage::game_storage_settings(&app)
+}
 
-    open_folder_path(&dir)?;
-    Ok(true)
+#[tauri::command]
+fn get_selected_run_mode(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    storage::selected_run_mode(&app)
 }
 
 #[tauri::command]
-async fn check_mod_updates(
-    app: tauri::AppHandle,
-    version: u32,
-    run_mode: Option<String>,
-) -> Result<bool, String> {
-    let client = reqwest::Client::new();
-    let run_mode_name = run_mode.as_deref().unwrap_or("hq");
+fn set_selected_run_mode(app: tauri::AppHandle, run_mode: String) -> Result<String, String> {
+    let normalized = run_mode.trim().to_lowercase();
+    if !matches!(
+        normalized.as_str(),
+        "hq" | "smhq"
+            | "c_moons"
+            | "c_moons_smhq"
+            | "c_moons_eclipsed"
+            | "c_moons_practice"
+            | "practice"
+            | "eclipsed_hq"
+            | "brutal"
+            | "brutal_smhq"
+            | "brutal_eclipsed"
+            | "brutal_practice"
+            | "wesley"
+            | "wesley_smhq"
+            | "wesley_eclipsed"
+            | "wesley_practice"
+    ) {
+        return Err(format!("invalid run mode: {run_mode}"));
+    }
+    storage::set_selected_run_mode(&app, normalized)
+}
 
-    let extract_dir = version_dir(&app, version)?;
-    let mods_cfg = exclude_disabled_mod_switches_from_updates(
-        &app,
-        effective_mods_config_for_run_mode(&client, version, run_mode_name, false, true).await?,
-    )?;
-    let (preset, _practice) = preset_and_practice_for_run_mode(run_mode_name);
-    let active_tags = preset_tags_for_name(&preset);
+#[tauri::command]
+fn get_selected_version(app: tauri::AppHandle) -> Result<Option<u32>, String> {
+    storage::selected_version(&app)
+}
 
-    let mut updatable_mods: Vec<String> = vec![];
+#[tauri::command]
+fn set_selected_version(app: tauri::AppHandle, version: u32) -> Result<u32, String> {
+    if version == 0 {
+        return Err("invalid selected version: 0".to_string());
+    }
+    storage::set_selected_version(&app, version)
+}
 
-    let res = mods::updatable_mods_with_progress(
-        &app,
-        &extract_dir,
-        version,
-        &mods_cfg,
-        &active_tags,
-        |checked, total, detail, mod_name| {
-            if let Some(mod_name) = mod_name {
-                if !updatable_mods.contains(&mod_name) {
-                    updatable_mods.push(mod_name.clone());
-                }
-            }
+#[tauri::command]
+fn get_events_enabled(app: tauri::AppHandle) -> Result<bool, String> {
+    storage::events_enabled(&app)
+}
 
-            progress::emit_updatable_progress(
-                &app,
-                TaskUpdatableProgressPayload {
-                    version,
-                    run_mode: Some(run_mode_name.to_string()),
-                    total,
-                    checked,
-                    updatable_mods: updatable_mods.clone(),
-                    detail,
-                },
-            );
-        },
-    )
-    .await;
+#[tauri::command]
+fn set_events_enabled(app: tauri::AppHandle, enabled: bool) -> Result<bool, String> {
+    storage::set_events_enabled(&app, enabled)
+}
 
-    if let Err(e) = res {
-        progress::emit_updatable_error(
-            &app,
-            TaskErrorPayload {
-                version,
-                run_mode: Some(run_mode_name.to_string()),
-                message: e.clone(),
-            },
-        );
-        return Err(e);
+fn same_storage_path(a: &std::path::Path, b: &std::path::Path) -> bool {
+    if let (Ok(a), Ok(b)) = (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        return a == b;
     }
+    a == b
+}
 
-    match purge_capped_incompatible_installed_mods(&app, version, Some(run_mode_name)).await {
-        Ok(purged) => {
-            if purged > 0 {
-                log::info!(
-                    "Purged {purged} incompatible capped mods after checking updates for v{version}"
-                );
+fn ensure_storage_can_move(
+    app: &tauri::AppHandle,
+    game_state: &State<'_, GameState>,
+    download_state: &State<'_, DownloadState>,
+    prepare_state: &State<'_, PrepareState>,
+) -> Result<(), String> {
+    {
+        let mut guard = game_state
+            .active
+            .lock()
+            .map_err(|_| "game state lock poisoned".to_string())?;
+        let scopes = game_process_scopes(&guard);
+        for (index, active) in guard.iter_mut().enumerate() {
+            if active
+                .child
+                .try_wait()
+                .map_err(|e| e.to_string())?
+                .is_none()
+                || linux_lingering_game_pid(app, &scopes, index).is_some()
+            {
+                return Err("Cannot change game storage while the game is running.".to_string());
             }
         }
-        Err(e) => {
-            log::warn!(
-                "Failed to purge incompatible capped mods after checking updates for v{version}: {e}"
-            );
-        }
+        guard.clear();
     }
 
-    progress::emit_updatable_finished(
-        &app,
-        TaskFinishedPayload {
-            version,
-            run_mode: Some(run_mode_name.to_string()),
-            path: extract_dir.to_string_lossy().to_string(),
-        },
-    );
-    Ok(true)
+    {
+        let guard = download_state
+            .active
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        if guard
+            .iter()
+            .any(|active| !active.cancel.load(Ordering::Relaxed))
+        {
+            return Err("Cannot change game storage while a download is running.".to_string());
+        }
+    }
+
+    {
+        let guard = prepare_state
+            .active
+            .lock()
+            .map_err(|_| "prepare state lock poisoned".to_string())?;
+        if guard
+            .as_ref()
+            .is_some_and(|active| !active.cancel.load(Ordering::Relaxed))
+        {
+            return Err("Cannot change game storage while a preset is being prepared.".to_string());
+        }
+    }
+
+    Ok(())
 }
 
-/// Check for and apply a pending native overlay update as the final step of the
-/// unified update flow, then propagate the refreshed `version.dll` into every
-/// installed version directory.
-///
-/// On non-Windows hosts (or when no update is available) this is a fast no-op
-/// that emits the completion progress for the overlay step and returns. Errors
-/// are logged and never propagated: an overlay update failure must not turn a
-/// successful mod update into a failure.
-///
-/// `version_dir` scopes the post-install proxy staging to the version being
-/// updated: mod updates are per-version, so the refreshed overlay is only
-/// re-staged into that version's directory rather than propagated everywhere.
-async fn apply_native_overlay_update_step(
+#[tauri::command]
+fn pick_game_storage_dir(initial_path: Option<String>) -> Result<Option<String>, String> {
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(path) = initial_path
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        let candidate = std::path::PathBuf::from(&path);
+        if candidate.exists() {
+            dialog = dialog.set_directory(candidate);
+        }
+    }
+
+    Ok(dialog
+        .pick_folder()
+        .map(|path| path.to_string_lossy().to_string()))
+}
+
+fn emit_storage_move_progress(
     app: &tauri::AppHandle,
-    steps_total: u32,
-    version_dir: &std::path::Path,
+    done: u64,
+    total: u64,
+    detail: Option<String>,
 ) {
-    const STEP: u32 = 3;
-    const STEP_NAME: &str = "HQ Overlay";
-
-    // Emit an initial progress tick so the UI shows the overlay step started.
+    let total = total.max(1);
+    let step_progress = (done as f64 / total as f64).clamp(0.0, 1.0);
     progress::emit_progress(
         app,
         TaskProgressPayload {
             version: 0,
-            steps_total,
-            step: STEP,
-            step_name: STEP_NAME.to_string(),
-            step_progress: 0.0,
-            overall_percent: overall_from_step(STEP, 0.0, steps_total),
-            detail: Some("Checking HQ Overlay...".to_string()),
+            steps_total: 1,
+            step: 1,
+            step_name: "Move Storage".to_string(),
+            step_progress,
+            overall_percent: step_progress * 100.0,
+            detail,
             downloaded_bytes: None,
             total_bytes: None,
-            extracted_files: None,
-            total_files: None,
+            extracted_files: Some(done),
+            total_files: Some(total),
         },
     );
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let app_handle = app.clone();
-        let installed = tauri::async_runtime::spawn_blocking(move || {
-            let client = native_overlay_http_client()?;
-            let release = fetch_native_overlay_release(&client)?;
-            let info = native_overlay_update_info_for_release(&app_handle, &release)?;
-            if info.available {
-                install_native_overlay_update_blocking(&app_handle).map(|_| true)
-            } else {
-                // The app-data DLL may already be current while this game
-                // version's `version.dll` proxy has a stale or missing
-                // `.hq-overlay-version` stamp. The proxy still needs to be
-                // staged in that case, just like a mod update is applied to
-                // the selected version directory.
-                Ok(false)
-            }
+fn config_linked_versions(app: &tauri::AppHandle) -> Result<Vec<u32>, String> {
+    Ok(list_installed_versions(app.clone())?
+        .into_iter()
+        .filter(|version| {
+            installer::get_config_link_state_for_version(app, *version)
+                .map(|state| state.is_linked)
+                .unwrap_or(false)
         })
-        .await;
+        .collect())
+}
 
-        match installed {
-            Ok(Ok(_)) => {
-                // Always run the stamp/size check for the version being
-                // updated. This also repairs a stale proxy when the central
-                // overlay DLL itself is already up to date.
-                if let Err(error) = stage_native_overlay_as_proxy(app, version_dir) {
-                    log::warn!("failed to stage overlay proxy during update: {error}");
-                }
-            }
-            Ok(Err(error)) => {
-                log::warn!("HQ Overlay update failed during unified update step: {error}");
-            }
-            Err(error) => {
-                log::warn!("HQ Overlay update worker failed: {error}");
+/// Run a journaled versions folder move and point the storage setting at the new
+/// folder once the copy is verified.
+fn run_storage_move(
+    app: &tauri::AppHandle,
+    journal: relocation::RelocationJournal,
+) -> Result<storage::GameStorageSettings, String> {
+    let linked_versions = config_linked_versions(app)?;
+    relocation::run(
+        &storage::relocation_journal_path(app)?,
+        journal,
+        |done, total, detail| emit_storage_move_progress(app, done, total, detail),
+        |journal| storage::set_game_storage_dir(app, journal.custom_dir.clone()).map(|_| ()),
+    )?;
+
+    let settings = storage::game_storage_settings(app)?;
+    for version in linked_versions {
+        installer::link_config_for_version(app, version)?;
+    }
+    // The content store was left behind, so the moved versions share nothing yet.
+    let versions_dir = storage::versions_dir(app)?;
+    let versions = list_installed_versions(app.clone())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        for version in versions {
+            if let Err(e) = dedup_store::dedup_version(&versions_dir, version) {
+                log::warn!("Failed to dedup v{version} after the storage move: {e}");
             }
         }
+    });
+    let _ = app.emit("game-storage://changed", &settings);
+    Ok(settings)
+}
+
+#[tauri::command]
+fn set_game_storage_dir(
+    app: tauri::AppHandle,
+    custom_dir: Option<String>,
+    verify_hashes: Option<bool>,
+    game_state: State<'_, GameState>,
+    download_state: State<'_, DownloadState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<storage::GameStorageSettings, String> {
+    ensure_storage_can_move(&app, &game_state, &download_state, &prepare_state)?;
+
+    let normalized_custom = custom_dir
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(std::path::PathBuf::from);
+    let old_versions_dir = storage::versions_dir(&app)?;
+    let new_versions_dir = storage::versions_dir_for_custom(&app, normalized_custom.clone())?;
+
+    if !same_storage_path(&old_versions_dir, &new_versions_dir) {
+        let journal_path = storage::relocation_journal_path(&app)?;
+        relocation::check_can_start(&journal_path, &new_versions_dir)?;
+        let journal = relocation::RelocationJournal::new(
+            &old_versions_dir,
+            &new_versions_dir,
+            normalized_custom,
+            verify_hashes.unwrap_or(false),
+        );
+        return run_storage_move(&app, journal);
     }
 
-    // Emit the completion tick regardless of outcome.
-    progress::emit_progress(
-        app,
-        TaskProgressPayload {
-            version: 0,
-            steps_total,
-            step: STEP,
-            step_name: STEP_NAME.to_string(),
-            step_progress: 1.0,
-            overall_percent: overall_from_step(STEP, 1.0, steps_total),
-            detail: Some("HQ Overlay up to date".to_string()),
-            downloaded_bytes: None,
-            total_bytes: None,
-            extracted_files: None,
-            total_files: None,
-        },
-    );
+    let linked_versions = config_linked_versions(&app)?;
+    std::fs::create_dir_all(&new_versions_dir).map_err(|e| e.to_string())?;
+    emit_storage_move_progress(&app, 1, 1, Some("Storage folder is ready".to_string()));
+
+    let settings = storage::set_game_storage_dir(&app, normalized_custom)?;
+    for version in linked_versions {
+        installer::link_config_for_version(&app, version)?;
+    }
+    let _ = app.emit("game-storage://changed", &settings);
+    Ok(settings)
+}
+
+/// A move whose copy was verified only needs its storage switch and cleanup, so it
+/// is finished right away. An earlier phase waits for the user to resume it.
+fn finish_storage_move_on_startup(app: &tauri::AppHandle) -> Result<(), String> {
+    let journal_path = storage::relocation_journal_path(app)?;
+    let Some(mut journal) = relocation::RelocationJournal::load(&journal_path)? else {
+        return Ok(());
+    };
+    if journal.is_verified() {
+        log::info!(
+            "Finishing storage move from {} to {}",
+            journal.from.display(),
+            journal.to.display()
+        );
+        if let Err(e) = run_storage_move(app, journal) {
+            log::warn!("Failed to finish the storage move: {e}");
+        }
+    } else {
+        log::warn!(
+            "Storage move from {} to {} did not finish ({:?})",
+            journal.from.display(),
+            journal.to.display(),
+            journal.phase
+        );
+        if journal.stopped.is_none() {
+            journal.mark_stopped(
+                &journal_path,
+                "The launcher closed before the move finished.".to_string(),
+            )?;
+        }
+    }
+    if let Some(journal) = relocation::RelocationJournal::load(&journal_path)? {
+        let _ = app.emit("game-storage://move-pending", &journal);
+    }
+    Ok(())
 }
 
 #[tauri::command]
-async fn apply_mod_updates(
+fn get_pending_storage_move(
     app: tauri::AppHandle,
-    version: u32,
-    run_mode: Option<String>,
-) -> Result<bool, String> {
-    let run_mode_name = run_mode.as_deref().unwrap_or("hq").to_string();
-    let res: Result<(), String> = async {
-        let client = reqwest::Client::new();
+) -> Result<Option<relocation::RelocationJournal>, String> {
+    relocation::RelocationJournal::load(&storage::relocation_journal_path(&app)?)
+}
 
-        let game_root = version_dir(&app, version)?;
-        if !game_root.exists() {
-            return Err(format!(
-                "version folder not found: {}",
-                game_root.to_string_lossy()
-            ));
-        }
+#[tauri::command]
+fn resume_storage_move(
+    app: tauri::AppHandle,
+    game_state: State<'_, GameState>,
+    download_state: State<'_, DownloadState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<storage::GameStorageSettings, String> {
+    ensure_storage_can_move(&app, &game_state, &download_state, &prepare_state)?;
+    let journal = relocation::RelocationJournal::load(&storage::relocation_journal_path(&app)?)?
+        .ok_or_else(|| "there is no unfinished storage move".to_string())?;
+    run_storage_move(&app, journal)
+}
 
-        let mods_cfg = exclude_disabled_mod_switches_from_updates(
-            &app,
-            effective_mods_config_for_run_mode(
-                &client,
-                version,
-                &run_mode_name,
-                false,
-                true,
-            )
-            .await?,
-        )?;
-        let (preset, _practice) = preset_and_practice_for_run_mode(&run_mode_name);
-        let active_tags = preset_tags_for_name(&preset);
+#[tauri::command]
+fn discard_storage_move(
+    app: tauri::AppHandle,
+    game_state: State<'_, GameState>,
+    download_state: State<'_, DownloadState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<bool, String> {
+    ensure_storage_can_move(&app, &game_state, &download_state, &prepare_state)?;
+    relocation::discard(&storage::relocation_journal_path(&app)?)
+}
 
-        const STEPS_TOTAL: u32 = 3;
-        progress::emit_progress(
-            &app,
-            TaskProgressPayload {
-                version,
-                steps_total: STEPS_TOTAL,
-                step: 1,
-                step_name: "Check Updates".to_string(),
-                step_progress: 0.0,
-                overall_percent: overall_from_step(1, 0.0, STEPS_TOTAL),
-                detail: Some("Checking updatable mods...".to_string()),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: None,
-                total_files: None,
-            },
-        );
+#[tauri::command]
+async fn open_custom_layout_docs() -> Result<bool, String> {
+    opener::open("https://github.com/P-Asta/hq-launcher/blob/main/docs/CUSTOM_LAYOUT.md")
+        .map_err(|e| format!("failed to open Custom Layout docs: {e}"))?;
+    Ok(true)
+}
 
-        let mut updatable: Vec<String> = vec![];
-        mods::updatable_mods_with_progress(
-            &app,
-            &game_root,
-            version,
-            &mods_cfg,
-            &active_tags,
-            |checked, total, detail, mod_name| {
-                if let Some(m) = mod_name {
-                    if !updatable.contains(&m) {
-                        updatable.push(m);
-                    }
-                }
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (checked as f64 / total as f64).clamp(0.0, 1.0)
-                };
-                progress::emit_progress(
-                    &app,
-                    TaskProgressPayload {
-                        version,
-                        steps_total: STEPS_TOTAL,
-                        step: 1,
-                        step_name: "Check Updates".to_string(),
-                        step_progress,
-                        overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
-                        detail,
-                        downloaded_bytes: None,
-                        total_bytes: None,
-                        extracted_files: Some(checked),
-                        total_files: Some(total),
-                    },
-                );
-            },
-        )
-        .await?;
-
-        match purge_capped_incompatible_installed_mods(&app, version, Some(&run_mode_name)).await {
-            Ok(purged) => {
-                if purged > 0 {
-                    log::info!(
-                        "Purged {purged} incompatible capped mods during update check for v{version}"
-                    );
-                }
-            }
-            Err(e) => {
-                log::warn!(
-                    "Failed to purge incompatible capped mods during update check for v{version}: {e}"
-                );
-            }
-        }
-
-        if updatable.is_empty() {
-            progress::emit_progress(
-                &app,
-                TaskProgressPayload {
-                    version,
-                    steps_total: STEPS_TOTAL,
-                    step: 2,
-                    step_name: "Update Mods".to_string(),
-                    step_progress: 1.0,
-                    overall_percent: overall_from_step(2, 1.0, STEPS_TOTAL),
-                    detail: Some("No updates available".to_string()),
-                    downloaded_bytes: None,
-                    total_bytes: None,
-                    extracted_files: None,
-                    total_files: None,
-                },
-            );
-            // Even with no mod updates, a pending overlay update is still applied.
-            apply_native_overlay_update_step(&app, STEPS_TOTAL, &game_root).await;
-            return Ok(());
-        }
-
-        progress::emit_progress(
-            &app,
-            TaskProgressPayload {
-                version,
-                steps_total: STEPS_TOTAL,
-                step: 2,
-                step_name: "Update Mods".to_string(),
-                step_progress: 0.0,
-                overall_percent: overall_from_step(2, 0.0, STEPS_TOTAL),
-                detail: Some(format!("Updating {} mods...", updatable.len())),
-                downloaded_bytes: None,
-                total_bytes: None,
-                extracted_files: Some(0),
-                total_files: Some(updatable.len() as u64),
-            },
-        );
-
-        mods::update_mods_with_progress(
-            &app,
-            &game_root,
-            version,
-            &mods_cfg,
-            updatable.clone(),
-            |done, total, progress_info| {
-                let step_progress = if total == 0 {
-                    1.0
-                } else {
-                    (done as f64 / total as f64).clamp(0.0, 1.0)
-                };
-                progress::emit_progress(
-                    &app,
-                    TaskProgressPayload {
-                        version,
-                        steps_total: STEPS_TOTAL,
-                        step: 2,
-                        step_name: "Update Mods".to_string(),
-                        step_progress,
-                        overall_percent: overall_from_step(2, step_progress, STEPS_TOTAL),
-                        detail: progress_info.detail,
-                        downloaded_bytes: progress_info.downloaded_bytes,
-                        total_bytes: progress_info.total_bytes,
-                        extracted_files: progress_info.extracted_files.or(Some(done)),
-                        total_files: progress_info.total_files.or(Some(total)),
-                    },
-                );
-            },
-        )
-        .await?;
-
-        let _ = ensure_reverb_trigger_fix_cfg(&app, version);
-
-        // Step 3: apply a pending native overlay update (if any) so the single
-        // "Update" action refreshes mods and the overlay together, staging the
-        // fresh DLL into this version's directory only.
-        apply_native_overlay_update_step(&app, STEPS_TOTAL, &game_root).await;
-
-        Ok(())
-    }
-    .await;
-
-    match res {
-        Ok(()) => {
-            progress::emit_finished(
-                &app,
-                TaskFinishedPayload {
-                    version,
-                    run_mode: Some(run_mode_name.clone()),
-                    path: version_dir(&app, version)?.to_string_lossy().to_string(),
-                },
-            );
-            Ok(true)
-        }
-        Err(e) => {
-            progress::emit_error(
-                &app,
-                TaskErrorPayload {
-                    version,
-                    run_mode: Some(run_mode_name.clone()),
-                    message: e.clone(),
-                },
-            );
-            Err(e)
-        }
+#[tauri::command]
+async fn open_external_url(url: String) -> Result<bool, String> {
+    let trimmed = url.trim();
+    if !(trimmed.starts_with("https://") || trimmed.starts_with("http://")) {
+        return Err("only http(s) URLs can be opened".to_string());
     }
+    opener::open(trimmed).map_err(|e| format!("failed to open URL: {e}"))?;
+    Ok(true)
 }
 
-#[cfg(target_os = "linux")]
-fn get_steam_client_path(
-    launcher_root: &std::path::Path,
-    configured_path: Option<&str>,
-) -> std::path::PathBuf {
-    if let Some(configured) = configured_path
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-    {
-        let candidate = std::path::PathBuf::from(configured);
-        if candidate.exists() {
-            return candidate;
-        }
-    }
-
-    if let Some(home_dir) = dirs::home_dir() {
-        let steam_paths = [
-            home_dir.join(".steam/steam"),
-            home_dir.join(".local/share/Steam"),
-            home_dir.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
-        ];
-        for path in steam_paths.iter() {
-            if path.exists() && path.join("steamapps").exists() {
-                println!("Found real Steam installation at: {:?}", path);
-                return path.clone();
-            }
-        }
-    }
-
-    println!("Steam not found. Mocking client path.");
-    launcher_root.to_path_buf()
+fn open_folder_path(path: &Path) -> Result<(), String> {
+    open_path_with_fallbacks(path, true)
 }
 
-#[cfg(target_os = "linux")]
-fn linux_overlay_preload_value(steam_path: &std::path::Path) -> Option<String> {
-    let mut libs: Vec<String> = vec![];
-    for rel in [
-        std::path::Path::new("ubuntu12_32").join("gameoverlayrenderer.so"),
-        std::path::Path::new("ubuntu12_64").join("gameoverlayrenderer.so"),
-    ] {
-        let candidate = steam_path.join(rel);
-        if candidate.exists() {
-            libs.push(candidate.to_string_lossy().to_string());
-        }
-    }
-
-    if libs.is_empty() {
-        None
-    } else {
-        Some(libs.join(":"))
-    }
+fn open_file_path(path: &Path) -> Result<(), String> {
+    open_path_with_fallbacks(path, false)
 }
 
 #[cfg(target_os = "linux")]
-fn sanitize_linux_host_process_env(command: &mut std::process::Command) {
+fn host_open_command(program: &str) -> std::process::Command {
+    let mut command = std::process::Command::new(program);
     for key in [
         "APPDIR",
         "APPIMAGE",
         "ARGV0",
         "GIO_MODULE_DIR",
         "GTK_PATH",
+        "LD_PRELOAD",
         "PYTHONHOME",
         "PYTHONPATH",
         "PYTHONPLATLIBDIR",
@@ -9893,415 +9049,1776 @@ fn sanitize_linux_host_process_env(command: &mut std::process::Command) {
     } else {
         command.env_remove("LD_LIBRARY_PATH");
     }
+    command
 }
 
-fn resolve_game_launch_paths(
-    app: &tauri::AppHandle,
-    version: u32,
-) -> Result<(std::path::PathBuf, std::path::PathBuf, std::path::PathBuf), String> {
-    let dir = version_dir(app, version)?;
-    if !dir.exists() {
-        return Err(format!(
-            "version folder not found: {}",
-            dir.to_string_lossy()
-        ));
+#[cfg(target_os = "linux")]
+fn command_status_ok(program: &str, args: &[&std::ffi::OsStr]) -> bool {
+    let mut command = host_open_command(program);
+    for arg in args {
+        command.arg(arg);
     }
+    command.status().is_ok_and(|status| status.success())
+}
 
-    let exe_name = "Lethal Company.exe";
-    let exe_path = dir.join(exe_name);
-    let exe_path = if exe_path.exists() {
-        exe_path
-    } else {
-        find_file_named(&dir, exe_name, 3)
-            .ok_or_else(|| format!("{exe_name} not found under {}", dir.to_string_lossy()))?
-    };
-
-    let exe_dir = exe_path
-        .parent()
-        .ok_or_else(|| "invalid exe path".to_string())?
-        .to_path_buf();
+#[cfg(target_os = "linux")]
+fn spawn_open_command(program: &str, path: &Path) -> bool {
+    host_open_command(program).arg(path).spawn().is_ok()
+}
 
-    Ok((dir, exe_path, exe_dir))
+#[cfg(target_os = "linux")]
+fn spawn_url_command(program: &str, url: &str) -> bool {
+    host_open_command(program).arg(url).spawn().is_ok()
 }
 
-fn ensure_game_not_running(
-    app: &tauri::AppHandle,
-    state: &State<'_, GameState>,
-) -> Result<(), String> {
-    let mut guard = state
-        .active
-        .lock()
-        .map_err(|_| "game state lock poisoned".to_string())?;
-    let mut has_running = false;
-    for active in guard.iter_mut() {
-        if active
-            .child
-            .try_wait()
-            .map_err(|e| e.to_string())?
-            .is_none()
-        {
-            has_running = true;
-        } else if linux_lingering_game_pid(app, active.version).is_some() {
-            has_running = true;
+#[cfg(target_os = "linux")]
+fn url_command_status_ok(program: &str, args: &[&std::ffi::OsStr]) -> bool {
+    let mut command = host_open_command(program);
+    command.args(args);
+    match command.status() {
+        Ok(status) if status.success() => {
+            log::info!("Opened browser URL with {program}");
+            true
+        }
+        Ok(status) => {
+            log::warn!("Browser opener {program} exited with {status}");
+            false
+        }
+        Err(error) => {
+            log::warn!("Browser opener {program} could not start: {error}");
+            false
         }
     }
-    if has_running {
-        return Err("game is already running".to_string());
-    }
-    guard.clear();
-    Ok(())
 }
 
-fn ensure_launch_compatible_with_active_games(
-    app: &tauri::AppHandle,
-    state: &State<'_, GameState>,
-    version: u32,
-    mutates_proxy_dlls: bool,
-) -> Result<(), String> {
-    let mut guard = state
-        .active
-        .lock()
-        .map_err(|_| "game state lock poisoned".to_string())?;
-    cleanup_active_games(app, &mut guard)?;
-    let conflict = guard.iter().any(|active| {
-        active.version == version
-            && (mutates_proxy_dlls
-                || active._vanilla_proxy_dll_guards.is_some()
-                || active._non_native_overlay_proxy_guard.is_some())
-    });
-    if conflict {
-        return Err(
-            "Runs that change proxy DLL availability cannot use the same game version at the same time"
-                .to_string(),
-        );
+#[cfg(target_os = "linux")]
+pub(crate) fn open_url_with_fallbacks(url: &str) -> Result<(), String> {
+    let url_arg = std::ffi::OsStr::new(url);
+    if url_command_status_ok("xdg-open", &[url_arg])
+        || url_command_status_ok("gio", &[std::ffi::OsStr::new("open"), url_arg])
+        || url_command_status_ok("kioclient6", &[std::ffi::OsStr::new("exec"), url_arg])
+        || url_command_status_ok("kioclient5", &[std::ffi::OsStr::new("exec"), url_arg])
+    {
+        return Ok(());
     }
-    Ok(())
-}
 
-fn cleanup_active_games(
-    app: &tauri::AppHandle,
-    active_games: &mut Vec<ActiveGame>,
-) -> Result<bool, String> {
-    let mut any_finished = false;
-    let mut kept = Vec::with_capacity(active_games.len());
-    for mut active in active_games.drain(..) {
-        match active.child.try_wait().map_err(|e| e.to_string())? {
-            None => kept.push(active),
-            Some(_) => {
-                if linux_lingering_game_pid(app, active.version).is_some() {
-                    kept.push(active);
-                } else {
-                    let finished_pid = active.child.id();
-                    clear_native_overlay_runtime_for_pid(app, finished_pid)?;
-                    any_finished = true;
-                }
-            }
+    if let Some(browser) = std::env::var_os("BROWSER").and_then(|value| {
+        value
+            .to_string_lossy()
+            .split(':')
+            .find_map(|command| shlex::split(command).and_then(|parts| parts.into_iter().next()))
+    }) {
+        if spawn_url_command(&browser, url) {
+            log::info!("Opened browser URL with BROWSER={browser}");
+            return Ok(());
         }
+        log::warn!("Browser from BROWSER could not start: {browser}");
     }
-    *active_games = kept;
-    Ok(any_finished)
-}
 
-fn clear_native_overlay_runtime_for_pid(
-    app: &tauri::AppHandle,
-    finished_pid: u32,
-) -> Result<(), String> {
-    let overlay_state = app.state::<GameOverlayState>();
-    if overlay_state.native_pid.load(Ordering::Relaxed) != finished_pid {
-        return Ok(());
-    }
-    let _transition_guard = overlay_state
-        .native_transition_lock
-        .lock()
-        .map_err(|_| "native overlay transition lock poisoned".to_string())?;
-    if overlay_state.native_pid.load(Ordering::Relaxed) != finished_pid {
-        return Ok(());
-    }
-    overlay_state
-        .native_transition_epoch
-        .fetch_add(1, Ordering::AcqRel);
-    overlay_state.native_pid.store(0, Ordering::Relaxed);
-    overlay_state.native_ready.store(false, Ordering::Relaxed);
-    if let Ok(cfg) = read_game_overlay_config(app) {
-        sync_game_overlay_runtime_state(app, &cfg);
+    for browser in [
+        "firefox",
+        "google-chrome-stable",
+        "google-chrome",
+        "chromium",
+        "chromium-browser",
+        "brave",
+        "brave-browser",
+        "vivaldi",
+        "vivaldi-stable",
+    ] {
+        if spawn_url_command(browser, url) {
+            log::info!("Opened browser URL with {browser}");
+            return Ok(());
+        }
     }
-    #[cfg(not(target_os = "windows"))]
-    register_game_overlay_shortcut(app);
-    Ok(())
-}
 
-fn active_game_dto(order: usize, active: &ActiveGame) -> RunningGameDto {
-    RunningGameDto {
-        id: active.id,
-        order,
-        pid: Some(active.child.id()),
-        version: active.version,
-        mode_label: active.mode_label.clone(),
-        launch_options: active.launch_options.clone(),
-        launch_command_template: active.launch_command_template.clone(),
-    }
+    Err("no working system browser opener was found".to_string())
 }
 
-#[cfg(target_os = "windows")]
-fn non_verbatim_windows_path(path: &std::path::Path) -> std::path::PathBuf {
-    let text = path.to_string_lossy();
-    if let Some(stripped) = text.strip_prefix(r"\\?\UNC\") {
-        return std::path::PathBuf::from(format!(r"\\{stripped}"));
-    }
-    if let Some(stripped) = text.strip_prefix(r"\\?\") {
-        return std::path::PathBuf::from(stripped);
-    }
-    path.to_path_buf()
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn open_url_with_fallbacks(url: &str) -> Result<(), String> {
+    opener::open(url).map_err(|e| e.to_string())
 }
 
-#[cfg(target_os = "windows")]
-fn inject_launch_dlls(
-    pid: u32,
-    version_dir: &std::path::Path,
-    steam_path_override: Option<&str>,
-    load_bepinex: bool,
-) -> Result<(), String> {
-    let Some(steam_path) = get_windows_steam_install_path(steam_path_override) else {
-        log::warn!("Steam install path not found; skipping Steam overlay DLL injection");
+#[cfg(target_os = "linux")]
+fn open_path_with_fallbacks(path: &Path, is_folder: bool) -> Result<(), String> {
+    if command_status_ok("xdg-open", &[path.as_os_str()])
+        || command_status_ok("gio", &[std::ffi::OsStr::new("open"), path.as_os_str()])
+        || (is_folder
+            && (command_status_ok(
+                "kioclient6",
+                &[std::ffi::OsStr::new("exec"), path.as_os_str()],
+            ) || command_status_ok(
+                "kioclient5",
+                &[std::ffi::OsStr::new("exec"), path.as_os_str()],
+            )))
+    {
         return Ok(());
-    };
-
-    for dll in steam_overlay_dlls(&steam_path) {
-        if !dll.exists() {
-            log::warn!(
-                "Steam overlay DLL not found, skipping injection for {}",
-                dll.to_string_lossy()
-            );
-            continue;
-        }
-        inject_dll_into_process(pid, &dll).map_err(|error| {
-            format!(
-                "failed to inject optional Steam overlay DLL {}: {error}",
-                dll.display()
-            )
-        })?;
     }
 
-    if !load_bepinex {
-        return Ok(());
-    }
+    if !is_folder {
+        if let Some(editor) = std::env::var_os("VISUAL")
+            .or_else(|| std::env::var_os("EDITOR"))
+            .and_then(|value| {
+                let value = value.to_string_lossy();
+                shlex::split(&value).and_then(|parts| parts.into_iter().next())
+            })
+        {
+            if host_open_command(&editor).arg(path).spawn().is_ok() {
+                return Ok(());
+            }
+        }
 
-    let dll_path = version_dir.join("winhttp.dll");
-    if !dll_path.exists() {
-        log::warn!("winhttp.dll not found: {}", dll_path.to_string_lossy());
-        return Ok(());
-    }
-    inject_dll_into_process(pid, &dll_path).map_err(|error| {
-        format!(
-            "failed to inject BepInEx bootstrap DLL {}: {error}",
-            dll_path.display()
-        )
-    })?;
-    Ok(())
-}
+        for program in [
+            "kate", "kwrite", "gedit", "mousepad", "xed", "code", "codium",
+        ] {
+            if spawn_open_command(program, path) {
+                return Ok(());
+            }
+        }
 
-fn parse_launch_env_assignment(entry: &str) -> Option<(&str, &str)> {
-    let trimmed = entry.trim();
-    let (name, value) = trimmed.split_once('=')?;
-    if name.is_empty() {
-        return None;
+        if let Some(parent) = path.parent() {
+            if open_folder_path(parent).is_ok() {
+                return Ok(());
+            }
+        }
     }
 
-    let mut chars = name.chars();
-    let first = chars.next()?;
-    if !(first == '_' || first.is_ascii_alphabetic()) {
-        return None;
-    }
-    if !chars.all(|ch| ch == '_' || ch.is_ascii_alphanumeric()) {
-        return None;
+    if is_folder {
+        for program in [
+            "dolphin",
+            "nautilus",
+            "thunar",
+            "nemo",
+            "pcmanfm",
+            "pcmanfm-qt",
+            "caja",
+        ] {
+            if spawn_open_command(program, path) {
+                return Ok(());
+            }
+        }
     }
 
-    Some((name, value))
+    opener::open(path).map_err(|e| e.to_string())
 }
 
-fn apply_custom_launch_options(command: &mut std::process::Command, launch_options: &[String]) {
-    for raw_entry in launch_options {
-        let entry = raw_entry.trim();
-        if entry.is_empty() {
-            continue;
-        }
+#[cfg(not(target_os = "linux"))]
+fn open_path_with_fallbacks(path: &Path, _is_folder: bool) -> Result<(), String> {
+    opener::open(path).map_err(|e| e.to_string())
+}
 
-        if let Some((name, value)) = parse_launch_env_assignment(entry) {
-            command.env(name, value);
+fn collect_delete_targets(
+    path: &std::path::Path,
+    out: &mut Vec<std::path::PathBuf>,
+) -> Result<(), String> {
+    let rd = std::fs::read_dir(path).map_err(|e| e.to_string())?;
+    for entry in rd {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let child = entry.path();
+        let ty = entry.file_type().map_err(|e| e.to_string())?;
+        if ty.is_dir() && !ty.is_symlink() {
+            collect_delete_targets(&child, out)?;
+            out.push(child);
         } else {
-            command.arg(entry);
+            out.push(child);
         }
     }
+    Ok(())
 }
 
-#[cfg(target_os = "linux")]
-fn put_command_in_new_process_group(command: &mut std::process::Command) {
-    use std::os::unix::process::CommandExt;
+fn remove_delete_target(path: &std::path::Path) -> Result<(), String> {
+    let ty = std::fs::symlink_metadata(path)
+        .map_err(|e| e.to_string())?
+        .file_type();
 
-    unsafe {
-        command.pre_exec(|| {
-            if libc::setsid() == -1 {
-                return Err(std::io::Error::last_os_error());
-            }
-            Ok(())
-        });
+    if ty.is_dir() && !ty.is_symlink() {
+        std::fs::remove_dir(path).map_err(|e| e.to_string())?;
+        return Ok(());
     }
-}
 
-#[cfg(not(target_os = "linux"))]
-fn put_command_in_new_process_group(_command: &mut std::process::Command) {}
-
-#[cfg(target_os = "linux")]
-fn terminate_child_process_tree(child: &mut std::process::Child) {
-    let pgid = child.id() as libc::pid_t;
-    unsafe {
-        let _ = libc::kill(-pgid, libc::SIGTERM);
+    if let Err(file_err) = std::fs::remove_file(path) {
+        std::fs::remove_dir(path).map_err(|dir_err| format!("{file_err}; {dir_err}"))?;
     }
+    Ok(())
+}
 
-    for _ in 0..15 {
-        if child.try_wait().ok().flatten().is_some() {
-            return;
+#[tauri::command]
+fn delete_installed_version(
+    app: tauri::AppHandle,
+    version: u32,
+    game_state: State<'_, GameState>,
+    download_state: State<'_, DownloadState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<bool, String> {
+    {
+        let mut guard = game_state
+            .active
+            .lock()
+            .map_err(|_| "game state lock poisoned".to_string())?;
+        let scopes = game_process_scopes(&guard);
+        for (index, active) in guard.iter_mut().enumerate() {
+            if active.version == version
+                && active
+                    .child
+                    .try_wait()
+                    .map_err(|e| e.to_string())?
+                    .is_none()
+            {
+                return Err("Cannot delete a version while the game is running.".to_string());
+            }
+            if active.version == version && linux_lingering_game_pid(&app, &scopes, index).is_some()
+            {
+                return Err("Cannot delete a version while the game is running.".to_string());
+            }
         }
-        std::thread::sleep(std::time::Duration::from_millis(100));
+        guard.retain(|active| active.version != version);
     }
 
-    unsafe {
-        let _ = libc::kill(-pgid, libc::SIGKILL);
+    {
+        let guard = download_state
+            .active
+            .lock()
+            .map_err(|_| "download state lock poisoned".to_string())?;
+        if guard
+            .iter()
+            .any(|active| active.version == version && !active.cancel.load(Ordering::Relaxed))
+        {
+            return Err("Cannot delete a version while it is downloading.".to_string());
+        }
     }
-    let _ = child.kill();
-}
-
-#[cfg(not(target_os = "linux"))]
-fn terminate_child_process_tree(child: &mut std::process::Child) {
-    let _ = child.kill();
-}
 
-#[cfg(target_os = "linux")]
-fn read_proc_bytes(pid: libc::pid_t, name: &str) -> Option<Vec<u8>> {
-    std::fs::read(format!("/proc/{pid}/{name}")).ok()
-}
+    {
+        let guard = prepare_state
+            .active
+            .lock()
+            .map_err(|_| "prepare state lock poisoned".to_string())?;
+        if let Some(active) = guard.as_ref() {
+            if active.version == version && !active.cancel.load(Ordering::Relaxed) {
+                return Err("Cannot delete a version while it is being prepared.".to_string());
+            }
+        }
+    }
 
-#[cfg(target_os = "linux")]
-fn bytes_contain(haystack: &[u8], needle: &std::path::Path) -> bool {
-    let needle = needle.to_string_lossy();
-    haystack
-        .windows(needle.len())
-        .any(|window| window == needle.as_bytes())
-}
+    let dir = version_dir(&app, version)?;
+    if !dir.exists() {
+        return Ok(false);
+    }
 
-#[cfg(target_os = "linux")]
-fn collect_linux_game_processes(app: &tauri::AppHandle, version: u32) -> Vec<libc::pid_t> {
-    let self_pid = std::process::id() as libc::pid_t;
-    let Ok(game_root) = version_dir(app, version) else {
-        return vec![];
-    };
-    let compat_prefix = installer::proton_env_dir(app)
-        .ok()
-        .map(|path| path.join("wine_prefix"));
-    let compat_env = compat_prefix
-        .as_ref()
-        .map(|path| format!("STEAM_COMPAT_DATA_PATH={}", path.to_string_lossy()));
+    let mut targets = Vec::new();
+    collect_delete_targets(&dir, &mut targets)?;
+    targets.push(dir.clone());
 
-    let Ok(entries) = std::fs::read_dir("/proc") else {
-        return vec![];
-    };
+    let total = targets.len() as u64;
+    progress::emit_progress(
+        &app,
+        TaskProgressPayload {
+            version,
+            steps_total: 1,
+            step: 1,
+            step_name: "Delete Version".to_string(),
+            step_progress: 0.0,
+            overall_percent: 0.0,
+            detail: Some(format!("Preparing to delete v{version}...")),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: Some(0),
+            total_files: Some(total),
+        },
+    );
 
-    let mut pids = Vec::new();
-    for entry in entries.flatten() {
-        let Some(pid) = entry
-            .file_name()
-            .to_str()
-            .and_then(|name| name.parse::<libc::pid_t>().ok())
-        else {
-            continue;
+    for (idx, target) in targets.iter().enumerate() {
+        remove_delete_target(target)?;
+        let done = idx as u64 + 1;
+        let step_progress = if total == 0 {
+            1.0
+        } else {
+            done as f64 / total as f64
         };
-        if pid <= 1 || pid == self_pid {
-            continue;
-        }
-
-        let cmdline = read_proc_bytes(pid, "cmdline").unwrap_or_default();
-        let environ = read_proc_bytes(pid, "environ").unwrap_or_default();
-
-        let matches_version =
-            bytes_contain(&cmdline, &game_root) || bytes_contain(&environ, &game_root);
-        let matches_prefix = compat_prefix.as_ref().is_some_and(|prefix| {
-            bytes_contain(&cmdline, prefix) || bytes_contain(&environ, prefix)
-        }) || compat_env.as_ref().is_some_and(|env| {
-            environ
-                .windows(env.len())
-                .any(|window| window == env.as_bytes())
-        });
+        let detail = target
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| format!("Deleting {name}"))
+            .unwrap_or_else(|| "Deleting files...".to_string());
+        progress::emit_progress(
+            &app,
+            TaskProgressPayload {
+                version,
+                steps_total: 1,
+                step: 1,
+                step_name: "Delete Version".to_string(),
+                step_progress,
+                overall_percent: step_progress * 100.0,
+                detail: Some(detail),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: Some(done),
+                total_files: Some(total),
+            },
+        );
+    }
 
-        if matches_version || matches_prefix {
-            pids.push(pid);
-        }
+    if let Err(e) = dedup_store::forget_version(&storage::versions_dir(&app)?, version) {
+        log::warn!("Failed to release shared files of v{version}: {e}");
     }
 
-    pids.sort_unstable();
-    pids.dedup();
-    pids
+    Ok(true)
 }
 
-#[cfg(target_os = "linux")]
-fn pid_is_alive(pid: libc::pid_t) -> bool {
-    unsafe { libc::kill(pid, 0) == 0 }
+#[tauri::command]
+fn get_storage_dedup_report(app: tauri::AppHandle) -> Result<dedup_store::DedupReport, String> {
+    Ok(dedup_store::report(&storage::versions_dir(&app)?))
 }
 
-#[cfg(target_os = "linux")]
-fn terminate_linux_game_processes_for_version(app: &tauri::AppHandle, version: u32) {
-    let pids = collect_linux_game_processes(app, version);
-    if pids.is_empty() {
-        return;
-    }
-
-    log::info!(
-        "Stopping {} lingering Linux game processes for v{}: {:?}",
-        pids.len(),
-        version,
-        pids
-    );
-
-    for pid in &pids {
-        unsafe {
-            let _ = libc::kill(*pid, libc::SIGTERM);
-        }
-    }
-
-    for _ in 0..15 {
-        if pids.iter().all(|pid| !pid_is_alive(*pid)) {
-            return;
-        }
-        std::thread::sleep(std::time::Duration::from_millis(100));
-    }
+/// Link identical game files of every installed version into the content store.
+#[tauri::command]
+async fn dedup_installed_versions(
+    app: tauri::AppHandle,
+    game_state: State<'_, GameState>,
+    download_state: State<'_, DownloadState>,
+) -> Result<dedup_store::DedupReport, String> {
+    ensure_game_not_running(&app, &game_state)?;
+    let busy: Vec<u32> = download_state
+        .active
+        .lock()
+        .map_err(|_| "download state lock poisoned".to_string())?
+        .iter()
+        .map(|active| active.version)
+        .collect();
 
-    for pid in pids {
-        if pid_is_alive(pid) {
-            unsafe {
-                let _ = libc::kill(pid, libc::SIGKILL);
+    let versions_dir = storage::versions_dir(&app)?;
+    let versions = list_installed_versions(app.clone())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        for version in versions.into_iter().filter(|v| !busy.contains(v)) {
+            if let Err(e) = dedup_store::dedup_version(&versions_dir, version) {
+                log::warn!("Failed to dedup v{version}: {e}");
             }
         }
-    }
+        Ok(dedup_store::report(&versions_dir))
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
-#[cfg(target_os = "linux")]
-fn linux_lingering_game_pid(app: &tauri::AppHandle, version: u32) -> Option<u32> {
-    collect_linux_game_processes(app, version)
-        .into_iter()
-        .next()
-        .map(|pid| pid as u32)
+fn disk_layout(app: &tauri::AppHandle) -> Result<disk_usage::DiskLayout, String> {
+    Ok(disk_usage::DiskLayout {
+        versions_dir: storage::versions_dir(app)?,
+        app_data: app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("failed to resolve app data dir: {e}"))?,
+        selected_runtimes: runtimes::load_settings(app)?
+            .selections()
+            .cloned()
+            .collect(),
+    })
 }
 
-#[cfg(not(target_os = "linux"))]
-fn linux_lingering_game_pid(_app: &tauri::AppHandle, _version: u32) -> Option<u32> {
-    None
+#[tauri::command]
+async fn get_disk_usage(app: tauri::AppHandle) -> Result<disk_usage::DiskUsageNode, String> {
+    let layout = disk_layout(&app)?;
+    tauri::async_runtime::spawn_blocking(move || disk_usage::scan(&layout))
+        .await
+        .map_err(|e| e.to_string())
 }
 
-#[cfg(not(target_os = "linux"))]
-fn terminate_linux_game_processes_for_version(_app: &tauri::AppHandle, _version: u32) {}
-
-fn build_wrapped_launch_command(
-    template: Option<&str>,
-    default_program: &std::ffi::OsStr,
+/// Remove re-creatable launcher data. Refuses while anything could be using it.
+#[tauri::command]
+async fn cleanup_disk_usage(
+    app: tauri::AppHandle,
+    categories: Vec<disk_usage::DiskCategory>,
+    game_state: State<'_, GameState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<disk_usage::CleanupReport, String> {
+    ensure_game_not_running(&app, &game_state)?;
+    ensure_no_download_running(&app)?;
+    let preparing = prepare_state
+        .active
+        .lock()
+        .map_err(|_| "prepare state lock poisoned".to_string())?
+        .is_some();
+    if preparing {
+        return Err(
+            "mod file changes are still in progress; try again once they finish".to_string(),
+        );
+    }
+
+    let layout = disk_layout(&app)?;
+    tauri::async_runtime::spawn_blocking(move || disk_usage::clean(&layout, &categories))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+fn list_runtimes(app: tauri::AppHandle) -> Result<Vec<runtimes::Runtime>, String> {
+    detect_runtimes(&app)
+}
+
+#[tauri::command]
+fn get_runtime_settings(app: tauri::AppHandle) -> Result<runtimes::RuntimeSettings, String> {
+    runtimes::load_settings(&app)
+}
+
+#[tauri::command]
+fn set_runtime_settings(
+    app: tauri::AppHandle,
+    settings: runtimes::RuntimeSettings,
+) -> Result<runtimes::RuntimeSettings, String> {
+    settings.validate(&detect_runtimes(&app)?)?;
+    runtimes::save_settings(&app, &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn list_proton_ge_releases(
+    app: tauri::AppHandle,
+) -> Result<Vec<runtimes::ProtonRelease>, String> {
+    let settings = runtimes::load_settings(&app)?;
+    let client = reqwest::Client::new();
+    runtimes::fetch_release_index(&client, settings.release_index_url()).await
+}
+
+/// Install a GE-Proton release from the configured index next to the existing ones.
+#[tauri::command]
+async fn install_proton_ge_release(app: tauri::AppHandle, tag: String) -> Result<bool, String> {
+    let settings = runtimes::load_settings(&app)?;
+    let client = reqwest::Client::new();
+    let release = runtimes::fetch_release_index(&client, settings.release_index_url())
+        .await?
+        .into_iter()
+        .find(|release| release.tag == tag)
+        .ok_or_else(|| format!("{tag} is not in the release index"))?;
+    installer::install_proton_ge_release(&app, &release.tag, &release.download_url).await
+}
+
+/// Remove an installed GE-Proton release. Its prefix (and the saves in it) is kept.
+#[tauri::command]
+fn remove_proton_ge_release(
+    app: tauri::AppHandle,
+    tag: String,
+    game_state: State<'_, GameState>,
+) -> Result<bool, String> {
+    ensure_game_not_running(&app, &game_state)?;
+    if !runtimes::is_valid_tag(&tag) {
+        return Err(format!("invalid Proton-GE release tag: {tag}"));
+    }
+    let dir = installer::proton_root_dir(&app)?.join(&tag);
+    if !dir.exists() {
+        return Ok(false);
+    }
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[cfg(target_os = "linux")]
+fn wine_prefix_root(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    installer::proton_env_dir(app)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn wine_prefix_root(_app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    Err("Wine prefixes are only used on Linux".to_string())
+}
+
+fn ensure_wine_prefix_not_in_use(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+    prefix: &std::path::Path,
+) -> Result<(), String> {
+    let mut guard = state
+        .active
+        .lock()
+        .map_err(|_| "game state lock poisoned".to_string())?;
+    cleanup_active_games(app, &mut guard)?;
+    match guard
+        .iter()
+        .find(|active| active.wine_prefix.as_deref() == Some(prefix))
+    {
+        Some(active) => Err(format!(
+            "v{} is running in this prefix; stop it first",
+            active.version
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Runtime id of every installed version, which picks its prefix by default.
+#[cfg(target_os = "linux")]
+fn installed_version_runtimes(app: &tauri::AppHandle) -> BTreeMap<u32, String> {
+    let Ok(settings) = runtimes::load_settings(app) else {
+        return BTreeMap::new();
+    };
+    let detected = detect_runtimes(app).unwrap_or_default();
+    list_installed_versions(app.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|version| {
+            let runtime_id = match settings.selected(version) {
+                Some(id) => id.to_string(),
+                None => runtimes::resolve(&detected, &settings, version)
+                    .ok()?
+                    .id
+                    .clone(),
+            };
+            Some((version, runtime_id))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn installed_version_runtimes(_app: &tauri::AppHandle) -> BTreeMap<u32, String> {
+    BTreeMap::new()
+}
+
+fn list_wine_prefixes_impl(
+    app: &tauri::AppHandle,
+) -> Result<Vec<wine_prefixes::PrefixInfo>, String> {
+    Ok(wine_prefixes::list(
+        &wine_prefix_root(app)?,
+        &wine_prefixes::load_settings(app)?,
+        &launch_profiles::load(app)?,
+        &installed_version_runtimes(app),
+    ))
+}
+
+/// Refuse to wipe a prefix a version or profile launches in, including the
+/// runtime prefix versions without a selection use.
+fn ensure_wine_prefix_unused(app: &tauri::AppHandle, id: &str) -> Result<(), String> {
+    let Some(info) = list_wine_prefixes_impl(app)?
+        .into_iter()
+        .find(|info| info.id == id)
+    else {
+        return Ok(());
+    };
+    let users: Vec<String> = info
+        .versions
+        .iter()
+        .map(|version| format!("v{version}"))
+        .chain(
+            info.profiles
+                .iter()
+                .map(|profile| format!("profile `{profile}`")),
+        )
+        .collect();
+    if users.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "prefix `{id}` is still used by {}",
+            users.join(", ")
+        ))
+    }
+}
+
+#[tauri::command]
+fn get_wine_prefix_settings(
+    app: tauri::AppHandle,
+) -> Result<wine_prefixes::PrefixSettings, String> {
+    wine_prefixes::load_settings(&app)
+}
+
+#[tauri::command]
+fn set_wine_prefix_settings(
+    app: tauri::AppHandle,
+    settings: wine_prefixes::PrefixSettings,
+) -> Result<wine_prefixes::PrefixSettings, String> {
+    wine_prefixes::save_settings(&app, &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+fn list_wine_prefixes(app: tauri::AppHandle) -> Result<Vec<wine_prefixes::PrefixInfo>, String> {
+    list_wine_prefixes_impl(&app)
+}
+
+#[tauri::command]
+fn create_wine_prefix(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<Vec<wine_prefixes::PrefixInfo>, String> {
+    wine_prefixes::create(&wine_prefix_root(&app)?, id.trim())?;
+    list_wine_prefixes_impl(&app)
+}
+
+/// Empty a prefix, saves included; the next launch in it sets it up again.
+/// A prefix versions or profiles still use is only reset with `force`, once the
+/// user confirmed it.
+#[tauri::command]
+async fn reset_wine_prefix(
+    app: tauri::AppHandle,
+    id: String,
+    force: Option<bool>,
+    game_state: State<'_, GameState>,
+) -> Result<Vec<wine_prefixes::PrefixInfo>, String> {
+    let path = wine_prefixes::existing(&wine_prefix_root(&app)?, &id)?;
+    ensure_wine_prefix_not_in_use(&app, &game_state, &path)?;
+    if !force.unwrap_or(false) {
+        ensure_wine_prefix_unused(&app, &id)?;
+    }
+    tauri::async_runtime::spawn_blocking(move || wine_prefixes::reset(&path))
+        .await
+        .map_err(|e| e.to_string())??;
+    list_wine_prefixes_impl(&app)
+}
+
+#[tauri::command]
+async fn clone_wine_prefix(
+    app: tauri::AppHandle,
+    from: String,
+    to: String,
+    game_state: State<'_, GameState>,
+) -> Result<Vec<wine_prefixes::PrefixInfo>, String> {
+    let root = wine_prefix_root(&app)?;
+    let source = wine_prefixes::existing(&root, &from)?;
+    // A running game keeps writing to its prefix, so a copy could be torn.
+    ensure_wine_prefix_not_in_use(&app, &game_state, &source)?;
+    let to = to.trim().to_string();
+    tauri::async_runtime::spawn_blocking(move || wine_prefixes::clone(&root, &source, &to))
+        .await
+        .map_err(|e| e.to_string())??;
+    list_wine_prefixes_impl(&app)
+}
+
+#[tauri::command]
+async fn delete_wine_prefix(
+    app: tauri::AppHandle,
+    id: String,
+    game_state: State<'_, GameState>,
+) -> Result<Vec<wine_prefixes::PrefixInfo>, String> {
+    let path = wine_prefixes::existing(&wine_prefix_root(&app)?, &id)?;
+    ensure_wine_prefix_not_in_use(&app, &game_state, &path)?;
+    ensure_wine_prefix_unused(&app, &id)?;
+    tauri::async_runtime::spawn_blocking(move || wine_prefixes::delete(&path))
+        .await
+        .map_err(|e| e.to_string())??;
+    list_wine_prefixes_impl(&app)
+}
+
+#[tauri::command]
+async fn open_downloader_folder(app: tauri::AppHandle) -> Result<bool, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("downloader");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create downloader dir: {e}"))?;
+    open_folder_path(&dir)?;
+    Ok(true)
+}
+
+#[tauri::command]
+async fn open_mod_folder(
+    app: tauri::AppHandle,
+    version: u32,
+    dev: String,
+    name: String,
+) -> Result<bool, String> {
+    let plugins = plugins_dir(&app, version)?;
+    let patchers = patchers_dir(&app, version)?;
+
+    let Some(dir) =
+        mod_dir_for(&plugins, &dev, &name).or_else(|| mod_dir_for(&patchers, &dev, &name))
+    else {
+        return Err(format!(
+            "mod folder not found for {dev}-{name} on v{version}"
+        ));
+    };
+
+    open_folder_path(&dir)?;
+    Ok(true)
+}
+
+#[tauri::command]
+async fn check_mod_updates(
+    app: tauri::AppHandle,
+    version: u32,
+    run_mode: Option<String>,
+) -> Result<bool, String> {
+    let client = reqwest::Client::new();
+    let run_mode_name = run_mode.as_deref().unwrap_or("hq");
+
+    let extract_dir = version_dir(&app, version)?;
+    let mods_cfg = exclude_disabled_mod_switches_from_updates(
+        &app,
+        effective_mods_config_for_run_mode(&client, version, run_mode_name, false, true).await?,
+    )?;
+    let (preset, _practice) = preset_and_practice_for_run_mode(run_mode_name);
+    let active_tags = preset_tags_for_name(&preset);
+
+    let mut updatable_mods: Vec<String> = vec![];
+
+    let res = mods::updatable_mods_with_progress(
+        &app,
+        &extract_dir,
+        version,
+        &mods_cfg,
+        &active_tags,
+        |checked, total, detail, mod_name| {
+            if let Some(mod_name) = mod_name {
+                if !updatable_mods.contains(&mod_name) {
+                    updatable_mods.push(mod_name.clone());
+                }
+            }
+
+            progress::emit_updatable_progress(
+                &app,
+                TaskUpdatableProgressPayload {
+                    version,
+                    run_mode: Some(run_mode_name.to_string()),
+                    total,
+                    checked,
+                    updatable_mods: updatable_mods.clone(),
+                    detail,
+                },
+            );
+        },
+    )
+    .await;
+
+    if let Err(e) = res {
+        progress::emit_updatable_error(
+            &app,
+            TaskErrorPayload {
+                version,
+                run_mode: Some(run_mode_name.to_string()),
+                message: e.clone(),
+            },
+        );
+        return Err(e);
+    }
+
+    match purge_capped_incompatible_installed_mods(&app, version, Some(run_mode_name)).await {
+        Ok(purged) => {
+            if purged > 0 {
+                log::info!(
+                    "Purged {purged} incompatible capped mods after checking updates for v{version}"
+                );
+            }
+        }
+        Err(e) => {
+            log::warn!(
+                "Failed to purge incompatible capped mods after checking updates for v{version}: {e}"
+            );
+        }
+    }
+
+    progress::emit_updatable_finished(
+        &app,
+        TaskFinishedPayload {
+            version,
+            run_mode: Some(run_mode_name.to_string()),
+            path: extract_dir.to_string_lossy().to_string(),
+        },
+    );
+    Ok(true)
+}
+
+/// Check for and apply a pending native overlay update as the final step of the
+/// unified update flow, then propagate the refreshed `version.dll` into every
+/// installed version directory.
+///
+/// On non-Windows hosts (or when no update is available) this is a fast no-op
+/// that emits the completion progress for the overlay step and returns. Errors
+/// are logged and never propagated: an overlay update failure must not turn a
+/// successful mod update into a failure.
+///
+/// `version_dir` scopes the post-install proxy staging to the version being
+/// updated: mod updates are per-version, so the refreshed overlay is only
+/// re-staged into that version's directory rather than propagated everywhere.
+async fn apply_native_overlay_update_step(
+    app: &tauri::AppHandle,
+    steps_total: u32,
+    version_dir: &std::path::Path,
+) {
+    const STEP: u32 = 3;
+    const STEP_NAME: &str = "HQ Overlay";
+
+    // Emit an initial progress tick so the UI shows the overlay step started.
+    progress::emit_progress(
+        app,
+        TaskProgressPayload {
+            version: 0,
+            steps_total,
+            step: STEP,
+            step_name: STEP_NAME.to_string(),
+            step_progress: 0.0,
+            overall_percent: overall_from_step(STEP, 0.0, steps_total),
+            detail: Some("Checking HQ Overlay...".to_string()),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: None,
+            total_files: None,
+        },
+    );
+
+    #[cfg(target_os = "windows")]
+    {
+        let app_handle = app.clone();
+        let installed = tauri::async_runtime::spawn_blocking(move || {
+            let client = native_overlay_http_client()?;
+            let release = fetch_native_overlay_release(&client)?;
+            let info = native_overlay_update_info_for_release(&app_handle, &release)?;
+            if info.available {
+                install_native_overlay_update_blocking(&app_handle).map(|_| true)
+            } else {
+                // The app-data DLL may already be current while this game
+                // version's `version.dll` proxy has a stale or missing
+                // `.hq-overlay-version` stamp. The proxy still needs to be
+                // staged in that case, just like a mod update is applied to
+                // the selected version directory.
+                Ok(false)
+            }
+        })
+        .await;
+
+        match installed {
+            Ok(Ok(_)) => {
+                // Always run the stamp/size check for the version being
+                // updated. This also repairs a stale proxy when the central
+                // overlay DLL itself is already up to date.
+                if let Err(error) = stage_native_overlay_as_proxy(app, version_dir) {
+                    log::warn!("failed to stage overlay proxy during update: {error}");
+                }
+            }
+            Ok(Err(error)) => {
+                log::warn!("HQ Overlay update failed during unified update step: {error}");
+            }
+            Err(error) => {
+                log::warn!("HQ Overlay update worker failed: {error}");
+            }
+        }
+    }
+
+    // Emit the completion tick regardless of outcome.
+    progress::emit_progress(
+        app,
+        TaskProgressPayload {
+            version: 0,
+            steps_total,
+            step: STEP,
+            step_name: STEP_NAME.to_string(),
+            step_progress: 1.0,
+            overall_percent: overall_from_step(STEP, 1.0, steps_total),
+            detail: Some("HQ Overlay up to date".to_string()),
+            downloaded_bytes: None,
+            total_bytes: None,
+            extracted_files: None,
+            total_files: None,
+        },
+    );
+}
+
+#[tauri::command]
+async fn apply_mod_updates(
+    app: tauri::AppHandle,
+    version: u32,
+    run_mode: Option<String>,
+) -> Result<bool, String> {
+    let run_mode_name = run_mode.as_deref().unwrap_or("hq").to_string();
+    let res: Result<(), String> = async {
+        let client = reqwest::Client::new();
+
+        let game_root = version_dir(&app, version)?;
+        if !game_root.exists() {
+            return Err(format!(
+                "version folder not found: {}",
+                game_root.to_string_lossy()
+            ));
+        }
+
+        let mods_cfg = exclude_disabled_mod_switches_from_updates(
+            &app,
+            effective_mods_config_for_run_mode(
+                &client,
+                version,
+                &run_mode_name,
+                false,
+                true,
+            )
+            .await?,
+        )?;
+        let (preset, _practice) = preset_and_practice_for_run_mode(&run_mode_name);
+        let active_tags = preset_tags_for_name(&preset);
+
+        const STEPS_TOTAL: u32 = 3;
+        progress::emit_progress(
+            &app,
+            TaskProgressPayload {
+                version,
+                steps_total: STEPS_TOTAL,
+                step: 1,
+                step_name: "Check Updates".to_string(),
+                step_progress: 0.0,
+                overall_percent: overall_from_step(1, 0.0, STEPS_TOTAL),
+                detail: Some("Checking updatable mods...".to_string()),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: None,
+                total_files: None,
+            },
+        );
+
+        let mut updatable: Vec<String> = vec![];
+        mods::updatable_mods_with_progress(
+            &app,
+            &game_root,
+            version,
+            &mods_cfg,
+            &active_tags,
+            |checked, total, detail, mod_name| {
+                if let Some(m) = mod_name {
+                    if !updatable.contains(&m) {
+                        updatable.push(m);
+                    }
+                }
+                let step_progress = if total == 0 {
+                    1.0
+                } else {
+                    (checked as f64 / total as f64).clamp(0.0, 1.0)
+                };
+                progress::emit_progress(
+                    &app,
+                    TaskProgressPayload {
+                        version,
+                        steps_total: STEPS_TOTAL,
+                        step: 1,
+                        step_name: "Check Updates".to_string(),
+                        step_progress,
+                        overall_percent: overall_from_step(1, step_progress, STEPS_TOTAL),
+                        detail,
+                        downloaded_bytes: None,
+                        total_bytes: None,
+                        extracted_files: Some(checked),
+                        total_files: Some(total),
+                    },
+                );
+            },
+        )
+        .await?;
+
+        match purge_capped_incompatible_installed_mods(&app, version, Some(&run_mode_name)).await {
+            Ok(purged) => {
+                if purged > 0 {
+                    log::info!(
+                        "Purged {purged} incompatible capped mods during update check for v{version}"
+                    );
+                }
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to purge incompatible capped mods during update check for v{version}: {e}"
+                );
+            }
+        }
+
+        if updatable.is_empty() {
+            progress::emit_progress(
+                &app,
+                TaskProgressPayload {
+                    version,
+                    steps_total: STEPS_TOTAL,
+                    step: 2,
+                    step_name: "Update Mods".to_string(),
+                    step_progress: 1.0,
+                    overall_percent: overall_from_step(2, 1.0, STEPS_TOTAL),
+                    detail: Some("No updates available".to_string()),
+                    downloaded_bytes: None,
+                    total_bytes: None,
+                    extracted_files: None,
+                    total_files: None,
+                },
+            );
+            // Even with no mod updates, a pending overlay update is still applied.
+            apply_native_overlay_update_step(&app, STEPS_TOTAL, &game_root).await;
+            return Ok(());
+        }
+
+        progress::emit_progress(
+            &app,
+            TaskProgressPayload {
+                version,
+                steps_total: STEPS_TOTAL,
+                step: 2,
+                step_name: "Update Mods".to_string(),
+                step_progress: 0.0,
+                overall_percent: overall_from_step(2, 0.0, STEPS_TOTAL),
+                detail: Some(format!("Updating {} mods...", updatable.len())),
+                downloaded_bytes: None,
+                total_bytes: None,
+                extracted_files: Some(0),
+                total_files: Some(updatable.len() as u64),
+            },
+        );
+
+        mods::update_mods_with_progress(
+            &app,
+            &game_root,
+            version,
+            &mods_cfg,
+            updatable.clone(),
+            |done, total, progress_info| {
+                let step_progress = if total == 0 {
+                    1.0
+                } else {
+                    (done as f64 / total as f64).clamp(0.0, 1.0)
+                };
+                progress::emit_progress(
+                    &app,
+                    TaskProgressPayload {
+                        version,
+                        steps_total: STEPS_TOTAL,
+                        step: 2,
+                        step_name: "Update Mods".to_string(),
+                        step_progress,
+                        overall_percent: overall_from_step(2, step_progress, STEPS_TOTAL),
+                        detail: progress_info.detail,
+                        downloaded_bytes: progress_info.downloaded_bytes,
+                        total_bytes: progress_info.total_bytes,
+                        extracted_files: progress_info.extracted_files.or(Some(done)),
+                        total_files: progress_info.total_files.or(Some(total)),
+                    },
+                );
+            },
+        )
+        .await?;
+
+        let _ = ensure_reverb_trigger_fix_cfg(&app, version);
+
+        // Step 3: apply a pending native overlay update (if any) so the single
+        // "Update" action refreshes mods and the overlay together, staging the
+        // fresh DLL into this version's directory only.
+        apply_native_overlay_update_step(&app, STEPS_TOTAL, &game_root).await;
+
+        Ok(())
+    }
+    .await;
+
+    match res {
+        Ok(()) => {
+            progress::emit_finished(
+                &app,
+                TaskFinishedPayload {
+                    version,
+                    run_mode: Some(run_mode_name.clone()),
+                    path: version_dir(&app, version)?.to_string_lossy().to_string(),
+                },
+            );
+            Ok(true)
+        }
+        Err(e) => {
+            progress::emit_error(
+                &app,
+                TaskErrorPayload {
+                    version,
+                    run_mode: Some(run_mode_name.clone()),
+                    message: e.clone(),
+                },
+            );
+            Err(e)
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_steam_client_path(
+    launcher_root: &std::path::Path,
+    configured_path: Option<&str>,
+) -> std::path::PathBuf {
+    if let Some(configured) = configured_path
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        let candidate = std::path::PathBuf::from(configured);
+        if candidate.exists() {
+            return candidate;
+        }
+    }
+
+    if let Some(home_dir) = dirs::home_dir() {
+        let steam_paths = [
+            home_dir.join(".steam/steam"),
+            home_dir.join(".local/share/Steam"),
+            home_dir.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+        ];
+        for path in steam_paths.iter() {
+            if path.exists() && path.join("steamapps").exists() {
+                println!("Found real Steam installation at: {:?}", path);
+                return path.clone();
+            }
+        }
+    }
+
+    println!("Steam not found. Mocking client path.");
+    launcher_root.to_path_buf()
+}
+
+#[cfg(target_os = "linux")]
+fn linux_overlay_preload_value(steam_path: &std::path::Path) -> Option<String> {
+    let mut libs: Vec<String> = vec![];
+    for rel in [
+        std::path::Path::new("ubuntu12_32").join("gameoverlayrenderer.so"),
+        std::path::Path::new("ubuntu12_64").join("gameoverlayrenderer.so"),
+    ] {
+        let candidate = steam_path.join(rel);
+        if candidate.exists() {
+            libs.push(candidate.to_string_lossy().to_string());
+        }
+    }
+
+    if libs.is_empty() {
+        None
+    } else {
+        Some(libs.join(":"))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sanitize_linux_host_process_env(command: &mut launch_plan::GameCommand) {
+    for key in [
+        "APPDIR",
+        "APPIMAGE",
+        "ARGV0",
+        "GIO_MODULE_DIR",
+        "GTK_PATH",
+        "PYTHONHOME",
+        "PYTHONPATH",
+        "PYTHONPLATLIBDIR",
+        "PYTHONSAFEPATH",
+        "QT_PLUGIN_PATH",
+        "QT_QPA_PLATFORM_PLUGIN_PATH",
+    ] {
+        command.remove_env(key);
+    }
+    if let Some(original_ld_library_path) = std::env::var_os("APPIMAGE_ORIGINAL_LD_LIBRARY_PATH") {
+        command.set_env(
+            "LD_LIBRARY_PATH",
+            original_ld_library_path.to_string_lossy(),
+        );
+    } else {
+        command.remove_env("LD_LIBRARY_PATH");
+    }
+}
+
+fn resolve_game_launch_paths(
+    app: &tauri::AppHandle,
+    version: u32,
+) -> Result<(std::path::PathBuf, std::path::PathBuf, std::path::PathBuf), String> {
+    let dir = version_dir(app, version)?;
+    if !dir.exists() {
+        return Err(format!(
+            "version folder not found: {}",
+            dir.to_string_lossy()
+        ));
+    }
+
+    let exe_name = "Lethal Company.exe";
+    let exe_path = dir.join(exe_name);
+    let exe_path = if exe_path.exists() {
+        exe_path
+    } else {
+        find_file_named(&dir, exe_name, 3)
+            .ok_or_else(|| format!("{exe_name} not found under {}", dir.to_string_lossy()))?
+    };
+
+    let exe_dir = exe_path
+        .parent()
+        .ok_or_else(|| "invalid exe path".to_string())?
+        .to_path_buf();
+
+    Ok((dir, exe_path, exe_dir))
+}
+
+fn ensure_game_not_running(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+) -> Result<(), String> {
+    let mut guard = state
+        .active
+        .lock()
+        .map_err(|_| "game state lock poisoned".to_string())?;
+    let mut has_running = false;
+    let scopes = game_process_scopes(&guard);
+    for (index, active) in guard.iter_mut().enumerate() {
+        if active
+            .child
+            .try_wait()
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            has_running = true;
+        } else if linux_lingering_game_pid(app, &scopes, index).is_some() {
+            has_running = true;
+        }
+    }
+    if has_running {
+        return Err("game is already running".to_string());
+    }
+    guard.clear();
+    Ok(())
+}
+
+fn ensure_launch_compatible_with_active_games(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+    version: u32,
+    mutates_proxy_dlls: bool,
+) -> Result<(), String> {
+    let mut guard = state
+        .active
+        .lock()
+        .map_err(|_| "game state lock poisoned".to_string())?;
+    cleanup_active_games(app, &mut guard)?;
+    let conflict = guard.iter().any(|active| {
+        active.version == version && (mutates_proxy_dlls || !active._proxy_dll_guards.is_empty())
+    });
+    if conflict {
+        return Err(
+            "Runs that change proxy DLL availability cannot use the same game version at the same time"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn cleanup_active_games(
+    app: &tauri::AppHandle,
+    active_games: &mut Vec<ActiveGame>,
+) -> Result<bool, String> {
+    let mut any_finished = false;
+    let mut kept = Vec::with_capacity(active_games.len());
+    let scopes = game_process_scopes(active_games);
+    for (index, mut active) in active_games.drain(..).enumerate() {
+        match active.child.try_wait() {
+            // Dropping the game would run its exit handling while it may still run.
+            Err(e) => {
+                log::warn!(
+                    "Failed to poll v{} (pid {}): {e}",
+                    active.version,
+                    active.child.id()
+                );
+                kept.push(active);
+            }
+            Ok(None) => kept.push(active),
+            Ok(Some(_)) => {
+                if linux_lingering_game_pid(app, &scopes, index).is_some() {
+                    kept.push(active);
+                } else {
+                    let finished_pid = active.child.id();
+                    if let Err(e) = clear_native_overlay_runtime_for_pid(app, finished_pid) {
+                        log::warn!(
+                            "Failed to clear native overlay state of pid {finished_pid}: {e}"
+                        );
+                    }
+                    any_finished = true;
+                }
+            }
+        }
+    }
+    *active_games = kept;
+    Ok(any_finished)
+}
+
+fn clear_native_overlay_runtime_for_pid(
+    app: &tauri::AppHandle,
+    finished_pid: u32,
+) -> Result<(), String> {
+    let overlay_state = app.state::<GameOverlayState>();
+    if overlay_state.native_pid.load(Ordering::Relaxed) != finished_pid {
+        return Ok(());
+    }
+    let _transition_guard = overlay_state
+        .native_transition_lock
+        .lock()
+        .map_err(|_| "native overlay transition lock poisoned".to_string())?;
+    if overlay_state.native_pid.load(Ordering::Relaxed) != finished_pid {
+        return Ok(());
+    }
+    overlay_state
+        .native_transition_epoch
+        .fetch_add(1, Ordering::AcqRel);
+    overlay_state.native_pid.store(0, Ordering::Relaxed);
+    overlay_state.native_ready.store(false, Ordering::Relaxed);
+    if let Ok(cfg) = read_game_overlay_config(app) {
+        sync_game_overlay_runtime_state(app, &cfg);
+    }
+    #[cfg(not(target_os = "windows"))]
+    register_game_overlay_shortcut(app);
+    Ok(())
+}
+
+fn active_game_dto(order: usize, active: &ActiveGame) -> RunningGameDto {
+    RunningGameDto {
+        id: active.id,
+        order,
+        pid: Some(active.child.id()),
+        version: active.version,
+        mode_label: active.mode_label.clone(),
+        launch_options: active.launch_options.clone(),
+        launch_command_template: active.launch_command_template.clone(),
+        bepinex_log_session: active
+            .bepinex_log_tail
+            .as_ref()
+            .map(|tail| tail.session_id.clone()),
+        wine_prefix: active
+            .wine_prefix
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn non_verbatim_windows_path(path: &std::path::Path) -> std::path::PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(stripped) = text.strip_prefix(r"\\?\UNC\") {
+        return std::path::PathBuf::from(format!(r"\\{stripped}"));
+    }
+    if let Some(stripped) = text.strip_prefix(r"\\?\") {
+        return std::path::PathBuf::from(stripped);
+    }
+    path.to_path_buf()
+}
+
+#[cfg(target_os = "windows")]
+fn inject_launch_dlls(
+    pid: u32,
+    version_dir: &std::path::Path,
+    steam_path_override: Option<&str>,
+    load_bepinex: bool,
+) -> Result<(), String> {
+    let Some(steam_path) = get_windows_steam_install_path(steam_path_override) else {
+        log::warn!("Steam install path not found; skipping Steam overlay DLL injection");
+        return Ok(());
+    };
+
+    for dll in steam_overlay_dlls(&steam_path) {
+        if !dll.exists() {
+            log::warn!(
+                "Steam overlay DLL not found, skipping injection for {}",
+                dll.to_string_lossy()
+            );
+            continue;
+        }
+        inject_dll_into_process(pid, &dll).map_err(|error| {
+            format!(
+                "failed to inject optional Steam overlay DLL {}: {error}",
+                dll.display()
+            )
+        })?;
+    }
+
+    if !load_bepinex {
+        return Ok(());
+    }
+
+    let dll_path = version_dir.join("winhttp.dll");
+    if !dll_path.exists() {
+        log::warn!("winhttp.dll not found: {}", dll_path.to_string_lossy());
+        return Ok(());
+    }
+    inject_dll_into_process(pid, &dll_path).map_err(|error| {
+        format!(
+            "failed to inject BepInEx bootstrap DLL {}: {error}",
+            dll_path.display()
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn put_command_in_new_process_group(command: &mut std::process::Command) {
+    use std::os::unix::process::CommandExt;
+
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn put_command_in_new_process_group(_command: &mut std::process::Command) {}
+
+#[cfg(target_os = "linux")]
+fn terminate_child_process_tree(child: &mut std::process::Child) {
+    let pgid = child.id() as libc::pid_t;
+    unsafe {
+        let _ = libc::kill(-pgid, libc::SIGTERM);
+    }
+
+    for _ in 0..15 {
+        if child.try_wait().ok().flatten().is_some() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    unsafe {
+        let _ = libc::kill(-pgid, libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+#[cfg(not(target_os = "linux"))]
+fn terminate_child_process_tree(child: &mut std::process::Child) {
+    let _ = child.kill();
+}
+
+#[cfg(target_os = "linux")]
+fn read_proc_bytes(pid: libc::pid_t, name: &str) -> Option<Vec<u8>> {
+    std::fs::read(format!("/proc/{pid}/{name}")).ok()
+}
+
+/// `needle` occurs as a whole path, not as the start of a longer name: `v7` does
+/// not match `v73`, nor `prefixes/brutal` match `prefixes/brutal-copy`.
+#[cfg(target_os = "linux")]
+fn bytes_contain(haystack: &[u8], needle: &std::path::Path) -> bool {
+    let needle = needle.to_string_lossy();
+    let needle = needle.as_bytes();
+    if needle.is_empty() {
+        return false;
+    }
+    haystack
+        .windows(needle.len())
+        .enumerate()
+        .any(|(start, window)| {
+            window == needle
+                && !matches!(
+                    haystack.get(start + needle.len()),
+                    Some(next) if next.is_ascii_alphanumeric() || matches!(next, b'_' | b'-' | b'.')
+                )
+        })
+}
+
+fn detect_runtimes(app: &tauri::AppHandle) -> Result<Vec<runtimes::Runtime>, String> {
+    let steam_path = read_steam_overlay_config(app)?.steam_path;
+    Ok(runtimes::detect(
+        &installer::proton_root_dir(app)?,
+        &steam_import::steam_roots(steam_path.as_deref()),
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn runtime_for_version(app: &tauri::AppHandle, version: u32) -> Result<runtimes::Runtime, String> {
+    let settings = runtimes::load_settings(app)?;
+    let installed = detect_runtimes(app)?;
+    runtimes::resolve(&installed, &settings, version).cloned()
+}
+
+/// Prefix the game of `version` runs in without a launch profile, without scanning
+/// for runtimes when one was chosen explicitly.
+#[cfg(target_os = "linux")]
+fn runtime_prefix_for_version(app: &tauri::AppHandle, version: u32) -> Option<std::path::PathBuf> {
+    let settings = runtimes::load_settings(app).ok()?;
+    let runtime_id = match settings.selected(version) {
+        Some(id) => id.to_string(),
+        None => runtime_for_version(app, version).ok()?.id,
+    };
+    let prefixes = wine_prefixes::load_settings(app).ok()?;
+    Some(wine_prefixes::dir(
+        &installer::proton_env_dir(app).ok()?,
+        &wine_prefixes::resolve(&prefixes, version, &runtime_id, None),
+    ))
+}
+
+/// What tells the processes of one running game apart from the others on Linux:
+/// the process tree under the launched child, and outside it (Proton reparents
+/// the game) the version folder and the game's own Wine prefix.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct GameProcessScope {
+    version: u32,
+    root_pid: u32,
+    wine_prefix: Option<std::path::PathBuf>,
+}
+
+impl GameProcessScope {
+    fn of(active: &ActiveGame) -> Self {
+        GameProcessScope {
+            version: active.version,
+            root_pid: active.child.id(),
+            wine_prefix: active.wine_prefix.clone(),
+        }
+    }
+}
+
+fn game_process_scopes(games: &[ActiveGame]) -> Vec<GameProcessScope> {
+    games.iter().map(GameProcessScope::of).collect()
+}
+
+#[cfg(target_os = "linux")]
+fn linux_parent_pid(pid: libc::pid_t) -> Option<libc::pid_t> {
+    let stat = read_proc_bytes(pid, "stat")?;
+    let stat = String::from_utf8_lossy(&stat);
+    // The command name before the state may itself contain spaces and parentheses.
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Processes of the game `scopes[index]`. A process in another game's tree, or one
+/// that matches another game's folder or prefix as well, is never included.
+#[cfg(target_os = "linux")]
+fn collect_linux_game_processes(
+    app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+    index: usize,
+) -> Vec<libc::pid_t> {
+    let mut all = collect_all_linux_game_processes(app, scopes);
+    if index < all.len() {
+        all.swap_remove(index)
+    } else {
+        vec![]
+    }
+}
+
+/// [`collect_linux_game_processes`] for every scope from one `/proc` scan.
+#[cfg(target_os = "linux")]
+fn collect_all_linux_game_processes(
+    app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+) -> Vec<Vec<libc::pid_t>> {
+    let self_pid = std::process::id() as libc::pid_t;
+    let game_roots: Vec<Option<std::path::PathBuf>> = scopes
+        .iter()
+        .map(|scope| version_dir(app, scope.version).ok())
+        .collect();
+    let mut all = vec![vec![]; scopes.len()];
+    if game_roots.iter().all(Option::is_none) {
+        return all;
+    }
+
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return all;
+    };
+
+    // pid -> (parent pid, which scopes it matches)
+    let mut processes = HashMap::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<libc::pid_t>().ok())
+        else {
+            continue;
+        };
+        if pid <= 1 || pid == self_pid {
+            continue;
+        }
+
+        let cmdline = read_proc_bytes(pid, "cmdline").unwrap_or_default();
+        let environ = read_proc_bytes(pid, "environ").unwrap_or_default();
+        let mentions =
+            |path: &std::path::Path| bytes_contain(&cmdline, path) || bytes_contain(&environ, path);
+        let matches: Vec<bool> = scopes
+            .iter()
+            .zip(&game_roots)
+            .map(|(scope, game_root)| {
+                game_root.as_deref().is_some_and(mentions)
+                    || scope.wine_prefix.as_deref().is_some_and(mentions)
+            })
+            .collect();
+        processes.insert(pid, (linux_parent_pid(pid).unwrap_or(0), matches));
+    }
+
+    let tree_of = |mut pid: libc::pid_t| -> Option<usize> {
+        // Bounded in case of a pid reused while walking.
+        for _ in 0..64 {
+            if let Some(owner) = scopes
+                .iter()
+                .position(|scope| scope.root_pid as libc::pid_t == pid)
+            {
+                return Some(owner);
+            }
+            pid = processes.get(&pid)?.0;
+            if pid <= 1 {
+                return None;
+            }
+        }
+        None
+    };
+
+    for (pid, (_, matches)) in &processes {
+        let owner = tree_of(*pid).or_else(|| {
+            let mut matched = matches.iter().enumerate().filter(|(_, matched)| **matched);
+            match (matched.next(), matched.next()) {
+                (Some((index, _)), None) => Some(index),
+                _ => None,
+            }
+        });
+        if let Some(owner) = owner.filter(|owner| game_roots[*owner].is_some()) {
+            all[owner].push(*pid);
+        }
+    }
+    for pids in &mut all {
+        pids.sort_unstable();
+    }
+    all
+}
+
+#[cfg(target_os = "linux")]
+fn pid_is_alive(pid: libc::pid_t) -> bool {
+    unsafe { libc::kill(pid, 0) == 0 }
+}
+
+#[cfg(target_os = "linux")]
+fn terminate_linux_game_processes(
+    app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+    index: usize,
+) {
+    let pids = collect_linux_game_processes(app, scopes, index);
+    if pids.is_empty() {
+        return;
+    }
+
+    log::info!(
+        "Stopping {} lingering Linux game processes for v{}: {:?}",
+        pids.len(),
+        scopes[index].version,
+        pids
+    );
+
+    for pid in &pids {
+        unsafe {
+            let _ = libc::kill(*pid, libc::SIGTERM);
+        }
+    }
+
+    for _ in 0..15 {
+        if pids.iter().all(|pid| !pid_is_alive(*pid)) {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    for pid in pids {
+        if pid_is_alive(pid) {
+            unsafe {
+                let _ = libc::kill(pid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_lingering_game_pid(
+    app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+    index: usize,
+) -> Option<u32> {
+    collect_linux_game_processes(app, scopes, index)
+        .into_iter()
+        .next()
+        .map(|pid| pid as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn linux_lingering_game_pid(
+    _app: &tauri::AppHandle,
+    _scopes: &[GameProcessScope],
+    _index: usize,
+) -> Option<u32> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn game_resource_usage(
+    app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+) -> Vec<resource_monitor::Usage> {
+    collect_all_linux_game_processes(app, scopes)
+        .iter()
+        .map(|pids| resource_monitor::linux_usage(pids))
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn game_resource_usage(
+    _app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+) -> Vec<resource_monitor::Usage> {
+    scopes
+        .iter()
+        .map(|scope| resource_monitor::windows_usage(scope.root_pid))
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn game_resource_usage(
+    _app: &tauri::AppHandle,
+    scopes: &[GameProcessScope],
+) -> Vec<resource_monitor::Usage> {
+    vec![resource_monitor::Usage::default(); scopes.len()]
+}
+
+/// One sample per running game, or `None` once no game is left. The monitor is
+/// marked stopped under the game lock, so a launch that adds its game afterwards
+/// starts a new one.
+fn sample_running_games(app: &tauri::AppHandle) -> Option<Vec<resource_monitor::GameSample>> {
+    let monitor = app.state::<resource_monitor::ResourceMonitorState>();
+    let state = app.state::<GameState>();
+    let (games, scopes) = {
+        let guard = match state.active.lock() {
+            Ok(guard) if !guard.is_empty() => guard,
+            _ => {
+                monitor.running.store(false, Ordering::Relaxed);
+                return None;
+            }
+        };
+        let games: Vec<resource_monitor::GameInfo> = guard
+            .iter()
+            .map(|active| resource_monitor::GameInfo {
+                game_id: active.id,
+                version: active.version,
+                mode_label: active.mode_label.clone(),
+                started_at: active.started_at,
+            })
+            .collect();
+        (games, game_process_scopes(&guard))
+    };
+    // The scan reads every process, so it runs without holding up game commands.
+    let usages = game_resource_usage(app, &scopes);
+    let at = std::time::Instant::now();
+    let at_unix = now_unix_secs();
+    Some(
+        games
+            .into_iter()
+            .zip(usages)
+            .map(|(game, usage)| monitor.record(game, usage, at, at_unix))
+            .collect(),
+    )
+}
+
+fn start_resource_monitor(app: &tauri::AppHandle) {
+    let monitor = app.state::<resource_monitor::ResourceMonitorState>();
+    if monitor.running.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let app_handle = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(resource_monitor::SAMPLE_INTERVAL);
+        let Some(samples) = sample_running_games(&app_handle) else {
+            break;
+        };
+        let _ = app_handle.emit(resource_monitor::SAMPLE_EVENT, &samples);
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+fn terminate_linux_game_processes(
+    _app: &tauri::AppHandle,
+    _scopes: &[GameProcessScope],
+    _index: usize,
+) {
+}
+
+fn build_wrapped_launch_command(
+    template: Option<&str>,
+    vars: &launch_profiles::TemplateVars,
+    default_program: &std::ffi::OsStr,
     default_args: &[OsString],
 ) -> Result<(OsString, Vec<OsString>), String> {
     let default_tokens: Vec<OsString> = std::iter::once(default_program.to_os_string())
@@ -10312,8 +10829,7 @@ fn build_wrapped_launch_command(
         return Ok((default_program.to_os_string(), default_args.to_vec()));
     };
 
-    let parsed = shlex::split(template)
-        .ok_or_else(|| "failed to parse launch command template".to_string())?;
+    let parsed = launch_profiles::expand_template(template, vars)?;
     if parsed.is_empty() {
         return Ok((default_program.to_os_string(), default_args.to_vec()));
     }
@@ -10321,7 +10837,7 @@ fn build_wrapped_launch_command(
     let mut wrapped_tokens = Vec::new();
     let mut inserted_command = false;
     for token in parsed {
-        if token == "%command%" {
+        if token == launch_profiles::COMMAND_PLACEHOLDER {
             wrapped_tokens.extend(default_tokens.iter().cloned());
             inserted_command = true;
         } else {
@@ -10340,13 +10856,126 @@ fn build_wrapped_launch_command(
     Ok((program, parts.collect()))
 }
 
-fn spawn_game_process(
-    _app: &tauri::AppHandle,
-    _version_dir: &std::path::Path,
+/// The command line and environment the game of `version` starts with, before
+/// launch profile and user options.
+fn game_command(
+    app: &tauri::AppHandle,
+    _version: u32,
     exe_path: &std::path::Path,
     exe_dir: &std::path::Path,
-    launch_options: &[String],
     launch_command_template: Option<&str>,
+    vars: &launch_profiles::TemplateVars,
+    _profile_prefix: Option<&str>,
+) -> Result<launch_plan::GameCommand, String> {
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    let overlay_config = read_steam_overlay_config(app)?;
+
+    #[cfg(target_os = "windows")]
+    let command = {
+        let launch_exe_path = non_verbatim_windows_path(exe_path);
+        let default_program = launch_exe_path.as_os_str().to_os_string();
+        let (program, args) =
+            build_wrapped_launch_command(launch_command_template, vars, &default_program, &[])?;
+        let mut cmd = launch_plan::GameCommand::new(program, args);
+        cmd.current_dir = non_verbatim_windows_path(exe_dir)
+            .to_string_lossy()
+            .to_string();
+        if overlay_config.enabled {
+            cmd.set_env("SteamGameId", LETHAL_COMPANY_STEAM_APP_ID);
+            cmd.set_env("SteamAppId", LETHAL_COMPANY_STEAM_APP_ID);
+        }
+        cmd
+    };
+
+    #[cfg(target_os = "macos")]
+    let command = {
+        let _ = app;
+        let default_program = OsString::from("open");
+        let default_args = vec![OsString::from("-a"), exe_path.as_os_str().to_os_string()];
+        let (program, args) = build_wrapped_launch_command(
+            launch_command_template,
+            vars,
+            &default_program,
+            &default_args,
+        )?;
+        let mut cmd = launch_plan::GameCommand::new(program, args);
+        cmd.current_dir = exe_dir.to_string_lossy().to_string();
+        cmd
+    };
+
+    #[cfg(target_os = "linux")]
+    let command = {
+        let app_path = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("app path not found: {e}"))?;
+        let proton_env_path = installer::proton_env_dir(app)
+            .map_err(|e| format!("proton_env path not found: {e}"))?;
+        let runtime = runtime_for_version(app, _version)?;
+        let prefix_id = wine_prefixes::resolve(
+            &wine_prefixes::load_settings(app)?,
+            _version,
+            &runtime.id,
+            _profile_prefix,
+        );
+        let compat_pre_path = wine_prefixes::dir(&proton_env_path, &prefix_id)
+            .to_string_lossy()
+            .to_string();
+        let steam_path = get_steam_client_path(&app_path, overlay_config.steam_path.as_deref());
+        let (default_program, default_args) = runtime.command(exe_path);
+        let (program, args) = build_wrapped_launch_command(
+            launch_command_template,
+            vars,
+            &default_program,
+            &default_args,
+        )?;
+        let mut cmd = launch_plan::GameCommand::new(program, args);
+        cmd.current_dir = exe_dir.to_string_lossy().to_string();
+        sanitize_linux_host_process_env(&mut cmd);
+        if runtime.kind == runtimes::RuntimeKind::Wine {
+            cmd.set_env("WINEPREFIX", compat_pre_path.clone());
+        } else {
+            cmd.set_env("STEAM_COMPAT_DATA_PATH", compat_pre_path.clone());
+            cmd.set_env(
+                "STEAM_COMPAT_CLIENT_INSTALL_PATH",
+                steam_path.to_string_lossy(),
+            );
+        }
+        // Let the Wine loader pick up the local proxy `winhttp.dll` (BepInEx)
+        // and `version.dll` (native HQ overlay) ahead of the built-in system
+        // copies, mirroring the native Windows DLL search order.
+        cmd.set_env("WINEDLLOVERRIDES", "winhttp=n,b;version=n,b");
+        if overlay_config.enabled {
+            cmd.set_env("SteamGameId", LETHAL_COMPANY_STEAM_APP_ID);
+            cmd.set_env("SteamAppId", LETHAL_COMPANY_STEAM_APP_ID);
+            if let Some(overlay_preload) = linux_overlay_preload_value(&steam_path) {
+                let preload = std::env::var("LD_PRELOAD")
+                    .ok()
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|existing| format!("{overlay_preload}:{existing}"))
+                    .unwrap_or(overlay_preload);
+                cmd.set_env("LD_PRELOAD", preload);
+            } else {
+                log::warn!(
+                    "Steam overlay enabled, but no Linux overlay renderer found under {}",
+                    steam_path.to_string_lossy()
+                );
+            }
+        }
+        cmd.wine_prefix = Some(compat_pre_path);
+        cmd
+    };
+
+    Ok(command)
+}
+
+fn spawn_game_process(
+    _app: &tauri::AppHandle,
+    _version: u32,
+    _version_dir: &std::path::Path,
+    _exe_path: &std::path::Path,
+    _exe_dir: &std::path::Path,
+    game_command: &launch_plan::GameCommand,
     load_bepinex: bool,
 ) -> Result<std::process::Child, String> {
     if load_bepinex {
@@ -10355,8 +10984,6 @@ fn spawn_game_process(
 
     #[cfg(target_os = "windows")]
     let overlay_config = read_steam_overlay_config(_app)?;
-    #[cfg(target_os = "linux")]
-    let overlay_config = read_steam_overlay_config(_app)?;
 
     let game_overlay_config = read_game_overlay_config(_app)?;
     let requested_backend = game_overlay_config.general.backend;
@@ -10377,12 +11004,8 @@ fn spawn_game_process(
         use std::os::windows::process::CommandExt;
         use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
 
-        let launch_exe_path = non_verbatim_windows_path(exe_path);
-        let default_program = launch_exe_path.as_os_str().to_os_string();
-        let default_args = Vec::new();
-        let (program, args) =
-            build_wrapped_launch_command(launch_command_template, &default_program, &default_args)?;
-        let launches_game_directly = windows_program_matches_game(&program, exe_path, exe_dir);
+        let program = OsString::from(&game_command.program);
+        let launches_game_directly = windows_program_matches_game(&program, _exe_path, _exe_dir);
         // The native overlay is now loaded by the OS PE loader as a
         // `version.dll` proxy dropped into the game directory, replacing the
         // former `CreateRemoteThread` injection. Staging must happen before
@@ -10443,15 +11066,10 @@ fn spawn_game_process(
         let needs_suspended_launch = overlay_config.enabled
             || (native_requested && launches_game_directly && !native_process_monitor_mode);
 
-        let mut cmd = std::process::Command::new(program);
-        cmd.args(args);
+        let mut cmd = game_command.to_command();
         if needs_suspended_launch {
             cmd.creation_flags(CREATE_SUSPENDED);
         }
-        if overlay_config.enabled {
-            cmd.env("SteamGameId", LETHAL_COMPANY_STEAM_APP_ID);
-            cmd.env("SteamAppId", LETHAL_COMPANY_STEAM_APP_ID);
-        }
         (
             cmd,
             native_dll_path,
@@ -10460,68 +11078,18 @@ fn spawn_game_process(
         )
     };
 
-    #[cfg(target_os = "macos")]
-    let mut command = {
-        let default_program = OsString::from("open");
-        let default_args = vec![OsString::from("-a"), exe_path.as_os_str().to_os_string()];
-        let (program, args) =
-            build_wrapped_launch_command(launch_command_template, &default_program, &default_args)?;
-        let mut cmd = std::process::Command::new(program);
-        cmd.args(args);
-        cmd
-    };
-
-    #[cfg(target_os = "linux")]
+    #[cfg(not(target_os = "windows"))]
     let mut command = {
-        let app_path = _app
-            .path()
-            .app_data_dir()
-            .map_err(|e| format!("app path not found: {e}"))?;
-        let proton_env_path = installer::proton_env_dir(_app)
-            .map_err(|e| format!("proton_env path not found: {e}"))?;
-        let proton_bin_path = installer::get_current_proton_dir_impl(_app)
-            .map_err(|e| format!("proton path not found: {e}"))?
-            .ok_or("found proton path but is None")?;
-        let compat_pre_path = proton_env_path.join("wine_prefix");
-        if !compat_pre_path.exists() {
-            std::fs::create_dir(&compat_pre_path)
-                .map_err(|e| format!("could not make prefix: {e}"))?;
-        }
-        let steam_path = get_steam_client_path(&app_path, overlay_config.steam_path.as_deref());
-        let default_program = proton_bin_path.join("proton").into_os_string();
-        let default_args = vec![OsString::from("run"), exe_path.as_os_str().to_os_string()];
-        let (program, args) =
-            build_wrapped_launch_command(launch_command_template, &default_program, &default_args)?;
-        let mut cmd = std::process::Command::new(program);
-        cmd.args(args);
-        sanitize_linux_host_process_env(&mut cmd);
-        cmd.env("STEAM_COMPAT_DATA_PATH", &compat_pre_path);
-        cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", &steam_path);
-        // Let the Wine loader pick up the local proxy `winhttp.dll` (BepInEx)
-        // and `version.dll` (native HQ overlay) ahead of the built-in system
-        // copies, mirroring the native Windows DLL search order.
-        cmd.env("WINEDLLOVERRIDES", "winhttp=n,b;version=n,b");
-        if overlay_config.enabled {
-            cmd.env("SteamGameId", LETHAL_COMPANY_STEAM_APP_ID);
-            cmd.env("SteamAppId", LETHAL_COMPANY_STEAM_APP_ID);
-            if let Some(overlay_preload) = linux_overlay_preload_value(&steam_path) {
-                let preload = std::env::var("LD_PRELOAD")
-                    .ok()
-                    .filter(|value| !value.trim().is_empty())
-                    .map(|existing| format!("{overlay_preload}:{existing}"))
-                    .unwrap_or(overlay_preload);
-                cmd.env("LD_PRELOAD", preload);
-            } else {
-                log::warn!(
-                    "Steam overlay enabled, but no Linux overlay renderer found under {}",
-                    steam_path.to_string_lossy()
-                );
-            }
+        if let Some(prefix) = game_command.wine_prefix.as_deref() {
+            std::fs::create_dir_all(prefix).map_err(|e| format!("could not make prefix: {e}"))?;
+            log::info!(
+                "Launching v{_version} with {} (prefix {prefix})",
+                game_command.program
+            );
         }
-        cmd
+        game_command.to_command()
     };
 
-    apply_custom_launch_options(&mut command, launch_options);
     put_command_in_new_process_group(&mut command);
 
     // On Linux/Proton the proxy `version.dll` must be staged next to the game
@@ -10541,19 +11109,12 @@ fn spawn_game_process(
         }
     } else {
         None
-    };
-    #[cfg(target_os = "windows")]
-    let _ = native_requested;
-
-    #[allow(unused_mut)]
+    };
     #[cfg(target_os = "windows")]
-    let launch_current_dir = non_verbatim_windows_path(exe_dir);
-    #[cfg(not(target_os = "windows"))]
-    let launch_current_dir = exe_dir.to_path_buf();
+    let _ = native_requested;
 
     #[allow(unused_mut)]
     let mut child = command
-        .current_dir(&launch_current_dir)
         .spawn()
         .map_err(|e| format!("failed to launch: {e}"))?;
 
@@ -11029,821 +11590,1353 @@ impl VanillaProxyDllGuard {
             ));
         }
 
-        let renamed = original.exists();
-        if renamed {
-            std::fs::rename(&original, &hidden).map_err(|e| {
-                format!(
-                    "failed to disable {dll_name} by renaming {}: {e}",
-                    original.to_string_lossy()
-                )
-            })?;
+        let renamed = original.exists();
+        if renamed {
+            std::fs::rename(&original, &hidden).map_err(|e| {
+                format!(
+                    "failed to disable {dll_name} by renaming {}: {e}",
+                    original.to_string_lossy()
+                )
+            })?;
+        }
+
+        Ok(Self {
+            dll_name,
+            original,
+            hidden,
+            renamed,
+        })
+    }
+
+    fn restore(&mut self) -> Result<(), String> {
+        if !self.renamed {
+            return Ok(());
+        }
+        std::fs::rename(&self.hidden, &self.original).map_err(|e| {
+            format!(
+                "the game started, but {} could not be restored from {}: {e}",
+                self.dll_name,
+                self.hidden.to_string_lossy()
+            )
+        })?;
+        self.renamed = false;
+        Ok(())
+    }
+}
+
+impl Drop for VanillaProxyDllGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.restore() {
+            log::error!("Failed to restore {} after game exit: {e}", self.dll_name);
+        }
+    }
+}
+
+fn native_overlay_proxy_enabled_for_launch(app: &tauri::AppHandle) -> Result<bool, String> {
+    let config = read_game_overlay_config(app)?;
+    Ok(config.general.enabled && config.general.backend.requests_native())
+}
+
+fn prepare_native_overlay_proxy_for_launch(
+    version_dir: &std::path::Path,
+    enabled: bool,
+) -> Result<Option<VanillaProxyDllGuard>, String> {
+    if enabled {
+        VanillaProxyDllGuard::restore_stale(version_dir, VanillaProxyDllGuard::VERSION)?;
+        Ok(None)
+    } else {
+        VanillaProxyDllGuard::hide(version_dir, VanillaProxyDllGuard::VERSION).map(Some)
+    }
+}
+
+fn restore_stale_vanilla_winhttp_backups(app: &tauri::AppHandle) -> Result<(), String> {
+    let versions = storage::versions_dir(app)?;
+    let Ok(entries) = std::fs::read_dir(versions) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            VanillaProxyDllGuard::restore_stale(&path, VanillaProxyDllGuard::WINHTTP)?;
+            VanillaProxyDllGuard::restore_stale(&path, VanillaProxyDllGuard::VERSION)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_steam_overlay_config(app: tauri::AppHandle) -> Result<SteamOverlayConfigDto, String> {
+    Ok(read_steam_overlay_config(&app)?.into_dto())
+}
+
+#[tauri::command]
+fn set_steam_overlay_config(
+    app: tauri::AppHandle,
+    enabled: bool,
+    steam_path: Option<String>,
+) -> Result<SteamOverlayConfigDto, String> {
+    let normalized_path = steam_path
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if let Some(path) = normalized_path.as_deref() {
+        let candidate = std::path::Path::new(path);
+        if !candidate.exists() {
+            return Err(format!(
+                "Steam path does not exist: {}",
+                candidate.to_string_lossy()
+            ));
+        }
+    }
+
+    let cfg = SteamOverlayConfig {
+        enabled,
+        steam_path: normalized_path,
+    };
+    write_steam_overlay_config(&app, &cfg)?;
+    Ok(cfg.into_dto())
+}
+
+#[tauri::command]
+fn pick_steam_overlay_path(initial_path: Option<String>) -> Result<Option<String>, String> {
+    let mut dialog = rfd::FileDialog::new();
+    if let Some(path) = initial_path
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        let candidate = std::path::PathBuf::from(&path);
+        if candidate.exists() {
+            dialog = dialog.set_directory(candidate);
+        }
+    }
+
+    Ok(dialog
+        .pick_folder()
+        .map(|path| path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+fn get_game_overlay_config(app: tauri::AppHandle) -> Result<GameOverlayConfig, String> {
+    let cfg = read_game_overlay_config(&app)?;
+    sync_game_overlay_runtime_state(&app, &cfg);
+    Ok(cfg)
+}
+
+#[tauri::command]
+fn get_game_overlay_modules(app: tauri::AppHandle) -> Result<Vec<GameOverlayModuleDto>, String> {
+    read_game_overlay_modules(&app)
+}
+
+#[tauri::command]
+fn get_game_overlay_debug_status(
+    state: State<'_, GameOverlayState>,
+) -> Result<GameOverlayDebugStatus, String> {
+    Ok(game_overlay_debug_status(&state))
+}
+
+#[tauri::command]
+fn get_stream_overlays_data(
+    app: tauri::AppHandle,
+) -> Result<Option<discord_presence::StreamOverlaysEnvelope>, String> {
+    if !read_game_overlay_config(&app)?
+        .general
+        .use_stream_overlays_api
+    {
+        return Ok(None);
+    }
+    Ok(discord_presence::receive_stream_overlays_data())
+}
+
+#[tauri::command]
+fn get_stream_overlays_ws_url(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    if !read_game_overlay_config(&app)?
+        .general
+        .use_stream_overlays_api
+    {
+        return Ok(None);
+    }
+    Ok(Some(discord_presence::stream_overlays_ws_url()))
+}
+
+#[tauri::command]
+fn report_game_overlay_frontend_ready(app: tauri::AppHandle) -> Result<(), String> {
+    set_game_overlay_debug(&app, "frontend ready");
+    Ok(())
+}
+
+#[tauri::command]
+fn report_game_overlay_frontend_info(app: tauri::AppHandle, message: String) -> Result<(), String> {
+    set_game_overlay_debug(&app, format!("frontend info: {message}"));
+    Ok(())
+}
+
+#[tauri::command]
+fn report_game_overlay_frontend_error(
+    app: tauri::AppHandle,
+    message: String,
+) -> Result<(), String> {
+    set_game_overlay_error(&app, format!("frontend error: {message}"));
+    Ok(())
+}
+
+#[tauri::command]
+fn set_game_overlay_config(
+    app: tauri::AppHandle,
+    mut config: GameOverlayConfig,
+) -> Result<GameOverlayConfig, String> {
+    #[cfg(target_os = "windows")]
+    let native_transition_state = app.state::<GameOverlayState>();
+    #[cfg(target_os = "windows")]
+    let native_transition_guard = native_transition_state
+        .native_transition_lock
+        .lock()
+        .map_err(|_| "native overlay transition lock poisoned".to_string())?;
+
+    // Serialize the full read/write/sync transaction with launch readiness and
+    // injection-result decisions. This makes the persisted config the single
+    // source of truth even when settings are changed while a game is launching.
+    let current_cfg = read_game_overlay_config(&app)?;
+    if current_cfg.general.obs_capture_armed {
+        config.general.obs_capture_armed = true;
+    }
+    let cfg = sanitize_game_overlay_config(config);
+
+    #[cfg(target_os = "windows")]
+    {
+        let state = &native_transition_state;
+        let pid = state.native_pid.load(Ordering::Relaxed);
+        let native_ready = state.native_ready.load(Ordering::Relaxed);
+        let native_pending = state
+            .effective_backend
+            .lock()
+            .map(|backend| backend.as_str() == "pending")
+            .unwrap_or(false);
+        let keep_native = cfg.general.enabled && cfg.general.backend.requests_native();
+        let transitioning_away = current_cfg.general.enabled
+            && current_cfg.general.backend.requests_native()
+            && !keep_native;
+        let active_native_transition = pid != 0 && (native_ready || native_pending);
+        if !keep_native && (transitioning_away || active_native_transition) {
+            // Invalidate an in-flight Ready decision before signalling the
+            // DLL. The waiter must never promote a native renderer after this
+            // settings transition has started.
+            state.native_transition_epoch.fetch_add(1, Ordering::AcqRel);
+            if active_native_transition {
+                signal_native_overlay_event("Disable", pid).map_err(|error| {
+                    format!(
+                        "Could not switch away from the active native overlay without risking a double overlay: {error}"
+                    )
+                })?;
+                state.native_ready.store(false, Ordering::Relaxed);
+                if let Ok(mut effective) = state.effective_backend.lock() {
+                    *effective =
+                        if cfg.general.enabled && cfg.general.backend.allows_legacy_fallback() {
+                            "legacy".to_string()
+                        } else {
+                            "off".to_string()
+                        };
+                }
+                if let Ok(mut error) = state.native_error.lock() {
+                    *error = Some(
+                        "Native overlay disabled by settings; relaunch to enable it again"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    write_game_overlay_config(&app, &cfg)?;
+    sync_game_overlay_runtime_state(&app, &cfg);
+    #[cfg(target_os = "windows")]
+    drop(native_transition_guard);
+    #[cfg(target_os = "windows")]
+    start_native_overlay_process_monitor(&app);
+    if !cfg.general.enabled {
+        app.state::<GameOverlayState>()
+            .preview_mode
+            .store(false, Ordering::Relaxed);
+        request_hide_game_overlay_window(&app, true);
+    } else {
+        start_game_overlay_monitor(&app);
+        if cfg.general.use_stream_overlays_api {
+            start_stream_overlays_monitor(&app);
         }
+    }
+    register_game_overlay_shortcut(&app);
+    let _ = app.emit("overlay://config-changed", cfg.clone());
+    Ok(cfg)
+}
 
-        Ok(Self {
-            dll_name,
-            original,
-            hidden,
-            renamed,
-        })
+#[tauri::command]
+fn open_obs_overlay_window(
+    app: tauri::AppHandle,
+    state: State<'_, GameOverlayState>,
+) -> Result<GameOverlayConfig, String> {
+    let current_cfg = read_game_overlay_config(&app)?;
+    if !current_cfg.general.enabled {
+        request_hide_game_overlay_window(&app, true);
+        set_game_overlay_debug(
+            &app,
+            "OBS selector ignored because HQLC overlay is disabled",
+        );
+        return Ok(current_cfg);
     }
+    let owned_preview = !state.preview_mode.load(Ordering::Relaxed)
+        && !state.window_visible.load(Ordering::Relaxed);
+    let cfg = set_game_overlay_obs_capture_armed(&app, true)?;
+    state
+        .obs_capture_owned_preview
+        .store(owned_preview, Ordering::Relaxed);
+    state.preview_mode.store(true, Ordering::Relaxed);
+    // OBS preview is an explicit legacy-only action and is allowed even while
+    // the in-game effective backend is native.
+    state.overlay_enabled.store(true, Ordering::Relaxed);
+    state.controls_open.store(false, Ordering::Relaxed);
+    let _ = app.emit_to(
+        GAME_OVERLAY_WINDOW_LABEL,
+        "overlay://controls-open-changed",
+        false,
+    );
+    start_game_overlay_monitor(&app);
+    let app_handle = app.clone();
+    #[cfg(not(target_os = "windows"))]
+    request_game_overlay_ui_task(&app_handle, "open OBS overlay window", move |app| {
+        open_obs_overlay_window_inner(app)
+    });
 
-    fn restore(&mut self) -> Result<(), String> {
-        if !self.renamed {
-            return Ok(());
+    #[cfg(target_os = "windows")]
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = open_obs_overlay_window_inner(&app_handle) {
+            set_game_overlay_error(
+                &app_handle,
+                format!("OBS overlay capture window failed: {e}"),
+            );
         }
-        std::fs::rename(&self.hidden, &self.original).map_err(|e| {
-            format!(
-                "the game started, but {} could not be restored from {}: {e}",
-                self.dll_name,
-                self.hidden.to_string_lossy()
-            )
-        })?;
-        self.renamed = false;
-        Ok(())
-    }
+    });
+    set_game_overlay_debug(
+        &app,
+        "OBS overlay capture window requested after overlay only preview",
+    );
+    Ok(cfg)
 }
 
-impl Drop for VanillaProxyDllGuard {
-    fn drop(&mut self) {
-        if let Err(e) = self.restore() {
-            log::error!("Failed to restore {} after game exit: {e}", self.dll_name);
-        }
+#[tauri::command]
+fn close_obs_overlay_window_if_owned(app: tauri::AppHandle) -> Result<bool, String> {
+    let state = app.state::<GameOverlayState>();
+    if !state
+        .obs_capture_owned_preview
+        .swap(false, Ordering::Relaxed)
+    {
+        set_game_overlay_debug(
+            &app,
+            "settings OBS selector close skipped; overlay was already active",
+        );
+        return Ok(false);
+    }
+    state.preview_mode.store(false, Ordering::Relaxed);
+    let restore_legacy = state
+        .effective_backend
+        .lock()
+        .map(|backend| backend.as_str() == "legacy")
+        .unwrap_or(false);
+    state
+        .overlay_enabled
+        .store(restore_legacy, Ordering::Relaxed);
+    state.controls_open.store(false, Ordering::Relaxed);
+    state.window_visible.store(false, Ordering::Relaxed);
+    if let Ok(mut last_rect) = state.last_window_rect.lock() {
+        *last_rect = None;
     }
+    request_hide_game_overlay_window(&app, true);
+    let _ = app.emit_to(
+        GAME_OVERLAY_WINDOW_LABEL,
+        "overlay://controls-open-changed",
+        false,
+    );
+    set_game_overlay_debug(&app, "settings-owned OBS selector overlay closed");
+    Ok(true)
 }
 
-fn native_overlay_proxy_enabled_for_launch(app: &tauri::AppHandle) -> Result<bool, String> {
-    let config = read_game_overlay_config(app)?;
-    Ok(config.general.enabled && config.general.backend.requests_native())
-}
+fn open_obs_overlay_window_inner(app: &tauri::AppHandle) -> Result<(), String> {
+    let window = ensure_game_overlay_window(app)?;
+    apply_game_overlay_selector_window_style(&window)?;
 
-fn prepare_native_overlay_proxy_for_launch(
-    version_dir: &std::path::Path,
-    enabled: bool,
-) -> Result<Option<VanillaProxyDllGuard>, String> {
-    if enabled {
-        VanillaProxyDllGuard::restore_stale(version_dir, VanillaProxyDllGuard::VERSION)?;
-        Ok(None)
+    #[cfg(target_os = "windows")]
+    if let Some((_pid, rect)) = foreground_lethal_company_window() {
+        let width = (rect.right - rect.left).max(1) as u32;
+        let height = (rect.bottom - rect.top).max(1) as u32;
+        let _ = window.set_fullscreen(false);
+        let _ = window.set_size(tauri::PhysicalSize::new(width, height));
+        let _ = window.set_position(tauri::PhysicalPosition::new(rect.left, rect.top));
     } else {
-        VanillaProxyDllGuard::hide(version_dir, VanillaProxyDllGuard::VERSION).map(Some)
+        let _ = window.set_fullscreen(false);
+        let _ = window.set_size(tauri::PhysicalSize::new(1280, 720));
+        let _ = window.set_position(tauri::PhysicalPosition::new(96, 96));
     }
-}
 
-fn restore_stale_vanilla_winhttp_backups(app: &tauri::AppHandle) -> Result<(), String> {
-    let versions = storage::versions_dir(app)?;
-    let Ok(entries) = std::fs::read_dir(versions) else {
-        return Ok(());
-    };
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_dir() {
-            VanillaProxyDllGuard::restore_stale(&path, VanillaProxyDllGuard::WINHTTP)?;
-            VanillaProxyDllGuard::restore_stale(&path, VanillaProxyDllGuard::VERSION)?;
-        }
-    }
+    let _ = window.set_title(GAME_OVERLAY_WINDOW_TITLE);
+    let _ = window.set_always_on_top(false);
+    apply_game_overlay_interaction(&window, false)?;
+    window.show().map_err(|e| e.to_string())?;
+    set_game_overlay_debug(app, "game overlay selector window opened for OBS capture");
     Ok(())
 }
 
 #[tauri::command]
-fn get_steam_overlay_config(app: tauri::AppHandle) -> Result<SteamOverlayConfigDto, String> {
-    Ok(read_steam_overlay_config(&app)?.into_dto())
-}
-
-#[tauri::command]
-fn set_steam_overlay_config(
-    app: tauri::AppHandle,
-    enabled: bool,
-    steam_path: Option<String>,
-) -> Result<SteamOverlayConfigDto, String> {
-    let normalized_path = steam_path
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty());
-    if let Some(path) = normalized_path.as_deref() {
-        let candidate = std::path::Path::new(path);
-        if !candidate.exists() {
-            return Err(format!(
-                "Steam path does not exist: {}",
-                candidate.to_string_lossy()
-            ));
-        }
+fn close_obs_overlay_window(app: tauri::AppHandle) -> Result<bool, String> {
+    let state = app.state::<GameOverlayState>();
+    state
+        .obs_capture_owned_preview
+        .store(false, Ordering::Relaxed);
+    state.preview_mode.store(false, Ordering::Relaxed);
+    let restore_legacy = state
+        .effective_backend
+        .lock()
+        .map(|backend| backend.as_str() == "legacy")
+        .unwrap_or(false);
+    state
+        .overlay_enabled
+        .store(restore_legacy, Ordering::Relaxed);
+    state.controls_open.store(false, Ordering::Relaxed);
+    state.window_visible.store(false, Ordering::Relaxed);
+    if let Ok(mut last_rect) = state.last_window_rect.lock() {
+        *last_rect = None;
     }
-
-    let cfg = SteamOverlayConfig {
-        enabled,
-        steam_path: normalized_path,
-    };
-    write_steam_overlay_config(&app, &cfg)?;
-    Ok(cfg.into_dto())
+    request_hide_game_overlay_window(&app, true);
+    let _ = app.emit_to(
+        GAME_OVERLAY_WINDOW_LABEL,
+        "overlay://controls-open-changed",
+        false,
+    );
+    set_game_overlay_debug(
+        &app,
+        "game overlay selector hidden; OBS capture remains armed",
+    );
+    Ok(true)
 }
 
 #[tauri::command]
-fn pick_steam_overlay_path(initial_path: Option<String>) -> Result<Option<String>, String> {
-    let mut dialog = rfd::FileDialog::new();
-    if let Some(path) = initial_path
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
-    {
-        let candidate = std::path::PathBuf::from(&path);
-        if candidate.exists() {
-            dialog = dialog.set_directory(candidate);
-        }
-    }
-
-    Ok(dialog
-        .pick_folder()
-        .map(|path| path.to_string_lossy().to_string()))
+fn open_game_overlay_modules_folder(app: tauri::AppHandle) -> Result<bool, String> {
+    ensure_default_game_overlay_modules(&app)?;
+    let dir = game_overlay_module_dir(&app)?;
+    open_folder_path(&dir)?;
+    Ok(true)
 }
 
 #[tauri::command]
-fn get_game_overlay_config(app: tauri::AppHandle) -> Result<GameOverlayConfig, String> {
-    let cfg = read_game_overlay_config(&app)?;
-    sync_game_overlay_runtime_state(&app, &cfg);
-    Ok(cfg)
+fn set_game_overlay_controls_open(
+    app: tauri::AppHandle,
+    state: State<'_, GameOverlayState>,
+    open: bool,
+) -> Result<bool, String> {
+    set_game_overlay_controls_open_inner(&app, &state, open)
 }
 
 #[tauri::command]
-fn get_game_overlay_modules(app: tauri::AppHandle) -> Result<Vec<GameOverlayModuleDto>, String> {
-    read_game_overlay_modules(&app)
+fn suspend_game_overlay_controls_for_focus_loss(
+    app: tauri::AppHandle,
+    state: State<'_, GameOverlayState>,
+) -> Result<bool, String> {
+    suspend_game_overlay_controls_for_focus_loss_inner(&app, &state)
 }
 
 #[tauri::command]
-fn get_game_overlay_debug_status(
+fn set_game_overlay_file_dialog_active(
     state: State<'_, GameOverlayState>,
-) -> Result<GameOverlayDebugStatus, String> {
-    Ok(game_overlay_debug_status(&state))
+    active: bool,
+) -> Result<bool, String> {
+    state.file_dialog_active.store(active, Ordering::Relaxed);
+    Ok(active)
 }
 
 #[tauri::command]
-fn get_stream_overlays_data(
+fn set_game_overlay_input_shortcuts(
     app: tauri::AppHandle,
-) -> Result<Option<discord_presence::StreamOverlaysEnvelope>, String> {
-    if !read_game_overlay_config(&app)?
-        .general
-        .use_stream_overlays_api
+    state: State<'_, GameOverlayState>,
+    shortcuts: Vec<String>,
+) -> Result<bool, String> {
+    let overlay_key = read_game_overlay_config(&app)
+        .map(|cfg| cfg.general.overlay_key)
+        .unwrap_or_else(|_| "Insert".to_string());
+    let next: HashSet<String> = shortcuts
+        .into_iter()
+        .map(|shortcut| sanitize_overlay_key(&shortcut))
+        .filter(|shortcut| !shortcut.is_empty())
+        .filter(|shortcut| !shortcut.eq_ignore_ascii_case(&overlay_key))
+        .collect();
+
+    #[cfg(target_os = "windows")]
     {
-        return Ok(None);
+        let mut registered = state.registered_overlay_input_shortcuts.lock().unwrap();
+        *registered = next.clone();
+        state
+            .overlay_input_down_shortcuts
+            .lock()
+            .unwrap()
+            .retain(|shortcut| next.contains(shortcut));
+        start_game_overlay_input_monitor(&app);
+        return Ok(true);
     }
-    Ok(discord_presence::receive_stream_overlays_data())
-}
 
-#[tauri::command]
-fn get_stream_overlays_ws_url(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    if !read_game_overlay_config(&app)?
-        .general
-        .use_stream_overlays_api
+    #[cfg(not(target_os = "windows"))]
     {
-        return Ok(None);
-    }
-    Ok(Some(discord_presence::stream_overlays_ws_url()))
-}
+        let mut registered = state.registered_overlay_input_shortcuts.lock().unwrap();
+        let previous = registered.clone();
 
-#[tauri::command]
-fn report_game_overlay_frontend_ready(app: tauri::AppHandle) -> Result<(), String> {
-    set_game_overlay_debug(&app, "frontend ready");
-    Ok(())
-}
+        for shortcut in previous.difference(&next) {
+            let parsed = overlay_shortcuts_for_key(shortcut);
+            if !parsed.is_empty() {
+                if let Err(e) = app.global_shortcut().unregister_multiple(parsed) {
+                    log::warn!("Failed to unregister overlay input shortcut {shortcut}: {e}");
+                }
+            }
+        }
 
-#[tauri::command]
-fn report_game_overlay_frontend_info(app: tauri::AppHandle, message: String) -> Result<(), String> {
-    set_game_overlay_debug(&app, format!("frontend info: {message}"));
-    Ok(())
-}
+        for shortcut in next.difference(&previous) {
+            let parsed = overlay_shortcuts_for_key(shortcut);
+            if parsed.is_empty() {
+                log::warn!("Unsupported overlay input shortcut: {shortcut}");
+                continue;
+            }
+            let shortcut_for_handler = shortcut.clone();
+            if let Err(e) =
+                app.global_shortcut()
+                    .on_shortcuts(parsed, move |app, _shortcut, event| {
+                        emit_game_overlay_input_shortcut(
+                            app,
+                            &shortcut_for_handler,
+                            event.state,
+                            "module-key",
+                        );
+                    })
+            {
+                log::warn!("Failed to register overlay input shortcut {shortcut}: {e}");
+            }
+        }
 
-#[tauri::command]
-fn report_game_overlay_frontend_error(
-    app: tauri::AppHandle,
-    message: String,
-) -> Result<(), String> {
-    set_game_overlay_error(&app, format!("frontend error: {message}"));
-    Ok(())
+        *registered = next;
+        Ok(true)
+    }
 }
 
 #[tauri::command]
-fn set_game_overlay_config(
+fn show_game_overlay_end_summary(
     app: tauri::AppHandle,
-    mut config: GameOverlayConfig,
-) -> Result<GameOverlayConfig, String> {
-    #[cfg(target_os = "windows")]
-    let native_transition_state = app.state::<GameOverlayState>();
-    #[cfg(target_os = "windows")]
-    let native_transition_guard = native_transition_state
-        .native_transition_lock
-        .lock()
-        .map_err(|_| "native overlay transition lock poisoned".to_string())?;
-
-    // Serialize the full read/write/sync transaction with launch readiness and
-    // injection-result decisions. This makes the persisted config the single
-    // source of truth even when settings are changed while a game is launching.
-    let current_cfg = read_game_overlay_config(&app)?;
-    if current_cfg.general.obs_capture_armed {
-        config.general.obs_capture_armed = true;
+    mut payload: GameOverlayEndSummaryPayload,
+) -> Result<bool, String> {
+    if !app
+        .state::<GameOverlayState>()
+        .overlay_enabled
+        .load(Ordering::Relaxed)
+    {
+        return Ok(false);
+    }
+    if payload.duration_ms.is_none() {
+        payload.duration_ms = Some(
+            read_game_overlay_config(&app)?
+                .general
+                .end_summary_duration_ms,
+        );
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        request_game_overlay_ui_task(&app, "show overlay end summary", move |app| {
+            let window = ensure_game_overlay_window(app)?;
+            window.show().map_err(|e| e.to_string())?;
+            window.set_always_on_top(true).map_err(|e| e.to_string())?;
+            force_game_overlay_topmost(&window)?;
+            apply_game_overlay_interaction(&window, false)?;
+            app.state::<GameOverlayState>()
+                .window_visible
+                .store(true, Ordering::Relaxed);
+            set_game_overlay_frontend_active(app, true);
+            app.emit("overlay://show-end-summary", payload)
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        });
+        return Ok(true);
     }
-    let cfg = sanitize_game_overlay_config(config);
 
     #[cfg(target_os = "windows")]
     {
-        let state = &native_transition_state;
-        let pid = state.native_pid.load(Ordering::Relaxed);
-        let native_ready = state.native_ready.load(Ordering::Relaxed);
-        let native_pending = state
-            .effective_backend
-            .lock()
-            .map(|backend| backend.as_str() == "pending")
-            .unwrap_or(false);
-        let keep_native = cfg.general.enabled && cfg.general.backend.requests_native();
-        let transitioning_away = current_cfg.general.enabled
-            && current_cfg.general.backend.requests_native()
-            && !keep_native;
-        let active_native_transition = pid != 0 && (native_ready || native_pending);
-        if !keep_native && (transitioning_away || active_native_transition) {
-            // Invalidate an in-flight Ready decision before signalling the
-            // DLL. The waiter must never promote a native renderer after this
-            // settings transition has started.
-            state.native_transition_epoch.fetch_add(1, Ordering::AcqRel);
-            if active_native_transition {
-                signal_native_overlay_event("Disable", pid).map_err(|error| {
-                    format!(
-                        "Could not switch away from the active native overlay without risking a double overlay: {error}"
-                    )
-                })?;
-                state.native_ready.store(false, Ordering::Relaxed);
-                if let Ok(mut effective) = state.effective_backend.lock() {
-                    *effective =
-                        if cfg.general.enabled && cfg.general.backend.allows_legacy_fallback() {
-                            "legacy".to_string()
-                        } else {
-                            "off".to_string()
-                        };
-                }
-                if let Ok(mut error) = state.native_error.lock() {
-                    *error = Some(
-                        "Native overlay disabled by settings; relaunch to enable it again"
-                            .to_string(),
-                    );
-                }
-            }
+        let window = ensure_game_overlay_window(&app)?;
+        window.show().map_err(|e| e.to_string())?;
+        window.set_always_on_top(true).map_err(|e| e.to_string())?;
+        force_game_overlay_topmost(&window)?;
+        apply_game_overlay_interaction(&window, false)?;
+        app.emit("overlay://show-end-summary", payload)
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+}
+
+/// `build_launch_plan`'s result: the plan plus what carrying it out needs.
+struct PreparedLaunch {
+    plan: launch_plan::LaunchPlan,
+    practice_installs: Vec<mod_config::ModEntry>,
+    preset_installs: Vec<mod_config::ModEntry>,
+    version_dir: std::path::PathBuf,
+    exe_path: std::path::PathBuf,
+    exe_dir: std::path::PathBuf,
+}
+
+/// Resolve everything `request` would do, without changing the version folder.
+async fn build_launch_plan(
+    app: &tauri::AppHandle,
+    request: launch_plan::LaunchRequest,
+) -> Result<PreparedLaunch, String> {
+    let version = request.version;
+    let (version_dir, exe_path, exe_dir) = resolve_game_launch_paths(app, version)?;
+
+    let mut inputs = launch_plan::ModInputs::default();
+    let mut practice_installs = vec![];
+    let mut preset_installs = vec![];
+    if request.mode.loads_mods() {
+        if let Some(event_id) = request.event_id() {
+            inputs.event_mods = event_mods_for_launch(app, version, event_id).await?;
+        }
+        inputs.practice_list = practice_mode_mod_ids();
+        if request.mode.is_practice() {
+            let practice = practice_mods_for_launch(app, version)?;
+            inputs.practice_mods = practice
+                .iter()
+                .map(|m| (m.dev.clone(), m.name.clone()))
+                .collect();
+            practice_installs = filter_missing_mods_for_version(app, version, &practice)?;
+        }
+        inputs.run_mode_mods = run_mode_tagged_mod_ids(version, None).await?;
+        if let launch_plan::LaunchMode::Preset { preset, .. } = &request.mode {
+            // For practice runs preset mods install AFTER practice mods so preset-specific
+            // pins can win. (Practice list has its own pinning, e.g. for LethalNetworkAPI.)
+            inputs.preset_tags = preset_tags_for_name(preset);
+            let tagged = tagged_mods_for_version(version, &inputs.preset_tags, None).await?;
+            inputs.preset_mods = mod_entry_pairs(&tagged);
+            preset_installs = filter_missing_mods_for_version(app, version, &tagged)?;
+            allow_eclipsed_hq_optional_mods(preset, &mut inputs.run_mode_mods);
+        }
+    }
+
+    let mut plan = launch_plan::LaunchPlan::compose(request, inputs);
+    plan.install_mods = practice_installs
+        .iter()
+        .chain(&preset_installs)
+        .map(|m| (m.dev.clone(), m.name.clone()))
+        .collect();
+    plan.proxy_dlls.version_dll =
+        plan.proxy_dlls.winhttp && native_overlay_proxy_enabled_for_launch(app)?;
+    let lcstats_key = normalize_mod_key("MikuOreo", "LCStatsTracker");
+    plan.start_lcstats = plan.request.mode.loads_mods()
+        && (is_lcstats_enabled(app)?
+            || plan
+                .persist_enabled
+                .iter()
+                .any(|(dev, name)| normalize_mod_key(dev, name) == lcstats_key));
+
+    let run_mode = plan.request.mode.run_mode();
+    let profiles = launch_profiles::load(app)?;
+    let profile = profiles.resolve(plan.request.profile.as_deref(), version, &run_mode)?;
+    // A template passed with the launch wins over the profile's.
+    let template = plan
+        .request
+        .launch_command_template
+        .as_deref()
+        .filter(|template| !template.trim().is_empty())
+        .or_else(|| profile.and_then(|p| p.command_template.as_deref()));
+    let vars = launch_profiles::TemplateVars {
+        version,
+        game_dir: version_dir.to_string_lossy().to_string(),
+        run_mode,
+        bepinex_log: bepinex_log::log_path(&version_dir)
+            .to_string_lossy()
+            .to_string(),
+        pid_file: launch_profiles::pid_file(app, version)?
+            .to_string_lossy()
+            .to_string(),
+    };
+    plan.command = game_command(
+        app,
+        version,
+        &exe_path,
+        &exe_dir,
+        template,
+        &vars,
+        profile.and_then(|p| p.wine_prefix.as_deref()),
+    )?;
+    if let Some(profile) = profile {
+        for (name, value) in &profile.env {
+            plan.command.set_env(name, value.clone());
+        }
+        plan.command.push_launch_options(&profile.launch_options);
+        plan.launch_profile = Some(profile.id.clone());
+    }
+    plan.command.push_launch_options(&plan.request.launch_options);
+
+    Ok(PreparedLaunch {
+        plan,
+        practice_installs,
+        preset_installs,
+        version_dir,
+        exe_path,
+        exe_dir,
+    })
+}
+
+fn apply_plan_mod_states(app: &tauri::AppHandle, version: u32, plan: &launch_plan::LaunchPlan) {
+    // Re-enable preset mods for good (Wesley includes LethalNetworkAPI which is otherwise forced off).
+    let _ = force_enable_mods_for_version(app, version, &plan.persist_enabled);
+    // Mode-required state must win over the saved disabled list.
+    let _ = apply_effective_mod_states_for_version(
+        app,
+        version,
+        &plan.force_disabled,
+        &plan.force_enabled,
+    );
+    if plan.request.mode.is_practice() {
+        if let Ok(plugins) = plugins_dir(app, version) {
+            let _ = sync_practice_locked_mods_for_version(&plugins);
         }
+    } else {
+        // For HQoL specifically, also ensure `.old` matches disablemod.json on normal runs.
+        let _ = sync_hqol_with_disablemod_for_version(app, version);
+        let _ = sync_vlog_with_disablemod_for_version(app, version);
     }
+}
 
-    write_game_overlay_config(&app, &cfg)?;
-    sync_game_overlay_runtime_state(&app, &cfg);
-    #[cfg(target_os = "windows")]
-    drop(native_transition_guard);
-    #[cfg(target_os = "windows")]
-    start_native_overlay_process_monitor(&app);
-    if !cfg.general.enabled {
-        app.state::<GameOverlayState>()
-            .preview_mode
-            .store(false, Ordering::Relaxed);
-        request_hide_game_overlay_window(&app, true);
-    } else {
-        start_game_overlay_monitor(&app);
-        if cfg.general.use_stream_overlays_api {
-            start_stream_overlays_monitor(&app);
+async fn apply_launch_cfg_step(
+    app: &tauri::AppHandle,
+    version: u32,
+    step: &launch_plan::CfgStep,
+    launching: bool,
+) -> Result<(), String> {
+    match step {
+        launch_plan::CfgStep::EventVlog { event_id } => {
+            ensure_event_vlog_cfg(app, version, event_id)?
+        }
+        launch_plan::CfgStep::CfgPatches { preset, practice } => {
+            apply_cfg_patches(app, version, preset, *practice, launching).await
         }
+        // Done by `revert_cfg_patches_for_vanilla_run` once the running-game checks passed.
+        launch_plan::CfgStep::RevertCfgPatches => {}
     }
-    register_game_overlay_shortcut(&app);
-    let _ = app.emit("overlay://config-changed", cfg.clone());
-    Ok(cfg)
+    Ok(())
 }
 
-#[tauri::command]
-fn open_obs_overlay_window(
-    app: tauri::AppHandle,
-    state: State<'_, GameOverlayState>,
-) -> Result<GameOverlayConfig, String> {
-    let current_cfg = read_game_overlay_config(&app)?;
-    if !current_cfg.general.enabled {
-        request_hide_game_overlay_window(&app, true);
-        set_game_overlay_debug(
-            &app,
-            "OBS selector ignored because HQLC overlay is disabled",
-        );
-        return Ok(current_cfg);
+/// Vanilla runs get the configs as the user left them, unless a run of `version`
+/// still uses the patched ones.
+fn revert_cfg_patches_for_vanilla_run(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+    version: u32,
+) -> Result<(), String> {
+    let mut guard = state
+        .active
+        .lock()
+        .map_err(|_| "game state lock poisoned".to_string())?;
+    cleanup_active_games(app, &mut guard)?;
+    if guard.iter().any(|active| active.version == version) {
+        log::info!("Keeping the cfg patches of v{version} while another run of it is active");
+        return Ok(());
     }
-    let owned_preview = !state.preview_mode.load(Ordering::Relaxed)
-        && !state.window_visible.load(Ordering::Relaxed);
-    let cfg = set_game_overlay_obs_capture_armed(&app, true)?;
-    state
-        .obs_capture_owned_preview
-        .store(owned_preview, Ordering::Relaxed);
-    state.preview_mode.store(true, Ordering::Relaxed);
-    // OBS preview is an explicit legacy-only action and is allowed even while
-    // the in-game effective backend is native.
-    state.overlay_enabled.store(true, Ordering::Relaxed);
-    state.controls_open.store(false, Ordering::Relaxed);
-    let _ = app.emit_to(
-        GAME_OVERLAY_WINDOW_LABEL,
-        "overlay://controls-open-changed",
-        false,
-    );
-    start_game_overlay_monitor(&app);
-    let app_handle = app.clone();
-    #[cfg(not(target_os = "windows"))]
-    request_game_overlay_ui_task(&app_handle, "open OBS overlay window", move |app| {
-        open_obs_overlay_window_inner(app)
-    });
+    if let Err(e) = cfg_patches::revert_all(app, version) {
+        log::warn!("Failed to revert cfg patches for v{version}: {e}");
+    }
+    Ok(())
+}
 
-    #[cfg(target_os = "windows")]
-    tauri::async_runtime::spawn(async move {
-        if let Err(e) = open_obs_overlay_window_inner(&app_handle) {
-            set_game_overlay_error(
-                &app_handle,
-                format!("OBS overlay capture window failed: {e}"),
-            );
-        }
-    });
-    set_game_overlay_debug(
-        &app,
-        "OBS overlay capture window requested after overlay only preview",
-    );
-    Ok(cfg)
+/// Hide the proxy DLLs the plan leaves out and spawn the game.
+fn start_game(
+    app: &tauri::AppHandle,
+    plan: &launch_plan::LaunchPlan,
+    version_dir: &std::path::Path,
+    exe_path: &std::path::Path,
+    exe_dir: &std::path::Path,
+) -> Result<(Vec<VanillaProxyDllGuard>, std::process::Child), String> {
+    let mut proxy_dll_guards = vec![];
+    if !plan.proxy_dlls.winhttp {
+        proxy_dll_guards.push(VanillaProxyDllGuard::hide(
+            version_dir,
+            VanillaProxyDllGuard::WINHTTP,
+        )?);
+    }
+    proxy_dll_guards.extend(prepare_native_overlay_proxy_for_launch(
+        version_dir,
+        plan.proxy_dlls.version_dll,
+    )?);
+    let child = spawn_game_process(
+        app,
+        plan.request.version,
+        version_dir,
+        exe_path,
+        exe_dir,
+        &plan.command,
+        plan.proxy_dlls.winhttp,
+    )?;
+    Ok((proxy_dll_guards, child))
 }
 
-#[tauri::command]
-fn close_obs_overlay_window_if_owned(app: tauri::AppHandle) -> Result<bool, String> {
-    let state = app.state::<GameOverlayState>();
-    if !state
-        .obs_capture_owned_preview
-        .swap(false, Ordering::Relaxed)
-    {
-        set_game_overlay_debug(
-            &app,
-            "settings OBS selector close skipped; overlay was already active",
-        );
-        return Ok(false);
+/// Carry out a plan from `build_launch_plan` and start the game.
+async fn execute_launch_plan(
+    app: &tauri::AppHandle,
+    prepared: PreparedLaunch,
+    state: &State<'_, GameState>,
+    lcstats_state: &State<'_, lcstats_autosheet::LcStatsAutosheetState>,
+) -> Result<u32, String> {
+    let PreparedLaunch {
+        plan,
+        practice_installs,
+        preset_installs,
+        version_dir,
+        exe_path,
+        exe_dir,
+    } = prepared;
+    let version = plan.request.version;
+    let loads_mods = plan.request.mode.loads_mods();
+
+    if plan.clear_event_mods {
+        clear_event_mods_for_version(app, version)?;
     }
-    state.preview_mode.store(false, Ordering::Relaxed);
-    let restore_legacy = state
-        .effective_backend
+    if loads_mods {
+        install_missing_practice_mods(app, version, practice_installs, None).await?;
+        install_missing_mods_with_progress(
+            app,
+            version,
+            preset_installs,
+            &plan.preset_tags,
+            "Preset Mods",
+            &format!(
+                "Installing missing tagged mods: {}",
+                plan.preset_tags.join(", ")
+            ),
+            None,
+        )
+        .await?;
+        apply_plan_mod_states(app, version, &plan);
+    }
+    for step in &plan.cfg_steps {
+        apply_launch_cfg_step(app, version, step, true).await?;
+    }
+    if loads_mods {
+        wait_for_mod_file_renames_to_settle();
+    }
+
+    let hook_settings = launch_hooks::load_settings(app)?;
+    let mut hook_context = launch_hooks::HookContext {
+        version,
+        run_mode: plan.request.mode.run_mode(),
+        event_id: plan.request.event_id().map(str::to_string),
+        game_dir: version_dir.clone(),
+        pid: None,
+    };
+    let _launch_guard = state
+        .launch_lock
         .lock()
-        .map(|backend| backend.as_str() == "legacy")
-        .unwrap_or(false);
+        .map_err(|_| "game launch lock poisoned".to_string())?;
+    ensure_launch_compatible_with_active_games(
+        app,
+        state,
+        version,
+        !plan.proxy_dlls.winhttp || !plan.proxy_dlls.version_dll,
+    )?;
+    if !plan.request.allow_multiple {
+        ensure_game_not_running(app, state)?;
+    }
+    if plan
+        .cfg_steps
+        .contains(&launch_plan::CfgStep::RevertCfgPatches)
+    {
+        revert_cfg_patches_for_vanilla_run(app, state, version)?;
+    }
+    run_pre_launch_doctor(
+        app,
+        state,
+        version,
+        loads_mods,
+        plan.command.wine_prefix.as_deref(),
+    )?;
+    let launch_plan_json = serde_json::to_string_pretty(&plan).map_err(|e| e.to_string())?;
+    // Only once nothing can refuse the launch: every pre-launch hook that ran is
+    // followed by the post-exit ones. The launch lock stays held meanwhile.
+    let ran_pre_launch_hooks = !hook_settings.pre_launch.is_empty();
+    if ran_pre_launch_hooks {
+        tokio::task::block_in_place(|| {
+            launch_hooks::run_pre_launch(&hook_settings, &hook_context)
+        })?;
+    }
+
+    let started = start_game(app, &plan, &version_dir, &exe_path, &exe_dir);
+    if started.is_err() && ran_pre_launch_hooks {
+        // No `ActiveGame` will run the post-exit hooks, so close the session here.
+        if let Some(hooks) = launch_hooks::ExitHooks::new(&hook_settings, hook_context.clone()) {
+            hooks.spawn(None);
+        }
+    }
+    let (proxy_dll_guards, child) = started?;
+    let pid = child.id();
+    write_launch_pid_file(app, version, pid);
+    hook_context.pid = Some(pid);
+    let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+    let started_at = now_unix_secs();
+    let run_id = format!("v{version}-{started_at}-{id}");
+    let lockfile_hash = if loads_mods {
+        let mods = instance_archive::locked_mods(&version_dir.join("BepInEx").join("plugins"));
+        launch_history::store_lockfile(app, &mods)
+            .map_err(|e| log::warn!("Failed to store the mod lockfile of v{version}: {e}"))
+            .ok()
+    } else {
+        None
+    };
+    let history = launch_history::start(
+        app,
+        launch_history::LaunchRecord {
+            id: run_id.clone(),
+            version,
+            mode_label: plan.mode_label.clone(),
+            run_mode: hook_context.run_mode.clone(),
+            event_id: hook_context.event_id.clone(),
+            launch_profile: plan.launch_profile.clone(),
+            lockfile_hash,
+            started_at,
+            ended_at: None,
+            exit: None,
+            exit_code: None,
+            crash_bundle: None,
+            bepinex_log_session: None,
+            lcstats_payloads: 0,
+            peak_resources: None,
+        },
+    )
+    .map_err(|e| log::warn!("Failed to record launch of v{version} in history: {e}"))
+    .ok();
+    let wine_prefix = plan
+        .command
+        .wine_prefix
+        .as_ref()
+        .map(std::path::PathBuf::from);
+    let exit_watch = crash_report::ExitWatch {
+        app: app.clone(),
+        game_id: id,
+        version,
+        mode_label: plan.mode_label.clone(),
+        run_mode: hook_context.run_mode.clone(),
+        started_at,
+        game_dir: version_dir.clone(),
+        wine_prefix: wine_prefix.clone(),
+        launch_plan: launch_plan_json,
+        history,
+    };
+    let bepinex_log_tail = if loads_mods {
+        let session = bepinex_log::LogSessionInfo {
+            id: run_id,
+            version,
+            mode_label: plan.mode_label.clone(),
+            started_at,
+            finished_at: None,
+            summary: Default::default(),
+        };
+        bepinex_log::start_tail(app.clone(), &version_dir, session)
+            .map_err(|e| log::warn!("Failed to start BepInEx log capture for v{version}: {e}"))
+            .ok()
+    } else {
+        None
+    };
     state
-        .overlay_enabled
-        .store(restore_legacy, Ordering::Relaxed);
-    state.controls_open.store(false, Ordering::Relaxed);
-    state.window_visible.store(false, Ordering::Relaxed);
-    if let Ok(mut last_rect) = state.last_window_rect.lock() {
-        *last_rect = None;
+        .active
+        .lock()
+        .map_err(|_| "game state lock poisoned".to_string())?
+        .push(ActiveGame {
+            id,
+            child,
+            version,
+            mode_label: plan.mode_label,
+            launch_options: plan.request.launch_options,
+            launch_command_template: plan.request.launch_command_template,
+            _proxy_dll_guards: proxy_dll_guards,
+            exit_hooks: launch_hooks::ExitHooks::new(&hook_settings, hook_context),
+            bepinex_log_tail,
+            exit_watch: Some(exit_watch),
+            stopped_by_launcher: false,
+            wine_prefix,
+            started_at,
+        });
+    if loads_mods {
+        lcstats_autosheet::start_for_launch(app.clone(), plan.start_lcstats, lcstats_state);
     }
-    request_hide_game_overlay_window(&app, true);
-    let _ = app.emit_to(
-        GAME_OVERLAY_WINDOW_LABEL,
-        "overlay://controls-open-changed",
-        false,
-    );
-    set_game_overlay_debug(&app, "settings-owned OBS selector overlay closed");
-    Ok(true)
+    start_resource_monitor(app);
+    show_game_overlay(app);
+    Ok(pid)
 }
 
-fn open_obs_overlay_window_inner(app: &tauri::AppHandle) -> Result<(), String> {
-    let window = ensure_game_overlay_window(app)?;
-    apply_game_overlay_selector_window_style(&window)?;
-
-    #[cfg(target_os = "windows")]
-    if let Some((_pid, rect)) = foreground_lethal_company_window() {
-        let width = (rect.right - rect.left).max(1) as u32;
-        let height = (rect.bottom - rect.top).max(1) as u32;
-        let _ = window.set_fullscreen(false);
-        let _ = window.set_size(tauri::PhysicalSize::new(width, height));
-        let _ = window.set_position(tauri::PhysicalPosition::new(rect.left, rect.top));
-    } else {
-        let _ = window.set_fullscreen(false);
-        let _ = window.set_size(tauri::PhysicalSize::new(1280, 720));
-        let _ = window.set_position(tauri::PhysicalPosition::new(96, 96));
+/// Best effort: `%pid_file%` is for wrapper scripts, the launcher never reads it.
+fn write_launch_pid_file(app: &tauri::AppHandle, version: u32, pid: u32) {
+    let result = launch_profiles::pid_file(app, version).and_then(|path| {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, format!("{pid}\n")).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to write pid file for v{version}: {e}");
     }
+}
 
-    let _ = window.set_title(GAME_OVERLAY_WINDOW_TITLE);
-    let _ = window.set_always_on_top(false);
-    apply_game_overlay_interaction(&window, false)?;
-    window.show().map_err(|e| e.to_string())?;
-    set_game_overlay_debug(app, "game overlay selector window opened for OBS capture");
-    Ok(())
+async fn launch_with_plan(
+    app: &tauri::AppHandle,
+    request: launch_plan::LaunchRequest,
+    state: &State<'_, GameState>,
+    lcstats_state: &State<'_, lcstats_autosheet::LcStatsAutosheetState>,
+    prepare_state: &PrepareState,
+) -> Result<u32, String> {
+    wait_for_prepare_to_finish(
+        prepare_state,
+        request.version,
+        std::time::Duration::from_secs(30),
+    )?;
+    let prepared = build_launch_plan(app, request).await?;
+    execute_launch_plan(app, prepared, state, lcstats_state).await
 }
 
 #[tauri::command]
-fn close_obs_overlay_window(app: tauri::AppHandle) -> Result<bool, String> {
-    let state = app.state::<GameOverlayState>();
-    state
-        .obs_capture_owned_preview
-        .store(false, Ordering::Relaxed);
-    state.preview_mode.store(false, Ordering::Relaxed);
-    let restore_legacy = state
-        .effective_backend
-        .lock()
-        .map(|backend| backend.as_str() == "legacy")
-        .unwrap_or(false);
-    state
-        .overlay_enabled
-        .store(restore_legacy, Ordering::Relaxed);
-    state.controls_open.store(false, Ordering::Relaxed);
-    state.window_visible.store(false, Ordering::Relaxed);
-    if let Ok(mut last_rect) = state.last_window_rect.lock() {
-        *last_rect = None;
-    }
-    request_hide_game_overlay_window(&app, true);
-    let _ = app.emit_to(
-        GAME_OVERLAY_WINDOW_LABEL,
-        "overlay://controls-open-changed",
-        false,
-    );
-    set_game_overlay_debug(
-        &app,
-        "game overlay selector hidden; OBS capture remains armed",
-    );
-    Ok(true)
+fn get_launch_hooks(app: tauri::AppHandle) -> Result<launch_hooks::HookSettings, String> {
+    launch_hooks::load_settings(&app)
+}
+
+#[tauri::command]
+fn set_launch_hooks(
+    app: tauri::AppHandle,
+    settings: launch_hooks::HookSettings,
+) -> Result<launch_hooks::HookSettings, String> {
+    launch_hooks::save_settings(&app, &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+fn get_launch_profiles(app: tauri::AppHandle) -> Result<launch_profiles::LaunchProfiles, String> {
+    launch_profiles::load(&app)
+}
+
+#[tauri::command]
+fn set_launch_profiles(
+    app: tauri::AppHandle,
+    profiles: launch_profiles::LaunchProfiles,
+) -> Result<launch_profiles::LaunchProfiles, String> {
+    launch_profiles::save(&app, &profiles)?;
+    Ok(profiles)
 }
 
 #[tauri::command]
-fn open_game_overlay_modules_folder(app: tauri::AppHandle) -> Result<bool, String> {
-    ensure_default_game_overlay_modules(&app)?;
-    let dir = game_overlay_module_dir(&app)?;
-    open_folder_path(&dir)?;
-    Ok(true)
+fn export_launch_profile_code(app: tauri::AppHandle, id: String) -> Result<String, String> {
+    let profiles = launch_profiles::load(&app)?;
+    let profile = profiles
+        .get(&id)
+        .ok_or_else(|| format!("launch profile `{id}` not found"))?;
+    launch_profiles::to_code(profile)
 }
 
 #[tauri::command]
-fn set_game_overlay_controls_open(
+fn import_launch_profile_code(
     app: tauri::AppHandle,
-    state: State<'_, GameOverlayState>,
-    open: bool,
-) -> Result<bool, String> {
-    set_game_overlay_controls_open_inner(&app, &state, open)
+    code: String,
+) -> Result<launch_profiles::LaunchProfile, String> {
+    import_launch_profile(&app, launch_profiles::from_code(&code)?)
+}
+
+fn import_launch_profile(
+    app: &tauri::AppHandle,
+    profile: launch_profiles::LaunchProfile,
+) -> Result<launch_profiles::LaunchProfile, String> {
+    let mut profiles = launch_profiles::load(app)?;
+    let imported = profiles.import(profile)?.clone();
+    launch_profiles::save(app, &profiles)?;
+    Ok(imported)
 }
 
 #[tauri::command]
-fn suspend_game_overlay_controls_for_focus_loss(
+fn list_bepinex_log_sessions(
     app: tauri::AppHandle,
-    state: State<'_, GameOverlayState>,
-) -> Result<bool, String> {
-    suspend_game_overlay_controls_for_focus_loss_inner(&app, &state)
+) -> Result<Vec<bepinex_log::LogSessionInfo>, String> {
+    bepinex_log::list_sessions(&app)
 }
 
 #[tauri::command]
-fn set_game_overlay_file_dialog_active(
-    state: State<'_, GameOverlayState>,
-    active: bool,
-) -> Result<bool, String> {
-    state.file_dialog_active.store(active, Ordering::Relaxed);
-    Ok(active)
+fn get_bepinex_log_session(
+    app: tauri::AppHandle,
+    session_id: String,
+) -> Result<bepinex_log::LogSessionInfo, String> {
+    bepinex_log::get_session(&app, &session_id)
 }
 
 #[tauri::command]
-fn set_game_overlay_input_shortcuts(
+fn get_bepinex_log_records(
     app: tauri::AppHandle,
-    state: State<'_, GameOverlayState>,
-    shortcuts: Vec<String>,
-) -> Result<bool, String> {
-    let overlay_key = read_game_overlay_config(&app)
-        .map(|cfg| cfg.general.overlay_key)
-        .unwrap_or_else(|_| "Insert".to_string());
-    let next: HashSet<String> = shortcuts
-        .into_iter()
-        .map(|shortcut| sanitize_overlay_key(&shortcut))
-        .filter(|shortcut| !shortcut.is_empty())
-        .filter(|shortcut| !shortcut.eq_ignore_ascii_case(&overlay_key))
-        .collect();
+    session_id: String,
+    filter: Option<bepinex_log::RecordFilter>,
+) -> Result<Vec<bepinex_log::LogRecord>, String> {
+    let filter = filter.unwrap_or_default();
+    let mut records = bepinex_log::read_records(&app, &session_id)?;
+    records.retain(|record| filter.matches(record));
+    Ok(records)
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let mut registered = state.registered_overlay_input_shortcuts.lock().unwrap();
-        *registered = next.clone();
-        state
-            .overlay_input_down_shortcuts
-            .lock()
-            .unwrap()
-            .retain(|shortcut| next.contains(shortcut));
-        start_game_overlay_input_monitor(&app);
-        return Ok(true);
-    }
+#[tauri::command]
+fn query_launch_history(
+    app: tauri::AppHandle,
+    query: Option<launch_history::HistoryQuery>,
+) -> Result<launch_history::HistoryPage, String> {
+    let records = launch_history::load(&app)?;
+    Ok(launch_history::query(&records, &query.unwrap_or_default()))
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let mut registered = state.registered_overlay_input_shortcuts.lock().unwrap();
-        let previous = registered.clone();
+#[tauri::command]
+fn get_launch_lockfile(
+    app: tauri::AppHandle,
+    hash: String,
+) -> Result<Vec<instance_archive::LockedMod>, String> {
+    launch_history::load_lockfile(&app, &hash)
+}
 
-        for shortcut in previous.difference(&next) {
-            let parsed = overlay_shortcuts_for_key(shortcut);
-            if !parsed.is_empty() {
-                if let Err(e) = app.global_shortcut().unregister_multiple(parsed) {
-                    log::warn!("Failed to unregister overlay input shortcut {shortcut}: {e}");
-                }
-            }
-        }
+/// Write the records matching `query` to `dest_path` as `csv` or `json`.
+#[tauri::command]
+fn export_launch_history(
+    app: tauri::AppHandle,
+    query: Option<launch_history::HistoryQuery>,
+    format: String,
+    dest_path: String,
+) -> Result<usize, String> {
+    let records = launch_history::load(&app)?;
+    let page = launch_history::query(&records, &query.unwrap_or_default());
+    let text = match format.to_ascii_lowercase().as_str() {
+        "csv" => launch_history::to_csv(&page.records),
+        "json" => serde_json::to_string_pretty(&page.records).map_err(|e| e.to_string())?,
+        other => return Err(format!("unsupported export format: {other}")),
+    };
+    std::fs::write(&dest_path, text).map_err(|e| e.to_string())?;
+    Ok(page.records.len())
+}
 
-        for shortcut in next.difference(&previous) {
-            let parsed = overlay_shortcuts_for_key(shortcut);
-            if parsed.is_empty() {
-                log::warn!("Unsupported overlay input shortcut: {shortcut}");
-                continue;
-            }
-            let shortcut_for_handler = shortcut.clone();
-            if let Err(e) =
-                app.global_shortcut()
-                    .on_shortcuts(parsed, move |app, _shortcut, event| {
-                        emit_game_overlay_input_shortcut(
-                            app,
-                            &shortcut_for_handler,
-                            event.state,
-                            "module-key",
-                        );
-                    })
-            {
-                log::warn!("Failed to register overlay input shortcut {shortcut}: {e}");
-            }
-        }
+#[tauri::command]
+fn list_crash_reports(app: tauri::AppHandle) -> Result<Vec<crash_report::ExitReport>, String> {
+    crash_report::list_bundles(&app)
+}
 
-        *registered = next;
-        Ok(true)
+#[cfg(target_os = "linux")]
+fn doctor_wine_prefix(app: &tauri::AppHandle, version: u32) -> Option<std::path::PathBuf> {
+    runtime_prefix_for_version(app, version)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn doctor_wine_prefix(_app: &tauri::AppHandle, _version: u32) -> Option<std::path::PathBuf> {
+    None
+}
+
+fn doctor_context(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+    version: u32,
+    wine_prefix: Option<std::path::PathBuf>,
+) -> Result<doctor::DoctorContext, String> {
+    let game_running = {
+        let mut guard = state
+            .active
+            .lock()
+            .map_err(|_| "game state lock poisoned".to_string())?;
+        cleanup_active_games(app, &mut guard)?;
+        guard.iter().any(|active| active.version == version)
+    };
+    Ok(doctor::DoctorContext {
+        version,
+        game_dir: version_dir(app, version)?,
+        game_running,
+        wine_prefix,
+    })
+}
+
+async fn apply_doctor_fix(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+    ctx: &doctor::DoctorContext,
+    fix: doctor::Fix,
+) -> Result<(), String> {
+    match fix {
+        doctor::Fix::RestoreProxyBackup => doctor::restore_proxy_backups(&ctx.game_dir),
+        doctor::Fix::RemoveProxyBackup => doctor::remove_proxy_backups(&ctx.game_dir),
+        doctor::Fix::RestoreBepInExFiles => {
+            installer::restore_missing_bepinex_files(app, ctx.version).await
+        }
+        doctor::Fix::ReapplyModStates => apply_disabled_mods_for_version(app, ctx.version),
+        doctor::Fix::ResetWinePrefix => {
+            let Some(prefix) = ctx.wine_prefix.clone() else {
+                return Ok(());
+            };
+            // Other versions can share the prefix, so this version not running is not enough.
+            ensure_wine_prefix_not_in_use(app, state, &prefix)?;
+            tauri::async_runtime::spawn_blocking(move || wine_prefixes::reset(&prefix))
+                .await
+                .map_err(|e| e.to_string())??;
+            log::info!("Reset the Wine prefix of v{}", ctx.version);
+            Ok(())
+        }
     }
 }
 
-#[tauri::command]
-fn show_game_overlay_end_summary(
-    app: tauri::AppHandle,
-    mut payload: GameOverlayEndSummaryPayload,
-) -> Result<bool, String> {
-    if !app
-        .state::<GameOverlayState>()
-        .overlay_enabled
-        .load(Ordering::Relaxed)
-    {
-        return Ok(false);
+/// Before a launch: put back proxy DLLs an interrupted run left renamed, report
+/// anything else, and refuse to start a run the checks say cannot work.
+fn run_pre_launch_doctor(
+    app: &tauri::AppHandle,
+    state: &State<'_, GameState>,
+    version: u32,
+    loads_mods: bool,
+    wine_prefix: Option<&str>,
+) -> Result<(), String> {
+    let ctx = doctor_context(
+        app,
+        state,
+        version,
+        wine_prefix.map(std::path::PathBuf::from),
+    )?;
+    let mut report = doctor::run(&ctx, loads_mods);
+    let restore_backups = report
+        .checks
+        .iter()
+        .any(|check| check.fix == Some(doctor::Fix::RestoreProxyBackup));
+    if restore_backups && !ctx.game_running {
+        match doctor::restore_proxy_backups(&ctx.game_dir) {
+            Ok(()) => report = doctor::run(&ctx, loads_mods),
+            Err(e) => log::warn!("Failed to restore proxy DLL backups of v{version}: {e}"),
+        }
     }
-    if payload.duration_ms.is_none() {
-        payload.duration_ms = Some(
-            read_game_overlay_config(&app)?
-                .general
-                .end_summary_duration_ms,
-        );
+    if report.status() == doctor::CheckStatus::Pass {
+        return Ok(());
     }
-    #[cfg(not(target_os = "windows"))]
-    {
-        request_game_overlay_ui_task(&app, "show overlay end summary", move |app| {
-            let window = ensure_game_overlay_window(app)?;
-            window.show().map_err(|e| e.to_string())?;
-            window.set_always_on_top(true).map_err(|e| e.to_string())?;
-            force_game_overlay_topmost(&window)?;
-            apply_game_overlay_interaction(&window, false)?;
-            app.state::<GameOverlayState>()
-                .window_visible
-                .store(true, Ordering::Relaxed);
-            set_game_overlay_frontend_active(app, true);
-            app.emit("overlay://show-end-summary", payload)
-                .map_err(|e| e.to_string())?;
-            Ok(())
-        });
-        return Ok(true);
+    for check in &report.checks {
+        if check.status != doctor::CheckStatus::Pass {
+            log::warn!("Health check {} of v{version}: {}", check.id, check.detail);
+        }
     }
-
-    #[cfg(target_os = "windows")]
-    {
-        let window = ensure_game_overlay_window(&app)?;
-        window.show().map_err(|e| e.to_string())?;
-        window.set_always_on_top(true).map_err(|e| e.to_string())?;
-        force_game_overlay_topmost(&window)?;
-        apply_game_overlay_interaction(&window, false)?;
-        app.emit("overlay://show-end-summary", payload)
-            .map_err(|e| e.to_string())?;
-        Ok(true)
+    let _ = app.emit(doctor::REPORT_EVENT, &report);
+    match report.failures().next() {
+        Some(check) => Err(format!(
+            "Health check failed: {}: {}",
+            check.title, check.detail
+        )),
+        None => Ok(()),
     }
 }
 
 #[tauri::command]
-async fn launch_game(
+fn run_doctor(
     app: tauri::AppHandle,
     version: u32,
-    event_id: Option<String>,
-    launch_options: Option<Vec<String>>,
-    launch_command_template: Option<String>,
-    allow_multiple: Option<bool>,
-    lcstats_state: State<'_, lcstats_autosheet::LcStatsAutosheetState>,
     state: State<'_, GameState>,
-    prepare_state: State<'_, PrepareState>,
-) -> Result<u32, String> {
-    wait_for_prepare_to_finish(&prepare_state, version, std::time::Duration::from_secs(30))?;
-    let has_event = event_id.as_deref().is_some_and(|id| !id.trim().is_empty());
-    let event_id_for_cfg = event_id.as_deref().unwrap_or_default().trim().to_string();
-    let event_forced_ids = event_forced_enabled_mods_for_launch(&app, version, event_id).await?;
-    let (dir, exe_path, exe_dir) = resolve_game_launch_paths(&app, version)?;
-    if has_event {
-        ensure_event_vlog_cfg(&app, version, &event_id_for_cfg)?;
-    }
-
-    let mut forced_disabled_ids = practice_mode_mod_ids();
-    forced_disabled_ids.extend(run_mode_tagged_mod_ids(version, None).await?);
-    forced_disabled_ids.push(if has_event {
-        base_vlog_mod_id()
-    } else {
-        event_vlog_mod_id()
-    });
-
-    // Non-practice launch: mode-required disabled mods win over the saved disabled list.
-    let _ = apply_effective_mod_states_for_version(
-        &app,
-        version,
-        &forced_disabled_ids,
-        &event_forced_ids,
-    );
-    // For HQoL specifically, also ensure `.old` matches disablemod.json on normal runs.
-    let _ = sync_hqol_with_disablemod_for_version(&app, version);
-    let _ = sync_vlog_with_disablemod_for_version(&app, version);
-    let _ = restore_hqol_wesley_dont_store_backup_if_present(&app);
-    let _ = ensure_reverb_trigger_fix_cfg(&app, version);
-    wait_for_mod_file_renames_to_settle();
+) -> Result<doctor::DoctorReport, String> {
+    let wine_prefix = doctor_wine_prefix(&app, version);
+    let ctx = doctor_context(&app, &state, version, wine_prefix)?;
+    Ok(doctor::run(&ctx, true))
+}
 
-    let _launch_guard = state
-        .launch_lock
-        .lock()
-        .map_err(|_| "game launch lock poisoned".to_string())?;
-    let use_native_overlay_proxy = native_overlay_proxy_enabled_for_launch(&app)?;
-    ensure_launch_compatible_with_active_games(
-        &app,
-        &state,
-        version,
-        !use_native_overlay_proxy,
-    )?;
-    if !allow_multiple.unwrap_or(false) {
-        ensure_game_not_running(&app, &state)?;
+/// Apply the given fixes in order and return the checks as they stand afterwards.
+#[tauri::command]
+async fn apply_doctor_fixes(
+    app: tauri::AppHandle,
+    version: u32,
+    fixes: Vec<doctor::Fix>,
+    state: State<'_, GameState>,
+) -> Result<doctor::DoctorReport, String> {
+    let wine_prefix = doctor_wine_prefix(&app, version);
+    let ctx = doctor_context(&app, &state, version, wine_prefix)?;
+    if ctx.game_running {
+        return Err(format!("Close v{version} before applying fixes"));
+    }
+    let mut applied = vec![];
+    for fix in fixes {
+        if applied.contains(&fix) {
+            continue;
+        }
+        apply_doctor_fix(&app, &state, &ctx, fix).await?;
+        applied.push(fix);
     }
+    Ok(doctor::run(&ctx, true))
+}
 
-    let launch_options = launch_options.unwrap_or_default();
-    let launch_command_template_for_state = launch_command_template.clone();
-    let non_native_overlay_proxy_guard =
-        prepare_native_overlay_proxy_for_launch(&dir, use_native_overlay_proxy)?;
-    let child = spawn_game_process(
-        &app,
-        &dir,
-        &exe_path,
-        &exe_dir,
-        &launch_options,
-        launch_command_template.as_deref(),
-        true,
-    )?;
-    let pid = child.id();
-    let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
-    let mut guard = state
-        .active
-        .lock()
-        .map_err(|_| "game state lock poisoned".to_string())?;
-    guard.push(ActiveGame {
-        id,
-        child,
-        version,
-        mode_label: "HQ".to_string(),
-        launch_options,
-        launch_command_template: launch_command_template_for_state,
-        _vanilla_proxy_dll_guards: None,
-        _non_native_overlay_proxy_guard: non_native_overlay_proxy_guard,
-    });
-    let lcstats_enabled = is_lcstats_enabled(&app)?;
-    lcstats_autosheet::start_for_launch(app.clone(), lcstats_enabled, &lcstats_state);
-    show_game_overlay(&app);
-    Ok(pid)
+/// What launching `request` would do, for the UI to show before the game starts.
+#[tauri::command]
+async fn preview_launch(
+    app: tauri::AppHandle,
+    request: launch_plan::LaunchRequest,
+) -> Result<launch_plan::LaunchPlan, String> {
+    Ok(build_launch_plan(&app, request).await?.plan)
 }
 
 #[tauri::command]
-async fn launch_game_vanilla(
+async fn launch_game(
     app: tauri::AppHandle,
     version: u32,
+    event_id: Option<String>,
     launch_options: Option<Vec<String>>,
     launch_command_template: Option<String>,
-    allow_multiple: Option<bool>,
-    state: State<'_, GameState>,
-    prepare_state: State<'_, PrepareState>,
-) -> Result<u32, String> {
-    wait_for_prepare_to_finish(&prepare_state, version, std::time::Duration::from_secs(30))?;
-    let (dir, exe_path, exe_dir) = resolve_game_launch_paths(&app, version)?;
-
-    let _launch_guard = state
-        .launch_lock
-        .lock()
-        .map_err(|_| "game launch lock poisoned".to_string())?;
-    ensure_launch_compatible_with_active_games(&app, &state, version, true)?;
-    if !allow_multiple.unwrap_or(false) {
-        ensure_game_not_running(&app, &state)?;
-    }
-
-    let launch_options = launch_options.unwrap_or_default();
-    let launch_command_template_for_state = launch_command_template.clone();
-    // Vanilla Run must hide both BepInEx (winhttp.dll) and the HQ overlay
-    // (version.dll) proxy DLLs so neither loads in the unmodded game.
-    let winhttp_guard = VanillaProxyDllGuard::hide(&dir, VanillaProxyDllGuard::WINHTTP)?;
-    let version_guard = VanillaProxyDllGuard::hide(&dir, VanillaProxyDllGuard::VERSION)?;
-    let child = spawn_game_process(
-        &app,
-        &dir,
-        &exe_path,
-        &exe_dir,
-        &launch_options,
-        launch_command_template.as_deref(),
-        false,
-    )?;
-    let pid = child.id();
-    let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
-    let mut guard = state
-        .active
-        .lock()
-        .map_err(|_| "game state lock poisoned".to_string())?;
-    guard.push(ActiveGame {
-        id,
-        child,
+    allow_multiple: Option<bool>,
+    profile: Option<String>,
+    lcstats_state: State<'_, lcstats_autosheet::LcStatsAutosheetState>,
+    state: State<'_, GameState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<u32, String> {
+    let request = launch_plan::LaunchRequest {
         version,
-        mode_label: "Vanilla".to_string(),
-        launch_options,
-        launch_command_template: launch_command_template_for_state,
-        // The guards restore winhttp.dll and version.dll when this active game
-        // is removed (dropped).
-        _vanilla_proxy_dll_guards: Some(vec![winhttp_guard, version_guard]),
-        _non_native_overlay_proxy_guard: None,
-    });
-    show_game_overlay(&app);
-    Ok(pid)
+        mode: launch_plan::LaunchMode::Hq,
+        event_id,
+        launch_options: launch_options.unwrap_or_default(),
+        launch_command_template,
+        allow_multiple: allow_multiple.unwrap_or(false),
+        profile,
+    };
+    launch_with_plan(&app, request, &state, &lcstats_state, &prepare_state).await
 }
 
 #[tauri::command]
-async fn launch_game_practice(
+async fn launch_game_vanilla(
     app: tauri::AppHandle,
     version: u32,
     launch_options: Option<Vec<String>>,
     launch_command_template: Option<String>,
     allow_multiple: Option<bool>,
+    profile: Option<String>,
     lcstats_state: State<'_, lcstats_autosheet::LcStatsAutosheetState>,
     state: State<'_, GameState>,
     prepare_state: State<'_, PrepareState>,
 ) -> Result<u32, String> {
-    wait_for_prepare_to_finish(&prepare_state, version, std::time::Duration::from_secs(30))?;
-    clear_event_mods_for_version(&app, version)?;
-    let (dir, exe_path, exe_dir) = resolve_game_launch_paths(&app, version)?;
-
-    // Practice run: install + enable practice mods (compatible with this game version).
-    let practice_ids = prepare_practice_mods_for_version(&app, version, None).await?;
-    let mut forced_disabled_ids = practice_mode_forced_disabled_ids();
-    forced_disabled_ids.extend(run_mode_tagged_mod_ids(version, None).await?);
-    let mut forced_enabled_ids = practice_ids.clone();
-    forced_enabled_ids.extend(practice_mode_forced_enabled_ids());
-
-    // Practice mode state wins over the saved disabled list on launch.
-    let _ = apply_effective_mod_states_for_version(
-        &app,
+    let request = launch_plan::LaunchRequest {
         version,
-        &forced_disabled_ids,
-        &forced_enabled_ids,
-    );
-    if let Ok(plugins) = plugins_dir(&app, version) {
-        let _ = sync_practice_locked_mods_for_version(&plugins);
-    }
-    let _ = restore_hqol_wesley_dont_store_backup_if_present(&app);
-    wait_for_mod_file_renames_to_settle();
-
-    let _launch_guard = state
-        .launch_lock
-        .lock()
-        .map_err(|_| "game launch lock poisoned".to_string())?;
-    let use_native_overlay_proxy = native_overlay_proxy_enabled_for_launch(&app)?;
-    ensure_launch_compatible_with_active_games(
-        &app,
-        &state,
-        version,
-        !use_native_overlay_proxy,
-    )?;
-    if !allow_multiple.unwrap_or(false) {
-        ensure_game_not_running(&app, &state)?;
-    }
+        mode: launch_plan::LaunchMode::Vanilla,
+        event_id: None,
+        launch_options: launch_options.unwrap_or_default(),
+        launch_command_template,
+        allow_multiple: allow_multiple.unwrap_or(false),
+        profile,
+    };
+    launch_with_plan(&app, request, &state, &lcstats_state, &prepare_state).await
+}
 
-    let launch_options = launch_options.unwrap_or_default();
-    let launch_command_template_for_state = launch_command_template.clone();
-    let non_native_overlay_proxy_guard =
-        prepare_native_overlay_proxy_for_launch(&dir, use_native_overlay_proxy)?;
-    let child = spawn_game_process(
-        &app,
-        &dir,
-        &exe_path,
-        &exe_dir,
-        &launch_options,
-        launch_command_template.as_deref(),
-        true,
-    )?;
-    let pid = child.id();
-    let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
-    let mut guard = state
-        .active
-        .lock()
-        .map_err(|_| "game state lock poisoned".to_string())?;
-    guard.push(ActiveGame {
-        id,
-        child,
+#[tauri::command]
+async fn launch_game_practice(
+    app: tauri::AppHandle,
+    version: u32,
+    launch_options: Option<Vec<String>>,
+    launch_command_template: Option<String>,
+    allow_multiple: Option<bool>,
+    profile: Option<String>,
+    lcstats_state: State<'_, lcstats_autosheet::LcStatsAutosheetState>,
+    state: State<'_, GameState>,
+    prepare_state: State<'_, PrepareState>,
+) -> Result<u32, String> {
+    let request = launch_plan::LaunchRequest {
         version,
-        mode_label: "Practice".to_string(),
-        launch_options,
-        launch_command_template: launch_command_template_for_state,
-        _vanilla_proxy_dll_guards: None,
-        _non_native_overlay_proxy_guard: non_native_overlay_proxy_guard,
-    });
-    let lcstats_enabled = is_lcstats_enabled(&app)?;
-    lcstats_autosheet::start_for_launch(app.clone(), lcstats_enabled, &lcstats_state);
-    show_game_overlay(&app);
-    Ok(pid)
+        mode: launch_plan::LaunchMode::Practice,
+        event_id: None,
+        launch_options: launch_options.unwrap_or_default(),
+        launch_command_template,
+        allow_multiple: allow_multiple.unwrap_or(false),
+        profile,
+    };
+    launch_with_plan(&app, request, &state, &lcstats_state, &prepare_state).await
 }
 
 #[tauri::command]
@@ -11856,152 +12949,21 @@ async fn launch_game_preset(
     launch_options: Option<Vec<String>>,
     launch_command_template: Option<String>,
     allow_multiple: Option<bool>,
+    profile: Option<String>,
     lcstats_state: State<'_, lcstats_autosheet::LcStatsAutosheetState>,
     state: State<'_, GameState>,
     prepare_state: State<'_, PrepareState>,
 ) -> Result<u32, String> {
-    wait_for_prepare_to_finish(&prepare_state, version, std::time::Duration::from_secs(30))?;
-    let has_event = event_id.as_deref().is_some_and(|id| !id.trim().is_empty());
-    let event_id_for_cfg = event_id.as_deref().unwrap_or_default().trim().to_string();
-    let event_forced_ids = event_forced_enabled_mods_for_launch(&app, version, event_id).await?;
-    // Normalize preset and map to manifest tags.
-    let tags = preset_tags_for_name(&preset);
-
-    let practice_ids = if practice {
-        // Practice run: install + enable practice mods (compatible with this game version).
-        prepare_practice_mods_for_version(&app, version, None).await?
-    } else {
-        vec![]
-    };
-
-    // Install preset-tagged mods additively (no overwrite).
-    // For practice runs, install these AFTER practice mods so preset-specific pins can win.
-    // (Practice list has its own pinning, e.g. for LethalNetworkAPI.)
-    let preset_ids =
-        prepare_tagged_mods_for_version(&app, version, &tags, "Preset Mods", None).await?;
-    // Ensure preset mods are enabled for this run (even if they overlap practice-disable rules).
-    // For practice runs we can apply immediately; for non-practice runs we must apply after
-    // `ensure_practice_mods_disabled_for_version` which rewrites disablemod.json.
-    if practice {
-        let _ = force_enable_mods_for_version(&app, version, &preset_ids);
-    }
-
-    let (dir, exe_path, exe_dir) = resolve_game_launch_paths(&app, version)?;
-    if has_event {
-        ensure_event_vlog_cfg(&app, version, &event_id_for_cfg)?;
-    }
-
-    if !practice {
-        // Non-practice run: force-disable practice mods.
-        ensure_practice_mods_disabled_for_version(&app, version)?;
-        // Re-enable preset mods for this run (Wesley includes LethalNetworkAPI which is otherwise forced off).
-        let _ = force_enable_mods_for_version(&app, version, &preset_ids);
-    }
-
-    let mut tagged_disabled_ids = run_mode_tagged_mod_ids(version, None).await?;
-    allow_eclipsed_hq_optional_mods(&preset, &mut tagged_disabled_ids);
-    let mut forced_enabled_ids = preset_ids.clone();
-    forced_enabled_ids.extend(practice_ids.clone());
-    forced_enabled_ids.extend(event_forced_ids);
-    if practice {
-        forced_enabled_ids.extend(practice_mode_forced_enabled_ids());
-    }
-    let forced_disabled_ids = if practice {
-        let mut ids = practice_mode_forced_disabled_ids();
-        ids.extend(tagged_disabled_ids);
-        ids
-    } else {
-        let mut ids = practice_mode_mod_ids();
-        ids.extend(tagged_disabled_ids);
-        ids.push(if has_event {
-            base_vlog_mod_id()
-        } else {
-            event_vlog_mod_id()
-        });
-        ids
-    };
-
-    // Mode-required state must win over the saved disabled list at launch time too.
-    let _ = apply_effective_mod_states_for_version(
-        &app,
-        version,
-        &forced_disabled_ids,
-        &forced_enabled_ids,
-    );
-    if practice {
-        if let Ok(plugins) = plugins_dir(&app, version) {
-            let _ = sync_practice_locked_mods_for_version(&plugins);
-        }
-    } else {
-        // For HQoL specifically, also ensure `.old` matches disablemod.json on normal runs.
-        let _ = sync_hqol_with_disablemod_for_version(&app, version);
-        let _ = sync_vlog_with_disablemod_for_version(&app, version);
-    }
-    let _ = ensure_reverb_trigger_fix_cfg(&app, version);
-    if is_wesley_base_run(&tags, practice) {
-        apply_wesley_hqol_dont_store_override(&app, version)?;
-    } else {
-        let _ = restore_hqol_wesley_dont_store_backup_if_present(&app);
-    }
-    if tags.iter().any(|t| t.eq_ignore_ascii_case("wesley")) {
-        let lock_moons = !practice && !tags.iter().any(|t| t.eq_ignore_ascii_case("smhq"));
-        let _ = ensure_wesley_moonscripts_cfg(&app, version, lock_moons);
-    }
-    wait_for_mod_file_renames_to_settle();
-
-    let _launch_guard = state
-        .launch_lock
-        .lock()
-        .map_err(|_| "game launch lock poisoned".to_string())?;
-    let use_native_overlay_proxy = native_overlay_proxy_enabled_for_launch(&app)?;
-    ensure_launch_compatible_with_active_games(
-        &app,
-        &state,
+    let request = launch_plan::LaunchRequest {
         version,
-        !use_native_overlay_proxy,
-    )?;
-    if !allow_multiple.unwrap_or(false) {
-        ensure_game_not_running(&app, &state)?;
-    }
-
-    let launch_options = launch_options.unwrap_or_default();
-    let launch_command_template_for_state = launch_command_template.clone();
-    let non_native_overlay_proxy_guard =
-        prepare_native_overlay_proxy_for_launch(&dir, use_native_overlay_proxy)?;
-    let child = spawn_game_process(
-        &app,
-        &dir,
-        &exe_path,
-        &exe_dir,
-        &launch_options,
-        launch_command_template.as_deref(),
-        true,
-    )?;
-    let pid = child.id();
-    let id = state.next_id.fetch_add(1, Ordering::Relaxed) + 1;
-    let mut guard = state
-        .active
-        .lock()
-        .map_err(|_| "game state lock poisoned".to_string())?;
-    let mode_label = if practice {
-        format!("{preset} Practice")
-    } else {
-        preset.clone()
+        mode: launch_plan::LaunchMode::Preset { preset, practice },
+        event_id,
+        launch_options: launch_options.unwrap_or_default(),
+        launch_command_template,
+        allow_multiple: allow_multiple.unwrap_or(false),
+        profile,
     };
-    guard.push(ActiveGame {
-        id,
-        child,
-        version,
-        mode_label,
-        launch_options,
-        launch_command_template: launch_command_template_for_state,
-        _vanilla_proxy_dll_guards: None,
-        _non_native_overlay_proxy_guard: non_native_overlay_proxy_guard,
-    });
-    let lcstats_enabled = is_lcstats_enabled(&app)?;
-    lcstats_autosheet::start_for_launch(app.clone(), lcstats_enabled, &lcstats_state);
-    show_game_overlay(&app);
-    Ok(pid)
+    launch_with_plan(&app, request, &state, &lcstats_state, &prepare_state).await
 }
 
 async fn prepare_preset_for_version(
@@ -12017,19 +12979,6 @@ async fn prepare_preset_for_version(
         return Err("Cancelled".to_string());
     }
 
-    // Wesley and Classic Moons presets: ensure companion configs exist during prepare.
-    if tags.iter().any(|t| t.eq_ignore_ascii_case("wesley")) {
-        let _ = ensure_weather_registry_cfg(app, version);
-        let lock_moons = !practice && !tags.iter().any(|t| t.eq_ignore_ascii_case("smhq"));
-        let _ = ensure_wesley_moonscripts_cfg(app, version, lock_moons);
-    }
-    if tags
-        .iter()
-        .any(|t| t.eq_ignore_ascii_case("wesley") || t.eq_ignore_ascii_case("c.moons"))
-    {
-        let _ = ensure_reverb_trigger_fix_cfg(app, version);
-    }
-
     let practice_ids = if practice {
         prepare_practice_mods_for_version(app, version, Some(cancel.clone())).await?
     } else {
@@ -12047,53 +12996,39 @@ async fn prepare_preset_for_version(
     let mod_files_detail = describe_mode_file_changes(practice, &tags);
     emit_basic_mod_files_progress(app, version, true, &mod_files_detail);
 
-    if !practice {
-        // Selecting a non-practice run should disable practice mods now (not at launch).
-        ensure_practice_mods_disabled_for_version(app, version)?;
-    }
-    // Ensure preset mods are enabled (can override practice-disable overlap).
-    let _ = force_enable_mods_for_version(app, version, &preset_ids);
-
     if cancel.load(Ordering::Relaxed) {
         return Err("Cancelled".to_string());
     }
 
-    let mut tagged_disabled_ids = run_mode_tagged_mod_ids(version, Some(&cancel)).await?;
-    allow_eclipsed_hq_optional_mods(preset, &mut tagged_disabled_ids);
-    let mut forced_enabled_ids = preset_ids.clone();
-    forced_enabled_ids.extend(practice_ids.clone());
-    if practice {
-        forced_enabled_ids.extend(practice_mode_forced_enabled_ids());
-    }
-    let forced_disabled_ids = if practice {
-        let mut ids = practice_mode_forced_disabled_ids();
-        ids.extend(tagged_disabled_ids);
-        ids
-    } else {
-        let mut ids = practice_mode_mod_ids();
-        ids.extend(tagged_disabled_ids);
-        ids.push(event_vlog_mod_id());
-        ids
-    };
-
-    // Apply the effective state for this mode now. Mode-required changes have priority.
-    let _ = apply_effective_mod_states_for_version(
-        app,
+    let mut run_mode_mods = run_mode_tagged_mod_ids(version, Some(&cancel)).await?;
+    allow_eclipsed_hq_optional_mods(preset, &mut run_mode_mods);
+    // The launch's own rules, applied now instead of at launch.
+    let request = launch_plan::LaunchRequest {
         version,
-        &forced_disabled_ids,
-        &forced_enabled_ids,
+        mode: launch_plan::LaunchMode::Preset {
+            preset: preset.to_string(),
+            practice,
+        },
+        event_id: None,
+        launch_options: vec![],
+        launch_command_template: None,
+        allow_multiple: false,
+        profile: None,
+    };
+    let plan = launch_plan::LaunchPlan::compose(
+        request,
+        launch_plan::ModInputs {
+            event_mods: vec![],
+            practice_list: practice_mode_mod_ids(),
+            practice_mods: practice_ids,
+            preset_tags: tags,
+            preset_mods: preset_ids,
+            run_mode_mods,
+        },
     );
-    if practice {
-        if let Ok(plugins) = plugins_dir(app, version) {
-            let _ = sync_practice_locked_mods_for_version(&plugins);
-        }
-    } else {
-        let _ = sync_hqol_with_disablemod_for_version(app, version);
-        let _ = sync_vlog_with_disablemod_for_version(app, version);
-    }
-    let _ = ensure_reverb_trigger_fix_cfg(app, version);
-    if !is_wesley_base_run(&tags, practice) {
-        let _ = restore_hqol_wesley_dont_store_backup_if_present(app);
+    apply_plan_mod_states(app, version, &plan);
+    for step in &plan.cfg_steps {
+        apply_launch_cfg_step(app, version, step, false).await?;
     }
     wait_for_mod_file_renames_to_settle();
     emit_basic_mod_files_progress(
@@ -12179,8 +13114,9 @@ fn get_game_status(
         .lock()
         .map_err(|_| "game state lock poisoned".to_string())?;
     let any_finished = cleanup_active_games(&app, &mut guard)?;
+    let scopes = game_process_scopes(&guard);
     let running_pid = guard.first().map(|active| {
-        linux_lingering_game_pid(&app, active.version).unwrap_or_else(|| active.child.id())
+        linux_lingering_game_pid(&app, &scopes, 0).unwrap_or_else(|| active.child.id())
     });
 
     if let Some(pid) = running_pid {
@@ -12193,9 +13129,7 @@ fn get_game_status(
     if any_finished {
         lcstats_autosheet::stop(&lcstats_state);
         hide_game_overlay(&app);
-        if let Err(e) = restore_hqol_wesley_dont_store_backup_if_present(&app) {
-            log::warn!("Failed to restore HQoL Wesley dont-store backup after exit: {e}");
-        }
+        revert_run_only_cfg_patches(&app, &guard);
     }
 
     Ok(GameStatus {
@@ -12220,9 +13154,7 @@ fn list_running_games(
             lcstats_autosheet::stop(&lcstats_state);
             hide_game_overlay(&app);
         }
-        if let Err(e) = restore_hqol_wesley_dont_store_backup_if_present(&app) {
-            log::warn!("Failed to restore HQoL Wesley dont-store backup after exit: {e}");
-        }
+        revert_run_only_cfg_patches(&app, &guard);
     }
 
     Ok(guard
@@ -12232,6 +13164,21 @@ fn list_running_games(
         .collect())
 }
 
+#[tauri::command]
+fn get_game_resource_samples(
+    state: State<'_, GameState>,
+    monitor: State<'_, resource_monitor::ResourceMonitorState>,
+) -> Result<Vec<resource_monitor::GameSample>, String> {
+    let game_ids: Vec<u64> = state
+        .active
+        .lock()
+        .map_err(|_| "game state lock poisoned".to_string())?
+        .iter()
+        .map(|active| active.id)
+        .collect();
+    Ok(monitor.latest(&game_ids))
+}
+
 #[tauri::command]
 fn stop_game_instance(
     app: tauri::AppHandle,
@@ -12252,20 +13199,20 @@ fn stop_game_instance(
     };
 
     let mut active = guard.remove(index);
+    active.stopped_by_launcher = true;
     terminate_child_process_tree(&mut active.child);
     let _ = active.child.wait();
     clear_native_overlay_runtime_for_pid(&app, active.child.id())?;
+    // Scoped to this instance, so games that keep running are left alone.
+    let mut scopes = vec![GameProcessScope::of(&active)];
+    scopes.extend(game_process_scopes(&guard));
+    terminate_linux_game_processes(&app, &scopes, 0);
 
     if guard.is_empty() {
         lcstats_autosheet::stop(&lcstats_state);
         hide_game_overlay(&app);
-        terminate_linux_game_processes_for_version(&app, active.version);
-        if let Err(e) = restore_hqol_wesley_dont_store_backup_if_present(&app) {
-            log::warn!(
-                "Failed to restore HQoL Wesley dont-store backup after stopping instance: {e}"
-            );
-        }
     }
+    revert_run_only_cfg_patches(&app, &guard);
 
     Ok(true)
 }
@@ -12286,30 +13233,25 @@ fn stop_game(
         .map_err(|_| "game state lock poisoned".to_string())?;
     if !guard.is_empty() {
         let mut active_games = std::mem::take(&mut *guard);
-        let mut versions = Vec::new();
+        let scopes = game_process_scopes(&active_games);
         for active in &mut active_games {
-            versions.push(active.version);
+            active.stopped_by_launcher = true;
             terminate_child_process_tree(&mut active.child);
         }
         for mut active in active_games {
             let _ = active.child.wait();
             clear_native_overlay_runtime_for_pid(&app, active.child.id())?;
         }
-        versions.sort_unstable();
-        versions.dedup();
-        for version in versions {
-            terminate_linux_game_processes_for_version(&app, version);
+        // Every game stops, so no scope needs protecting from another.
+        for scope in &scopes {
+            terminate_linux_game_processes(&app, std::slice::from_ref(scope), 0);
         }
         lcstats_autosheet::stop(&lcstats_state);
         hide_game_overlay(&app);
-        if let Err(e) = restore_hqol_wesley_dont_store_backup_if_present(&app) {
-            log::warn!("Failed to restore HQoL Wesley dont-store backup after stop: {e}");
-        }
+        revert_run_only_cfg_patches(&app, &[]);
         Ok(true)
     } else {
-        if let Err(e) = restore_hqol_wesley_dont_store_backup_if_present(&app) {
-            log::warn!("Failed to restore HQoL Wesley dont-store backup without active child: {e}");
-        }
+        revert_run_only_cfg_patches(&app, &[]);
         Ok(false)
     }
 }
@@ -13313,6 +14255,13 @@ fn set_bepinex_cfg_entry_for_version(
     Ok(true)
 }
 
+#[tauri::command]
+fn list_applied_cfg_patches(
+    app: tauri::AppHandle,
+) -> Result<Vec<cfg_patches::AppliedCfgPatch>, String> {
+    cfg_patches::applied_patches(&app)
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct WriteConfigArgs {
     rel_path: String,
@@ -13547,21 +14496,279 @@ fn set_release_channel(
     Ok(channel.into_dto())
 }
 
+/// Results of links and command lines, kept for a UI that was not listening yet,
+/// and the links waiting for the user to confirm them.
+#[derive(Default)]
+struct DeepLinkState {
+    handled: Mutex<Vec<deep_link::HandledRequest>>,
+    pending: Mutex<Vec<deep_link::PendingRequest>>,
+    next_pending_id: AtomicU64,
+}
+
+const MAX_KEPT_DEEP_LINK_RESULTS: usize = 20;
+
+fn launch_mode_for_run_mode(run_mode: &str) -> Result<launch_plan::LaunchMode, String> {
+    match run_mode {
+        "hq" => Ok(launch_plan::LaunchMode::Hq),
+        "vanilla" => Ok(launch_plan::LaunchMode::Vanilla),
+        "practice" => Ok(launch_plan::LaunchMode::Practice),
+        _ => {
+            let (preset, practice) = match run_mode.strip_suffix("_practice") {
+                Some(preset) => (preset, true),
+                None => (run_mode, false),
+            };
+            if preset_tags_for_name(preset).is_empty() {
+                return Err(format!("unknown run mode: {run_mode}"));
+            }
+            Ok(launch_plan::LaunchMode::Preset {
+                preset: preset.to_string(),
+                practice,
+            })
+        }
+    }
+}
+
+async fn route_external_request(
+    app: &tauri::AppHandle,
+    request: &deep_link::ExternalRequest,
+    from_link: bool,
+) -> Result<(), String> {
+    match request.clone() {
+        deep_link::ExternalRequest::Launch {
+            version,
+            mode,
+            event_id,
+            profile,
+        } => {
+            let request = launch_plan::LaunchRequest {
+                version,
+                mode: launch_mode_for_run_mode(&mode)?,
+                event_id,
+                launch_options: vec![],
+                launch_command_template: None,
+                allow_multiple: false,
+                profile,
+            };
+            let state = app.state::<GameState>();
+            let lcstats_state = app.state::<lcstats_autosheet::LcStatsAutosheetState>();
+            let prepare_state = app.state::<PrepareState>();
+            launch_with_plan(app, request, &state, &lcstats_state, &prepare_state).await?;
+        }
+        deep_link::ExternalRequest::PrepareEvent { version, event_id } => {
+            prepare_event(app.clone(), version, event_id, app.state()).await?;
+        }
+        deep_link::ExternalRequest::OpenConfig { version, path } => {
+            let base = match version {
+                Some(version) => version_config_dir(app, version)?,
+                None => shared_config_dir(app)?,
+            };
+            let rel = std::path::Path::new(&path);
+            if !is_safe_rel_path(rel) {
+                return Err("invalid path".to_string());
+            }
+            if !base.join(rel).is_file() {
+                return Err(format!("config file not found: {path}"));
+            }
+            // The editor lives in the UI; the path is checked before it gets there.
+            let _ = app.emit(deep_link::OPEN_CONFIG_EVENT, request);
+        }
+        deep_link::ExternalRequest::ImportProfile { code } => {
+            let mut profile = launch_profiles::from_code(&code)?;
+            // A profile shared by link keeps its name and prefix; commands only come
+            // along when the user pastes the code in the profile editor.
+            if from_link {
+                profile.strip_commands();
+            }
+            import_launch_profile(app, profile)?;
+        }
+    }
+    Ok(())
+}
+
+/// The profile a held link would launch with or import, for the confirmation.
+fn external_request_profile(
+    app: &tauri::AppHandle,
+    request: &deep_link::ExternalRequest,
+) -> Result<(Option<launch_profiles::LaunchProfile>, bool), String> {
+    match request {
+        deep_link::ExternalRequest::Launch {
+            version,
+            mode,
+            profile,
+            ..
+        } => {
+            let profiles = launch_profiles::load(app)?;
+            let profile = profiles.resolve(profile.as_deref(), *version, mode)?;
+            Ok((profile.cloned(), false))
+        }
+        deep_link::ExternalRequest::ImportProfile { code } => {
+            let mut profile = launch_profiles::from_code(code)?;
+            let stripped = profile.strip_commands();
+            Ok((Some(profile), stripped))
+        }
+        _ => Ok((None, false)),
+    }
+}
+
+fn record_external_result(
+    app: &tauri::AppHandle,
+    input: String,
+    request: Option<deep_link::ExternalRequest>,
+    result: Result<(), String>,
+) {
+    match &result {
+        Ok(()) => log::info!("Handled external request: {input}"),
+        Err(e) => log::warn!("External request `{input}` failed: {e}"),
+    }
+    let handled = deep_link::HandledRequest {
+        input,
+        request,
+        error: result.err(),
+    };
+    if let Ok(mut kept) = app.state::<DeepLinkState>().handled.lock() {
+        kept.push(handled.clone());
+        let excess = kept.len().saturating_sub(MAX_KEPT_DEEP_LINK_RESULTS);
+        kept.drain(..excess);
+    }
+    let _ = app.emit(deep_link::HANDLED_EVENT, &handled);
+}
+
+/// Hold a link until the user confirms it with `confirm_external_request`.
+fn hold_external_request(
+    app: &tauri::AppHandle,
+    input: String,
+    request: deep_link::ExternalRequest,
+) -> Result<(), String> {
+    let (profile, stripped) = external_request_profile(app, &request)?;
+    let state = app.state::<DeepLinkState>();
+    let pending = deep_link::PendingRequest {
+        id: state.next_pending_id.fetch_add(1, Ordering::Relaxed) + 1,
+        input,
+        request,
+        profile,
+        stripped,
+    };
+    log::info!("Waiting for confirmation of link: {}", pending.input);
+    state
+        .pending
+        .lock()
+        .map_err(|_| "deep link state lock poisoned".to_string())?
+        .push(pending.clone());
+    let _ = app.emit(deep_link::CONFIRM_EVENT, &pending);
+    Ok(())
+}
+
+/// Handle the links and flags in `argv` (`argv[0]` being the executable).
+fn handle_external_args(app: &tauri::AppHandle, argv: &[String]) {
+    for (input, request) in deep_link::parse_args(argv) {
+        let app = app.clone();
+        let from_link = deep_link::is_link(&input);
+        if let Ok(request) = &request {
+            if from_link && request.needs_confirmation() {
+                if let Err(e) = hold_external_request(&app, input.clone(), request.clone()) {
+                    record_external_result(&app, input, Some(request.clone()), Err(e));
+                }
+                continue;
+            }
+        }
+        tauri::async_runtime::spawn(async move {
+            let result = match &request {
+                Ok(request) => route_external_request(&app, request, from_link).await,
+                Err(e) => Err(e.clone()),
+            };
+            record_external_result(&app, input, request.ok(), result);
+        });
+    }
+}
+
+/// Links waiting for the user, oldest first.
+#[tauri::command]
+fn list_pending_external_requests(
+    state: State<'_, DeepLinkState>,
+) -> Result<Vec<deep_link::PendingRequest>, String> {
+    Ok(state
+        .pending
+        .lock()
+        .map_err(|_| "deep link state lock poisoned".to_string())?
+        .clone())
+}
+
+fn take_pending_external_request(
+    state: &DeepLinkState,
+    id: u64,
+) -> Result<deep_link::PendingRequest, String> {
+    let mut pending = state
+        .pending
+        .lock()
+        .map_err(|_| "deep link state lock poisoned".to_string())?;
+    let index = pending
+        .iter()
+        .position(|request| request.id == id)
+        .ok_or_else(|| format!("no link {id} is waiting"))?;
+    Ok(pending.remove(index))
+}
+
+/// Run a held link the user confirmed.
+#[tauri::command]
+async fn confirm_external_request(
+    app: tauri::AppHandle,
+    id: u64,
+    state: State<'_, DeepLinkState>,
+) -> Result<(), String> {
+    let pending = take_pending_external_request(&state, id)?;
+    let result = route_external_request(&app, &pending.request, true).await;
+    record_external_result(&app, pending.input, Some(pending.request), result.clone());
+    result
+}
+
+#[tauri::command]
+fn dismiss_external_request(
+    app: tauri::AppHandle,
+    id: u64,
+    state: State<'_, DeepLinkState>,
+) -> Result<(), String> {
+    let pending = take_pending_external_request(&state, id)?;
+    record_external_result(
+        &app,
+        pending.input,
+        Some(pending.request),
+        Err("dismissed".to_string()),
+    );
+    Ok(())
+}
+
+/// Results of links and command lines handled so far, oldest first; clears the list.
+#[tauri::command]
+fn take_deep_link_results(
+    state: State<'_, DeepLinkState>,
+) -> Result<Vec<deep_link::HandledRequest>, String> {
+    let mut handled = state
+        .handled
+        .lock()
+        .map_err(|_| "deep link state lock poisoned".to_string())?;
+    Ok(std::mem::take(&mut *handled))
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.unminimize();
                 let _ = window.show();
                 let _ = window.set_focus();
             }
+            // A second start, from a link or the command line, hands its arguments here.
+            handle_external_args(app, &argv);
         }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(GameState::default())
         .manage(DownloadState::default())
         .manage(PrepareState::default())
+        .manage(DeepLinkState::default())
+        .manage(resource_monitor::ResourceMonitorState::default())
         .manage(GameOverlayState::default())
         .manage(discord_presence::DiscordPresenceState::default())
         .manage(lcstats_autosheet::LcStatsAutosheetState::default())
@@ -13612,6 +14819,9 @@ pub fn run() {
                     }
                 }
             }
+            if let Err(e) = restore_download_queue_on_startup(app.handle()) {
+                log::warn!("Failed to restore the download queue: {e}");
+            }
             register_game_overlay_shortcut(app.handle());
             #[cfg(target_os = "windows")]
             start_game_overlay_input_monitor(app.handle());
@@ -13625,6 +14835,7 @@ pub fn run() {
             // - Ensure default config is downloaded if shared config dir is empty
             // - Ensure default overlay modules exist for user editing
             // - Run disablemod/security migrations when needed
+            // - Warn about version folders whose files belong to another version
             // - Warm the Thunderstore package cache for later update checks
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -13645,6 +14856,15 @@ pub fn run() {
                 if let Err(e) = installer::ensure_pack_specific_configs_on_startup(&app_handle) {
                     log::warn!("Failed to ensure pack-specific configs on startup: {e}");
                 }
+                if let Err(e) = check_installed_version_folders(app_handle.clone()).await {
+                    log::warn!("Failed to check installed version folders on startup: {e}");
+                }
+                if let Err(e) = installer::migrate_version_metadata_on_startup(&app_handle) {
+                    log::warn!("Failed to migrate version metadata on startup: {e}");
+                }
+                if let Err(e) = finish_storage_move_on_startup(&app_handle) {
+                    log::warn!("Failed to check the storage move on startup: {e}");
+                }
                 match thunderstore_cache_path(&app_handle) {
                     Ok(cache_path) => {
                         let client = reqwest::Client::new();
@@ -13658,7 +14878,12 @@ pub fn run() {
                         log::warn!("Failed to resolve Thunderstore cache path on startup: {e}");
                     }
                 }
+                // Only the bundled build is installed automatically; a runtime picked by
+                // the user is left as is.
                 #[cfg(target_os = "linux")]
+                if runtimes::load_settings(&app_handle)
+                    .map(|settings| settings.default_runtime.is_none())
+                    .unwrap_or(true)
                 {
                     if let Err(e) = installer::install_proton_ge_impl(&app_handle).await {
                         log::warn!("Failed to install Proton-GE on startup: {e}");
@@ -13666,24 +14891,79 @@ pub fn run() {
                 }
             });
 
+            // Installers register the scheme on Windows and macOS; AppImages and dev
+            // builds register it for the current user at runtime.
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("Failed to register the {} scheme: {e}", deep_link::SCHEME);
+                }
+            }
+            // macOS delivers links as events instead of process arguments.
+            #[cfg(target_os = "macos")]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let app_handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    let argv: Vec<String> = std::iter::once(String::new())
+                        .chain(event.urls().iter().map(|url| url.to_string()))
+                        .collect();
+                    handle_external_args(&app_handle, &argv);
+                });
+            }
+            handle_external_args(app.handle(), &std::env::args().collect::<Vec<_>>());
+
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             download,
             cancel_download,
+            get_partial_download_state,
+            discard_partial_download,
+            verify_version_files,
+            get_version_metadata,
+            list_version_metadata,
+            get_download_queue,
+            enqueue_downloads,
+            move_download_queue_entry,
+            pause_queued_download,
+            resume_queued_download,
+            set_download_queue_concurrency,
             prepare_preset,
             cancel_prepare,
             sync_latest_install_from_manifest,
             check_latest_install_manifest_update,
             check_mod_updates,
             apply_mod_updates,
+            get_launch_hooks,
+            set_launch_hooks,
+            get_launch_profiles,
+            set_launch_profiles,
+            export_launch_profile_code,
+            import_launch_profile_code,
+            take_deep_link_results,
+            list_pending_external_requests,
+            confirm_external_request,
+            dismiss_external_request,
+            list_bepinex_log_sessions,
+            get_bepinex_log_session,
+            get_bepinex_log_records,
+            list_crash_reports,
+            run_doctor,
+            apply_doctor_fixes,
+            query_launch_history,
+            get_launch_lockfile,
+            export_launch_history,
+            preview_launch,
             launch_game,
             launch_game_vanilla,
             launch_game_practice,
             launch_game_preset,
             get_game_status,
             list_running_games,
+            get_game_resource_samples,
             stop_game,
             stop_game_instance,
             get_disabled_mods,
@@ -13728,6 +15008,7 @@ pub fn run() {
             read_bepinex_cfg_for_version,
             set_bepinex_cfg_entry,
             set_bepinex_cfg_entry_for_version,
+            list_applied_cfg_patches,
             write_config_file,
             get_steam_overlay_config,
             set_steam_overlay_config,
@@ -13754,7 +15035,13 @@ pub fn run() {
             downloader::depot_login_start,
             downloader::depot_login_submit_code,
             downloader::depot_get_login_state,
+            downloader::depot_login_qr_start,
+            downloader::depot_login_qr_cancel,
             downloader::depot_logout,
+            downloader::depot_list_accounts,
+            downloader::depot_add_account,
+            downloader::depot_switch_account,
+            downloader::depot_remove_account,
             downloader::depot_download,
             downloader::depot_download_files,
             check_app_update,
@@ -13772,9 +15059,36 @@ pub fn run() {
             set_selected_version,
             pick_game_storage_dir,
             set_game_storage_dir,
+            get_pending_storage_move,
+            resume_storage_move,
+            discard_storage_move,
             installer::install_proton_ge,
             installer::get_current_proton_dir,
+            list_runtimes,
+            get_runtime_settings,
+            set_runtime_settings,
+            get_wine_prefix_settings,
+            set_wine_prefix_settings,
+            list_wine_prefixes,
+            create_wine_prefix,
+            reset_wine_prefix,
+            clone_wine_prefix,
+            delete_wine_prefix,
+            list_proton_ge_releases,
+            install_proton_ge_release,
+            remove_proton_ge_release,
             delete_installed_version,
+            get_storage_dedup_report,
+            detect_steam_installs,
+            import_steam_install,
+            identify_game_folder,
+            check_installed_version_folders,
+            pick_instance_archive_path,
+            export_version_instance,
+            import_version_instance,
+            dedup_installed_versions,
+            get_disk_usage,
+            cleanup_disk_usage,
             open_custom_layout_docs,
             open_external_url,
             open_version_folder,
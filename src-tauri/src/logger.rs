@@ -36,6 +36,13 @@ fn log_paths(app: &tauri::AppHandle) -> Result<(PathBuf, PathBuf), AnyError> {
     Ok((logs_dir, log_file))
 }
 
+/// The current launcher log, e.g. for diagnostic bundles.
+pub fn log_file(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    log_paths(app)
+        .map(|(_, log_file)| log_file)
+        .map_err(|e| e.to_string())
+}
+
 fn startup_housekeeping(logs_dir: &std::path::Path, log_file: &std::path::Path) {
     if let Ok(metadata) = std::fs::metadata(log_file) {
         if metadata.len() > MAX_LOG_FILE_BYTES {
@@ -142,6 +142,10 @@ pub struct RemoteManifest {
     pub preset_tag_constraints: BTreeMap<String, TagConstraint>,
     pub chain_config: Vec<Vec<String>>,
     pub mods: Vec<ModEntry>,
+    #[serde(default)]
+    pub cfg_patches: Vec<crate::cfg_patches::CfgPatch>,
+    #[serde(default)]
+    pub fingerprints: Vec<crate::fingerprint::VersionFingerprint>,
 }
 
 impl ModsConfig {
@@ -176,6 +180,52 @@ impl ModsConfig {
         client: &reqwest::Client,
         cancel: Option<&Arc<AtomicBool>>,
     ) -> Result<ManifestFetchResult, String> {
+        Ok(Self::load_manifest(client, cancel)
+            .await?
+            .into_fetch_result())
+    }
+
+    /// Declarative cfg edits from the same manifest as [`Self::fetch_manifest`].
+    pub async fn fetch_cfg_patches(
+        client: &reqwest::Client,
+    ) -> Result<Vec<crate::cfg_patches::CfgPatch>, String> {
+        Ok(Self::load_manifest(client, None).await?.cfg_patches)
+    }
+
+    /// Like [`Self::fetch_cfg_patches`], but reuses the last remote manifest of this
+    /// session whatever its age; launches already loaded it while preparing mods.
+    pub async fn cached_cfg_patches(
+        client: &reqwest::Client,
+    ) -> Result<Vec<crate::cfg_patches::CfgPatch>, String> {
+        let channel = crate::release_channel::current();
+        let cached = {
+            let (cache_lock, _) = remote_manifest_cache();
+            let cache = cache_lock
+                .lock()
+                .map_err(|_| "remote manifest cache lock poisoned".to_string())?;
+            cache
+                .cached
+                .as_ref()
+                .filter(|cached| cached.channel == channel)
+                .map(|cached| cached.manifest.cfg_patches.clone())
+        };
+        match cached {
+            Some(patches) => Ok(patches),
+            None => Self::fetch_cfg_patches(client).await,
+        }
+    }
+
+    /// Known key-file hashes per game version, see `fingerprint.rs`.
+    pub async fn fetch_fingerprints(
+        client: &reqwest::Client,
+    ) -> Result<Vec<crate::fingerprint::VersionFingerprint>, String> {
+        Ok(Self::load_manifest(client, None).await?.fingerprints)
+    }
+
+    async fn load_manifest(
+        client: &reqwest::Client,
+        cancel: Option<&Arc<AtomicBool>>,
+    ) -> Result<RemoteManifest, String> {
         // Test mode: if a local `manifest.json` exists next to the repo/current folder,
         // prefer it over the remote manifest. This enables rapid iteration without publishing.
         fn try_read_local_manifest() -> Option<(std::path::PathBuf, RemoteManifest)> {
@@ -207,14 +257,12 @@ impl ModsConfig {
             None
         }
 
-        let manifest = if let Some((path, mf)) = try_read_local_manifest() {
+        if let Some((path, mf)) = try_read_local_manifest() {
             log::info!("Using local manifest: {}", path.to_string_lossy());
-            mf
+            Ok(mf)
         } else {
-            Self::fetch_remote_manifest_cached(client, cancel).await?
-        };
-
-        Ok(manifest.into_fetch_result())
+            Self::fetch_remote_manifest_cached(client, cancel).await
+        }
     }
 
     async fn fetch_remote_manifest_cached(
@@ -0,0 +1,472 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+// Moving the versions folder to another volume copies every game file. The move is
+// journaled so a crash or a closed launcher resumes where it stopped, and the old
+// folder is only removed after the copy is verified and the storage setting points
+// at the new one. Links (the shared BepInEx config junction) are recreated, never
+// copied through. The dedup content store is left out: its links do not survive a
+// copy across volumes, so the launcher deduplicates the versions again afterwards.
+
+const TMP_SUFFIX: &str = ".hq_move_tmp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelocationPhase {
+    Copying,
+    Verifying,
+    /// Copy verified; the storage setting is being switched to `to`.
+    Switching,
+    RemovingSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelocationJournal {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    /// Storage setting applied once the copy is verified (`None` = default location).
+    pub custom_dir: Option<PathBuf>,
+    pub verify_hashes: bool,
+    pub phase: RelocationPhase,
+    pub started_at: u64,
+    /// Why the last attempt stopped, if it did.
+    #[serde(default)]
+    pub stopped: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EntryKind {
+    Dir,
+    File(u64),
+    Link(PathBuf),
+}
+
+impl RelocationJournal {
+    pub fn new(from: &Path, to: &Path, custom_dir: Option<PathBuf>, verify_hashes: bool) -> Self {
+        Self {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+            custom_dir,
+            verify_hashes,
+            phase: RelocationPhase::Copying,
+            started_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            stopped: None,
+        }
+    }
+
+    /// The copy is verified, so finishing the move only switches storage and removes
+    /// the old folder.
+    pub fn is_verified(&self) -> bool {
+        matches!(
+            self.phase,
+            RelocationPhase::Switching | RelocationPhase::RemovingSource
+        )
+    }
+
+    /// Record why the move stopped so it can be shown until it is resumed or discarded.
+    pub fn mark_stopped(&mut self, path: &Path, reason: String) -> Result<(), String> {
+        self.stopped = Some(reason);
+        self.save(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+    }
+}
+
+fn dir_has_any_entries(path: &Path) -> bool {
+    std::fs::read_dir(path)
+        .ok()
+        .and_then(|mut rd| rd.next())
+        .is_some()
+}
+
+/// Everything under `root` keyed by relative path, without following links. Parents
+/// sort before their children.
+fn walk(root: &Path) -> Result<BTreeMap<PathBuf, EntryKind>, String> {
+    fn visit(
+        root: &Path,
+        dir: &Path,
+        out: &mut BTreeMap<PathBuf, EntryKind>,
+    ) -> Result<(), String> {
+        for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
+            let name = entry.file_name();
+            // The content store is rebuilt after the move.
+            if (dir == root && name == crate::dedup_store::STORE_DIR_NAME)
+                || name.to_string_lossy().ends_with(TMP_SUFFIX)
+            {
+                continue;
+            }
+            let rel = path
+                .strip_prefix(root)
+                .map_err(|e| e.to_string())?
+                .to_path_buf();
+            let meta = std::fs::symlink_metadata(&path).map_err(|e| e.to_string())?;
+            let file_type = meta.file_type();
+            if file_type.is_symlink() {
+                let target = std::fs::read_link(&path).map_err(|e| e.to_string())?;
+                out.insert(rel, EntryKind::Link(target));
+            } else if file_type.is_dir() {
+                out.insert(rel, EntryKind::Dir);
+                visit(root, &path, out)?;
+            } else if file_type.is_file() {
+                out.insert(rel, EntryKind::File(meta.len()));
+            }
+        }
+        Ok(())
+    }
+
+    let mut out = BTreeMap::new();
+    visit(root, root, &mut out)?;
+    Ok(out)
+}
+
+#[cfg(unix)]
+fn make_link(target: &Path, link: &Path) -> Result<(), String> {
+    std::os::unix::fs::symlink(target, link).map_err(|e| e.to_string())
+}
+
+#[cfg(windows)]
+fn make_link(target: &Path, link: &Path) -> Result<(), String> {
+    // `read_link` reports junction targets in verbatim form, which `mklink` rejects.
+    let target_s = target.to_string_lossy();
+    let target = Path::new(target_s.strip_prefix(r"\\?\").unwrap_or(&target_s));
+    if target.is_dir() {
+        crate::installer::create_dir_junction(link, target)
+    } else {
+        std::os::windows::fs::symlink_file(target, link).map_err(|e| e.to_string())
+    }
+}
+
+/// Links into the tree being moved follow it; links elsewhere keep their target.
+fn relink_target(journal: &RelocationJournal, target: &Path) -> PathBuf {
+    match target.strip_prefix(&journal.from) {
+        Ok(rel) if target.is_absolute() => journal.to.join(rel),
+        _ => target.to_path_buf(),
+    }
+}
+
+fn copy_tree<F>(journal: &RelocationJournal, on_progress: &mut F) -> Result<(), String>
+where
+    F: FnMut(u64, u64, Option<String>),
+{
+    let entries = walk(&journal.from)?;
+    let total = (entries.len() as u64).max(1);
+    std::fs::create_dir_all(&journal.to).map_err(|e| e.to_string())?;
+
+    for (done, (rel, kind)) in entries.iter().enumerate() {
+        let src = journal.from.join(rel);
+        let dest = journal.to.join(rel);
+        let existing = std::fs::symlink_metadata(&dest).ok();
+        let name = rel
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match kind {
+            EntryKind::Dir => {
+                std::fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+            }
+            // Files are copied under a temporary name and renamed into place, so a
+            // full-size file at the destination is a finished copy from an earlier run.
+            EntryKind::File(len) => {
+                let finished = existing
+                    .as_ref()
+                    .is_some_and(|m| m.file_type().is_file() && m.len() == *len);
+                if !finished {
+                    let mut tmp_name = dest.file_name().unwrap_or_default().to_os_string();
+                    tmp_name.push(TMP_SUFFIX);
+                    let tmp = dest.with_file_name(tmp_name);
+                    std::fs::copy(&src, &tmp).map_err(|e| {
+                        format!(
+                            "failed to copy {} to {}: {e}",
+                            src.to_string_lossy(),
+                            dest.to_string_lossy()
+                        )
+                    })?;
+                    std::fs::rename(&tmp, &dest).map_err(|e| e.to_string())?;
+                }
+            }
+            EntryKind::Link(target) => {
+                if existing.is_none() {
+                    make_link(&relink_target(journal, target), &dest).map_err(|e| {
+                        format!("failed to recreate link {}: {e}", dest.to_string_lossy())
+                    })?;
+                }
+            }
+        }
+        on_progress(done as u64 + 1, total, Some(format!("Copying {name}")));
+    }
+    Ok(())
+}
+
+/// Compare the copy against the source. Mismatched files are removed from the
+/// destination so the next copy pass replaces them.
+fn verify_tree<F>(journal: &RelocationJournal, on_progress: &mut F) -> Result<Vec<String>, String>
+where
+    F: FnMut(u64, u64, Option<String>),
+{
+    let source = walk(&journal.from)?;
+    let copy = walk(&journal.to)?;
+    let total = (source.len() as u64).max(1);
+    let mut mismatched = Vec::new();
+
+    for (done, (rel, kind)) in source.iter().enumerate() {
+        let ok = match (kind, copy.get(rel)) {
+            (EntryKind::Dir, Some(EntryKind::Dir)) => true,
+            (EntryKind::Link(_), Some(EntryKind::Link(_))) => true,
+            (EntryKind::File(a), Some(EntryKind::File(b))) if a == b => {
+                !journal.verify_hashes
+                    || crate::dedup_store::hash_file(&journal.from.join(rel))?
+                        == crate::dedup_store::hash_file(&journal.to.join(rel))?
+            }
+            _ => false,
+        };
+        if !ok {
+            if let Some(EntryKind::File(_)) = copy.get(rel) {
+                let _ = std::fs::remove_file(journal.to.join(rel));
+            }
+            mismatched.push(rel.to_string_lossy().to_string());
+        }
+        on_progress(
+            done as u64 + 1,
+            total,
+            Some(format!("Verifying {}", rel.to_string_lossy())),
+        );
+    }
+    Ok(mismatched)
+}
+
+/// Check that a new move from `from` to `to` can start.
+pub fn check_can_start(journal_path: &Path, to: &Path) -> Result<(), String> {
+    if journal_path.exists() {
+        return Err(
+            "a previous storage move did not finish; resume or discard it first".to_string(),
+        );
+    }
+    if to.exists() && dir_has_any_entries(to) {
+        return Err(format!(
+            "target versions folder is not empty: {}",
+            to.to_string_lossy()
+        ));
+    }
+    Ok(())
+}
+
+/// Run (or resume) the move described by `journal`. `switch_storage` points the
+/// launcher at the new folder; it runs once the copy is verified and may run again
+/// when resuming, so it must be idempotent.
+pub fn run<F, S>(
+    journal_path: &Path,
+    mut journal: RelocationJournal,
+    mut on_progress: F,
+    mut switch_storage: S,
+) -> Result<(), String>
+where
+    F: FnMut(u64, u64, Option<String>),
+    S: FnMut(&RelocationJournal) -> Result<(), String>,
+{
+    journal.stopped = None;
+    let res = run_phases(
+        journal_path,
+        &mut journal,
+        &mut on_progress,
+        &mut switch_storage,
+    );
+    if let Err(e) = &res {
+        if journal_path.exists() {
+            let _ = journal.mark_stopped(journal_path, e.clone());
+        }
+    }
+    res
+}
+
+fn run_phases<F, S>(
+    journal_path: &Path,
+    journal: &mut RelocationJournal,
+    on_progress: &mut F,
+    switch_storage: &mut S,
+) -> Result<(), String>
+where
+    F: FnMut(u64, u64, Option<String>),
+    S: FnMut(&RelocationJournal) -> Result<(), String>,
+{
+    journal.save(journal_path)?;
+
+    if journal.phase == RelocationPhase::Copying {
+        if !journal.from.exists() {
+            std::fs::create_dir_all(&journal.to).map_err(|e| e.to_string())?;
+            journal.phase = RelocationPhase::Switching;
+        } else {
+            if let Some(parent) = journal.to.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            if journal.to.exists() && !dir_has_any_entries(&journal.to) {
+                let _ = std::fs::remove_dir(&journal.to);
+            }
+            on_progress(0, 1, Some("Preparing game files...".to_string()));
+            // A rename on the same volume is atomic and needs no verification.
+            if std::fs::rename(&journal.from, &journal.to).is_ok() {
+                journal.phase = RelocationPhase::Switching;
+            } else {
+                copy_tree(journal, on_progress)?;
+                journal.phase = RelocationPhase::Verifying;
+            }
+        }
+        journal.save(journal_path)?;
+    }
+
+    if journal.phase == RelocationPhase::Verifying {
+        let mismatched = verify_tree(journal, on_progress)?;
+        if !mismatched.is_empty() {
+            journal.phase = RelocationPhase::Copying;
+            journal.save(journal_path)?;
+            return Err(format!(
+                "{} copied files did not match the originals (first: {}); resume the move to copy them again",
+                mismatched.len(),
+                mismatched[0]
+            ));
+        }
+        journal.phase = RelocationPhase::Switching;
+        journal.save(journal_path)?;
+    }
+
+    if journal.phase == RelocationPhase::Switching {
+        switch_storage(journal)?;
+        journal.phase = RelocationPhase::RemovingSource;
+        journal.save(journal_path)?;
+    }
+
+    on_progress(1, 1, Some("Removing old game storage...".to_string()));
+    match std::fs::remove_dir_all(&journal.from) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.to_string()),
+    }
+    std::fs::remove_file(journal_path).map_err(|e| e.to_string())
+}
+
+/// Abandon a move that has not switched storage yet, removing the partial copy.
+pub fn discard(journal_path: &Path) -> Result<bool, String> {
+    let Some(journal) = RelocationJournal::load(journal_path)? else {
+        return Ok(false);
+    };
+    if journal.is_verified() {
+        return Err(
+            "the new storage folder is already in use; resume the move to finish it".to_string(),
+        );
+    }
+    if journal.to.exists() {
+        std::fs::remove_dir_all(&journal.to).map_err(|e| e.to_string())?;
+    }
+    std::fs::remove_file(journal_path).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn populate(from: &Path, shared: &Path) {
+        std::fs::create_dir_all(from.join("v73/BepInEx/plugins")).unwrap();
+        std::fs::write(from.join("v73/Lethal Company.exe"), b"game").unwrap();
+        std::fs::write(from.join("v73/BepInEx/plugins/a.dll"), b"mod").unwrap();
+        std::fs::create_dir_all(shared).unwrap();
+        std::fs::write(shared.join("BepInEx.cfg"), b"cfg").unwrap();
+        make_link(shared, &from.join("v73/BepInEx/config")).unwrap();
+    }
+
+    #[test]
+    fn copy_recreates_links_and_resumes_after_interruption() {
+        let root = temp_dir("relocation_copy");
+        let (from, to, shared) = (root.join("old"), root.join("new"), root.join("shared"));
+        let journal_path = root.join("storage_move.json");
+        populate(&from, &shared);
+
+        // An earlier run copied one file and died mid-way through another.
+        std::fs::create_dir_all(to.join("v73/BepInEx/plugins")).unwrap();
+        std::fs::write(to.join("v73/Lethal Company.exe"), b"game").unwrap();
+        std::fs::write(to.join("v73/BepInEx/plugins/a.dll.hq_move_tmp"), b"m").unwrap();
+        let mut journal = RelocationJournal::new(&from, &to, Some(root.clone()), true);
+        journal.save(&journal_path).unwrap();
+
+        // Simulate a cross-volume move by running the copy and verify phases directly.
+        copy_tree(&journal, &mut |_, _, _| {}).unwrap();
+        assert!(verify_tree(&journal, &mut |_, _, _| {}).unwrap().is_empty());
+        journal.phase = RelocationPhase::Switching;
+
+        let mut switched = 0;
+        run(
+            &journal_path,
+            journal,
+            |_, _, _| {},
+            |_| {
+                switched += 1;
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(switched, 1);
+        assert!(!from.exists());
+        assert!(!journal_path.exists());
+        let link = to.join("v73/BepInEx/config");
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read(link.join("BepInEx.cfg")).unwrap(), b"cfg");
+        assert_eq!(std::fs::read(shared.join("BepInEx.cfg")).unwrap(), b"cfg");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn mismatched_copy_keeps_the_source_and_rewinds() {
+        let root = temp_dir("relocation_verify");
+        let (from, to, shared) = (root.join("old"), root.join("new"), root.join("shared"));
+        let journal_path = root.join("storage_move.json");
+        populate(&from, &shared);
+
+        let mut journal = RelocationJournal::new(&from, &to, None, true);
+        copy_tree(&journal, &mut |_, _, _| {}).unwrap();
+        std::fs::write(to.join("v73/BepInEx/plugins/a.dll"), b"bad").unwrap();
+        journal.phase = RelocationPhase::Verifying;
+
+        let err = run(&journal_path, journal, |_, _, _| {}, |_| Ok(())).unwrap_err();
+        assert!(err.contains("a.dll"));
+        assert!(from.join("v73/BepInEx/plugins/a.dll").is_file());
+        assert!(!to.join("v73/BepInEx/plugins/a.dll").exists());
+        let saved = RelocationJournal::load(&journal_path).unwrap().unwrap();
+        assert_eq!(saved.phase, RelocationPhase::Copying);
+        assert!(!saved.is_verified());
+        assert!(saved.stopped.is_some_and(|reason| reason.contains("a.dll")));
+
+        assert!(discard(&journal_path).unwrap());
+        assert!(!to.exists());
+        assert!(from.join("v73/Lethal Company.exe").is_file());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+// CPU, memory and thread use of running games. While any game runs a background
+// sampler sums the use of each game's whole process set (on Linux the Proton and
+// Wine processes found by the launcher's process tracking, on Windows the
+// launched process and its children) every `SAMPLE_INTERVAL`, emits the samples
+// as `SAMPLE_EVENT` for the overlay and keeps the latest one per game. Each run's
+// peaks are handed to the launch history when the game exits.
+
+pub const SAMPLE_EVENT: &str = "resource-monitor://sample";
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Summed over every process of one game at one instant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub processes: u32,
+    /// Total CPU time used so far, user and kernel.
+    pub cpu_time: Duration,
+    pub rss_bytes: u64,
+    pub threads: u32,
+}
+
+impl Usage {
+    fn add(&mut self, other: Usage) {
+        self.processes += other.processes;
+        self.cpu_time += other.cpu_time;
+        self.rss_bytes += other.rss_bytes;
+        self.threads += other.threads;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameSample {
+    pub game_id: u64,
+    pub version: u32,
+    pub mode_label: String,
+    pub processes: u32,
+    /// Of one core, like `top`: a game busy on four cores shows 400.
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+    pub threads: u32,
+    pub uptime_secs: u64,
+    /// Unix seconds.
+    pub sampled_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePeaks {
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+    pub threads: u32,
+    pub processes: u32,
+}
+
+impl ResourcePeaks {
+    pub fn record(&mut self, sample: &GameSample) {
+        self.cpu_percent = self.cpu_percent.max(sample.cpu_percent);
+        self.rss_bytes = self.rss_bytes.max(sample.rss_bytes);
+        self.threads = self.threads.max(sample.threads);
+        self.processes = self.processes.max(sample.processes);
+    }
+}
+
+/// What the sampler knows about a running game besides its usage.
+#[derive(Debug, Clone)]
+pub struct GameInfo {
+    pub game_id: u64,
+    pub version: u32,
+    pub mode_label: String,
+    /// Unix seconds.
+    pub started_at: u64,
+}
+
+#[derive(Debug)]
+struct Tracked {
+    last_cpu: (Instant, Duration),
+    latest: GameSample,
+    peaks: ResourcePeaks,
+}
+
+#[derive(Default)]
+pub struct ResourceMonitorState {
+    pub running: AtomicBool,
+    games: Mutex<HashMap<u64, Tracked>>,
+}
+
+impl ResourceMonitorState {
+    /// Turn `usage` into a sample, with the CPU share since the previous one.
+    pub fn record(&self, game: GameInfo, usage: Usage, at: Instant, at_unix: u64) -> GameSample {
+        let Ok(mut games) = self.games.lock() else {
+            return sample(game, usage, 0.0, at_unix);
+        };
+        let cpu_percent = games
+            .get(&game.game_id)
+            .map(|tracked| cpu_percent(tracked.last_cpu, (at, usage.cpu_time)))
+            .unwrap_or(0.0);
+        let sample = sample(game, usage, cpu_percent, at_unix);
+        let tracked = games.entry(sample.game_id).or_insert_with(|| Tracked {
+            last_cpu: (at, usage.cpu_time),
+            latest: sample.clone(),
+            peaks: ResourcePeaks::default(),
+        });
+        tracked.last_cpu = (at, usage.cpu_time);
+        tracked.latest = sample.clone();
+        tracked.peaks.record(&sample);
+        sample
+    }
+
+    pub fn latest(&self, game_ids: &[u64]) -> Vec<GameSample> {
+        let Ok(games) = self.games.lock() else {
+            return vec![];
+        };
+        game_ids
+            .iter()
+            .filter_map(|id| games.get(id).map(|tracked| tracked.latest.clone()))
+            .collect()
+    }
+
+    /// Peaks of a finished run; `None` if it was never sampled.
+    pub fn take_peaks(&self, game_id: u64) -> Option<ResourcePeaks> {
+        self.games
+            .lock()
+            .ok()?
+            .remove(&game_id)
+            .map(|tracked| tracked.peaks)
+    }
+}
+
+fn sample(game: GameInfo, usage: Usage, cpu_percent: f64, at_unix: u64) -> GameSample {
+    GameSample {
+        game_id: game.game_id,
+        version: game.version,
+        mode_label: game.mode_label,
+        processes: usage.processes,
+        cpu_percent,
+        rss_bytes: usage.rss_bytes,
+        threads: usage.threads,
+        uptime_secs: at_unix.saturating_sub(game.started_at),
+        sampled_at: at_unix,
+    }
+}
+
+/// CPU time used between two readings over the wall time between them. A process
+/// that exited in between takes its CPU time along, so the total can drop; that
+/// reads as idle rather than negative.
+fn cpu_percent(before: (Instant, Duration), after: (Instant, Duration)) -> f64 {
+    let wall = after.0.saturating_duration_since(before.0).as_secs_f64();
+    if wall <= 0.0 {
+        return 0.0;
+    }
+    let used = after.1.saturating_sub(before.1).as_secs_f64();
+    (used / wall * 100.0 * 10.0).round() / 10.0
+}
+
+/// Fields of `/proc/<pid>/stat`: CPU time in clock ticks, threads, and resident
+/// pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcStat {
+    cpu_ticks: u64,
+    threads: u32,
+    rss_pages: u64,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Option<ProcStat> {
+    // The command name before the state may itself contain spaces and parentheses.
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    // Counted from the state, which is field 3 in proc(5).
+    let field = |n: usize| -> Option<u64> { fields.get(n - 3)?.parse().ok() };
+    Some(ProcStat {
+        cpu_ticks: field(14)? + field(15)?,
+        threads: field(20)? as u32,
+        rss_pages: field(24)?,
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub fn linux_usage(pids: &[libc::pid_t]) -> Usage {
+    let ticks_per_sec = match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        ticks if ticks > 0 => ticks as u64,
+        _ => 100,
+    };
+    let page_size = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        size if size > 0 => size as u64,
+        _ => 4096,
+    };
+    let mut usage = Usage::default();
+    for pid in pids {
+        // Gone since it was listed.
+        let Some(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .ok()
+            .and_then(|stat| parse_proc_stat(&stat))
+        else {
+            continue;
+        };
+        usage.add(Usage {
+            processes: 1,
+            cpu_time: Duration::from_nanos(stat.cpu_ticks * 1_000_000_000 / ticks_per_sec),
+            rss_bytes: stat.rss_pages * page_size,
+            threads: stat.threads,
+        });
+    }
+    usage
+}
+
+/// The game process and everything it started, from one process snapshot.
+#[cfg(target_os = "windows")]
+pub fn windows_usage(root_pid: u32) -> Usage {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return Usage::default();
+    }
+    // pid -> (parent pid, threads)
+    let mut processes = HashMap::new();
+    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+    entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+    if unsafe { Process32FirstW(snapshot, &mut entry) } != 0 {
+        loop {
+            processes.insert(
+                entry.th32ProcessID,
+                (entry.th32ParentProcessID, entry.cntThreads),
+            );
+            if unsafe { Process32NextW(snapshot, &mut entry) } == 0 {
+                break;
+            }
+        }
+    }
+    unsafe { CloseHandle(snapshot) };
+
+    let mut game_pids = vec![root_pid];
+    let mut next = 0;
+    while next < game_pids.len() {
+        let parent = game_pids[next];
+        let children: Vec<u32> = processes
+            .iter()
+            .filter(|(pid, (ppid, _))| *ppid == parent && **pid != 0)
+            .map(|(pid, _)| *pid)
+            .filter(|pid| !game_pids.contains(pid))
+            .collect();
+        game_pids.extend(children);
+        next += 1;
+    }
+
+    let filetime = |time: FILETIME| {
+        let ticks = ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64;
+        // 100 ns units.
+        Duration::from_nanos(ticks * 100)
+    };
+    let mut usage = Usage::default();
+    for pid in game_pids {
+        let Some((_, threads)) = processes.get(&pid) else {
+            continue;
+        };
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if handle.is_null() {
+            continue;
+        }
+        let mut times: [FILETIME; 4] = unsafe { std::mem::zeroed() };
+        let [created, exited, kernel, user] = &mut times;
+        let cpu_time = if unsafe { GetProcessTimes(handle, created, exited, kernel, user) } != 0 {
+            filetime(*kernel) + filetime(*user)
+        } else {
+            Duration::ZERO
+        };
+        let mut memory: PROCESS_MEMORY_COUNTERS = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        let rss_bytes = if unsafe { K32GetProcessMemoryInfo(handle, &mut memory, size) } != 0 {
+            memory.WorkingSetSize as u64
+        } else {
+            0
+        };
+        unsafe { CloseHandle(handle) };
+        usage.add(Usage {
+            processes: 1,
+            cpu_time,
+            rss_bytes,
+            threads: *threads,
+        });
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proc_stat_parses_names_with_spaces_and_parentheses() {
+        let stat = "4242 (Lethal Company.exe (wine)) S 4200 4242 4200 0 -1 4194560 \
+            120 0 0 0 350 150 0 0 20 0 37 0 98765 4096000000 250000 \
+            18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 17 3 0 0 0 0 0";
+        assert_eq!(
+            parse_proc_stat(stat),
+            Some(ProcStat {
+                cpu_ticks: 500,
+                threads: 37,
+                rss_pages: 250_000,
+            })
+        );
+        assert_eq!(parse_proc_stat("4242 (truncated) S 1 2"), None);
+    }
+
+    #[test]
+    fn samples_rate_cpu_between_readings_and_keep_peaks() {
+        let monitor = ResourceMonitorState::default();
+        let game = GameInfo {
+            game_id: 7,
+            version: 73,
+            mode_label: "Brutal".to_string(),
+            started_at: 1_000,
+        };
+        let start = Instant::now();
+        let usage = |cpu_ms: u64, rss_bytes: u64| Usage {
+            processes: 3,
+            cpu_time: Duration::from_millis(cpu_ms),
+            rss_bytes,
+            threads: 40,
+        };
+
+        let first = monitor.record(game.clone(), usage(5_000, 900), start, 1_010);
+        assert_eq!(first.cpu_percent, 0.0);
+        assert_eq!(first.uptime_secs, 10);
+        let second = monitor.record(
+            game.clone(),
+            usage(8_000, 2_000),
+            start + Duration::from_secs(2),
+            1_012,
+        );
+        assert_eq!(second.cpu_percent, 150.0);
+        // A child exited and took its CPU time along.
+        let third = monitor.record(
+            game,
+            usage(1_000, 1_200),
+            start + Duration::from_secs(4),
+            1_014,
+        );
+        assert_eq!(third.cpu_percent, 0.0);
+
+        assert_eq!(monitor.latest(&[7, 8]), vec![third]);
+        assert_eq!(
+            monitor.take_peaks(7),
+            Some(ResourcePeaks {
+                cpu_percent: 150.0,
+                rss_bytes: 2_000,
+                threads: 40,
+                processes: 3,
+            })
+        );
+        assert_eq!(monitor.take_peaks(7), None);
+        assert!(monitor.latest(&[7]).is_empty());
+    }
+}
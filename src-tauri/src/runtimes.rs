@@ -0,0 +1,475 @@
+// On Linux the game runs through a Windows compatibility runtime. GE-Proton builds
+// the launcher installs live side by side in `proton_env/proton/<tag>`; Steam's own
+// Proton builds and a system Wine are used in place. Every runtime gets its own
+// prefix so switching runtimes never mixes Wine versions in one prefix.
+// Other platforms only list runtimes.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+/// GE-Proton build the launcher installs when no runtime was chosen.
+pub const BUNDLED_PROTON_GE: &str = "GE-Proton10-28";
+pub const DEFAULT_RELEASE_INDEX_URL: &str =
+    "https://api.github.com/repos/GloriousEggroll/proton-ge-custom/releases";
+const SYSTEM_WINE_ID: &str = "system-wine";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    /// GE-Proton installed by the launcher.
+    ManagedProton,
+    /// Proton from Steam's `compatibilitytools.d` or `steamapps/common`.
+    SteamProton,
+    Wine,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Runtime {
+    pub id: String,
+    pub name: String,
+    pub kind: RuntimeKind,
+    /// Proton folder (containing the `proton` script), or the `wine` binary.
+    pub path: PathBuf,
+}
+
+impl Runtime {
+    /// Program and arguments that run `exe` with this runtime.
+    pub fn command(&self, exe: &Path) -> (OsString, Vec<OsString>) {
+        match self.kind {
+            RuntimeKind::Wine => (
+                self.path.clone().into_os_string(),
+                vec![exe.as_os_str().to_os_string()],
+            ),
+            RuntimeKind::ManagedProton | RuntimeKind::SteamProton => (
+                self.path.join("proton").into_os_string(),
+                vec![OsString::from("run"), exe.as_os_str().to_os_string()],
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtonRelease {
+    pub tag: String,
+    pub download_url: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+}
+
+/// Stored at `config/runtimes.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    /// GitHub releases API URL, a plain `[{tag, download_url}]` list, or a local file.
+    #[serde(default)]
+    pub release_index_url: Option<String>,
+    #[serde(default)]
+    pub default_runtime: Option<String>,
+    #[serde(default)]
+    pub per_version: BTreeMap<u32, String>,
+}
+
+impl RuntimeSettings {
+    pub fn selected(&self, version: u32) -> Option<&str> {
+        self.per_version
+            .get(&version)
+            .or(self.default_runtime.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Runtime ids picked as the default or for a version.
+    pub fn selections(&self) -> impl Iterator<Item = &String> {
+        self.default_runtime.iter().chain(self.per_version.values())
+    }
+
+    /// Every selected runtime must be one of `installed`.
+    pub fn validate(&self, installed: &[Runtime]) -> Result<(), String> {
+        self.selections().try_for_each(|id| {
+            if installed.iter().any(|runtime| &runtime.id == id) {
+                Ok(())
+            } else {
+                Err(format!("runtime `{id}` is not installed"))
+            }
+        })
+    }
+
+    pub fn release_index_url(&self) -> &str {
+        self.release_index_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .unwrap_or(DEFAULT_RELEASE_INDEX_URL)
+    }
+}
+
+pub fn managed_id(tag: &str) -> String {
+    format!("ge:{tag}")
+}
+
+/// Release tags become folder names, so only plain names are accepted.
+pub fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag != "."
+        && tag != ".."
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// `GE-Proton10-28` -> `[10, 28]`, so `GE-Proton10-*` sorts after `GE-Proton9-*`.
+fn version_key(name: &str) -> Vec<u32> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+/// Parse either a GitHub releases response or a plain list of [`ProtonRelease`].
+pub fn parse_release_index(text: &str) -> Result<Vec<ProtonRelease>, String> {
+    let mut releases = match serde_json::from_str::<Vec<GithubRelease>>(text) {
+        Ok(github) => github
+            .into_iter()
+            .filter_map(|release| {
+                let asset = release
+                    .assets
+                    .into_iter()
+                    .find(|asset| asset.name.ends_with(".tar.gz"))?;
+                Some(ProtonRelease {
+                    tag: release.tag_name,
+                    download_url: asset.browser_download_url,
+                    published_at: release.published_at,
+                })
+            })
+            .collect(),
+        Err(_) => serde_json::from_str::<Vec<ProtonRelease>>(text)
+            .map_err(|e| format!("unrecognized release index: {e}"))?,
+    };
+    releases.retain(|release| is_valid_tag(&release.tag));
+    releases.sort_by_key(|release| std::cmp::Reverse(version_key(&release.tag)));
+    Ok(releases)
+}
+
+pub async fn fetch_release_index(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Vec<ProtonRelease>, String> {
+    let local = url.strip_prefix("file://").unwrap_or(url);
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        let text = std::fs::read_to_string(local)
+            .map_err(|e| format!("failed to read release index {local}: {e}"))?;
+        return parse_release_index(&text);
+    }
+    let response = client
+        .get(url)
+        .header("User-Agent", "hq-launcher/0.1 (tauri)")
+        .send()
+        .await
+        .map_err(|e| format!("failed to fetch release index: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "release index request failed with status {}",
+            response.status()
+        ));
+    }
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    parse_release_index(&text)
+}
+
+fn is_proton_dir(path: &Path) -> bool {
+    path.join("proton").is_file()
+}
+
+/// GE-Proton builds under `proton_root`, newest first.
+pub fn managed_runtimes(proton_root: &Path) -> Vec<Runtime> {
+    let Ok(rd) = std::fs::read_dir(proton_root) else {
+        return vec![];
+    };
+    let mut out: Vec<Runtime> = rd
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let path = entry.path();
+            (name.starts_with("GE-Proton") && is_proton_dir(&path)).then(|| Runtime {
+                id: managed_id(&name),
+                name,
+                kind: RuntimeKind::ManagedProton,
+                path,
+            })
+        })
+        .collect();
+    out.sort_by_key(|runtime| std::cmp::Reverse(version_key(&runtime.name)));
+    out
+}
+
+/// Proton builds in `compatibilitytools.d` folders and Steam libraries.
+pub fn steam_runtimes(compat_tool_dirs: &[PathBuf], libraries: &[PathBuf]) -> Vec<Runtime> {
+    let mut candidates = Vec::new();
+    for dir in compat_tool_dirs {
+        if let Ok(rd) = std::fs::read_dir(dir) {
+            candidates.extend(rd.flatten().map(|entry| entry.path()));
+        }
+    }
+    for library in libraries {
+        if let Ok(rd) = std::fs::read_dir(library.join("steamapps").join("common")) {
+            candidates.extend(rd.flatten().map(|entry| entry.path()).filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("Proton"))
+            }));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut out = Vec::new();
+    for path in candidates {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        // `~/.steam/steam` usually links to `~/.local/share/Steam`.
+        let canonical = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !is_proton_dir(&path) || !seen.insert(canonical) {
+            continue;
+        }
+        let id = format!("steam:{name}");
+        if out.iter().any(|r: &Runtime| r.id == id) {
+            continue;
+        }
+        out.push(Runtime {
+            id,
+            name,
+            kind: RuntimeKind::SteamProton,
+            path,
+        });
+    }
+    out
+}
+
+/// `wine` on `PATH`, if any.
+pub fn system_wine(path_var: Option<OsString>) -> Option<Runtime> {
+    let path_var = path_var?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join("wine"))
+        .find(|candidate| candidate.is_file())
+        .map(|path| Runtime {
+            id: SYSTEM_WINE_ID.to_string(),
+            name: "Wine (system)".to_string(),
+            kind: RuntimeKind::Wine,
+            path,
+        })
+}
+
+/// Every runtime on this machine: managed builds first, then Steam's, then Wine.
+pub fn detect(proton_root: &Path, steam_roots: &[PathBuf]) -> Vec<Runtime> {
+    let mut compat_tool_dirs: Vec<PathBuf> = steam_roots
+        .iter()
+        .map(|root| root.join("compatibilitytools.d"))
+        .collect();
+    compat_tool_dirs.push(PathBuf::from("/usr/share/steam/compatibilitytools.d"));
+    compat_tool_dirs.push(PathBuf::from("/usr/local/share/steam/compatibilitytools.d"));
+    let libraries: Vec<PathBuf> = steam_roots
+        .iter()
+        .flat_map(|root| crate::steam_import::library_folders(root))
+        .collect();
+
+    let mut out = managed_runtimes(proton_root);
+    out.extend(steam_runtimes(&compat_tool_dirs, &libraries));
+    out.extend(system_wine(std::env::var_os("PATH")));
+    out
+}
+
+/// The runtime `version` should launch with. An explicit choice that is no longer
+/// installed is an error rather than a silent switch to another prefix.
+pub fn resolve<'a>(
+    runtimes: &'a [Runtime],
+    settings: &RuntimeSettings,
+    version: u32,
+) -> Result<&'a Runtime, String> {
+    if let Some(id) = settings.selected(version) {
+        return runtimes
+            .iter()
+            .find(|runtime| runtime.id == id)
+            .ok_or_else(|| format!("selected runtime `{id}` is not installed"));
+    }
+    let bundled = managed_id(BUNDLED_PROTON_GE);
+    runtimes
+        .iter()
+        .find(|runtime| runtime.id == bundled)
+        .or_else(|| {
+            runtimes
+                .iter()
+                .find(|runtime| runtime.kind == RuntimeKind::ManagedProton)
+        })
+        .or_else(|| runtimes.first())
+        .ok_or_else(|| "no Proton or Wine runtime is installed".to_string())
+}
+
+/// Prefix used with `runtime_id`. The bundled build keeps the launcher's original
+/// `wine_prefix` so existing saves stay where they were.
+pub fn prefix_dir(proton_env: &Path, runtime_id: &str) -> PathBuf {
+    if runtime_id == managed_id(BUNDLED_PROTON_GE) {
+        return proton_env.join("wine_prefix");
+    }
+    let slug: String = runtime_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    proton_env.join("prefixes").join(slug)
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("config")
+        .join("runtimes.json"))
+}
+
+pub fn load_settings(app: &tauri::AppHandle) -> Result<RuntimeSettings, String> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(RuntimeSettings::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn save_settings(app: &tauri::AppHandle, settings: &RuntimeSettings) -> Result<(), String> {
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn fake_proton(dir: &Path) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("proton"), b"#!/bin/sh\n").unwrap();
+    }
+
+    #[test]
+    fn parses_a_local_github_style_index_newest_first() {
+        let index = r#"[
+            {"tag_name": "GE-Proton9-27", "published_at": "2025-03-01T00:00:00Z", "assets": [
+                {"name": "GE-Proton9-27.sha512sum", "browser_download_url": "https://x/9.sha512sum"},
+                {"name": "GE-Proton9-27.tar.gz", "browser_download_url": "https://x/9.tar.gz"}
+            ]},
+            {"tag_name": "GE-Proton10-28", "assets": [
+                {"name": "GE-Proton10-28.tar.gz", "browser_download_url": "https://x/10.tar.gz"}
+            ]},
+            {"tag_name": "../evil", "assets": [
+                {"name": "evil.tar.gz", "browser_download_url": "https://x/evil.tar.gz"}
+            ]},
+            {"tag_name": "GE-Proton8-1", "assets": []}
+        ]"#;
+        let releases = parse_release_index(index).unwrap();
+        let tags: Vec<&str> = releases.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, ["GE-Proton10-28", "GE-Proton9-27"]);
+        assert_eq!(releases[1].download_url, "https://x/9.tar.gz");
+
+        let plain = r#"[{"tag": "GE-Proton9-1", "download_url": "file:///tmp/9.tar.gz"}]"#;
+        assert_eq!(parse_release_index(plain).unwrap()[0].tag, "GE-Proton9-1");
+    }
+
+    #[test]
+    fn detects_managed_and_steam_runtimes_side_by_side() {
+        let root = temp_dir("runtimes_detect");
+        let proton_root = root.join("proton_env/proton");
+        fake_proton(&proton_root.join("GE-Proton9-27"));
+        fake_proton(&proton_root.join("GE-Proton10-28"));
+        std::fs::create_dir_all(proton_root.join("GE-Proton8-1")).unwrap();
+        let steam = root.join("Steam");
+        fake_proton(&steam.join("compatibilitytools.d/GE-Proton9-20"));
+        fake_proton(&steam.join("steamapps/common/Proton 9.0 (Beta)"));
+        std::fs::create_dir_all(steam.join("steamapps/common/Lethal Company")).unwrap();
+
+        let mut runtimes = managed_runtimes(&proton_root);
+        runtimes.extend(steam_runtimes(
+            &[steam.join("compatibilitytools.d")],
+            std::slice::from_ref(&steam),
+        ));
+        let ids: Vec<&str> = runtimes.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "ge:GE-Proton10-28",
+                "ge:GE-Proton9-27",
+                "steam:GE-Proton9-20",
+                "steam:Proton 9.0 (Beta)"
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn resolves_per_version_choice_with_its_own_prefix() {
+        let runtime = |id: &str, kind| Runtime {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind,
+            path: PathBuf::from("/opt").join(id),
+        };
+        let runtimes = vec![
+            runtime("ge:GE-Proton10-28", RuntimeKind::ManagedProton),
+            runtime("steam:Proton 9.0", RuntimeKind::SteamProton),
+        ];
+        let mut settings = RuntimeSettings::default();
+        assert_eq!(
+            resolve(&runtimes, &settings, 73).unwrap().id,
+            "ge:GE-Proton10-28"
+        );
+
+        settings
+            .per_version
+            .insert(73, "steam:Proton 9.0".to_string());
+        settings.default_runtime = Some(SYSTEM_WINE_ID.to_string());
+        assert_eq!(
+            resolve(&runtimes, &settings, 73).unwrap().id,
+            "steam:Proton 9.0"
+        );
+        assert!(resolve(&runtimes, &settings, 72).is_err());
+        assert!(settings.validate(&runtimes).is_err());
+        settings.default_runtime = None;
+        settings.validate(&runtimes).unwrap();
+
+        let env = Path::new("/data/proton_env");
+        assert_eq!(
+            prefix_dir(env, "ge:GE-Proton10-28"),
+            env.join("wine_prefix")
+        );
+        assert_eq!(
+            prefix_dir(env, "steam:Proton 9.0"),
+            env.join("prefixes/steam_Proton_9.0")
+        );
+    }
+}
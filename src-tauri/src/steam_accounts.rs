@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+// Saved Steam accounts for DepotDownloader. Every account has its own config dir,
+// so the token DepotDownloader remembers for one never leaks into another. The
+// account the launcher had before this list existed keeps `depot_config` as
+// `default`; new ones live under `depot_accounts/<id>`.
+
+pub const LEGACY_ACCOUNT_ID: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SteamAccount {
+    pub id: String,
+    /// Shown instead of the username, e.g. "alt".
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Unix seconds.
+    #[serde(default)]
+    pub added_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SteamAccounts {
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub accounts: Vec<SteamAccount>,
+}
+
+impl SteamAccounts {
+    /// What an install without `steam_accounts.json` had: the single `depot_config` account.
+    pub fn legacy() -> Self {
+        Self {
+            active: Some(LEGACY_ACCOUNT_ID.to_string()),
+            accounts: vec![SteamAccount {
+                id: LEGACY_ACCOUNT_ID.to_string(),
+                label: None,
+                added_at: 0,
+            }],
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SteamAccount> {
+        self.accounts.iter().find(|a| a.id == id)
+    }
+
+    /// The selected account, or the first one when the selection is gone.
+    pub fn active_account(&self) -> Option<&SteamAccount> {
+        self.active
+            .as_deref()
+            .and_then(|id| self.get(id))
+            .or_else(|| self.accounts.first())
+    }
+
+    /// Add an empty account and make it active, so the next login lands in it.
+    pub fn add(&mut self, label: Option<String>, now: u64) -> SteamAccount {
+        let id = (1..)
+            .map(|n| format!("account-{n}"))
+            .find(|id| self.get(id).is_none())
+            .unwrap_or_default();
+        let account = SteamAccount {
+            id: id.clone(),
+            label: label
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty()),
+            added_at: now,
+        };
+        self.accounts.push(account.clone());
+        self.active = Some(id);
+        account
+    }
+
+    pub fn switch(&mut self, id: &str) -> Result<(), String> {
+        if self.get(id).is_none() {
+            return Err(format!("Unknown Steam account: {id}"));
+        }
+        self.active = Some(id.to_string());
+        Ok(())
+    }
+
+    /// Remove `id`; when it was active the first remaining account takes over.
+    pub fn remove(&mut self, id: &str) -> Result<SteamAccount, String> {
+        let index = self
+            .accounts
+            .iter()
+            .position(|a| a.id == id)
+            .ok_or_else(|| format!("Unknown Steam account: {id}"))?;
+        let removed = self.accounts.remove(index);
+        if self.active.as_deref() == Some(id) {
+            self.active = self.accounts.first().map(|a| a.id.clone());
+        }
+        Ok(removed)
+    }
+}
+
+pub fn account_dir(app_data: &Path, id: &str) -> PathBuf {
+    if id == LEGACY_ACCOUNT_ID {
+        app_data.join("depot_config")
+    } else {
+        app_data.join("depot_accounts").join(id)
+    }
+}
+
+fn app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))
+}
+
+fn accounts_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?
+        .join("config")
+        .join("steam_accounts.json"))
+}
+
+pub fn load(app: &tauri::AppHandle) -> Result<SteamAccounts, String> {
+    let path = accounts_path(app)?;
+    if !path.exists() {
+        return Ok(SteamAccounts::legacy());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let accounts: SteamAccounts = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    // Removing the last account starts over with an empty legacy one.
+    if accounts.accounts.is_empty() {
+        return Ok(SteamAccounts::legacy());
+    }
+    Ok(accounts)
+}
+
+pub fn save(app: &tauri::AppHandle, accounts: &SteamAccounts) -> Result<(), String> {
+    let path = accounts_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(accounts).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Config dir of `id` under this app's data dir.
+pub fn dir_for(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    Ok(account_dir(&app_data_dir(app)?, id))
+}
+
+/// Config dir DepotDownloader should run in.
+pub fn active_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let accounts = load(app)?;
+    let id = accounts
+        .active_account()
+        .map_or(LEGACY_ACCOUNT_ID, |a| a.id.as_str());
+    dir_for(app, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_switch_and_remove_keep_an_active_account() {
+        let mut accounts = SteamAccounts::legacy();
+        let alt = accounts.add(Some("  alt ".to_string()), 10);
+        assert_eq!(alt.id, "account-1");
+        assert_eq!(alt.label.as_deref(), Some("alt"));
+        assert_eq!(accounts.active_account().unwrap().id, "account-1");
+
+        accounts.switch(LEGACY_ACCOUNT_ID).unwrap();
+        assert!(accounts.switch("account-9").is_err());
+        assert_eq!(accounts.add(None, 11).id, "account-2");
+
+        accounts.remove("account-2").unwrap();
+        assert_eq!(accounts.active.as_deref(), Some(LEGACY_ACCOUNT_ID));
+        assert_eq!(accounts.add(None, 12).id, "account-2");
+
+        accounts.remove(LEGACY_ACCOUNT_ID).unwrap();
+        accounts.remove("account-2").unwrap();
+        assert_eq!(accounts.active.as_deref(), Some("account-1"));
+        accounts.remove("account-1").unwrap();
+        assert!(accounts.active_account().is_none());
+    }
+
+    #[test]
+    fn legacy_account_keeps_the_original_config_dir() {
+        let app_data = Path::new("data");
+        assert_eq!(
+            account_dir(app_data, LEGACY_ACCOUNT_ID),
+            app_data.join("depot_config")
+        );
+        assert_eq!(
+            account_dir(app_data, "account-1"),
+            app_data.join("depot_accounts").join("account-1")
+        );
+    }
+}
@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+
+use crate::downloader::DepotDownloader;
+
+// Steam's `appmanifest_<appid>.acf` bits (EAppState).
+const STATE_UPDATE_REQUIRED: u32 = 2;
+const STATE_FULLY_INSTALLED: u32 = 4;
+const STATE_UPDATE_STARTED: u32 = 1024;
+
+/// Minimal Valve KeyValues (VDF/ACF) tree. Keys are matched case-insensitively,
+/// like Steam does.
+#[derive(Debug, Clone, PartialEq)]
+enum Vdf {
+    Str(String),
+    Map(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Vdf::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Vdf::Str(_) => None,
+        }
+    }
+
+    fn str(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Vdf::Str(s) => Some(s),
+            Vdf::Map(_) => None,
+        }
+    }
+
+    fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Map(entries) => entries,
+            Vdf::Str(_) => &[],
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => tokens.push(c.to_string()),
+            '"' => {
+                let mut s = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(other) => s.push(other),
+                            None => break,
+                        },
+                        _ => s.push(c),
+                    }
+                }
+                // Quoted strings are kept distinguishable from bare braces.
+                tokens.push(format!("\"{s}"));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                let mut s = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(format!("\"{s}"));
+            }
+        }
+    }
+    tokens
+}
+
+fn parse_map(tokens: &mut std::vec::IntoIter<String>) -> Vdf {
+    let mut entries = Vec::new();
+    while let Some(token) = tokens.next() {
+        let Some(key) = token.strip_prefix('"') else {
+            // `}` closes this map; a stray `{` is ignored.
+            if token == "}" {
+                break;
+            }
+            continue;
+        };
+        let value = match tokens.next() {
+            Some(t) if t == "{" => parse_map(tokens),
+            Some(t) => match t.strip_prefix('"') {
+                Some(v) => Vdf::Str(v.to_string()),
+                None => break,
+            },
+            None => break,
+        };
+        entries.push((key.to_string(), value));
+    }
+    Vdf::Map(entries)
+}
+
+fn parse_vdf(text: &str) -> Vdf {
+    parse_map(&mut tokenize(text).into_iter())
+}
+
+/// A Lethal Company install found in one of the Steam libraries.
+#[derive(Debug, Clone, Serialize)]
+pub struct SteamInstall {
+    pub library: String,
+    pub game_dir: String,
+    pub build_id: Option<String>,
+    pub depot_manifest: Option<String>,
+    /// Installed and not waiting for (or in the middle of) a Steam update.
+    pub fully_installed: bool,
+    /// Launcher version the depot manifest maps to, if the remote manifest knows it.
+    pub version: Option<u32>,
+}
+
+/// Steam install folders to look in, starting with the user's configured one.
+pub fn steam_roots(configured: Option<&str>) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(configured) = configured.map(str::trim).filter(|v| !v.is_empty()) {
+        roots.push(PathBuf::from(configured));
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Some(path) = crate::get_windows_steam_install_path(None) {
+        roots.push(path);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join(".steam/steam"));
+        roots.push(home.join(".local/share/Steam"));
+        roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        roots.push(home.join("Library/Application Support/Steam"));
+    }
+
+    roots.retain(|root| root.join("steamapps").is_dir());
+    roots
+}
+
+/// Library folders listed in `steamapps/libraryfolders.vdf`, plus the Steam folder
+/// itself. Handles both the current (`"0" { "path" ... }`) and the legacy
+/// (`"1" "D:\\SteamLibrary"`) layouts.
+pub fn library_folders(steam_root: &Path) -> Vec<PathBuf> {
+    let mut out = vec![steam_root.to_path_buf()];
+    let Ok(text) = std::fs::read_to_string(steam_root.join("steamapps/libraryfolders.vdf")) else {
+        return out;
+    };
+    let root = parse_vdf(&text);
+    let folders = root
+        .get("libraryfolders")
+        .map(Vdf::entries)
+        .unwrap_or_default();
+    for (key, value) in folders {
+        if !key.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let path = match value {
+            Vdf::Str(path) => Some(path.as_str()),
+            Vdf::Map(_) => value.str("path"),
+        };
+        if let Some(path) = path.filter(|p| !p.is_empty()) {
+            out.push(PathBuf::from(path));
+        }
+    }
+    out
+}
+
+/// Read `appmanifest_1966720.acf` of a library.
+fn parse_app_manifest(text: &str, library: &Path) -> Option<SteamInstall> {
+    let root = parse_vdf(text);
+    let app = root.get("AppState")?;
+    let install_dir = app.str("installdir").filter(|d| !d.is_empty())?;
+    let flags = app
+        .str("StateFlags")
+        .and_then(|f| f.parse::<u32>().ok())
+        .unwrap_or(0);
+    let depot_manifest = app
+        .get("InstalledDepots")
+        .and_then(|depots| depots.get(DepotDownloader::DEPOT_ID))
+        .and_then(|depot| depot.str("manifest"))
+        .map(str::to_string);
+
+    Some(SteamInstall {
+        library: library.to_string_lossy().to_string(),
+        game_dir: library
+            .join("steamapps")
+            .join("common")
+            .join(install_dir)
+            .to_string_lossy()
+            .to_string(),
+        build_id: app.str("buildid").map(str::to_string),
+        depot_manifest,
+        fully_installed: flags & STATE_FULLY_INSTALLED != 0
+            && flags & (STATE_UPDATE_REQUIRED | STATE_UPDATE_STARTED) == 0,
+        version: None,
+    })
+}
+
+/// Every Lethal Company install across the given Steam folders' libraries.
+pub fn find_installs(steam_roots: &[PathBuf]) -> Vec<SteamInstall> {
+    let manifest_name = format!("appmanifest_{}.acf", crate::LETHAL_COMPANY_STEAM_APP_ID);
+    let mut seen = Vec::new();
+    let mut out = Vec::new();
+    for root in steam_roots {
+        for library in library_folders(root) {
+            let key = std::fs::canonicalize(&library).unwrap_or_else(|_| library.clone());
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+
+            let Ok(text) = std::fs::read_to_string(library.join("steamapps").join(&manifest_name))
+            else {
+                continue;
+            };
+            if let Some(install) = parse_app_manifest(&text, &library) {
+                if Path::new(&install.game_dir).is_dir() {
+                    out.push(install);
+                }
+            }
+        }
+    }
+    out
+}
+
+pub fn version_for_manifest(manifests: &BTreeMap<u32, String>, manifest_id: &str) -> Option<u32> {
+    manifests
+        .iter()
+        .find(|(_, id)| id.as_str() == manifest_id)
+        .map(|(version, _)| *version)
+}
+
+pub(crate) fn collect_files(
+    root: &Path,
+    rel_dir: &Path,
+    out: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    for entry in std::fs::read_dir(root.join(rel_dir)).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        // A modded Steam copy must not leak its own loader or plugins into the import.
+        if rel_dir.as_os_str().is_empty()
+            && crate::dedup_store::SKIPPED_ROOT_ENTRIES.contains(&name_str.as_ref())
+        {
+            continue;
+        }
+        let rel = rel_dir.join(&name);
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_files(root, &rel, out)?;
+        } else if file_type.is_file() {
+            out.push(rel);
+        }
+    }
+    Ok(())
+}
+
+/// Copy the game files of `src` into `dst`, sharing data with the Steam copy when
+/// `link` is set and the volume allows it. Returns (files, shared files).
+pub fn import_game_files<F>(
+    src: &Path,
+    dst: &Path,
+    link: bool,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> Result<(u64, u64), String>
+where
+    F: FnMut(u64, u64),
+{
+    let mut files = Vec::new();
+    collect_files(src, Path::new(""), &mut files)?;
+    let total = files.len() as u64;
+    let mut shared = 0u64;
+    for (idx, rel) in files.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+        let from = src.join(rel);
+        let to = dst.join(rel);
+        if link {
+            if crate::dedup_store::link_or_copy(&from, &to)? {
+                shared += 1;
+            }
+        } else {
+            if let Some(parent) = to.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::copy(&from, &to).map_err(|e| format!("{}: {e}", from.display()))?;
+        }
+        on_progress(idx as u64 + 1, total);
+    }
+    Ok((total, shared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP_MANIFEST: &str = r#"
+"AppState"
+{
+	"appid"		"1966720"
+	"name"		"Lethal Company"
+	"StateFlags"		"4"
+	"installdir"		"Lethal Company"
+	"buildid"		"16442656"
+	"InstalledDepots"
+	{
+		"1966721"
+		{
+			"manifest"		"7525563530173177311"
+			"size"		"1194723165"
+		}
+	}
+}
+"#;
+
+    #[test]
+    fn reads_build_and_depot_manifest_from_acf() {
+        let install = parse_app_manifest(APP_MANIFEST, Path::new("/games/SteamLibrary")).unwrap();
+        assert_eq!(install.build_id.as_deref(), Some("16442656"));
+        assert_eq!(
+            install.depot_manifest.as_deref(),
+            Some("7525563530173177311")
+        );
+        assert!(install.fully_installed);
+        assert!(install.game_dir.ends_with("Lethal Company"));
+
+        let updating = APP_MANIFEST.replace("\"StateFlags\"\t\t\"4\"", "\"StateFlags\"\t\t\"6\"");
+        assert!(
+            !parse_app_manifest(&updating, Path::new("/x"))
+                .unwrap()
+                .fully_installed
+        );
+    }
+
+    #[test]
+    fn parses_current_and_legacy_library_folders() {
+        let current = parse_vdf(
+            r#""libraryfolders" { "0" { "path" "C:\\Program Files (x86)\\Steam" "apps" { "1966720" "1194723165" } } "1" { "path" "D:\\SteamLibrary" } }"#,
+        );
+        let folders = current.get("libraryfolders").unwrap();
+        assert_eq!(
+            folders.get("1").and_then(|f| f.str("path")),
+            Some(r"D:\SteamLibrary")
+        );
+
+        let legacy = parse_vdf(
+            "\"LibraryFolders\"\n{\n\t// comment\n\t\"TimeNextStatsReport\"\t\"1\"\n\t\"1\"\t\"E:\\\\Games\"\n}",
+        );
+        assert_eq!(
+            legacy.get("libraryfolders").and_then(|f| f.str("1")),
+            Some(r"E:\Games")
+        );
+    }
+
+    #[test]
+    fn maps_depot_manifest_to_version() {
+        let manifests = BTreeMap::from([
+            (72, "1111".to_string()),
+            (73, "7525563530173177311".to_string()),
+        ]);
+        assert_eq!(
+            version_for_manifest(&manifests, "7525563530173177311"),
+            Some(73)
+        );
+        assert_eq!(version_for_manifest(&manifests, "9999"), None);
+    }
+}
@@ -29,6 +29,11 @@ fn config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
         .join("storage.json"))
 }
 
+/// Journal of an unfinished versions folder move; see [`crate::relocation`].
+pub fn relocation_journal_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(config_path(app)?.with_file_name("storage_move.json"))
+}
+
 pub fn default_versions_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(app
         .path()
@@ -159,138 +164,45 @@ pub fn set_events_enabled(app: &tauri::AppHandle, enabled: bool) -> Result<bool,
     Ok(enabled)
 }
 
-fn dir_has_any_entries(path: &Path) -> bool {
-    std::fs::read_dir(path)
-        .ok()
-        .and_then(|mut rd| rd.next())
-        .is_some()
-}
-
-fn count_copy_entries(path: &Path) -> Result<u64, String> {
-    let mut count = 0u64;
-    for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let src = entry.path();
-        let metadata = std::fs::symlink_metadata(&src).map_err(|e| e.to_string())?;
-        let file_type = metadata.file_type();
-        if file_type.is_symlink() {
-            continue;
-        }
-        if file_type.is_dir() {
-            count = count.saturating_add(1);
-            count = count.saturating_add(count_copy_entries(&src)?);
-        } else if file_type.is_file() {
-            count = count.saturating_add(1);
-        }
+/// Free bytes on the volume holding `path` (the closest existing ancestor is used).
+pub fn free_space_bytes(path: &Path) -> Option<u64> {
+    let mut probe = path;
+    while !probe.exists() {
+        probe = probe.parent()?;
     }
-    Ok(count)
+    free_space_bytes_impl(probe)
 }
 
-fn copy_dir_contents<F>(
-    from: &Path,
-    to: &Path,
-    copied: &mut u64,
-    total: u64,
-    on_progress: &mut F,
-) -> Result<(), String>
-where
-    F: FnMut(u64, u64, Option<String>),
-{
-    std::fs::create_dir_all(to).map_err(|e| e.to_string())?;
-    for entry in std::fs::read_dir(from).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let src = entry.path();
-        let dest = to.join(entry.file_name());
-        let metadata = std::fs::symlink_metadata(&src).map_err(|e| e.to_string())?;
-        let file_type = metadata.file_type();
+#[cfg(unix)]
+fn free_space_bytes_impl(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
 
-        if file_type.is_symlink() {
-            continue;
-        }
-
-        if file_type.is_dir() {
-            std::fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
-            *copied = copied.saturating_add(1);
-            on_progress(
-                *copied,
-                total,
-                dest.file_name()
-                    .and_then(|name| name.to_str())
-                    .map(|name| format!("Creating {name}")),
-            );
-            copy_dir_contents(&src, &dest, copied, total, on_progress)?;
-            continue;
-        }
-
-        if file_type.is_file() {
-            if let Some(parent) = dest.parent() {
-                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-            }
-            std::fs::copy(&src, &dest).map_err(|e| {
-                format!(
-                    "failed to copy {} to {}: {e}",
-                    src.to_string_lossy(),
-                    dest.to_string_lossy()
-                )
-            })?;
-            *copied = copied.saturating_add(1);
-            on_progress(
-                *copied,
-                total,
-                dest.file_name()
-                    .and_then(|name| name.to_str())
-                    .map(|name| format!("Copying {name}")),
-            );
-        }
-    }
-    Ok(())
-}
-
-pub fn move_versions_dir<F>(
-    old_dir: &Path,
-    new_dir: &Path,
-    mut on_progress: F,
-) -> Result<bool, String>
-where
-    F: FnMut(u64, u64, Option<String>),
-{
-    let old_exists = old_dir.exists();
-    if !old_exists || !dir_has_any_entries(old_dir) {
-        std::fs::create_dir_all(new_dir).map_err(|e| e.to_string())?;
-        on_progress(1, 1, Some("Storage folder is ready".to_string()));
-        return Ok(false);
-    }
-
-    if new_dir.exists() && dir_has_any_entries(new_dir) {
-        return Err(format!(
-            "target versions folder is not empty: {}",
-            new_dir.to_string_lossy()
-        ));
-    }
-
-    if let Some(parent) = new_dir.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-
-    let total = count_copy_entries(old_dir)?.max(1);
-    on_progress(0, total, Some("Preparing game files...".to_string()));
-
-    match std::fs::rename(old_dir, new_dir) {
-        Ok(()) => {
-            on_progress(total, total, Some("Moved game files".to_string()));
-            Ok(true)
-        }
-        Err(rename_err) => {
-            let mut copied = 0u64;
-            copy_dir_contents(old_dir, new_dir, &mut copied, total, &mut on_progress)
-                .map_err(|copy_err| format!("{rename_err}; {copy_err}"))?;
-            on_progress(
-                total,
-                total,
-                Some("Removing old game storage...".to_string()),
-            );
-            std::fs::remove_dir_all(old_dir).map_err(|e| e.to_string())?;
-            Ok(true)
-        }
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
     }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn free_space_bytes_impl(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available: u64 = 0;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
 }
@@ -0,0 +1,17 @@
+// Helpers shared by the unit tests.
+
+use std::path::PathBuf;
+
+/// A new, empty directory under the system temp dir, unique to this test run.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "hq_{name}_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
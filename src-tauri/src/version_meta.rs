@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+// What the launcher knows about one installed version, kept inside it at
+// `v{N}/.hq-launcher/version.json` so the record moves and disappears with the
+// folder. Installs from before this file existed are migrated from
+// `manifest_state.json` at startup (see
+// `installer::migrate_version_metadata_on_startup`).
+
+const META_DIR: &str = ".hq-launcher";
+const META_FILE: &str = "version.json";
+const MAX_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchStatus {
+    /// UnityApplicationPatcher ran on the current `UnityPlayer.dll`.
+    Applied,
+    /// v73 and later ship a fixed player.
+    NotNeeded,
+    /// Game files were replaced after patching (a depot update).
+    NotApplied,
+    #[default]
+    Unknown,
+}
+
+impl PatchStatus {
+    pub fn after_install(version: u32) -> Self {
+        if version >= 73 {
+            PatchStatus::NotNeeded
+        } else {
+            PatchStatus::Applied
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateOperation {
+    Install,
+    Import,
+    DepotSync,
+    ModSync,
+    Verify,
+    Patch,
+    /// Record created for an install that predates this file.
+    Migrated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRecord {
+    /// Unix seconds.
+    pub at: u64,
+    pub operation: UpdateOperation,
+    #[serde(default)]
+    pub depot_manifest: Option<String>,
+    #[serde(default)]
+    pub remote_manifest_version: Option<u32>,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionMetadata {
+    pub version: u32,
+    /// Unix seconds; only set once an install finished, so a folder without it is
+    /// incomplete.
+    #[serde(default)]
+    pub installed_at: Option<u64>,
+    #[serde(default)]
+    pub depot_manifest: Option<String>,
+    #[serde(default)]
+    pub bepinex_version: Option<String>,
+    #[serde(default)]
+    pub patch_status: PatchStatus,
+    /// Unix seconds of the last time mods were brought in line with the remote manifest.
+    #[serde(default)]
+    pub last_mod_sync: Option<u64>,
+    #[serde(default)]
+    pub remote_manifest_version: Option<u32>,
+    /// Oldest first, capped at the most recent entries.
+    #[serde(default)]
+    pub history: Vec<UpdateRecord>,
+}
+
+impl VersionMetadata {
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            installed_at: None,
+            depot_manifest: None,
+            bepinex_version: None,
+            patch_status: PatchStatus::Unknown,
+            last_mod_sync: None,
+            remote_manifest_version: None,
+            history: vec![],
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.installed_at.is_some()
+    }
+
+    /// Append `operation` to the history along with the manifests now in effect.
+    pub fn record(&mut self, at: u64, operation: UpdateOperation, detail: Option<String>) {
+        self.history.push(UpdateRecord {
+            at,
+            operation,
+            depot_manifest: self.depot_manifest.clone(),
+            remote_manifest_version: self.remote_manifest_version,
+            detail,
+        });
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+pub fn path(version_root: &Path) -> PathBuf {
+    version_root.join(META_DIR).join(META_FILE)
+}
+
+pub fn read(version_root: &Path) -> Option<VersionMetadata> {
+    let text = std::fs::read_to_string(path(version_root)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+pub fn write(version_root: &Path, meta: &VersionMetadata) -> Result<(), String> {
+    let path = path(version_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
+
+/// Read (or start) the record of `version_root`, apply `f` and write it back.
+pub fn update<F>(version_root: &Path, version: u32, f: F) -> Result<VersionMetadata, String>
+where
+    F: FnOnce(&mut VersionMetadata),
+{
+    let mut meta = read(version_root).unwrap_or_else(|| VersionMetadata::new(version));
+    f(&mut meta);
+    write(version_root, &meta)?;
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn update_creates_then_extends_the_record() {
+        let root = temp_dir("version_meta");
+        assert!(read(&root).is_none());
+
+        update(&root, 72, |meta| {
+            meta.depot_manifest = Some("4861510547912001926".to_string());
+            meta.remote_manifest_version = Some(12);
+            meta.record(
+                100,
+                UpdateOperation::Import,
+                Some("Steam library".to_string()),
+            );
+        })
+        .unwrap();
+        assert!(!read(&root).unwrap().is_complete());
+
+        let meta = update(&root, 72, |meta| {
+            meta.installed_at = Some(200);
+            meta.patch_status = PatchStatus::after_install(meta.version);
+            meta.record(200, UpdateOperation::Install, None);
+        })
+        .unwrap();
+        assert!(meta.is_complete());
+        assert_eq!(meta.patch_status, PatchStatus::Applied);
+        assert_eq!(meta.history.len(), 2);
+        assert_eq!(meta.history[0].operation, UpdateOperation::Import);
+        assert_eq!(meta.history[1].remote_manifest_version, Some(12));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_entries() {
+        let mut meta = VersionMetadata::new(81);
+        for at in 0..(MAX_HISTORY as u64 + 5) {
+            meta.record(at, UpdateOperation::ModSync, None);
+        }
+        assert_eq!(meta.history.len(), MAX_HISTORY);
+        assert_eq!(meta.history[0].at, 5);
+        assert_eq!(PatchStatus::after_install(81), PatchStatus::NotNeeded);
+    }
+}
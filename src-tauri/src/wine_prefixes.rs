@@ -0,0 +1,384 @@
+// Which Wine prefix a launch uses on Linux. By default a version runs in the prefix of
+// its runtime (`runtimes::prefix_dir`). A launch profile or a per-version selection
+// can name a prefix instead, and `separate_versions` gives every version a prefix of
+// its own, so parallel runs of different versions and a broken prefix never touch
+// each other. Named prefixes live beside the runtime ones in `proton_env/prefixes`;
+// a prefix's id is its folder name there.
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::launch_profiles::LaunchProfiles;
+use crate::runtimes;
+
+/// Stored at `config/wine_prefixes.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefixSettings {
+    /// Versions without a selection get `<runtime prefix>-v<version>`.
+    #[serde(default)]
+    pub separate_versions: bool,
+    #[serde(default)]
+    pub per_version: BTreeMap<u32, String>,
+}
+
+impl PrefixSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.per_version.values().try_for_each(|id| check_id(id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefixInfo {
+    pub id: String,
+    pub path: PathBuf,
+    /// Wine has set it up; a new prefix is only created on its first launch.
+    pub initialised: bool,
+    /// Versions that launch in it, selected explicitly or by default.
+    pub versions: Vec<u32>,
+    pub profiles: Vec<String>,
+}
+
+/// Prefix id for a launch: the profile's, then the version's, then the runtime's.
+pub fn resolve(
+    settings: &PrefixSettings,
+    version: u32,
+    runtime_id: &str,
+    profile_prefix: Option<&str>,
+) -> String {
+    profile_prefix
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .or_else(|| settings.per_version.get(&version).map(String::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            if settings.separate_versions {
+                format!("{runtime_id}-v{version}")
+            } else {
+                runtime_id.to_string()
+            }
+        })
+}
+
+/// Ids the user picks become folder names, so they are plain words.
+pub fn check_id(id: &str) -> Result<(), String> {
+    let ok = !id.is_empty()
+        && id.len() <= 64
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'));
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid prefix name `{id}`: use letters, digits, `-`, `_` and `.`"
+        ))
+    }
+}
+
+/// Folder of the prefix with `id`, which must already be a valid id or a runtime id.
+pub fn dir(proton_env: &Path, id: &str) -> PathBuf {
+    runtimes::prefix_dir(proton_env, id)
+}
+
+/// Folder of an existing prefix the user named, as listed by [`list`].
+pub fn existing(proton_env: &Path, id: &str) -> Result<PathBuf, String> {
+    if id != runtimes::managed_id(runtimes::BUNDLED_PROTON_GE) {
+        check_id(id)?;
+    }
+    let path = dir(proton_env, id);
+    if !path.is_dir() {
+        return Err(format!("prefix `{id}` not found"));
+    }
+    Ok(path)
+}
+
+/// Proton keeps the Wine prefix in the `pfx` folder of its compat data folder.
+pub fn is_initialised(prefix: &Path) -> bool {
+    prefix.join("system.reg").is_file() || prefix.join("pfx").join("system.reg").is_file()
+}
+
+/// Every prefix on disk and who uses it. `version_runtimes` maps each installed
+/// version to its runtime id, which picks the prefix of versions without a selection.
+pub fn list(
+    proton_env: &Path,
+    settings: &PrefixSettings,
+    profiles: &LaunchProfiles,
+    version_runtimes: &BTreeMap<u32, String>,
+) -> Vec<PrefixInfo> {
+    let versions: BTreeSet<u32> = settings
+        .per_version
+        .keys()
+        .chain(version_runtimes.keys())
+        .copied()
+        .collect();
+    let bundled = runtimes::managed_id(runtimes::BUNDLED_PROTON_GE);
+    let mut found = vec![];
+    if dir(proton_env, &bundled).is_dir() {
+        found.push(bundled.clone());
+    }
+    if let Ok(entries) = std::fs::read_dir(proton_env.join("prefixes")) {
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| check_id(name).is_ok())
+            .collect();
+        names.sort();
+        found.extend(names);
+    }
+    found
+        .into_iter()
+        .map(|id| {
+            let path = dir(proton_env, &id);
+            PrefixInfo {
+                initialised: is_initialised(&path),
+                versions: versions
+                    .iter()
+                    .copied()
+                    .filter(|version| {
+                        let runtime_id = version_runtimes.get(version).map_or("", String::as_str);
+                        dir(proton_env, &resolve(settings, *version, runtime_id, None)) == path
+                    })
+                    .collect(),
+                profiles: profiles
+                    .profiles
+                    .iter()
+                    .filter(|profile| profile.wine_prefix.as_deref() == Some(id.as_str()))
+                    .map(|profile| profile.id.clone())
+                    .collect(),
+                path,
+                id,
+            }
+        })
+        .collect()
+}
+
+/// Create an empty prefix; Wine fills it on the first launch that uses it.
+pub fn create(proton_env: &Path, id: &str) -> Result<PathBuf, String> {
+    check_id(id)?;
+    let path = dir(proton_env, id);
+    if path.exists() {
+        return Err(format!("prefix `{id}` already exists"));
+    }
+    std::fs::create_dir_all(&path).map_err(|e| format!("failed to create prefix `{id}`: {e}"))?;
+    Ok(path)
+}
+
+/// Empty a prefix, saves included, so the next launch sets it up again.
+pub fn reset(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        std::fs::remove_dir_all(path).map_err(|e| format!("failed to reset prefix: {e}"))?;
+    }
+    std::fs::create_dir_all(path).map_err(|e| format!("failed to reset prefix: {e}"))
+}
+
+pub fn delete(path: &Path) -> Result<(), String> {
+    std::fs::remove_dir_all(path).map_err(|e| format!("failed to delete prefix: {e}"))
+}
+
+/// Copy `from` to the new prefix `to_id`. Symlinks (`dosdevices`, Proton's shared
+/// DLLs) are copied as links so the clone points where the original does.
+pub fn clone(proton_env: &Path, from: &Path, to_id: &str) -> Result<PathBuf, String> {
+    check_id(to_id)?;
+    let to = dir(proton_env, to_id);
+    if to.exists() {
+        return Err(format!("prefix `{to_id}` already exists"));
+    }
+    let mut staging = to.clone().into_os_string();
+    staging.push(".partial");
+    let staging = PathBuf::from(staging);
+    let _ = std::fs::remove_dir_all(&staging);
+    let copied = copy_tree(from, &staging)
+        .and_then(|()| std::fs::rename(&staging, &to).map_err(|e| e.to_string()));
+    if let Err(e) = copied {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!("failed to clone prefix: {e}"));
+    }
+    Ok(to)
+}
+
+fn copy_tree(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| e.to_string())?;
+    for entry in std::fs::read_dir(from).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else if file_type.is_dir() {
+            copy_tree(&source, &target)?;
+        } else {
+            std::fs::copy(&source, &target)
+                .map_err(|e| format!("{}: {e}", source.to_string_lossy()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), String> {
+    let link = std::fs::read_link(source).map_err(|e| e.to_string())?;
+    std::os::unix::fs::symlink(link, target).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), String> {
+    std::fs::copy(source, target)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+        .join("config")
+        .join("wine_prefixes.json"))
+}
+
+pub fn load_settings(app: &tauri::AppHandle) -> Result<PrefixSettings, String> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(PrefixSettings::default());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
+
+pub fn save_settings(app: &tauri::AppHandle, settings: &PrefixSettings) -> Result<(), String> {
+    settings.validate()?;
+    let path = settings_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch_profiles::LaunchProfile;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn profile_then_version_then_runtime_prefix() {
+        let mut settings = PrefixSettings {
+            separate_versions: false,
+            per_version: BTreeMap::from([(73, "brutal".to_string())]),
+        };
+        let runtime = "ge:GE-Proton9-27";
+        assert_eq!(resolve(&settings, 73, runtime, Some("stream")), "stream");
+        assert_eq!(resolve(&settings, 73, runtime, Some(" ")), "brutal");
+        assert_eq!(resolve(&settings, 72, runtime, None), runtime);
+        settings.separate_versions = true;
+        assert_eq!(
+            resolve(&settings, 72, runtime, None),
+            "ge:GE-Proton9-27-v72"
+        );
+
+        let env = Path::new("/data/proton_env");
+        assert_eq!(
+            dir(env, &resolve(&settings, 72, runtime, None)),
+            env.join("prefixes/ge_GE-Proton9-27-v72")
+        );
+        assert_eq!(dir(env, "brutal"), env.join("prefixes/brutal"));
+
+        assert!(check_id("v73-brutal.2").is_ok());
+        for bad in ["", "..", ".hidden", "a/b", "ge:GE-Proton9-27", "a b"] {
+            assert!(check_id(bad).is_err(), "{bad}");
+        }
+        settings.per_version.insert(60, "../up".to_string());
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn create_clone_reset_and_list_prefixes() {
+        let env = temp_dir("wine_prefixes_ops");
+        let brutal = create(&env, "brutal").unwrap();
+        assert!(create(&env, "brutal").is_err());
+        std::fs::create_dir_all(brutal.join("pfx/drive_c/users/steamuser")).unwrap();
+        std::fs::write(brutal.join("pfx/system.reg"), "WINE REGISTRY").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("../drive_c", brutal.join("pfx/c_link")).unwrap();
+
+        let copy = clone(&env, &brutal, "brutal-copy").unwrap();
+        assert!(is_initialised(&copy));
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::read_link(copy.join("pfx/c_link")).unwrap(),
+            Path::new("../drive_c")
+        );
+        assert!(clone(&env, &brutal, "brutal-copy").is_err());
+
+        reset(&copy).unwrap();
+        assert!(copy.is_dir() && !is_initialised(&copy));
+
+        let settings = PrefixSettings {
+            separate_versions: false,
+            per_version: BTreeMap::from([(73, "brutal".to_string())]),
+        };
+        let profiles = LaunchProfiles {
+            profiles: vec![LaunchProfile {
+                id: "stream".to_string(),
+                name: "Stream".to_string(),
+                launch_options: vec![],
+                env: BTreeMap::new(),
+                command_template: None,
+                wine_prefix: Some("brutal-copy".to_string()),
+            }],
+            ..LaunchProfiles::default()
+        };
+        let version_runtimes = BTreeMap::from([
+            (72, "brutal-copy".to_string()),
+            (73, "ge:GE-Proton9-27".to_string()),
+        ]);
+        let listed = list(&env, &settings, &profiles, &version_runtimes);
+        let summary: Vec<(&str, bool, &[u32], &[String])> = listed
+            .iter()
+            .map(|p| {
+                (
+                    p.id.as_str(),
+                    p.initialised,
+                    p.versions.as_slice(),
+                    p.profiles.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("brutal", true, &[73][..], &[][..]),
+                ("brutal-copy", false, &[72][..], &["stream".to_string()][..]),
+            ]
+        );
+
+        delete(&copy).unwrap();
+        assert_eq!(list(&env, &settings, &profiles, &version_runtimes).len(), 1);
+
+        // Runtime prefixes are listed by folder name, not by runtime id.
+        let settings = PrefixSettings {
+            separate_versions: true,
+            ..settings
+        };
+        let version_runtimes = BTreeMap::from([(74, "ge:GE-Proton9-27".to_string())]);
+        std::fs::create_dir_all(dir(&env, "ge:GE-Proton9-27-v74")).unwrap();
+        let listed = list(&env, &settings, &profiles, &version_runtimes);
+        let summary: Vec<(&str, &[u32])> = listed
+            .iter()
+            .map(|p| (p.id.as_str(), p.versions.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [("brutal", &[73][..]), ("ge_GE-Proton9-27-v74", &[74][..])]
+        );
+        std::fs::remove_dir_all(&env).unwrap();
+    }
+}
@@ -73,6 +73,8 @@ where
             std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
 
+        // Never truncate a file that is hardlinked into the dedup store.
+        crate::dedup_store::unshare_file(&out_path)?;
         let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
         std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
 
@@ -237,6 +239,8 @@ where
             std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
 
+        // Never truncate a file that is hardlinked into the dedup store.
+        crate::dedup_store::unshare_file(&out_path)?;
         let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
         std::io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
 